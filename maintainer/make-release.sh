@@ -17,6 +17,7 @@ features=(
     evolve
     fastnlo
     fktable
+    npy
 )
 
 main=master
@@ -53,6 +53,33 @@ pub enum MergeBinError {
         /// Dimension of the bins of the second `BinRemapper`.
         rhs: usize,
     },
+
+    /// Returned by [`Grid::merge_bins_grouped`](super::grid::Grid::merge_bins_grouped) if one of
+    /// the ranges does not contain any bin.
+    #[error("can not merge the empty range of bins {0:?}")]
+    EmptyRange(Range<usize>),
+
+    /// Returned by [`Grid::merge_bins_grouped`](super::grid::Grid::merge_bins_grouped) if two
+    /// ranges overlap or are not given in increasing order.
+    #[error("the ranges of bins {lhs:?} and {rhs:?} overlap or are not in increasing order")]
+    OverlappingRanges {
+        /// The range given first.
+        lhs: Range<usize>,
+        /// The range given after `lhs`.
+        rhs: Range<usize>,
+    },
+
+    /// Returned by [`BinRemapper::merge_dimension`] whenever the dimension that should be merged
+    /// does not exist.
+    #[error(
+        "tried to merge bins in dimension {dimension}, but there are only {dimensions} dimensions"
+    )]
+    InvalidDimension {
+        /// Dimension given to [`BinRemapper::merge_dimension`].
+        dimension: usize,
+        /// Number of dimensions.
+        dimensions: usize,
+    },
 }
 
 /// Structure representing bin limits.
@@ -526,6 +553,134 @@ impl BinRemapper {
         Ok(())
     }
 
+    /// Merges bins along the dimension with index `dimension`. To this end the limits of all bins
+    /// in this dimension are sorted and deduplicated, and each range in `groups` selects
+    /// consecutive limits of this list, which are merged together. Bins are only merged if their
+    /// limits in all other dimensions are the same, which allows, for instance, to rebin one
+    /// dimension of a two-dimensional distribution while keeping the other one. Merged bins take
+    /// the place of the first bin they consist of and their normalizations are added. This method
+    /// returns the new `BinRemapper` together with the index of the new bin for each bin of
+    /// `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `dimension` is not smaller than the number of dimensions, if a range in
+    /// `groups` is outside the available limits or overlaps with another range, or if limits that
+    /// should be merged are not consecutive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a limit is NaN.
+    pub fn merge_dimension(
+        &self,
+        dimension: usize,
+        groups: &[Range<usize>],
+    ) -> Result<(Self, Vec<usize>), MergeBinError> {
+        let dimensions = self.dimensions();
+
+        if dimension >= dimensions {
+            return Err(MergeBinError::InvalidDimension {
+                dimension,
+                dimensions,
+            });
+        }
+
+        let mut intervals: Vec<_> = self
+            .limits
+            .iter()
+            .skip(dimension)
+            .step_by(dimensions)
+            .copied()
+            .collect();
+        intervals.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        intervals.dedup();
+
+        // for each interval the index of the range in `groups` it belongs to
+        let mut interval_groups = vec![None; intervals.len()];
+
+        for (index, range) in groups.iter().enumerate() {
+            if range.end > intervals.len() {
+                return Err(MergeBinError::InvalidRange {
+                    range: range.clone(),
+                    bins: intervals.len(),
+                });
+            }
+
+            for group in &mut interval_groups[range.clone()] {
+                if group.replace(index).is_some() {
+                    return Err(MergeBinError::NonConsecutiveRange(range.clone()));
+                }
+            }
+        }
+
+        // for each new bin the group it belongs to and the indices of the bins it consists of
+        let mut new_bins: Vec<(Option<usize>, Vec<usize>)> = Vec::new();
+        let mut indices = Vec::with_capacity(self.bins());
+
+        for (bin, limits) in self.limits.chunks_exact(dimensions).enumerate() {
+            // UNWRAP: `intervals` contains the limits of all bins
+            let interval = intervals
+                .iter()
+                .position(|interval| *interval == limits[dimension])
+                .unwrap();
+            let group = interval_groups[interval];
+            let other_limits_equal = |other: usize| {
+                self.limits[other * dimensions..(other + 1) * dimensions]
+                    .iter()
+                    .zip(limits)
+                    .enumerate()
+                    .all(|(dim, (lhs, rhs))| (dim == dimension) || (lhs == rhs))
+            };
+
+            if let Some(index) = new_bins.iter().position(|(other_group, bins)| {
+                group.is_some() && (*other_group == group) && other_limits_equal(bins[0])
+            }) {
+                new_bins[index].1.push(bin);
+                indices.push(index);
+            } else {
+                indices.push(new_bins.len());
+                new_bins.push((group, vec![bin]));
+            }
+        }
+
+        let mut normalizations = Vec::with_capacity(new_bins.len());
+        let mut limits = Vec::with_capacity(new_bins.len() * dimensions);
+
+        for (_, bins) in new_bins {
+            let mut merged: Vec<_> = bins
+                .iter()
+                .map(|&bin| self.limits[bin * dimensions + dimension])
+                .collect();
+            merged.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            if let Some((lhs, rhs)) = merged
+                .iter()
+                .tuple_windows()
+                .find(|((_, lhs), (rhs, _))| !approx_eq!(f64, *lhs, *rhs, ulps = 8))
+            {
+                return Err(MergeBinError::NonConsecutiveBins {
+                    lhs: lhs.1,
+                    rhs: rhs.0,
+                });
+            }
+
+            let first = bins[0] * dimensions;
+            let index = limits.len() + dimension;
+            limits.extend_from_slice(&self.limits[first..first + dimensions]);
+            // UNWRAP: every new bin consists of at least one bin
+            limits[index] = (merged[0].0, merged.last().unwrap().1);
+            normalizations.push(bins.iter().map(|&bin| self.normalizations[bin]).sum());
+        }
+
+        Ok((
+            Self {
+                normalizations,
+                limits,
+            },
+            indices,
+        ))
+    }
+
     /// Return the normalization factors for all bins.
     #[must_use]
     pub fn normalizations(&self) -> &[f64] {
@@ -1122,6 +1277,99 @@ mod test {
         assert_eq!(remapper.slices(), [(0, 1)]);
     }
 
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn bin_remapper_merge_dimension() {
+        let remapper = BinRemapper::new(
+            vec![1.0; 6],
+            vec![
+                (0.0, 1.0),
+                (0.0, 1.0),
+                (0.0, 1.0),
+                (1.0, 2.0),
+                (1.0, 2.0),
+                (0.0, 1.0),
+                (1.0, 2.0),
+                (1.0, 2.0),
+                (2.0, 3.0),
+                (0.0, 1.0),
+                (2.0, 3.0),
+                (1.0, 2.0),
+            ],
+        )
+        .unwrap();
+
+        // merge the first two bins of the first dimension, keeping the second dimension
+        let (merged, indices) = remapper.merge_dimension(0, &[0..2]).unwrap();
+        assert_eq!(indices, [0, 1, 0, 1, 2, 3]);
+        assert_eq!(merged.normalizations(), [2.0, 2.0, 1.0, 1.0]);
+        assert_eq!(
+            merged.limits(),
+            [
+                (0.0, 2.0),
+                (0.0, 1.0),
+                (0.0, 2.0),
+                (1.0, 2.0),
+                (2.0, 3.0),
+                (0.0, 1.0),
+                (2.0, 3.0),
+                (1.0, 2.0)
+            ]
+        );
+
+        // merge all bins of the second dimension
+        let (merged, indices) = remapper.merge_dimension(1, &[0..2]).unwrap();
+        assert_eq!(indices, [0, 0, 1, 1, 2, 2]);
+        assert_eq!(merged.normalizations(), [2.0, 2.0, 2.0]);
+        assert_eq!(
+            merged.limits(),
+            [
+                (0.0, 1.0),
+                (0.0, 2.0),
+                (1.0, 2.0),
+                (0.0, 2.0),
+                (2.0, 3.0),
+                (0.0, 2.0)
+            ]
+        );
+
+        assert_eq!(
+            remapper
+                .merge_dimension(2, &[0..2])
+                .unwrap_err()
+                .to_string(),
+            "tried to merge bins in dimension 2, but there are only 2 dimensions"
+        );
+        assert_eq!(
+            remapper
+                .merge_dimension(0, &[2..4])
+                .unwrap_err()
+                .to_string(),
+            "tried to merge bins with indices 2..4, but there are only 3 bins"
+        );
+        assert_eq!(
+            remapper
+                .merge_dimension(0, &[0..2, 1..3])
+                .unwrap_err()
+                .to_string(),
+            "can not merge bins with indices 1..3"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn bin_remapper_merge_dimension_non_consecutive() {
+        let remapper = BinRemapper::new(vec![1.0; 2], vec![(0.0, 1.0), (2.0, 3.0)]).unwrap();
+
+        assert_eq!(
+            remapper
+                .merge_dimension(0, &[0..2])
+                .unwrap_err()
+                .to_string(),
+            "can not merge bins which end at 1 with bins that start at 2"
+        );
+    }
+
     //#[test]
     //#[ignore] // FIXME: there's a bug in the `slices` method
     //#[should_panic]
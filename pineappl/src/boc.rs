@@ -1,10 +1,12 @@
 //! Module containing structures for the 3 dimensions of a [`Grid`]: bins, [`Order`] and channels
 //! (`boc`).
 
+use super::pids::PidBasis;
 use float_cmp::approx_eq;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
 use std::str::FromStr;
 use thiserror::Error;
 
@@ -15,8 +17,8 @@ pub struct ParseOrderError(String);
 
 // TODO: when possible change the types from `u32` to `u8` to change `try_into` to `into`
 
-/// Coupling powers for each grid.
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+/// Coupling powers for each grid. The default value has all exponents set to zero.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct Order {
     /// Exponent of the strong coupling.
     pub alphas: u32,
@@ -32,12 +34,7 @@ impl FromStr for Order {
     type Err = ParseOrderError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut result = Self {
-            alphas: 0,
-            alpha: 0,
-            logxir: 0,
-            logxif: 0,
-        };
+        let mut result = Self::default();
 
         for tuple in s
             .split(|c: char| c.is_ascii_digit())
@@ -76,6 +73,23 @@ impl FromStr for Order {
     }
 }
 
+impl Display for Order {
+    /// Write the exponents in the format accepted by [`Order::from_str`], for example `as1a2lf1`.
+    /// The exponents of the logarithms are only written if they are non-zero.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "as{}a{}", self.alphas, self.alpha)?;
+
+        if self.logxir != 0 {
+            write!(f, "lr{}", self.logxir)?;
+        }
+        if self.logxif != 0 {
+            write!(f, "lf{}", self.logxif)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl Ord for Order {
     fn cmp(&self, other: &Self) -> Ordering {
         // sort leading orders before next-to-leading orders, then the lowest power in alpha, the
@@ -109,6 +123,13 @@ impl Order {
         }
     }
 
+    /// Constructor for an order with the exponents `alphas` and `alpha` of the strong and
+    /// electromagnetic couplings, and without logarithms of the scale factors.
+    #[must_use]
+    pub const fn with_couplings(alphas: u32, alpha: u32) -> Self {
+        Self::new(alphas, alpha, 0, 0)
+    }
+
     /// Return a mask suitable to pass as the `order_mask` parameter of [`Grid::convolve`],
     /// [`Grid::evolve`] or [`Grid::evolve_info`]. The selection of `orders` is controlled using
     /// the `max_as` and `max_al` parameters, for instance setting `max_as = 1` and `max_al = 0`
@@ -259,6 +280,19 @@ impl Order {
     }
 }
 
+/// Kinematic variable of the nodes of a subgrid, see [`Grid::subgrid_nodes`] and
+/// [`Grid::interp_for_kinematic`].
+///
+/// [`Grid::subgrid_nodes`]: super::grid::Grid::subgrid_nodes
+/// [`Grid::interp_for_kinematic`]: super::grid::Grid::interp_for_kinematic
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Kinematics {
+    /// Squared scale. The index `0` denotes the renormalization and `1` the factorization scale.
+    Scale(usize),
+    /// Momentum fraction of the convolution with the given index.
+    X(usize),
+}
+
 /// This structure represents a channel. Each channel consists of a tuple containing in the
 /// following order, the particle ID of the first incoming parton, then the particle ID of the
 /// second parton, and finally a numerical factor that will multiply the result for this specific
@@ -378,10 +412,165 @@ impl Channel {
         &self.entry
     }
 
-    /// Creates a new object with the initial states transposed.
+    /// Returns a key that orders channels by their contents. Channels are compared entry by
+    /// entry, first by the PIDs and then by the factors, which are compared using
+    /// [`f64::total_cmp`]. Since the entries of a channel are always sorted, two channels that are
+    /// equal have the same key. See [`Grid::canonicalize_channels`] for its main use.
+    ///
+    /// [`Grid::canonicalize_channels`]: crate::grid::Grid::canonicalize_channels
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use pineappl::channel;
+    ///
+    /// let mut channels = vec![
+    ///     channel![2, -2, 1.0],
+    ///     channel![1, -1, 1.0; 3, -3, 1.0],
+    ///     channel![1, -1, 0.5; 3, -3, 1.0],
+    /// ];
+    /// channels.sort_by_key(|channel| channel.canonical_key());
+    ///
+    /// assert_eq!(
+    ///     channels,
+    ///     [
+    ///         channel![1, -1, 0.5; 3, -3, 1.0],
+    ///         channel![1, -1, 1.0; 3, -3, 1.0],
+    ///         channel![2, -2, 1.0],
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn canonical_key(&self) -> Vec<(i32, i32, u64)> {
+        self.entry
+            .iter()
+            .map(|&(a, b, factor)| {
+                // map the factors onto integers with the same ordering as `f64::total_cmp`:
+                // negative numbers have all bits flipped, positive ones only the sign bit
+                let bits = factor.to_bits();
+                let bits = if bits >> 63 == 1 {
+                    !bits
+                } else {
+                    bits | (1 << 63)
+                };
+                (a, b, bits)
+            })
+            .collect()
+    }
+
+    /// Returns a LaTeX representation of this channel, which for instance can be used as a label
+    /// in plots or tables. Each entry is written as the product of its particles, which are
+    /// converted with [`PidBasis::to_latex_str`] using `pid_basis`, and the entries are joined with
+    /// `+`. The factors of the entries are not shown. The particles of the convolutions whose
+    /// entries in `convolutions` are `false`, for instance the lepton in DIS, are omitted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use pineappl::channel;
+    /// use pineappl::pids::PidBasis;
+    ///
+    /// let channel = channel![2, -1, 1.0; 4, -3, 1.0];
+    ///
+    /// assert_eq!(
+    ///     channel.to_tex(PidBasis::Pdg, [true, true]),
+    ///     r"\mathrm{u}\bar{\mathrm{d}} + \mathrm{c}\bar{\mathrm{s}}"
+    /// );
+    /// assert_eq!(
+    ///     channel![21, 11, 1.0].to_tex(PidBasis::Pdg, [true, false]),
+    ///     r"\mathrm{g}"
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if a particle of a convolution selected by `convolutions` has no LaTeX
+    /// representation in `pid_basis`.
+    #[must_use]
+    pub fn to_tex(&self, pid_basis: PidBasis, convolutions: [bool; 2]) -> String {
+        self.entry
+            .iter()
+            .map(|&(a, b, _)| {
+                format!(
+                    "{}{}",
+                    if convolutions[0] {
+                        pid_basis.to_latex_str(a)
+                    } else {
+                        ""
+                    },
+                    if convolutions[1] {
+                        pid_basis.to_latex_str(b)
+                    } else {
+                        ""
+                    }
+                )
+            })
+            .join(" + ")
+    }
+
+    /// Creates a new object with the initial states of the convolutions with indices `a` and `b`
+    /// exchanged. If `a` and `b` are the same index, the channel is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use pineappl::channel;
+    ///
+    /// let channel = channel![2, -2, 1.0; 4, -4, 0.5];
+    ///
+    /// assert_eq!(channel.transpose(0, 1), channel![-2, 2, 1.0; -4, 4, 0.5]);
+    /// assert_eq!(channel.transpose(1, 1), channel);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` isn't a valid convolution index, which is either `0` or `1`.
+    #[must_use]
+    pub fn transpose(&self, a: usize, b: usize) -> Self {
+        assert!(a < 2 && b < 2, "convolution indices must be either 0 or 1");
+
+        if a == b {
+            self.clone()
+        } else {
+            Self::new(self.entry.iter().map(|(a, b, c)| (*b, *a, *c)).collect())
+        }
+    }
+
+    /// Returns `true` if `other` is this channel with the initial states of the convolutions with
+    /// indices `a` and `b` exchanged, see [`Channel::transpose`]. The ordering of the entries
+    /// doesn't matter, but the factors of each pair of PIDs must agree; two channels that are
+    /// transposed only up to a common factor are not considered transposes of each other, use
+    /// [`Channel::common_factor`] together with [`Channel::transpose`] to detect them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use pineappl::channel;
+    ///
+    /// let ch1 = channel![2, -2, 1.0; 4, -4, 0.5];
+    /// let ch2 = channel![-4, 4, 0.5; -2, 2, 1.0];
+    /// let ch3 = channel![-4, 4, 1.0; -2, 2, 2.0];
+    ///
+    /// // the ordering of the entries doesn't matter
+    /// assert!(ch1.is_transpose_of(&ch2, 0, 1));
+    /// // the factors must be the same
+    /// assert!(!ch1.is_transpose_of(&ch3, 0, 1));
+    /// assert_eq!(ch1.transpose(0, 1).common_factor(&ch3), Some(0.5));
+    /// // a channel with identical initial states is its own transpose
+    /// assert!(channel![21, 21, 1.0].is_transpose_of(&channel![21, 21, 1.0], 0, 1));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` isn't a valid convolution index, which is either `0` or `1`.
     #[must_use]
-    pub fn transpose(&self) -> Self {
-        Self::new(self.entry.iter().map(|(a, b, c)| (*b, *a, *c)).collect())
+    pub fn is_transpose_of(&self, other: &Self, a: usize, b: usize) -> bool {
+        let transposed = self.transpose(a, b);
+
+        (transposed.entry.len() == other.entry.len())
+            && transposed.entry.iter().zip(&other.entry).all(|(lhs, rhs)| {
+                (lhs.0 == rhs.0) && (lhs.1 == rhs.1) && approx_eq!(f64, lhs.2, rhs.2, ulps = 4)
+            })
     }
 
     /// If `other` is the same channel when only comparing PIDs and neglecting the factors, return
@@ -536,6 +725,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn order_display() {
+        assert_eq!(Order::default().to_string(), "as0a0");
+        assert_eq!(Order::with_couplings(1, 2).to_string(), "as1a2");
+        assert_eq!(Order::new(1, 2, 1, 0).to_string(), "as1a2lr1");
+        assert_eq!(Order::new(0, 3, 2, 1).to_string(), "as0a3lr2lf1");
+
+        for order in [Order::new(1, 2, 0, 1), Order::new(2, 0, 1, 1)] {
+            assert_eq!(order.to_string().parse(), Ok(order));
+        }
+    }
+
     #[test]
     fn order_cmp() {
         let mut orders = [
@@ -731,6 +932,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn channel_transpose() {
+        let channel = channel![2, 1, 1.0; -2, 21, 2.0; 21, 21, 3.0];
+
+        assert_eq!(
+            channel.transpose(0, 1),
+            channel![1, 2, 1.0; 21, -2, 2.0; 21, 21, 3.0]
+        );
+        assert_eq!(channel.transpose(1, 0), channel.transpose(0, 1));
+        assert_eq!(channel.transpose(0, 0), channel);
+        assert_eq!(channel.transpose(0, 1).transpose(0, 1), channel);
+
+        assert!(channel.is_transpose_of(&channel.transpose(0, 1), 0, 1));
+        assert!(channel.transpose(0, 1).is_transpose_of(&channel, 1, 0));
+        assert!(channel.is_transpose_of(&channel, 0, 0));
+        assert!(!channel.is_transpose_of(&channel, 0, 1));
+        assert!(!channel.is_transpose_of(&channel![1, 2, 1.0; 21, -2, 2.0], 0, 1));
+        assert!(!channel.is_transpose_of(&channel![1, 2, 2.0; 21, -2, 4.0; 21, 21, 6.0], 0, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "convolution indices must be either 0 or 1")]
+    fn channel_transpose_invalid_index() {
+        let _ = channel![2, 1, 1.0].transpose(0, 2);
+    }
+
+    #[test]
+    fn channel_to_tex() {
+        let channel = channel![100, 21, 1.0; 103, 103, 0.5];
+
+        assert_eq!(
+            channel.to_tex(PidBasis::Evol, [true, true]),
+            r"\Sigma\mathrm{g} + \mathrm{T}_3\mathrm{T}_3"
+        );
+        assert_eq!(
+            channel.to_tex(PidBasis::Evol, [false, true]),
+            r"\mathrm{g} + \mathrm{T}_3"
+        );
+        assert_eq!(
+            channel![22, 11, 1.0].to_tex(PidBasis::Pdg, [true, false]),
+            r"\gamma"
+        );
+    }
+
     #[test]
     fn channel_from_str() {
         assert_eq!(
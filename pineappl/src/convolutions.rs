@@ -1,9 +1,16 @@
 //! Module for everything related to luminosity functions.
 
-use super::grid::Grid;
+use super::evolution::{AlphasTable, EVOLVE_INFO_TOL_ULPS};
+use super::grid::{Grid, GridError};
 use super::pids;
 use super::subgrid::{Mu2, Subgrid};
+use float_cmp::approx_eq;
+use ndarray::Array3;
 use rustc_hash::FxHashMap;
+use std::collections::hash_map::Entry;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
 
 enum Pdfs<'a> {
     Two {
@@ -16,6 +23,10 @@ enum Pdfs<'a> {
         xfx: &'a mut dyn FnMut(i32, f64, f64) -> f64,
         xfx_cache: FxHashMap<(i32, usize, usize), f64>,
     },
+    Tables {
+        xfx1: TableIndex<'a>,
+        xfx2: TableIndex<'a>,
+    },
 }
 
 impl<'a> Pdfs<'a> {
@@ -30,15 +41,104 @@ impl<'a> Pdfs<'a> {
                 xfx1_cache.clear();
                 xfx2_cache.clear();
             }
+            Self::Tables { xfx1, xfx2 } => {
+                xfx1.clear();
+                xfx2.clear();
+            }
+        }
+    }
+}
+
+// an `XfxTable` together with the positions of the nodes of a `LumiCache` in it, which are
+// determined once in `LumiCache::setup` so that looking up a value doesn't search the nodes
+struct TableIndex<'a> {
+    table: &'a XfxTable,
+    ipid: FxHashMap<i32, usize>,
+    ix: Vec<usize>,
+    ifac: Vec<usize>,
+}
+
+impl<'a> TableIndex<'a> {
+    fn new(table: &'a XfxTable) -> Self {
+        Self {
+            table,
+            ipid: table
+                .pids
+                .iter()
+                .enumerate()
+                .map(|(index, &pid)| (pid, index))
+                .collect(),
+            ix: Vec::new(),
+            ifac: Vec::new(),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.ix.clear();
+        self.ifac.clear();
+    }
+
+    fn index(&mut self, x_grid: &[f64], muf2_grid: &[f64]) -> Result<(), GridError> {
+        self.ix = x_grid
+            .iter()
+            .map(|&x| {
+                node_index(&self.table.x, x)
+                    .ok_or_else(|| GridError::IncompleteTables(format!("PDF at x = {x}")))
+            })
+            .collect::<Result<_, _>>()?;
+        self.ifac = muf2_grid
+            .iter()
+            .map(|&fac| {
+                node_index(&self.table.fac1, fac)
+                    .ok_or_else(|| GridError::IncompleteTables(format!("PDF at Q2 = {fac}")))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(())
+    }
+
+    fn check_pid(&self, pid: i32) -> Result<(), GridError> {
+        if self.ipid.contains_key(&pid) {
+            Ok(())
+        } else {
+            Err(GridError::IncompleteTables(format!(
+                "PDF of particle {pid}"
+            )))
         }
     }
+
+    fn get(&self, pid: i32, ix: usize, imuf2: usize) -> f64 {
+        self.table.xfx[[self.ipid[&pid], self.ix[ix], self.ifac[imuf2]]]
+    }
+}
+
+enum Alphas<'a> {
+    Fun(&'a mut dyn FnMut(f64) -> f64),
+    Table(&'a AlphasTable),
+}
+
+/// Statistics of a [`LumiCache`], which are returned by [`LumiCache::stats`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct LumiCacheStats {
+    /// Number of PDF values that were requested and found in the cache.
+    pub hits: u64,
+    /// Number of PDF values that were requested but not found in the cache. Each miss evaluates the
+    /// PDF once.
+    pub misses: u64,
+    /// Number of evaluations of the strong coupling.
+    pub alphas_evaluations: u64,
 }
 
 /// A cache for evaluating PDFs. Methods like [`Grid::convolve`] accept instances of this `struct`
 /// instead of the PDFs themselves.
+///
+/// The cached values are kept between convolutions as long as the nodes of the grids don't change.
+/// The PDFs and the strong coupling can be replaced independently of each other with
+/// [`LumiCache::set_pdfs`] and [`LumiCache::set_alphas`], which keeps the cached values of the
+/// other one.
 pub struct LumiCache<'a> {
     pdfs: Pdfs<'a>,
-    alphas: &'a mut dyn FnMut(f64) -> f64,
+    alphas: Alphas<'a>,
     alphas_cache: Vec<f64>,
     mur2_grid: Vec<f64>,
     muf2_grid: Vec<f64>,
@@ -51,6 +151,7 @@ pub struct LumiCache<'a> {
     pdg2: i32,
     cc1: i32,
     cc2: i32,
+    stats: LumiCacheStats,
 }
 
 impl<'a> LumiCache<'a> {
@@ -74,7 +175,7 @@ impl<'a> LumiCache<'a> {
                 xfx2,
                 xfx2_cache: FxHashMap::default(),
             },
-            alphas,
+            alphas: Alphas::Fun(alphas),
             alphas_cache: vec![],
             mur2_grid: vec![],
             muf2_grid: vec![],
@@ -87,6 +188,7 @@ impl<'a> LumiCache<'a> {
             pdg2,
             cc1: 0,
             cc2: 0,
+            stats: LumiCacheStats::default(),
         }
     }
 
@@ -106,7 +208,7 @@ impl<'a> LumiCache<'a> {
                 xfx,
                 xfx_cache: FxHashMap::default(),
             },
-            alphas,
+            alphas: Alphas::Fun(alphas),
             alphas_cache: vec![],
             mur2_grid: vec![],
             muf2_grid: vec![],
@@ -119,43 +221,92 @@ impl<'a> LumiCache<'a> {
             pdg2: pdg,
             cc1: 0,
             cc2: 0,
+            stats: LumiCacheStats::default(),
         }
     }
 
-    pub(crate) fn setup(&mut self, grid: &Grid, xi: &[(f64, f64)]) -> Result<(), ()> {
+    /// Construct a luminosity cache that looks up the PDFs in the tables `xfx` and the strong
+    /// coupling in the table `alphas` instead of evaluating functions. If `xfx` has a single table
+    /// it is used for both initial states, otherwise the first table is used for the first and the
+    /// second table for the second initial state. The positions of the nodes of a grid in the
+    /// tables are determined once when the cache is set up for the grid, and values that are
+    /// missing in the tables are reported as errors at this point.
+    pub(crate) fn with_tables(
+        xfx: &'a [XfxTable],
+        alphas: &'a AlphasTable,
+    ) -> Result<Self, GridError> {
+        let (xfx1, xfx2) = match xfx {
+            [xfx] => (xfx, xfx),
+            [xfx1, xfx2] => (xfx1, xfx2),
+            _ => return Err(GridError::TableCountMismatch { tables: xfx.len() }),
+        };
+
+        Ok(Self {
+            pdfs: Pdfs::Tables {
+                xfx1: TableIndex::new(xfx1),
+                xfx2: TableIndex::new(xfx2),
+            },
+            alphas: Alphas::Table(alphas),
+            alphas_cache: vec![],
+            mur2_grid: vec![],
+            muf2_grid: vec![],
+            x_grid: vec![],
+            imur2: Vec::new(),
+            imuf2: Vec::new(),
+            ix1: Vec::new(),
+            ix2: Vec::new(),
+            pdg1: xfx1.pdg,
+            pdg2: xfx2.pdg,
+            cc1: 0,
+            cc2: 0,
+            stats: LumiCacheStats::default(),
+        })
+    }
+
+    pub(crate) fn setup(&mut self, grid: &Grid, xi: &[(f64, f64)]) -> Result<(), GridError> {
         let convolutions = grid.convolutions();
 
         // TODO: the following code only works with exactly two convolutions
         assert_eq!(convolutions.len(), 2);
 
         // do we have to charge-conjugate the initial states?
-        let cc1 = if let Some(pid) = convolutions[0].pid() {
-            if self.pdg1 == pid {
-                1
-            } else if self.pdg1 == pids::charge_conjugate_pdg_pid(pid) {
-                -1
-            } else {
-                // TODO: return a proper error
-                return Err(());
-            }
-        } else {
-            0
-        };
-        let cc2 = if let Some(pid) = convolutions[1].pid() {
-            if self.pdg2 == pid {
-                1
-            } else if self.pdg2 == pids::charge_conjugate_pdg_pid(pid) {
-                -1
-            } else {
-                // TODO: return a proper error
-                return Err(());
+        let mut cc = [0; 2];
+
+        for (index, (convolution, pdg)) in convolutions
+            .into_iter()
+            .zip([self.pdg1, self.pdg2])
+            .enumerate()
+        {
+            if let Some(pid) = convolution.pid() {
+                cc[index] = if pdg == pid {
+                    1
+                } else if pdg == pids::charge_conjugate_pdg_pid(pid) {
+                    -1
+                } else {
+                    return Err(GridError::HadronMismatch {
+                        index,
+                        pdg,
+                        convolution,
+                    });
+                };
             }
-        } else {
-            0
-        };
+        }
+
+        let [cc1, cc2] = cc;
 
-        // TODO: try to avoid calling clear
-        self.clear();
+        if let Pdfs::Tables { xfx1, xfx2 } = &self.pdfs {
+            for channel in grid.pdg_channels().iter() {
+                for &(a, b, _) in channel.entry() {
+                    for (table, cc, pid) in [(xfx1, cc1, a), (xfx2, cc2, b)] {
+                        match cc {
+                            1 => table.check_pid(pid)?,
+                            -1 => table.check_pid(pids::charge_conjugate_pdg_pid(pid))?,
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
 
         let mut x_grid: Vec<_> = grid
             .subgrids()
@@ -214,7 +365,34 @@ impl<'a> LumiCache<'a> {
         muf2_grid.sort_by(|a, b| a.partial_cmp(b).unwrap_or_else(|| unreachable!()));
         muf2_grid.dedup();
 
-        self.alphas_cache = mur2_grid.iter().map(|&mur2| (self.alphas)(mur2)).collect();
+        // the cached PDF values are indexed with the positions in `x_grid` and `muf2_grid`, so
+        // they stay valid as long as both grids don't change
+        if x_grid != self.x_grid || muf2_grid != self.muf2_grid {
+            self.pdfs.clear();
+
+            if let Pdfs::Tables { xfx1, xfx2 } = &mut self.pdfs {
+                xfx1.index(&x_grid, &muf2_grid)?;
+                xfx2.index(&x_grid, &muf2_grid)?;
+            }
+        }
+
+        // `set_alphas` clears the cache of the strong coupling, which then must be re-evaluated
+        if mur2_grid != self.mur2_grid || self.alphas_cache.len() != mur2_grid.len() {
+            self.alphas_cache = match &mut self.alphas {
+                Alphas::Fun(alphas) => {
+                    self.stats.alphas_evaluations += u64::try_from(mur2_grid.len()).unwrap();
+                    mur2_grid.iter().map(|&mur2| alphas(mur2)).collect()
+                }
+                Alphas::Table(table) => mur2_grid
+                    .iter()
+                    .map(|&mur2| {
+                        table.get(mur2).ok_or_else(|| {
+                            GridError::IncompleteTables(format!("strong coupling at Q2 = {mur2}"))
+                        })
+                    })
+                    .collect::<Result<_, _>>()?,
+            };
+        }
 
         self.mur2_grid = mur2_grid;
         self.muf2_grid = muf2_grid;
@@ -244,10 +422,18 @@ impl<'a> LumiCache<'a> {
                 Pdfs::Two {
                     xfx1, xfx1_cache, ..
                 } => (xfx1, xfx1_cache),
+                Pdfs::Tables { xfx1, .. } => return xfx1.get(pid, ix1, imuf2),
             };
-            *xfx_cache
-                .entry((pid, ix1, imuf2))
-                .or_insert_with(|| xfx(pid, x, muf2))
+            match xfx_cache.entry((pid, ix1, imuf2)) {
+                Entry::Occupied(entry) => {
+                    self.stats.hits += 1;
+                    *entry.get()
+                }
+                Entry::Vacant(entry) => {
+                    self.stats.misses += 1;
+                    *entry.insert(xfx(pid, x, muf2))
+                }
+            }
         }
     }
 
@@ -270,10 +456,18 @@ impl<'a> LumiCache<'a> {
                 Pdfs::Two {
                     xfx2, xfx2_cache, ..
                 } => (xfx2, xfx2_cache),
+                Pdfs::Tables { xfx2, .. } => return xfx2.get(pid, ix2, imuf2),
             };
-            *xfx_cache
-                .entry((pid, ix2, imuf2))
-                .or_insert_with(|| xfx(pid, x, muf2))
+            match xfx_cache.entry((pid, ix2, imuf2)) {
+                Entry::Occupied(entry) => {
+                    self.stats.hits += 1;
+                    *entry.get()
+                }
+                Entry::Vacant(entry) => {
+                    self.stats.misses += 1;
+                    *entry.insert(xfx(pid, x, muf2))
+                }
+            }
         }
     }
 
@@ -284,6 +478,53 @@ impl<'a> LumiCache<'a> {
         self.alphas_cache[self.imur2[imu2]]
     }
 
+    /// Replace the function that evaluates the strong coupling with `alphas`. The cached PDF values
+    /// are kept, so that the next convolution only re-evaluates the strong coupling.
+    pub fn set_alphas(&mut self, alphas: &'a mut dyn FnMut(f64) -> f64) {
+        self.alphas = Alphas::Fun(alphas);
+        self.alphas_cache.clear();
+    }
+
+    /// Replace the PDFs with a single PDF `xfx` for the hadron `pdg`, see [`LumiCache::with_one`].
+    /// The cached values of the strong coupling are kept, so that the next convolution only
+    /// re-evaluates the PDF.
+    pub fn set_pdf(&mut self, pdg: i32, xfx: &'a mut dyn FnMut(i32, f64, f64) -> f64) {
+        self.pdfs = Pdfs::One {
+            xfx,
+            xfx_cache: FxHashMap::default(),
+        };
+        self.pdg1 = pdg;
+        self.pdg2 = pdg;
+    }
+
+    /// Replace the PDFs with `xfx1` and `xfx2` for the hadrons `pdg1` and `pdg2`, see
+    /// [`LumiCache::with_two`]. The cached values of the strong coupling are kept, so that the next
+    /// convolution only re-evaluates the PDFs.
+    pub fn set_pdfs(
+        &mut self,
+        pdg1: i32,
+        xfx1: &'a mut dyn FnMut(i32, f64, f64) -> f64,
+        pdg2: i32,
+        xfx2: &'a mut dyn FnMut(i32, f64, f64) -> f64,
+    ) {
+        self.pdfs = Pdfs::Two {
+            xfx1,
+            xfx1_cache: FxHashMap::default(),
+            xfx2,
+            xfx2_cache: FxHashMap::default(),
+        };
+        self.pdg1 = pdg1;
+        self.pdg2 = pdg2;
+    }
+
+    /// Return the statistics of this cache, which are accumulated over all convolutions since its
+    /// construction. Clearing the cache does not reset them. Requests for the 'PDF' of an initial
+    /// state that is not hadronic and lookups in tables are not counted.
+    #[must_use]
+    pub const fn stats(&self) -> LumiCacheStats {
+        self.stats
+    }
+
     /// Clears the cache.
     pub fn clear(&mut self) {
         self.alphas_cache.clear();
@@ -342,6 +583,115 @@ impl<'a> LumiCache<'a> {
     }
 }
 
+/// The PDF values `x f(x, Q2)` of a hadron tabulated at the nodes of a `Grid`, which
+/// [`Grid::convolve_with_tables`] uses instead of evaluating the PDF for each node.
+pub struct XfxTable {
+    /// PDG MC ID of the hadron.
+    pub pdg: i32,
+    /// PDG MC IDs of the partons, sorted in ascending order.
+    pub pids: Vec<i32>,
+    /// Momentum fractions, sorted in ascending order.
+    pub x: Vec<f64>,
+    /// Squared factorization scales, sorted in ascending order.
+    pub fac1: Vec<f64>,
+    /// PDF values, where the first, second and third index corresponds to the order given in
+    /// [`pids`](Self::pids), [`x`](Self::x) and [`fac1`](Self::fac1), respectively.
+    pub xfx: Array3<f64>,
+}
+
+impl XfxTable {
+    /// Create an `XfxTable` for `grid` and the hadron `pdg`, varying the factorization scale by
+    /// the second factor of each pair in `xi`, for the PDF given by `xfx`. The arguments of `xfx`
+    /// must be the particle ID, the momentum fraction and the squared factorization scale. The
+    /// table contains the partons of all channels of `grid`, and their anti-partons.
+    pub fn from_grid(
+        grid: &Grid,
+        pdg: i32,
+        xi: &[(f64, f64)],
+        xfx: &dyn Fn(i32, f64, f64) -> f64,
+    ) -> Self {
+        let mut pids: Vec<_> = grid
+            .pdg_channels()
+            .iter()
+            .flat_map(|channel| {
+                channel
+                    .entry()
+                    .iter()
+                    .flat_map(|&(a, b, _)| [a, b])
+                    .collect::<Vec<_>>()
+            })
+            .flat_map(|pid| [pid, pids::charge_conjugate_pdg_pid(pid)])
+            .collect();
+        pids.sort_unstable();
+        pids.dedup();
+
+        let subgrids = grid.subgrids();
+        let subgrids: Vec<_> = subgrids
+            .iter()
+            .filter(|subgrid| !subgrid.is_empty())
+            .collect();
+
+        let mut x: Vec<_> = subgrids
+            .iter()
+            .flat_map(|subgrid| {
+                let mut vec = subgrid.x1_grid().into_owned();
+                vec.extend_from_slice(&subgrid.x2_grid());
+                vec
+            })
+            .collect();
+        x.sort_by(f64::total_cmp);
+        x.dedup();
+
+        let mut fac1: Vec<_> = subgrids
+            .iter()
+            .flat_map(|subgrid| subgrid.mu2_grid().into_owned())
+            .flat_map(|Mu2 { fac, .. }| xi.iter().map(move |(_, xif)| xif * xif * fac))
+            .collect();
+        fac1.sort_by(f64::total_cmp);
+        fac1.dedup();
+
+        let xfx = Array3::from_shape_fn((pids.len(), x.len(), fac1.len()), |(i, j, k)| {
+            xfx(pids[i], x[j], fac1[k])
+        });
+
+        Self {
+            pdg,
+            pids,
+            x,
+            fac1,
+            xfx,
+        }
+    }
+
+    /// Return the tabulated value for the parton `pid` at the momentum fraction `x` and the
+    /// squared factorization scale `fac`, or `None` if the table doesn't contain it. Nodes that
+    /// differ only by rounding are considered the same.
+    #[must_use]
+    pub fn get(&self, pid: i32, x: f64, fac: f64) -> Option<f64> {
+        let ipid = self.pids.binary_search(&pid).ok()?;
+        let ix = node_index(&self.x, x)?;
+        let ifac = node_index(&self.fac1, fac)?;
+
+        Some(self.xfx[[ipid, ix, ifac]])
+    }
+}
+
+/// Return the index of the node in the sorted slice `nodes` that is equal to `value` up to
+/// rounding.
+pub(crate) fn node_index(nodes: &[f64], value: f64) -> Option<usize> {
+    // only the neighbours of the insertion point can be equal to `value`
+    let index = nodes.partition_point(|&node| node < value);
+
+    [index.checked_sub(1), Some(index)]
+        .into_iter()
+        .flatten()
+        .find(|&index| {
+            nodes
+                .get(index)
+                .is_some_and(|&node| approx_eq!(f64, node, value, ulps = EVOLVE_INFO_TOL_ULPS))
+        })
+}
+
 /// Data type that indentifies different types of convolutions.
 #[derive(Debug, Eq, PartialEq)]
 pub enum Convolution {
@@ -386,9 +736,61 @@ impl Convolution {
     }
 }
 
+/// Error returned by [`Convolution::from_str`] when passed with an unknown argument.
+#[derive(Debug, Eq, Error, PartialEq)]
+#[error("unknown convolution '{convolution}', valid values are 'none', 'unpolpdf', 'polpdf', 'unpolff' and 'polff', optionally followed by ':<PID>'")]
+pub struct UnknownConvolution {
+    convolution: String,
+}
+
+impl Display for Convolution {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::UnpolPDF(pid) => write!(f, "unpolpdf:{pid}"),
+            Self::PolPDF(pid) => write!(f, "polpdf:{pid}"),
+            Self::UnpolFF(pid) => write!(f, "unpolff:{pid}"),
+            Self::PolFF(pid) => write!(f, "polff:{pid}"),
+        }
+    }
+}
+
+impl FromStr for Convolution {
+    type Err = UnknownConvolution;
+
+    /// Parse a convolution type, which is one of `none`, `unpolpdf`, `polpdf`, `unpolff` and
+    /// `polff`, ignoring the case. Except for `none` the type can be followed by a colon and the
+    /// PDG MC ID of the hadron, for instance `unpolpdf:-2212`; if it is missing a proton is
+    /// assumed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || UnknownConvolution {
+            convolution: s.to_owned(),
+        };
+
+        let (type_, pid) = match s.split_once(':') {
+            Some((type_, pid)) => (type_, Some(pid.parse().map_err(|_| error())?)),
+            None => (s, None),
+        };
+
+        match (type_.to_ascii_lowercase().as_str(), pid) {
+            ("none", None) => Ok(Self::None),
+            ("unpolpdf", pid) => Ok(Self::UnpolPDF(pid.unwrap_or(2212))),
+            ("polpdf", pid) => Ok(Self::PolPDF(pid.unwrap_or(2212))),
+            ("unpolff", pid) => Ok(Self::UnpolFF(pid.unwrap_or(2212))),
+            ("polff", pid) => Ok(Self::PolFF(pid.unwrap_or(2212))),
+            _ => Err(error()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::boc::Order;
+    use crate::channel;
+    use crate::grid::Grid;
+    use crate::import_only_subgrid::ImportOnlySubgridV2;
+    use crate::subgrid::SubgridParams;
 
     #[test]
     fn convolution_charge_conjugate() {
@@ -419,4 +821,89 @@ mod tests {
         assert_eq!(Convolution::UnpolFF(2212).pid(), Some(2212));
         assert_eq!(Convolution::PolFF(2212).pid(), Some(2212));
     }
+
+    #[test]
+    fn convolution_display_from_str() {
+        for convolution in [
+            Convolution::None,
+            Convolution::UnpolPDF(2212),
+            Convolution::PolPDF(-2212),
+            Convolution::UnpolFF(211),
+            Convolution::PolFF(-211),
+        ] {
+            assert_eq!(
+                convolution.to_string().parse::<Convolution>().unwrap(),
+                convolution
+            );
+        }
+
+        assert_eq!(Convolution::UnpolPDF(2212).to_string(), "unpolpdf:2212");
+        assert_eq!("unpolff".parse(), Ok(Convolution::UnpolFF(2212)));
+        assert_eq!("PolPDF:-2212".parse(), Ok(Convolution::PolPDF(-2212)));
+        assert_eq!(
+            "none:2212".parse::<Convolution>().unwrap_err().to_string(),
+            "unknown convolution 'none:2212', valid values are 'none', 'unpolpdf', 'polpdf', \
+             'unpolff' and 'polff', optionally followed by ':<PID>'"
+        );
+        assert!("unpolpdf:proton".parse::<Convolution>().is_err());
+        assert!("pdf".parse::<Convolution>().is_err());
+    }
+    #[test]
+    fn node_index_sorted_nodes() {
+        let nodes = [1e-5, 1e-3, 0.1, 0.5, 1.0];
+
+        assert_eq!(node_index(&nodes, 1e-5), Some(0));
+        assert_eq!(node_index(&nodes, 0.5), Some(3));
+        assert_eq!(node_index(&nodes, 1.0), Some(4));
+        // values that differ only by rounding are found on both sides of the node
+        assert_eq!(node_index(&nodes, 0.1 + f64::EPSILON / 16.0), Some(2));
+        assert_eq!(node_index(&nodes, 0.1 - f64::EPSILON / 16.0), Some(2));
+        assert_eq!(node_index(&nodes, 0.2), None);
+        assert_eq!(node_index(&nodes, 2.0), None);
+        assert_eq!(node_index(&[], 1.0), None);
+    }
+
+    #[test]
+    fn lumi_cache_stats() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0; 4, 4, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        grid.set_subgrid(0, 0, 0, ImportOnlySubgridV2::single_node(1.0, 0.5).into());
+
+        let mut xfx = |_, x, _| x;
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        assert_eq!(lumi_cache.stats(), LumiCacheStats::default());
+
+        grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+
+        // both initial states share the same PDF and x value, so the second one is cached
+        assert_eq!(
+            lumi_cache.stats(),
+            LumiCacheStats {
+                hits: 2,
+                misses: 2,
+                alphas_evaluations: 1,
+            }
+        );
+
+        // the statistics accumulate; the factorization scales and `x` values don't change, so
+        // all PDF values are cached from the previous convolution, but the new renormalization
+        // scale requires re-evaluating the strong coupling
+        grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0), (2.0, 1.0)]);
+
+        assert_eq!(
+            lumi_cache.stats(),
+            LumiCacheStats {
+                hits: 10,
+                misses: 2,
+                alphas_evaluations: 3,
+            }
+        );
+    }
 }
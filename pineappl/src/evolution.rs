@@ -2,7 +2,7 @@
 
 use super::boc::{Channel, Order};
 use super::channel;
-use super::convolutions::Convolution;
+use super::convolutions::{self, Convolution};
 use super::grid::{Grid, GridError};
 use super::import_only_subgrid::ImportOnlySubgridV2;
 use super::pids::PidBasis;
@@ -160,9 +160,18 @@ impl AlphasTable {
 
         Self { ren1, alphas }
     }
+
+    /// Return the strong coupling for the squared renormalization scale `ren`, or `None` if the
+    /// table doesn't contain it. Scales that differ only by rounding are considered the same. The
+    /// scales in [`ren1`](Self::ren1) must be sorted in ascending order, as they are by
+    /// [`AlphasTable::from_grid`].
+    #[must_use]
+    pub fn get(&self, ren: f64) -> Option<f64> {
+        convolutions::node_index(&self.ren1, ren).map(|index| self.alphas[index])
+    }
 }
 
-fn gluon_has_pid_zero(grid: &Grid) -> bool {
+pub(crate) fn gluon_has_pid_zero(grid: &Grid) -> bool {
     // if there are any PID zero particles ...
     grid.channels()
         .iter()
@@ -268,6 +277,43 @@ fn operator_slices(
 
 type X1aX1bOp2Tuple = (Vec<Vec<f64>>, Option<Array2<f64>>);
 
+/// For each bin and channel of `grid`, the `x1` values of both convolutions and the subgrids of
+/// all orders summed at the squared factorization scale `fac1`, or `None` if they're zero. This
+/// only depends on the grid, so it can be shared by the operators of several starting scales.
+pub(crate) type GridSlice = Array2<X1aX1bOp2Tuple>;
+
+pub(crate) fn grid_slice(
+    grid: &Grid,
+    fac1: f64,
+    order_mask: &[bool],
+    xi: (f64, f64),
+    alphas_table: &AlphasTable,
+) -> Result<GridSlice, GridError> {
+    let slices = grid
+        .subgrids()
+        .axis_iter(Axis(1))
+        .flat_map(|subgrids_oc| {
+            subgrids_oc
+                .axis_iter(Axis(1))
+                .map(|subgrids_o| {
+                    ndarray_from_subgrid_orders_slice(
+                        fac1,
+                        &subgrids_o,
+                        grid.orders(),
+                        order_mask,
+                        xi,
+                        alphas_table,
+                    )
+                })
+                // the iterator borrows `subgrids_oc`, which is a temporary
+                .collect::<Vec<_>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // UNWRAP: there's exactly one slice for each bin and channel
+    Ok(Array2::from_shape_vec((grid.bin_info().bins(), grid.channels().len()), slices).unwrap())
+}
+
 fn ndarray_from_subgrid_orders_slice(
     fac1: f64,
     subgrids: &ArrayView1<SubgridEnum>,
@@ -390,11 +436,9 @@ pub(crate) fn evolve_slice_with_one(
     grid: &Grid,
     operator: &ArrayView4<f64>,
     info: &OperatorSliceInfo,
-    order_mask: &[bool],
-    xi: (f64, f64),
-    alphas_table: &AlphasTable,
+    grid_slice: &GridSlice,
+    gluon_has_pid_zero: bool,
 ) -> Result<(Array3<SubgridEnum>, Vec<Channel>), GridError> {
-    let gluon_has_pid_zero = gluon_has_pid_zero(grid);
     let has_pdf1 = grid.convolutions()[0] != Convolution::None;
 
     let (pid_indices, pids) = pid_slices(operator, info, gluon_has_pid_zero, &|pid| {
@@ -411,25 +455,16 @@ pub(crate) fn evolve_slice_with_one(
     let mut last_x1 = Vec::new();
     let mut ops = Vec::new();
 
-    for subgrids_ol in grid.subgrids().axis_iter(Axis(1)) {
+    for slices_c in grid_slice.outer_iter() {
         let mut tables = vec![Array1::zeros(info.x0.len()); channels0.len()];
 
-        for (subgrids_o, channel1) in subgrids_ol.axis_iter(Axis(1)).zip(grid.channels()) {
-            let (mut x1, array) = ndarray_from_subgrid_orders_slice(
-                info.fac1,
-                &subgrids_o,
-                grid.orders(),
-                order_mask,
-                xi,
-                alphas_table,
-            )?;
-
+        for ((x1, array), channel1) in slices_c.iter().zip(grid.channels()) {
             // skip over zero arrays to speed up evolution and avoid problems with NaNs
             let Some(array) = array else {
                 continue;
             };
 
-            let x1 = if has_pdf1 { x1.remove(0) } else { x1.remove(1) };
+            let x1 = &x1[usize::from(!has_pdf1)];
 
             if x1.is_empty() {
                 continue;
@@ -441,8 +476,8 @@ pub(crate) fn evolve_slice_with_one(
                     .zip(x1.iter())
                     .any(|(&lhs, &rhs)| !approx_eq!(f64, lhs, rhs, ulps = EVOLUTION_TOL_ULPS))
             {
-                ops = operator_slices(operator, info, &pid_indices, &x1)?;
-                last_x1 = x1;
+                ops = operator_slices(operator, info, &pid_indices, x1)?;
+                last_x1.clone_from(x1);
             }
 
             for (&pid1, &factor) in
@@ -519,12 +554,9 @@ pub(crate) fn evolve_slice_with_two(
     grid: &Grid,
     operator: &ArrayView4<f64>,
     info: &OperatorSliceInfo,
-    order_mask: &[bool],
-    xi: (f64, f64),
-    alphas_table: &AlphasTable,
+    grid_slice: &GridSlice,
+    gluon_has_pid_zero: bool,
 ) -> Result<(Array3<SubgridEnum>, Vec<Channel>), GridError> {
-    let gluon_has_pid_zero = gluon_has_pid_zero(grid);
-
     // TODO: generalize by iterating up to `n`
     let (pid_indices, pids01): (Vec<_>, Vec<_>) = (0..2)
         .map(|d| {
@@ -559,19 +591,10 @@ pub(crate) fn evolve_slice_with_two(
     let mut last_x1 = vec![Vec::new(); 2];
     let mut operators = vec![Vec::new(); 2];
 
-    for subgrids_oc in grid.subgrids().axis_iter(Axis(1)) {
+    for slices_c in grid_slice.outer_iter() {
         let mut tables = vec![Array2::zeros((info.x0.len(), info.x0.len())); channels0.len()];
 
-        for (subgrids_o, channel1) in subgrids_oc.axis_iter(Axis(1)).zip(grid.channels()) {
-            let (x1, array) = ndarray_from_subgrid_orders_slice(
-                info.fac1,
-                &subgrids_o,
-                grid.orders(),
-                order_mask,
-                xi,
-                alphas_table,
-            )?;
-
+        for ((x1, array), channel1) in slices_c.iter().zip(grid.channels()) {
             // skip over zero arrays to speed up evolution and avoid problems with NaNs
             let Some(array) = array else {
                 continue;
@@ -586,8 +609,8 @@ pub(crate) fn evolve_slice_with_two(
                         .zip(x1.iter())
                         .any(|(&lhs, &rhs)| !approx_eq!(f64, lhs, rhs, ulps = EVOLUTION_TOL_ULPS))
                 {
-                    *operators = operator_slices(operator, info, pid_indices, &x1)?;
-                    *last_x1 = x1;
+                    *operators = operator_slices(operator, info, pid_indices, x1)?;
+                    last_x1.clone_from(x1);
                 }
             }
 
@@ -614,7 +637,7 @@ pub(crate) fn evolve_slice_with_two(
                                 .map(|ops| (fk_table, ops))
                         })
                 {
-                    linalg::general_mat_mul(1.0, &array, &ops[1].t(), 0.0, &mut tmp);
+                    linalg::general_mat_mul(1.0, array, &ops[1].t(), 0.0, &mut tmp);
                     linalg::general_mat_mul(factor, ops[0], &tmp, 1.0, fk_table);
                 }
             }
@@ -654,9 +677,8 @@ pub(crate) fn evolve_slice_with_two2(
     order_mask: &[bool],
     xi: (f64, f64),
     alphas_table: &AlphasTable,
+    gluon_has_pid_zero: bool,
 ) -> Result<(Array3<SubgridEnum>, Vec<Channel>), GridError> {
-    let gluon_has_pid_zero = gluon_has_pid_zero(grid);
-
     // TODO: implement matching of different scales for different EKOs
     let mut fac1_scales: Vec<_> = infos.iter().map(|info| info.fac1).collect();
     fac1_scales.sort_by(f64::total_cmp);
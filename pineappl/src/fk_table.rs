@@ -2,10 +2,12 @@
 
 use super::boc::Order;
 use super::convolutions::{Convolution, LumiCache};
-use super::grid::Grid;
+use super::grid::{Grid, GridError};
+use super::pids;
 use super::subgrid::Subgrid;
 use float_cmp::approx_eq;
-use ndarray::Array4;
+use ndarray::{Array4, ArrayView2};
+use std::collections::BTreeSet;
 use std::fmt::{self, Display, Formatter};
 use std::str::FromStr;
 use thiserror::Error;
@@ -40,6 +42,27 @@ pub enum TryFromGridError {
     NonTrivialOrder,
 }
 
+/// The error type returned by [`FkTable::merge`].
+#[derive(Debug, Error)]
+pub enum MergeFkTableError {
+    /// Error if the FK tables have different `x` grids.
+    #[error("the FK tables have different x grids")]
+    DifferentXGrids,
+    /// Error if the FK tables have different factorization scales.
+    #[error("the FK tables have different factorization scales")]
+    DifferentScales,
+    /// Error if the FK tables have different channels.
+    #[error("the FK tables have different channels")]
+    DifferentChannels,
+    /// Error if the FK tables have different convolutions.
+    #[error("the FK tables have different convolutions")]
+    DifferentConvolutions,
+    /// Error if merging the grids of the FK tables failed, for instance because the bins can not
+    /// be concatenated.
+    #[error(transparent)]
+    GridError(#[from] GridError),
+}
+
 /// The optimization assumptions for an [`FkTable`], needed for [`FkTable::optimize`]. Since FK
 /// tables are typically stored at very small `Q2 = Q0`, the PDFs `f(x,Q0)` of heavy quarks are
 /// typically set to zero at this scale or set to the same value as their anti-quark PDF. This is
@@ -79,6 +102,72 @@ pub struct UnknownFkAssumption {
     variant: String,
 }
 
+/// Non-zero entries of an [`FkTable`], precomputed by [`FkTable::grid_cache`], which allow to
+/// convolve the FK table repeatedly with [`FkTableCache::convolve_into`] without allocating memory.
+#[derive(Clone, Debug)]
+pub struct FkTableCache {
+    pids: Vec<i32>,
+    x_grid: Vec<f64>,
+    bins: usize,
+    entries: Vec<FkTableCacheEntry>,
+}
+
+#[derive(Clone, Debug)]
+struct FkTableCacheEntry {
+    bin: usize,
+    // indices of the particle and `x` value in the PDF array, if there is a PDF
+    pdf1: Option<(usize, usize)>,
+    pdf2: Option<(usize, usize)>,
+    // the FK-table value divided by the bin normalization and the `x` values of the PDFs
+    value: f64,
+}
+
+impl FkTableCache {
+    /// Return the particle IDs, in the basis given by [`Grid::pid_basis`], whose PDFs
+    /// [`FkTableCache::convolve_into`] needs. They label the rows of its argument `pdfs`.
+    #[must_use]
+    pub fn pids(&self) -> &[i32] {
+        &self.pids
+    }
+
+    /// Return the `x` values at which [`FkTableCache::convolve_into`] needs the PDFs. They label
+    /// the columns of its argument `pdfs`.
+    #[must_use]
+    pub fn x_grid(&self) -> &[f64] {
+        &self.x_grid
+    }
+
+    /// Convolve the FK table with `pdfs` and write the result for each bin into `results`. The
+    /// value `pdfs[[i, j]]` must be the PDF of the particle `self.pids()[i]` at the momentum
+    /// fraction `self.x_grid()[j]` and the factorization scale [`FkTable::muf2`], multiplied by the
+    /// momentum fraction. The results are the same as the ones of [`FkTable::convolve`], but this
+    /// method does not allocate any memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shape of `pdfs` does not match the particle IDs and `x` values of this cache,
+    /// or if the length of `results` is not the number of bins.
+    pub fn convolve_into(&self, pdfs: ArrayView2<f64>, results: &mut [f64]) {
+        assert_eq!(pdfs.dim(), (self.pids.len(), self.x_grid.len()));
+        assert_eq!(results.len(), self.bins);
+
+        results.fill(0.0);
+
+        for entry in &self.entries {
+            let mut value = entry.value;
+
+            if let Some(index) = entry.pdf1 {
+                value *= pdfs[index];
+            }
+            if let Some(index) = entry.pdf2 {
+                value *= pdfs[index];
+            }
+
+            results[entry.bin] += value;
+        }
+    }
+}
+
 impl Display for FkAssumptions {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(
@@ -213,6 +302,121 @@ impl FkTable {
         self.grid.evolve_info(&[true]).x1
     }
 
+    /// Precompute the non-zero entries of this FK table, so that it can be convolved repeatedly
+    /// with [`FkTableCache::convolve_into`] without allocating memory. Both convolutions use the
+    /// PDFs of the hadron of the first convolution; if the hadron of the second convolution is its
+    /// antiparticle, the PDFs are charge conjugated, in the same way as [`LumiCache::with_one`]
+    /// does it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the hadrons of the two convolutions are neither the same particle nor each
+    /// other's antiparticles.
+    #[must_use]
+    pub fn grid_cache(&self) -> FkTableCache {
+        let convolutions = self.grid.convolutions();
+        let pid_basis = self.grid.pid_basis();
+        let normalizations = self.grid.bin_info().normalizations();
+        let x_grid = self.x_grid();
+
+        // for each convolution `None` if it has no PDF, otherwise whether the PDF of the first
+        // hadron must be charge conjugated
+        let hadron = convolutions.iter().find_map(Convolution::pid);
+        let cc: Vec<_> = convolutions
+            .iter()
+            .map(|convolution| {
+                convolution.pid().zip(hadron).map(|(pid, hadron)| {
+                    assert!(
+                        pid == hadron || pid == pids::charge_conjugate_pdg_pid(hadron),
+                        "the hadrons {hadron} and {pid} of the convolutions are different"
+                    );
+                    pid != hadron
+                })
+            })
+            .collect();
+
+        // the particle IDs and factors of each channel for the PDFs of the first hadron
+        let channels: Vec<_> = self
+            .channels()
+            .into_iter()
+            .map(|(pid1, pid2)| {
+                [(0, pid1), (1, pid2)].map(|(index, pid)| {
+                    if cc[index] == Some(true) {
+                        pid_basis.charge_conjugate(pid)
+                    } else {
+                        (pid, 1.0)
+                    }
+                })
+            })
+            .collect();
+        let pids: Vec<_> = channels
+            .iter()
+            .flat_map(|channel| {
+                channel
+                    .iter()
+                    .zip(&cc)
+                    .filter_map(|(&(pid, _), cc)| cc.map(|_| pid))
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let pdf_index = |index: usize, pid: i32, x: f64| {
+            cc[index].map(|_| {
+                (
+                    // UNWRAP: `pids` contains the particle IDs of all channels
+                    pids.binary_search(&pid).unwrap(),
+                    // UNWRAP: all subgrids of an FK table share the same `x` grid
+                    x_grid
+                        .iter()
+                        .position(|&node| approx_eq!(f64, node, x, ulps = 2))
+                        .unwrap(),
+                )
+            })
+        };
+
+        let mut entries = Vec::new();
+
+        for ((_, bin, channel), subgrid) in self.grid.subgrids().indexed_iter() {
+            if subgrid.is_empty() {
+                continue;
+            }
+
+            let x1_grid = subgrid.x1_grid();
+            let x2_grid = subgrid.x2_grid();
+            let [(pid1, factor1), (pid2, factor2)] = channels[channel];
+
+            for ((_, ix1, ix2), value) in subgrid.indexed_iter() {
+                let x1 = x1_grid[ix1];
+                let x2 = x2_grid[ix2];
+                let pdf1 = pdf_index(0, pid1, x1);
+                let pdf2 = pdf_index(1, pid2, x2);
+                let mut value = value / normalizations[bin];
+
+                if pdf1.is_some() {
+                    value *= factor1 / x1;
+                }
+                if pdf2.is_some() {
+                    value *= factor2 / x2;
+                }
+
+                entries.push(FkTableCacheEntry {
+                    bin,
+                    pdf1,
+                    pdf2,
+                    value,
+                });
+            }
+        }
+
+        FkTableCache {
+            pids,
+            x_grid,
+            bins: normalizations.len(),
+            entries,
+        }
+    }
+
     /// Convolve the FK-table. This method has fewer arguments than [`Grid::convolve`], because
     /// FK-tables have all orders merged together and do not support scale variations.
     pub fn convolve(
@@ -225,6 +429,38 @@ impl FkTable {
             .convolve(lumi_cache, &[], bin_indices, channel_mask, &[(1.0, 1.0)])
     }
 
+    /// Merge the bins of `other` into `self`, which is useful to combine FK tables that were
+    /// obtained by evolving subsets of the bins of a grid separately. Both FK tables must share
+    /// the same `x` grid, factorization scale, channels and convolutions. See [`Grid::merge`] for
+    /// the requirements on the bins.
+    ///
+    /// # Errors
+    ///
+    /// If the FK tables are not compatible or their bins can not be merged, an error is
+    /// returned.
+    pub fn merge(&mut self, other: Self) -> Result<(), MergeFkTableError> {
+        let lhs = self.grid.evolve_info(&[true]);
+        let rhs = other.grid.evolve_info(&[true]);
+
+        if lhs.x1 != rhs.x1 {
+            return Err(MergeFkTableError::DifferentXGrids);
+        }
+
+        if lhs.fac1 != rhs.fac1 {
+            return Err(MergeFkTableError::DifferentScales);
+        }
+
+        if self.grid.channels() != other.grid.channels() {
+            return Err(MergeFkTableError::DifferentChannels);
+        }
+
+        if self.grid.convolutions() != other.grid.convolutions() {
+            return Err(MergeFkTableError::DifferentConvolutions);
+        }
+
+        Ok(self.grid.merge(other.grid)?)
+    }
+
     /// Set a metadata key-value pair
     pub fn set_key_value(&mut self, key: &str, value: &str) {
         self.grid.set_key_value(key, value);
@@ -302,14 +538,7 @@ impl TryFrom<Grid> for FkTable {
     fn try_from(grid: Grid) -> Result<Self, Self::Error> {
         let mut muf2 = -1.0;
 
-        if grid.orders()
-            != [Order {
-                alphas: 0,
-                alpha: 0,
-                logxir: 0,
-                logxif: 0,
-            }]
-        {
+        if grid.orders() != [Order::default()] {
             return Err(TryFromGridError::NonTrivialOrder);
         }
 
@@ -352,6 +581,11 @@ impl TryFrom<Grid> for FkTable {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::channel;
+    use crate::import_only_subgrid::ImportOnlySubgridV2;
+    use crate::sparse_array3::SparseArray3;
+    use crate::subgrid::{Mu2, SubgridParams};
+    use float_cmp::assert_approx_eq;
 
     #[test]
     fn fk_assumptions_try_from() {
@@ -380,4 +614,125 @@ mod tests {
         assert_eq!(format!("{}", FkAssumptions::Nf3Ind), "Nf3Ind");
         assert_eq!(format!("{}", FkAssumptions::Nf3Sym), "Nf3Sym");
     }
+
+    fn fk_table(bin_limits: Vec<f64>, x: Vec<f64>) -> FkTable {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0], channel![21, 21, 1.0]],
+            vec![Order::new(0, 0, 0, 0)],
+            bin_limits,
+            SubgridParams::default(),
+        );
+
+        let mut array = SparseArray3::new(1, x.len(), x.len());
+        array[[0, 0, 0]] = 1.0;
+
+        grid.set_subgrid(
+            0,
+            0,
+            0,
+            ImportOnlySubgridV2::new(array, vec![Mu2 { ren: 2.0, fac: 2.0 }], x.clone(), x).into(),
+        );
+
+        FkTable::try_from(grid).unwrap()
+    }
+
+    #[test]
+    fn fk_table_merge() {
+        let mut fk_table = fk_table(vec![0.0, 1.0], vec![0.1, 0.5]);
+        fk_table
+            .merge(self::fk_table(vec![1.0, 2.0, 3.0], vec![0.1, 0.5]))
+            .unwrap();
+
+        assert_eq!(
+            fk_table.grid().bin_info().limits(),
+            [[(0.0, 1.0)], [(1.0, 2.0)], [(2.0, 3.0)]]
+        );
+        assert_eq!(fk_table.channels(), [(2, 2), (21, 21)]);
+
+        let mut xfx = |_, x, _| x;
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        assert_eq!(
+            fk_table.convolve(&mut lumi_cache, &[], &[]),
+            [1.0, 1.0, 0.0]
+        );
+
+        assert_eq!(
+            fk_table
+                .merge(self::fk_table(vec![3.0, 4.0], vec![0.1, 0.6]))
+                .unwrap_err()
+                .to_string(),
+            "the FK tables have different x grids"
+        );
+        assert_eq!(
+            fk_table
+                .merge(self::fk_table(vec![4.0, 5.0], vec![0.1, 0.5]))
+                .unwrap_err()
+                .to_string(),
+            "can not merge bins which end at 3 with bins that start at 4"
+        );
+    }
+
+    #[test]
+    fn fk_table_grid_cache() {
+        let x = vec![0.1, 0.3, 0.7];
+        let mut grid = Grid::new(
+            vec![channel![2, -2, 1.0], channel![21, 1, 1.0]],
+            vec![Order::new(0, 0, 0, 0)],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
+        grid.set_convolution(1, Convolution::UnpolPDF(-2212));
+
+        for bin in 0..2 {
+            for channel in 0..2 {
+                let mut array = SparseArray3::new(1, x.len(), x.len());
+
+                for ix1 in 0..x.len() {
+                    for ix2 in 0..x.len() {
+                        #[allow(clippy::cast_precision_loss)]
+                        let value = (1 + bin + 2 * channel + 3 * ix1 + 5 * ix2) as f64;
+                        array[[0, ix1, ix2]] = value;
+                    }
+                }
+
+                grid.set_subgrid(
+                    0,
+                    bin,
+                    channel,
+                    ImportOnlySubgridV2::new(
+                        array,
+                        vec![Mu2 { ren: 2.0, fac: 2.0 }],
+                        x.clone(),
+                        x.clone(),
+                    )
+                    .into(),
+                );
+            }
+        }
+
+        let fk_table = FkTable::try_from(grid).unwrap();
+        let cache = fk_table.grid_cache();
+
+        // the PDFs of the antiproton are charge conjugated into the ones of the proton
+        assert_eq!(cache.pids(), [-1, 2, 21]);
+        assert_eq!(cache.x_grid(), x);
+
+        let xfx_proton = |pid: i32, x: f64| x * f64::from(pid + 30) * (1.0 - x);
+        let pdfs = ndarray::Array2::from_shape_fn((3, 3), |(ipid, ix)| {
+            xfx_proton(cache.pids()[ipid], cache.x_grid()[ix])
+        });
+        let mut results = vec![0.0; 2];
+        cache.convolve_into(pdfs.view(), &mut results);
+
+        let mut xfx = |pid, x, _| xfx_proton(pid, x);
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+        let reference = fk_table.convolve(&mut lumi_cache, &[], &[]);
+
+        for (result, reference) in results.iter().zip(&reference) {
+            assert_approx_eq!(f64, *result, *reference, ulps = 16);
+        }
+    }
 }
@@ -1,28 +1,37 @@
 //! Module containing all traits and supporting structures for grids.
 
-use super::bin::{BinInfo, BinLimits, BinRemapper};
-use super::boc::{Channel, Order};
-use super::convolutions::{Convolution, LumiCache};
+use super::bin::{BinInfo, BinLimits, BinRemapper, MergeBinError};
+use super::boc::{Channel, Kinematics, Order};
+use super::convolutions::{Convolution, LumiCache, XfxTable};
 use super::empty_subgrid::EmptySubgridV1;
 use super::evolution::{self, AlphasTable, EvolveInfo, OperatorInfo, OperatorSliceInfo};
 use super::fk_table::FkTable;
-use super::import_only_subgrid::ImportOnlySubgridV2;
+use super::import_only_subgrid::{ImportOnlySubgridF32, ImportOnlySubgridV2};
 use super::lagrange_subgrid::{LagrangeSparseSubgridV1, LagrangeSubgridV1, LagrangeSubgridV2};
 use super::ntuple_subgrid::NtupleSubgridV1;
 use super::pids::{self, PidBasis};
-use super::subgrid::{ExtraSubgridParams, Mu2, Subgrid, SubgridEnum, SubgridParams};
+use super::sparse_array3::SparseArray3;
+use super::subgrid::{
+    ExtraSubgridParams, Interp, Mu2, Subgrid, SubgridEnum, SubgridKind, SubgridParams, SubgridStats,
+};
 use bitflags::bitflags;
 use float_cmp::{approx_eq, assert_approx_eq};
 use git_version::git_version;
+use itertools::Itertools;
 use lz4_flex::frame::{FrameDecoder, FrameEncoder};
-use ndarray::{s, Array3, ArrayView3, ArrayView5, ArrayViewMut3, Axis, CowArray, Dimension, Ix4};
-use serde::{Deserialize, Serialize, Serializer};
+use ndarray::{
+    s, Array2, Array3, ArrayView3, ArrayView5, ArrayViewMut3, Axis, CowArray, Dimension, Ix4,
+};
+use serde::de::{self, DeserializeOwned, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::borrow::Cow;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
 use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
 use std::iter;
 use std::mem;
 use std::ops::Range;
+use std::slice;
 use thiserror::Error;
 
 /// This structure represents a position (`x1`, `x2`, `q2`) in a `Subgrid` together with a
@@ -57,6 +66,18 @@ pub enum GridError {
     /// Returned when it was tried to merge bins that are non-consecutive.
     #[error(transparent)]
     MergeBinError(super::bin::MergeBinError),
+    /// Returned if the shape of channel- and bin-dependent factors does not match the grid.
+    #[error(
+        "the factors have shape {shape:?}, but the grid has {channels} channels and {bins} bins"
+    )]
+    FactorShapeMismatch {
+        /// Shape of the factors, given as number of rows and columns.
+        shape: (usize, usize),
+        /// Number of channels in the grid.
+        channels: usize,
+        /// Number of bins in the grid.
+        bins: usize,
+    },
     /// Returned when trying to construct a `Grid` using an unknown subgrid type.
     #[error("tried constructing a Grid with unknown Subgrid type `{0}`")]
     UnknownSubgridType(String),
@@ -84,6 +105,255 @@ pub enum GridError {
     /// Errors that do no originate from this crate itself.
     #[error(transparent)]
     Other(#[from] anyhow::Error),
+    /// Returned when constructing a `Grid` with fewer than two bin limits.
+    #[error("at least two bin limits are required, but {limits} were given")]
+    TooFewBinLimits {
+        /// Number of bin limits given.
+        limits: usize,
+    },
+    /// Returned when constructing a `Grid` with a bin limit that is infinite or NaN.
+    #[error("the bin limit with index {index} is not finite: {limit}")]
+    NonFiniteBinLimit {
+        /// Index of the bin limit.
+        index: usize,
+        /// Value of the bin limit.
+        limit: f64,
+    },
+    /// Returned when constructing a `Grid` with a bin limit given more than once, which would
+    /// result in a bin with zero width.
+    #[error(
+        "the bin limit with index {index} is the same as the one with index {previous}: {limit}"
+    )]
+    RepeatedBinLimit {
+        /// Index of the repeated bin limit.
+        index: usize,
+        /// Index of the first occurrence of the bin limit.
+        previous: usize,
+        /// Value of the repeated bin limit.
+        limit: f64,
+    },
+    /// Returned when constructing a `Grid` with bin limits that are not sorted in increasing
+    /// order.
+    #[error("the bin limit with index {index} is smaller than its predecessor: {limit}")]
+    UnsortedBinLimits {
+        /// Index of the first bin limit that is smaller than its predecessor.
+        index: usize,
+        /// Value of the bin limit.
+        limit: f64,
+    },
+    /// Returned when constructing a `Grid` with a channel whose factors are not all finite.
+    #[error(
+        "the factor of entry {entry} of the channel with index {channel} is not finite: {factor}"
+    )]
+    NonFiniteChannelFactor {
+        /// Index of the channel.
+        channel: usize,
+        /// Index of the entry in the channel.
+        entry: usize,
+        /// Value of the factor.
+        factor: f64,
+    },
+    /// Returned when constructing a `Grid` with an interpolation range that is not positive or
+    /// whose minimum is larger than its maximum.
+    #[error("the interpolation range of {variable} from {min} to {max} is invalid")]
+    InvalidInterpolationRange {
+        /// Name of the interpolated variable, either `x` or `q2`.
+        variable: &'static str,
+        /// Lower limit of the interpolation range.
+        min: f64,
+        /// Upper limit of the interpolation range.
+        max: f64,
+    },
+    /// Returned when resampling a subgrid with a node that lies outside the new interpolation
+    /// range.
+    #[error(
+        "the node with x1 = {x1}, x2 = {x2} and q2 = {q2} lies outside the interpolation range"
+    )]
+    NodeOutsideInterpolationRange {
+        /// Momentum fraction of the first convolution.
+        x1: f64,
+        /// Momentum fraction of the second convolution.
+        x2: f64,
+        /// Factorization scale.
+        q2: f64,
+    },
+    /// Returned when resampling a subgrid whose renormalization and factorization scales differ.
+    #[error("the renormalization scale {ren} and the factorization scale {fac} are different")]
+    UnequalScales {
+        /// Renormalization scale.
+        ren: f64,
+        /// Factorization scale.
+        fac: f64,
+    },
+    /// Returned when merging two grids with [`MetadataMergePolicy::ErrorOnConflict`] whose
+    /// metadata have different values for the same key.
+    #[error("the metadata key '{key}' has different values: '{lhs}' and '{rhs}'")]
+    MetadataConflict {
+        /// Key with different values.
+        key: String,
+        /// Value of the key in the grid that is merged into.
+        lhs: String,
+        /// Value of the key in the grid that is merged.
+        rhs: String,
+    },
+    /// Returned from [`Grid::set_scales`] if a scale is not positive and finite.
+    #[error(
+        "the renormalization scale {ren} and factorization scale {fac} must be positive and finite"
+    )]
+    InvalidScale {
+        /// Renormalization scale.
+        ren: f64,
+        /// Factorization scale.
+        fac: f64,
+    },
+    /// Returned from [`Grid::set_bin_scales`] if the number of scales does not match the number of
+    /// bins.
+    #[error("the grid has {grid_bins} bins, but {scales} scales were given")]
+    BinScaleMismatch {
+        /// Number of bins in the grid.
+        grid_bins: usize,
+        /// Number of scales given.
+        scales: usize,
+    },
+    /// Returned from [`Grid::set_reference`] if the number of reference results does not match
+    /// the number of bins.
+    #[error("the reference has {reference_bins} results, but the grid has {grid_bins} bins")]
+    ReferenceBinMismatch {
+        /// Number of bins in the grid.
+        grid_bins: usize,
+        /// Number of results in the reference.
+        reference_bins: usize,
+    },
+    /// Returned from [`Grid::calibrate_to_reference`] if the grid does not store a reference.
+    #[error("the grid does not store a reference")]
+    MissingReference,
+    /// Returned from [`Grid::calibrate_to_reference`] if the ratios of the reference to the
+    /// predictions are not the same for all bins.
+    #[error("the ratio {ratio} of the reference to the prediction in bin {bin} is inconsistent with the factor {factor}")]
+    InconsistentReference {
+        /// Index of the first bin with an inconsistent ratio.
+        bin: usize,
+        /// Ratio of the reference to the prediction in this bin.
+        ratio: f64,
+        /// Common factor determined from all bins.
+        factor: f64,
+    },
+    /// Returned from [`Grid::check_mergeable`] if two grids can not be merged.
+    #[error("the {property} are incompatible: {reason}")]
+    IncompatibleGrids {
+        /// Property of the grids that is incompatible, for instance `convolutions`.
+        property: &'static str,
+        /// Description of the incompatibility.
+        reason: String,
+    },
+    /// Returned from [`Grid::convolve_with_tables`] if a value needed by the convolution is
+    /// missing in the tables.
+    #[error("the tables do not contain the {0}")]
+    IncompleteTables(String),
+    /// Returned from [`Grid::convolve_with_tables`] if neither one nor two PDF tables are given.
+    #[error("expected one or two PDF tables, but {tables} were given")]
+    TableCountMismatch {
+        /// Number of the given tables.
+        tables: usize,
+    },
+    /// Returned from [`Grid::convolve_with_tables`] if the hadron of a PDF does not match the
+    /// corresponding convolution of the grid.
+    #[error("the PDF of the hadron {pdg} can not be used for the convolution with index {index}: {convolution}")]
+    HadronMismatch {
+        /// Index of the convolution.
+        index: usize,
+        /// PDG MC ID of the hadron of the PDF.
+        pdg: i32,
+        /// Convolution of the grid.
+        convolution: Convolution,
+    },
+    /// Returned from [`Grid::try_set_convolution`] if the convolution is not compatible with the
+    /// channels of the grid.
+    #[error("the convolution with index {index} can not be set: {reason}")]
+    IncompatibleConvolution {
+        /// Index of the convolution.
+        index: usize,
+        /// Description of the incompatibility.
+        reason: String,
+    },
+    /// Returned from [`Grid::scale_order_by_bin`] if the number of factors and bins differ.
+    #[error("the grid has {grid_bins} bins, but {factors} factors were given")]
+    BinFactorMismatch {
+        /// Number of bins in the grid.
+        grid_bins: usize,
+        /// Number of factors.
+        factors: usize,
+    },
+    /// Returned if an order index is not smaller than the number of orders.
+    #[error("the order with index {order} does not exist, the grid has {orders} orders")]
+    InvalidOrderIndex {
+        /// Index of the order.
+        order: usize,
+        /// Number of orders in the grid.
+        orders: usize,
+    },
+    /// Returned from [`Grid::rebin_to`] if the bins of the grid are not one-dimensional.
+    #[error("rebinning requires one-dimensional bins, but the bins have {dimensions} dimensions")]
+    MultiDimensionalBins {
+        /// Number of dimensions of the bins.
+        dimensions: usize,
+    },
+    /// Returned from [`Grid::rebin_to`] if the new bin limits exceed the range of the old ones.
+    #[error("the new bin limits from {min} to {max} are not within the old bin limits from {old_min} to {old_max}")]
+    BinLimitsOutOfRange {
+        /// Smallest new bin limit.
+        min: f64,
+        /// Largest new bin limit.
+        max: f64,
+        /// Smallest old bin limit.
+        old_min: f64,
+        /// Largest old bin limit.
+        old_max: f64,
+    },
+    /// Returned from [`Grid::extra_couplings`] if the metadata storing the powers of the
+    /// additional couplings is malformed or does not match the orders of the grid.
+    #[error("the additional couplings are malformed: {0}")]
+    InvalidExtraCouplings(String),
+}
+
+/// Policy for merging the metadata of two grids with [`Grid::merge_with_metadata`].
+///
+/// Keys present in only one of the grids are always kept; the policies differ only for keys that
+/// are present in both grids with different values.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MetadataMergePolicy {
+    /// Keep the value of the grid that is merged into. This is the policy used by [`Grid::merge`].
+    KeepFirst,
+    /// Keep the value of the grid that is merged.
+    KeepLast,
+    /// Return [`GridError::MetadataConflict`] and leave both grids unchanged.
+    ErrorOnConflict,
+}
+
+/// Labels of a single bin dimension, read from the metadata by [`Grid::bin_dimension_labels`].
+///
+/// For the `n`-th dimension, counting from one, the labels are stored under the keys
+/// `x{n}_label`, `x{n}_label_tex` and `x{n}_unit`. Labels not present in the metadata are empty.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DimLabel {
+    /// Label of the observable in plain text.
+    pub label: String,
+    /// Label of the observable in LaTeX.
+    pub label_tex: String,
+    /// Unit of the observable, in the notation of the `siunitx` LaTeX package.
+    pub unit: String,
+}
+
+/// Reference results of a grid, read from the metadata by [`Grid::reference`].
+///
+/// The results are stored under the key `reference`, separated by spaces, and the PDF set they
+/// were obtained with under the key `results_pdf`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Reference {
+    /// Name or LHAPDF ID of the PDF set used to calculate the results.
+    pub pdf: String,
+    /// Differential result for each bin, as returned by [`Grid::convolve`].
+    pub results: Vec<f64>,
 }
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -211,6 +481,289 @@ bitflags! {
     }
 }
 
+/// Changes made by [`Grid::optimize_using_report`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OptimizeReport {
+    /// Number of subgrids whose type was changed by [`GridOptFlags::OPTIMIZE_SUBGRID_TYPE`].
+    pub subgrids_converted: usize,
+    /// Number of channels merged into their transposed ones by
+    /// [`GridOptFlags::SYMMETRIZE_CHANNELS`]. The emptied channels are removed by
+    /// [`GridOptFlags::STRIP_EMPTY_CHANNELS`].
+    pub channels_symmetrized: usize,
+    /// Number of orders removed by [`GridOptFlags::STRIP_EMPTY_ORDERS`].
+    pub orders_stripped: usize,
+    /// Number of channels merged into other ones by [`GridOptFlags::MERGE_SAME_CHANNELS`]. The
+    /// emptied channels are removed by [`GridOptFlags::STRIP_EMPTY_CHANNELS`].
+    pub channels_merged: usize,
+    /// Number of channels removed by [`GridOptFlags::STRIP_EMPTY_CHANNELS`].
+    pub channels_stripped: usize,
+    /// Size of the uncompressed grid in bytes before the optimization.
+    pub bytes_before: u64,
+    /// Size of the uncompressed grid in bytes after the optimization.
+    pub bytes_after: u64,
+}
+
+/// Distribution of the weights of a single subgrid, which is returned by
+/// [`Grid::weight_histograms`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WeightHistogram {
+    /// Number of non-zero weights for each decimal exponent `e`, which counts the weights whose
+    /// absolute values lie in the interval `[10^e, 10^(e+1))`.
+    pub decades: BTreeMap<i32, usize>,
+    /// Sum of all weights.
+    pub sum: f64,
+    /// Sum of the absolute errors made by rounding each weight to single precision. This is
+    /// infinite if a weight is too large to be represented as `f32`.
+    pub f32_error: f64,
+}
+
+impl WeightHistogram {
+    fn new(weights: impl Iterator<Item = f64>) -> Self {
+        let mut histogram = Self::default();
+
+        for weight in weights.filter(|&weight| weight != 0.0) {
+            #[allow(clippy::cast_possible_truncation)]
+            let decade = weight.abs().log10().floor() as i32;
+            #[allow(clippy::cast_possible_truncation)]
+            let rounded = f64::from(weight as f32);
+
+            *histogram.decades.entry(decade).or_default() += 1;
+            histogram.sum += weight;
+            histogram.f32_error += (rounded - weight).abs();
+        }
+
+        histogram
+    }
+
+    /// Returns `true` if the subgrid is not empty and the sum of its rounding errors,
+    /// [`Self::f32_error`], is at most `rel_tol` times the absolute value of the sum of its
+    /// weights. These are the subgrids that [`Grid::compress_to_f32`] stores with single
+    /// precision.
+    #[must_use]
+    pub fn is_f32_safe(&self, rel_tol: f64) -> bool {
+        !self.decades.is_empty() && (self.f32_error <= rel_tol * self.sum.abs())
+    }
+}
+
+// the file version written by `Grid::write`
+const FILE_VERSION: u64 = 1;
+
+/// Everything of a [`Grid`] except its subgrids, which is returned by [`Grid::read_header`].
+pub struct GridHeader {
+    // a grid whose subgrids are all empty
+    grid: Grid,
+}
+
+impl GridHeader {
+    /// Return the bin information of the grid.
+    #[must_use]
+    pub const fn bin_info(&self) -> BinInfo {
+        self.grid.bin_info()
+    }
+
+    /// Return the channels of the grid.
+    #[must_use]
+    pub fn channels(&self) -> &[Channel] {
+        self.grid.channels()
+    }
+
+    /// Return the orders of the grid.
+    #[must_use]
+    pub fn orders(&self) -> &[Order] {
+        self.grid.orders()
+    }
+
+    /// Return the key-value pairs of the grid, if there are any.
+    #[must_use]
+    pub const fn key_values(&self) -> Option<&HashMap<String, String>> {
+        self.grid.key_values()
+    }
+
+    /// Convert this header into a [`Grid`] with the same orders, bins, channels and metadata,
+    /// whose subgrids are all empty.
+    #[must_use]
+    pub fn into_grid(self) -> Grid {
+        self.grid
+    }
+}
+
+// everything but the subgrids of a `Grid`, which files with version 1 store before the subgrids
+#[derive(Deserialize, Serialize)]
+struct GridHeaderV1<C, B, O, S, M> {
+    channels: C,
+    bin_limits: B,
+    orders: O,
+    subgrid_params: S,
+    more_members: M,
+}
+
+type OwnedGridHeaderV1 =
+    GridHeaderV1<Vec<Channel>, BinLimits, Vec<Order>, SubgridParams, MoreMembers>;
+
+impl OwnedGridHeaderV1 {
+    fn into_grid(self, subgrids: Option<Array3<SubgridEnum>>) -> Result<Grid, GridError> {
+        let shape = (
+            self.orders.len(),
+            self.bin_limits.bins(),
+            self.channels.len(),
+        );
+        let subgrids = match subgrids {
+            Some(subgrids) if subgrids.dim() == shape => subgrids,
+            Some(subgrids) => {
+                return Err(GridError::ReadFailure(Box::new(
+                    bincode::ErrorKind::Custom(format!(
+                        "the subgrids have shape {:?}, but the header requires {shape:?}",
+                        subgrids.dim()
+                    )),
+                )))
+            }
+            None => Array3::from_shape_simple_fn(shape, || EmptySubgridV1.into()),
+        };
+
+        Ok(Grid {
+            subgrids,
+            channels: self.channels,
+            bin_limits: self.bin_limits,
+            orders: self.orders,
+            subgrid_params: self.subgrid_params,
+            more_members: self.more_members,
+        })
+    }
+}
+
+// the same layout as `Grid` in files with version 0, but with the subgrids discarded during
+// deserialization
+#[derive(Deserialize)]
+struct GridHeaderData {
+    subgrids: DiscardedSubgrids,
+    channels: Vec<Channel>,
+    bin_limits: BinLimits,
+    orders: Vec<Order>,
+    subgrid_params: SubgridParams,
+    more_members: MoreMembers,
+}
+
+// the shape of a serialized `Array3<SubgridEnum>`, whose subgrids are deserialized and dropped one
+// by one; bincode isn't self-describing, so they can't be skipped with `IgnoredAny`
+struct DiscardedSubgrids((usize, usize, usize));
+
+impl<'de> Deserialize<'de> for DiscardedSubgrids {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SubgridsVisitor;
+
+        impl<'de> Visitor<'de> for SubgridsVisitor {
+            type Value = ();
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a sequence of subgrids")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+                while seq.next_element::<SubgridEnum>()?.is_some() {}
+                Ok(())
+            }
+        }
+
+        struct Subgrids;
+
+        impl<'de> Deserialize<'de> for Subgrids {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_seq(SubgridsVisitor)?;
+                Ok(Self)
+            }
+        }
+
+        struct ArrayVisitor;
+
+        impl<'de> Visitor<'de> for ArrayVisitor {
+            type Value = DiscardedSubgrids;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a three-dimensional array of subgrids")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                // the fields of the serialized array are its version, its shape and its elements
+                let _: u8 = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let shape = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                let _: Subgrids = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+
+                Ok(DiscardedSubgrids(shape))
+            }
+        }
+
+        deserializer.deserialize_struct("Array", &["v", "dim", "data"], ArrayVisitor)
+    }
+}
+
+/// Weight of [`ConvolveOptions::reweight`], whose arguments are the index of the channel, the
+/// squared scales and the momentum fractions `x1` and `x2` of a node.
+pub type ChannelReweight<'a> = &'a dyn Fn(usize, &Mu2, f64, f64) -> f64;
+
+// the weight of `ChannelReweight` restricted to the nodes of a single channel
+type NodeReweight<'a> = &'a dyn Fn(&Mu2, f64, f64) -> f64;
+
+/// Options for [`Grid::convolve_with_options`]. The default options convolve all orders, bins
+/// and channels with the central scales, without any corrections.
+#[derive(Clone, Copy)]
+pub struct ConvolveOptions<'a> {
+    /// Selects the orders that are convolved. If this is empty, all orders are selected,
+    /// otherwise it must have an entry for each order of the [`Grid`].
+    pub order_mask: &'a [bool],
+    /// Indices of the bins that are convolved, in the order in which they are returned. If this is
+    /// empty, all bins are convolved.
+    pub bin_indices: &'a [usize],
+    /// Selects the channels that are convolved. If this is empty, all channels are selected,
+    /// otherwise it must have an entry for each channel of the [`Grid`].
+    pub channel_mask: &'a [bool],
+    /// Pairs of factors varying the renormalization and factorization scale, respectively. The
+    /// results of each bin contain one value for each pair. For the variations to be trusted all
+    /// non-zero log-grids must be contained in the [`Grid`]. The default is `[(1.0, 1.0)]`.
+    pub xi: &'a [(f64, f64)],
+    /// For each bin the index of the cache that is used for it, which allows convolving bins
+    /// with different PDFs, for instance for different nuclear targets. If this is empty, all
+    /// bins use the first cache.
+    pub bin_caches: &'a [usize],
+    /// Factors that multiply the result of each bin after the normalization by the bin widths,
+    /// for instance for non-perturbative corrections that are not part of the [`Grid`]. If this is
+    /// not empty, it must have an entry for each bin; if only a subset of bins is convolved, only
+    /// their factors are used.
+    pub bin_corrections: &'a [f64],
+    /// Weight that multiplies the luminosity of each node of each subgrid before it is
+    /// accumulated. Its arguments are the index of the channel, the squared renormalization and
+    /// factorization scales of the node before any scale variation, and the momentum fractions
+    /// `x1` and `x2` of the node. The weight is evaluated for every node of every non-empty
+    /// subgrid, which for expensive weights can dominate the time needed for the convolution.
+    pub reweight: Option<ChannelReweight<'a>>,
+    /// If `true`, the contributions of the different orders and channels to each bin are
+    /// accumulated using Kahan-Babuška-Neumaier compensated summation, which avoids the loss of
+    /// precision that occurs with large cancellations between orders or channels. The summation
+    /// adds a few floating-point operations per subgrid, which is negligible compared to the
+    /// convolution of the subgrid itself.
+    pub compensated: bool,
+}
+
+impl Default for ConvolveOptions<'_> {
+    fn default() -> Self {
+        Self {
+            order_mask: &[],
+            bin_indices: &[],
+            channel_mask: &[],
+            xi: &[(1.0, 1.0)],
+            bin_caches: &[],
+            bin_corrections: &[],
+            reweight: None,
+            compensated: false,
+        }
+    }
+}
+
 /// Main data structure of `PineAPPL`. This structure contains a `Subgrid` for each `LumiEntry`,
 /// bin, and coupling order it was created with.
 #[derive(Clone, Deserialize, Serialize)]
@@ -225,6 +778,12 @@ pub struct Grid {
 
 impl Grid {
     /// Constructor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arguments are invalid, for instance if the bin limits are not sorted in
+    /// increasing order, see [`Grid::try_new`] for the complete list of conditions and a
+    /// non-panicking version.
     #[must_use]
     pub fn new(
         channels: Vec<Channel>,
@@ -232,7 +791,30 @@ impl Grid {
         bin_limits: Vec<f64>,
         subgrid_params: SubgridParams,
     ) -> Self {
-        Self {
+        Self::try_new(channels, orders, bin_limits, subgrid_params)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Constructor. This function can be used like [`Grid::new`], but returns an error instead of
+    /// panicking if the arguments are invalid.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a factor of a channel is not finite, if there are fewer than two bin
+    /// limits, if a bin limit is not finite or given more than once, if the bin limits are not
+    /// sorted in increasing order, or if the interpolation ranges in `subgrid_params` are not
+    /// positive or their minima are larger than their maxima. The error names the index of the
+    /// offending channel or bin limit.
+    pub fn try_new(
+        channels: Vec<Channel>,
+        orders: Vec<Order>,
+        bin_limits: Vec<f64>,
+        subgrid_params: SubgridParams,
+    ) -> Result<Self, GridError> {
+        Self::validate_channels(&channels)?;
+        Self::validate(&bin_limits, &subgrid_params)?;
+
+        Ok(Self {
             subgrids: Array3::from_shape_simple_fn(
                 (orders.len(), bin_limits.len() - 1, channels.len()),
                 || EmptySubgridV1.into(),
@@ -245,7 +827,85 @@ impl Grid {
                     .into(),
             )),
             subgrid_params,
+        })
+    }
+
+    fn validate(bin_limits: &[f64], subgrid_params: &SubgridParams) -> Result<(), GridError> {
+        Self::validate_bin_limits(bin_limits)?;
+
+        for (variable, min, max) in [
+            ("x", subgrid_params.x_min(), subgrid_params.x_max()),
+            ("q2", subgrid_params.q2_min(), subgrid_params.q2_max()),
+        ] {
+            // written such that NaNs are rejected as well
+            if !((min > 0.0) && (min <= max)) {
+                return Err(GridError::InvalidInterpolationRange { variable, min, max });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_channels(channels: &[Channel]) -> Result<(), GridError> {
+        for (channel, entries) in channels.iter().enumerate() {
+            if let Some((entry, &(_, _, factor))) = entries
+                .entry()
+                .iter()
+                .enumerate()
+                .find(|(_, &(_, _, factor))| !factor.is_finite())
+            {
+                return Err(GridError::NonFiniteChannelFactor {
+                    channel,
+                    entry,
+                    factor,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_bin_limits(bin_limits: &[f64]) -> Result<(), GridError> {
+        if bin_limits.len() < 2 {
+            return Err(GridError::TooFewBinLimits {
+                limits: bin_limits.len(),
+            });
+        }
+
+        if let Some((index, &limit)) = bin_limits
+            .iter()
+            .enumerate()
+            .find(|(_, limit)| !limit.is_finite())
+        {
+            return Err(GridError::NonFiniteBinLimit { index, limit });
+        }
+
+        // a stable sort keeps repeated limits in the order of their indices
+        let mut sorted_limits: Vec<_> = bin_limits.iter().copied().enumerate().collect();
+        sorted_limits.sort_by(|(_, lhs), (_, rhs)| lhs.total_cmp(rhs));
+
+        if let Some(limits) = sorted_limits
+            .windows(2)
+            .find(|limits| limits[0].1.total_cmp(&limits[1].1).is_eq())
+        {
+            return Err(GridError::RepeatedBinLimit {
+                index: limits[1].0,
+                previous: limits[0].0,
+                limit: limits[1].1,
+            });
+        }
+
+        if let Some(index) = bin_limits
+            .windows(2)
+            .position(|limits| limits[0] > limits[1])
+        {
+            return Err(GridError::UnsortedBinLimits {
+                index: index + 1,
+                limit: bin_limits[index + 1],
+            });
         }
+
+        Ok(())
     }
 
     /// Constructor. This function can be used like `new`, but the additional parameter
@@ -256,7 +916,8 @@ impl Grid {
     ///
     /// # Errors
     ///
-    /// If `subgrid_type` is none of the values listed above, an error is returned.
+    /// If `subgrid_type` is none of the values listed above, an error is returned. The same errors
+    /// as for [`Grid::try_new`] are returned for invalid arguments.
     pub fn with_subgrid_type(
         channels: Vec<Channel>,
         orders: Vec<Order>,
@@ -275,6 +936,9 @@ impl Grid {
             _ => return Err(GridError::UnknownSubgridType(subgrid_type.to_owned())),
         };
 
+        Self::validate_channels(&channels)?;
+        Self::validate(&bin_limits, &subgrid_params)?;
+
         Ok(Self {
             subgrids: Array3::from_shape_simple_fn(
                 (orders.len(), bin_limits.len() - 1, channels.len()),
@@ -313,7 +977,7 @@ impl Grid {
         }
     }
 
-    fn pdg_channels(&self) -> Cow<[Channel]> {
+    pub(crate) fn pdg_channels(&self) -> Cow<[Channel]> {
         match self.pid_basis() {
             PidBasis::Evol => self
                 .channels
@@ -330,9 +994,14 @@ impl Grid {
     /// first factor varies the renormalization scale, the second the factorization scale. Note
     /// that for the variation to be trusted all non-zero log-grids must be contained.
     ///
+    /// This is a shortcut for [`Grid::convolve_with_options`] with the corresponding fields of
+    /// [`ConvolveOptions`].
+    ///
     /// # Panics
     ///
-    /// TODO
+    /// Panics if the hadrons of `lumi_cache` do not match the convolutions of this `Grid`, or if
+    /// `order_mask` or `channel_mask` are neither empty nor have at least as many entries as this
+    /// `Grid` has orders or channels, respectively.
     pub fn convolve(
         &self,
         lumi_cache: &mut LumiCache,
@@ -341,7 +1010,77 @@ impl Grid {
         channel_mask: &[bool],
         xi: &[(f64, f64)],
     ) -> Vec<f64> {
-        lumi_cache.setup(self, xi).unwrap();
+        self.convolve_with_options(
+            slice::from_mut(lumi_cache),
+            &ConvolveOptions {
+                order_mask,
+                bin_indices,
+                channel_mask,
+                xi,
+                ..ConvolveOptions::default()
+            },
+        )
+    }
+
+    /// Perform a convolution using the PDFs and strong couplings in `lumi_caches`, with the
+    /// orders, bins, channels, scale variations and corrections selected by `options`; see
+    /// [`ConvolveOptions`] for a description of each option. For each bin and scale variation the
+    /// result is the sum of the contributions of all selected subgrids, divided by the bin width.
+    ///
+    /// The results are reproducible bit by bit, because the contributions are always summed in
+    /// the same order: the subgrids are visited by increasing order index, then bin index and
+    /// then channel index, such that for each bin the contributions of the channels of the first
+    /// order are added first, and within each subgrid the nodes are summed in the order given by
+    /// [`Subgrid::convolve`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lumi_caches` is empty, if the hadrons of the caches do not match the
+    /// convolutions of this `Grid`, if [`ConvolveOptions::order_mask`] or
+    /// [`ConvolveOptions::channel_mask`] are neither empty nor have at least as many entries as
+    /// this `Grid` has orders or channels, respectively, if [`ConvolveOptions::bin_caches`] or
+    /// [`ConvolveOptions::bin_corrections`] are neither empty nor have an entry for each bin, or
+    /// if an entry of [`ConvolveOptions::bin_caches`] is not a valid index of `lumi_caches`.
+    pub fn convolve_with_options(
+        &self,
+        lumi_caches: &mut [LumiCache],
+        options: &ConvolveOptions,
+    ) -> Vec<f64> {
+        let &ConvolveOptions {
+            order_mask,
+            bin_indices,
+            channel_mask,
+            xi,
+            bin_caches,
+            bin_corrections,
+            reweight,
+            compensated,
+        } = options;
+
+        assert!(
+            !lumi_caches.is_empty(),
+            "`lumi_caches` must contain at least one cache"
+        );
+        assert!(
+            bin_caches.is_empty() || (bin_caches.len() == self.bin_info().bins()),
+            "`bin_caches` must have an entry for each of the {} bins, but it has {}",
+            self.bin_info().bins(),
+            bin_caches.len()
+        );
+        assert!(
+            bin_caches.iter().all(|&index| index < lumi_caches.len()),
+            "`bin_caches` refers to caches that are not in `lumi_caches`"
+        );
+        assert!(
+            bin_corrections.is_empty() || (bin_corrections.len() == self.bin_info().bins()),
+            "`bin_corrections` must have an entry for each of the {} bins, but it has {}",
+            self.bin_info().bins(),
+            bin_corrections.len()
+        );
+
+        for lumi_cache in lumi_caches.iter_mut() {
+            lumi_cache.setup(self, xi).unwrap();
+        }
 
         let bin_indices = if bin_indices.is_empty() {
             (0..self.bin_info().bins()).collect()
@@ -349,17 +1088,16 @@ impl Grid {
             bin_indices.to_vec()
         };
         let mut bins = vec![0.0; bin_indices.len() * xi.len()];
+        let mut compensations = vec![0.0; bins.len()];
         let normalizations = self.bin_info().normalizations();
         let pdg_channels = self.pdg_channels();
 
         for (xi_index, &(xir, xif)) in xi.iter().enumerate() {
+            // `indexed_iter` visits the subgrids in the logical order of their indices, independent
+            // of the memory layout, which makes the summation order deterministic
             for ((ord, bin, chan), subgrid) in self.subgrids.indexed_iter() {
                 let order = &self.orders[ord];
 
-                if ((order.logxir > 0) && (xir == 1.0)) || ((order.logxif > 0) && (xif == 1.0)) {
-                    continue;
-                }
-
                 if (!order_mask.is_empty() && !order_mask[ord])
                     || (!channel_mask.is_empty() && !channel_mask[chan])
                 {
@@ -374,72 +1112,221 @@ impl Grid {
                     continue;
                 }
 
-                let channel = &pdg_channels[chan];
-                let mu2_grid = subgrid.mu2_grid();
-                let x1_grid = subgrid.x1_grid();
-                let x2_grid = subgrid.x2_grid();
-
-                lumi_cache.set_grids(&mu2_grid, &x1_grid, &x2_grid, xir, xif);
-
-                let mut value =
-                    subgrid.convolve(&x1_grid, &x2_grid, &mu2_grid, &mut |ix1, ix2, imu2| {
-                        let x1 = x1_grid[ix1];
-                        let x2 = x2_grid[ix2];
-                        let mut lumi = 0.0;
-
-                        for entry in channel.entry() {
-                            let xfx1 = lumi_cache.xfx1(entry.0, ix1, imu2);
-                            let xfx2 = lumi_cache.xfx2(entry.1, ix2, imu2);
-                            lumi += xfx1 * xfx2 * entry.2 / (x1 * x2);
-                        }
-
-                        let alphas = lumi_cache.alphas(imu2);
-
-                        lumi *= alphas.powi(order.alphas.try_into().unwrap());
-                        lumi
-                    });
-
-                if order.logxir > 0 {
-                    value *= (xir * xir).ln().powi(order.logxir.try_into().unwrap());
+                let lumi_cache = &mut lumi_caches[bin_caches.get(bin).copied().unwrap_or(0)];
+                let value = Self::convolve_subgrid_value(
+                    lumi_cache,
+                    order,
+                    &pdg_channels[chan],
+                    subgrid,
+                    xir,
+                    xif,
+                    reweight
+                        .map(|reweight| move |mu2: &Mu2, x1, x2| reweight(chan, mu2, x1, x2))
+                        .as_ref()
+                        .map(|reweight| reweight as NodeReweight),
+                ) / normalizations[bin];
+                let index = xi_index + xi.len() * bin_index;
+                let sum = bins[index] + value;
+
+                if compensated {
+                    // collect the low-order bits lost in `sum`
+                    compensations[index] += if bins[index].abs() >= value.abs() {
+                        (bins[index] - sum) + value
+                    } else {
+                        (value - sum) + bins[index]
+                    };
                 }
 
-                if order.logxif > 0 {
-                    value *= (xif * xif).ln().powi(order.logxif.try_into().unwrap());
-                }
+                bins[index] = sum;
+            }
+        }
+
+        if compensated {
+            for (bin, compensation) in bins.iter_mut().zip(compensations) {
+                *bin += compensation;
+            }
+        }
 
-                bins[xi_index + xi.len() * bin_index] += value / normalizations[bin];
+        if !bin_corrections.is_empty() {
+            for (index, value) in bins.iter_mut().enumerate() {
+                *value *= bin_corrections[bin_indices[index / xi.len()]];
             }
         }
 
         bins
     }
 
-    /// Convolutes a single subgrid `(order, bin, channel)` with the PDFs strong coupling given by
-    /// `xfx1`, `xfx2` and `alphas`. The convolution result is fully differentially, such that the
-    /// axes of the result correspond to the values given by the subgrid `q2`, `x1` and `x2` grid
-    /// values.
+    /// Same as [`Grid::convolve_with_options`], but instead of functions the PDFs and the strong
+    /// coupling are given as tables of values at the nodes of this `Grid`, see
+    /// [`XfxTable::from_grid`] and [`AlphasTable::from_grid`]. If `xfx` has a single table it is
+    /// used for both convolutions, otherwise the first table is used for the first and the second
+    /// table for the second convolution. This avoids repeated evaluations of the PDFs when the
+    /// same tables are used for many convolutions, for example with different orders or bins. The
+    /// nodes of the tables must be sorted in ascending order, which the `from_grid` constructors
+    /// guarantee.
+    ///
+    /// # Errors
+    ///
+    /// If `xfx` does not have one or two tables, if the hadrons of the tables do not match the
+    /// convolutions of this `Grid`, or if the tables do not contain a value that is needed by the
+    /// convolution, an error is returned.
     ///
     /// # Panics
     ///
-    /// TODO
-    pub fn convolve_subgrid(
+    /// Panics if [`ConvolveOptions::bin_caches`] is not empty, or if
+    /// [`ConvolveOptions::bin_corrections`] is neither empty nor has an entry for each bin.
+    pub fn convolve_with_tables(
         &self,
-        lumi_cache: &mut LumiCache,
-        ord: usize,
-        bin: usize,
-        channel: usize,
-        xir: f64,
-        xif: f64,
-    ) -> Array3<f64> {
-        lumi_cache.setup(self, &[(xir, xif)]).unwrap();
+        xfx: &[XfxTable],
+        alphas: &AlphasTable,
+        options: &ConvolveOptions,
+    ) -> Result<Vec<f64>, GridError> {
+        assert!(
+            options.bin_caches.is_empty(),
+            "`bin_caches` is not supported for convolutions with tables"
+        );
+
+        let mut lumi_cache = LumiCache::with_tables(xfx, alphas)?;
+        // find all errors before the convolution, which would panic
+        lumi_cache.setup(self, options.xi)?;
 
+        Ok(self.convolve_with_options(slice::from_mut(&mut lumi_cache), options))
+    }
+
+    /// Returns the contributions of every order, channel and bin to the predictions of
+    /// [`Grid::convolve`] with the scale factors `xi`, which are calculated in a single pass over
+    /// all subgrids. The axes of the returned array are in this order: the index of the order, the
+    /// index of the channel and the index of the bin, as in [`Grid::orders`], [`Grid::channels`]
+    /// and [`Grid::bin_info`]. Summing over the first two axes gives the results of
+    /// [`Grid::convolve`] without masks. Orders with logarithms of the scale factors that vanish
+    /// for `xi` contribute zeros.
+    ///
+    /// The array has as many entries as the grid has subgrids, which for grids with many orders,
+    /// channels and bins may require much more memory than the results of [`Grid::convolve`]: eight
+    /// bytes per order, channel and bin.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the hadrons of `lumi_cache` do not match the convolutions of this `Grid`.
+    pub fn convolve_full(&self, lumi_cache: &mut LumiCache, xi: (f64, f64)) -> Array3<f64> {
+        lumi_cache.setup(self, &[xi]).unwrap();
+
+        let (xir, xif) = xi;
         let normalizations = self.bin_info().normalizations();
         let pdg_channels = self.pdg_channels();
+        let (orders, bins, channels) = self.subgrids.dim();
+        let mut result = Array3::zeros((orders, channels, bins));
 
-        let subgrid = &self.subgrids[[ord, bin, channel]];
-        let order = &self.orders[ord];
+        for ((ord, bin, chan), subgrid) in self.subgrids.indexed_iter() {
+            let order = &self.orders[ord];
+
+            if subgrid.is_empty() {
+                continue;
+            }
+
+            result[[ord, chan, bin]] = Self::convolve_subgrid_value(
+                lumi_cache,
+                order,
+                &pdg_channels[chan],
+                subgrid,
+                xir,
+                xif,
+                None,
+            ) / normalizations[bin];
+        }
+
+        result
+    }
+
+    /// Convolves `subgrid`, which belongs to `order` and `channel`, and returns its integrated
+    /// contribution before the division by the bin width. Orders with logarithms of scale factors
+    /// that are one don't contribute. If given, the luminosity of each node is multiplied with
+    /// `reweight`, which is called with the scales and momentum fractions of the node.
+    fn convolve_subgrid_value(
+        lumi_cache: &mut LumiCache,
+        order: &Order,
+        channel: &Channel,
+        subgrid: &SubgridEnum,
+        xir: f64,
+        xif: f64,
+        reweight: Option<NodeReweight>,
+    ) -> f64 {
+        if ((order.logxir > 0) && (xir == 1.0)) || ((order.logxif > 0) && (xif == 1.0)) {
+            return 0.0;
+        }
 
-        let channel = &pdg_channels[channel];
+        let mu2_grid = subgrid.mu2_grid();
+        let x1_grid = subgrid.x1_grid();
+        let x2_grid = subgrid.x2_grid();
+
+        lumi_cache.set_grids(&mu2_grid, &x1_grid, &x2_grid, xir, xif);
+
+        let mut value = subgrid.convolve(&x1_grid, &x2_grid, &mu2_grid, &mut |ix1, ix2, imu2| {
+            let x1 = x1_grid[ix1];
+            let x2 = x2_grid[ix2];
+            let mut lumi = 0.0;
+
+            for entry in channel.entry() {
+                let xfx1 = lumi_cache.xfx1(entry.0, ix1, imu2);
+                let xfx2 = lumi_cache.xfx2(entry.1, ix2, imu2);
+                lumi += xfx1 * xfx2 * entry.2 / (x1 * x2);
+            }
+
+            let alphas = lumi_cache.alphas(imu2);
+
+            lumi *= alphas.powi(order.alphas.try_into().unwrap());
+
+            if let Some(reweight) = reweight {
+                lumi *= reweight(&mu2_grid[imu2], x1, x2);
+            }
+
+            lumi
+        });
+
+        if order.logxir > 0 {
+            value *= (xir * xir).ln().powi(order.logxir.try_into().unwrap());
+        }
+
+        if order.logxif > 0 {
+            value *= (xif * xif).ln().powi(order.logxif.try_into().unwrap());
+        }
+
+        value
+    }
+
+    /// Convolutes a single subgrid `(order, bin, channel)` with the PDFs strong coupling given by
+    /// `xfx1`, `xfx2` and `alphas`. The convolution result is fully differentially, such that the
+    /// axes of the result correspond to the values given by the subgrid `q2`, `x1` and `x2` grid
+    /// values. The values are multiplied with the same scale logarithms and divided by the same
+    /// bin normalization as in [`Grid::convolve`], so that their sum is the contribution of the
+    /// subgrid to the bin. If the subgrid is empty, an empty array is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ord`, `bin` or `channel` are out of range, or if `lumi_cache` does not provide
+    /// the PDFs needed by this grid.
+    pub fn convolve_subgrid(
+        &self,
+        lumi_cache: &mut LumiCache,
+        ord: usize,
+        bin: usize,
+        channel: usize,
+        xir: f64,
+        xif: f64,
+    ) -> Array3<f64> {
+        lumi_cache.setup(self, &[(xir, xif)]).unwrap();
+
+        let normalizations = self.bin_info().normalizations();
+        let pdg_channels = self.pdg_channels();
+
+        let subgrid = &self.subgrids[[ord, bin, channel]];
+        let order = &self.orders[ord];
+
+        if subgrid.is_empty() {
+            return Array3::zeros((0, 0, 0));
+        }
+
+        let channel = &pdg_channels[channel];
         let mu2_grid = subgrid.mu2_grid();
         let x1_grid = subgrid.x1_grid();
         let x2_grid = subgrid.x2_grid();
@@ -485,17 +1372,29 @@ impl Grid {
     /// TODO
     pub fn fill(&mut self, order: usize, observable: f64, channel: usize, ntuple: &Ntuple<f64>) {
         if let Some(bin) = self.bin_limits.index(observable) {
-            let subgrid = &mut self.subgrids[[order, bin, channel]];
-            if let SubgridEnum::EmptySubgridV1(_) = subgrid {
-                if let MoreMembers::V3(mmv3) = &self.more_members {
-                    *subgrid = mmv3.subgrid_template.clone_empty();
-                } else {
-                    unreachable!();
-                }
-            }
+            self.fill_at_bin(order, bin, channel, ntuple);
+        }
+    }
 
-            subgrid.fill(ntuple);
+    /// Fills the grid with an ntuple for the given `order`, `bin`, and `channel`. In contrast to
+    /// [`Grid::fill`] the bin is not determined from an observable, which saves its lookup when
+    /// the caller already knows the bin.
+    ///
+    /// # Panics
+    ///
+    /// The caller is responsible for passing a valid `bin` index; if `bin`, `order` or `channel`
+    /// is out of range, this method panics.
+    pub fn fill_at_bin(&mut self, order: usize, bin: usize, channel: usize, ntuple: &Ntuple<f64>) {
+        let subgrid = &mut self.subgrids[[order, bin, channel]];
+        if let SubgridEnum::EmptySubgridV1(_) = subgrid {
+            if let MoreMembers::V3(mmv3) = &self.more_members {
+                *subgrid = mmv3.subgrid_template.clone_empty();
+            } else {
+                unreachable!();
+            }
         }
+
+        subgrid.fill(ntuple);
     }
 
     /// Construct a `Grid` by deserializing it from `reader`. Reading is buffered.
@@ -504,18 +1403,78 @@ impl Grid {
     ///
     /// If reading from the compressed or uncompressed stream fails an error is returned.
     pub fn read(reader: impl Read) -> Result<Self, GridError> {
+        Self::read_with(reader, |file_version, mut reader| {
+            if file_version == 0 {
+                Self::deserialize_from(reader)
+            } else {
+                let header: OwnedGridHeaderV1 = Self::deserialize_from(&mut reader)?;
+                header.into_grid(Some(Self::deserialize_from(reader)?))
+            }
+        })
+    }
+
+    /// Construct a [`GridHeader`] by deserializing everything but the subgrids from `reader`.
+    /// Files written with file version 1, the one written by [`Grid::write`], store the header
+    /// before the subgrids, which are therefore not read at all. Files with version 0 store the
+    /// subgrids first and don't record their sizes, so their subgrids must still be decoded,
+    /// which takes about as long as reading the entire grid; each subgrid is dropped right after
+    /// it is decoded, however, so that at most one subgrid is kept in memory. Reading is
+    /// buffered.
+    ///
+    /// # Errors
+    ///
+    /// If reading from the compressed or uncompressed stream fails an error is returned.
+    pub fn read_header(reader: impl Read) -> Result<GridHeader, GridError> {
+        let grid = Self::read_with(reader, |file_version, reader| {
+            if file_version == 0 {
+                let GridHeaderData {
+                    subgrids: DiscardedSubgrids(shape),
+                    channels,
+                    bin_limits,
+                    orders,
+                    subgrid_params,
+                    more_members,
+                } = Self::deserialize_from(reader)?;
+
+                Ok(Self {
+                    subgrids: Array3::from_shape_simple_fn(shape, || EmptySubgridV1.into()),
+                    channels,
+                    bin_limits,
+                    orders,
+                    subgrid_params,
+                    more_members,
+                })
+            } else {
+                Self::deserialize_from::<OwnedGridHeaderV1>(reader)?.into_grid(None)
+            }
+        })?;
+
+        Ok(GridHeader { grid })
+    }
+
+    fn deserialize_from<T: DeserializeOwned>(reader: impl Read) -> Result<T, GridError> {
+        bincode::deserialize_from(reader).map_err(GridError::ReadFailure)
+    }
+
+    fn read_with<T>(
+        reader: impl Read,
+        read: impl FnOnce(u64, &mut dyn BufRead) -> Result<T, GridError>,
+    ) -> Result<T, GridError> {
         let mut reader = BufReader::new(reader);
         let buffer = reader.fill_buf().map_err(GridError::IoFailure)?;
         let magic_bytes: [u8; 4] = buffer[0..4].try_into().unwrap_or_else(|_| unreachable!());
 
         if u32::from_le_bytes(magic_bytes) == 0x18_4D_22_04 {
-            Self::read_uncompressed(FrameDecoder::new(reader))
+            Self::read_uncompressed(FrameDecoder::new(reader), read)
         } else {
-            Self::read_uncompressed(reader)
+            Self::read_uncompressed(reader, read)
         }
     }
 
-    fn read_uncompressed(mut reader: impl BufRead) -> Result<Self, GridError> {
+    fn read_uncompressed<T>(
+        mut reader: impl BufRead,
+        read: impl FnOnce(u64, &mut dyn BufRead) -> Result<T, GridError>,
+    ) -> Result<T, GridError> {
         let magic_bytes: [u8; 16] = reader.fill_buf().map_err(GridError::IoFailure)?[0..16]
             .try_into()
             .unwrap_or_else(|_| unreachable!());
@@ -531,30 +1490,45 @@ impl Grid {
             0
         };
 
-        if file_version != 0 {
+        if file_version > FILE_VERSION {
             return Err(GridError::FileVersionMismatch {
                 file_version,
-                supported_version: 0,
+                supported_version: FILE_VERSION,
             });
         }
 
-        bincode::deserialize_from(reader).map_err(GridError::ReadFailure)
+        read(file_version, &mut reader)
     }
 
-    /// Serializes `self` into `writer`. Writing is buffered.
+    /// Serializes `self` into `writer`, using the file version 1, which stores the orders,
+    /// channels, bins and metadata before the subgrids; see [`Grid::read_header`]. Files with
+    /// this version can't be read by versions of this library up to 0.8.2, which only support the
+    /// file version 0. Writing is buffered.
     ///
     /// # Errors
     ///
     /// If writing fails an error is returned.
     pub fn write(&self, writer: impl Write) -> Result<(), GridError> {
         let mut writer = BufWriter::new(writer);
-        let file_header = b"PineAPPL\0\0\0\0\0\0\0\0";
+        let mut file_header = *b"PineAPPL\0\0\0\0\0\0\0\0";
+        file_header[8..16].copy_from_slice(&FILE_VERSION.to_le_bytes());
 
         // first write PineAPPL file header
-        writer.write(file_header).map_err(GridError::IoFailure)?;
-
-        // then serialize
-        bincode::serialize_into(writer, self).map_err(GridError::WriteFailure)
+        writer.write(&file_header).map_err(GridError::IoFailure)?;
+
+        // then serialize the header and the subgrids
+        bincode::serialize_into(
+            &mut writer,
+            &GridHeaderV1 {
+                channels: &self.channels,
+                bin_limits: &self.bin_limits,
+                orders: &self.orders,
+                subgrid_params: &self.subgrid_params,
+                more_members: &self.more_members,
+            },
+        )
+        .map_err(GridError::WriteFailure)?;
+        bincode::serialize_into(writer, &self.subgrids).map_err(GridError::WriteFailure)
     }
 
     /// Serializes `self` into `writer`, using LZ4 compression. Writing is buffered.
@@ -600,12 +1574,82 @@ impl Grid {
         }
     }
 
+    /// Return the number of events filled into each subgrid in the current session, indexed by
+    /// order, bin and channel, in the same way as [`Grid::subgrids`]. Events with zero weight and
+    /// events outside the interpolation range are not counted.
+    ///
+    /// The counts are only meant to diagnose undersampled bins while a grid is being filled. Only
+    /// subgrids of type [`LagrangeSubgridV2`] count events, which costs eight bytes per subgrid.
+    /// The counters are not written to disk, so all counts of a grid that was read from a file are
+    /// zero, and they are lost when [`Grid::optimize`] converts the subgrids. Merging grids and
+    /// [`Grid::resample_nodes`] keep them.
+    #[must_use]
+    pub fn session_fill_counts(&self) -> Array3<u64> {
+        self.subgrids.map(|subgrid| {
+            if let SubgridEnum::LagrangeSubgridV2(subgrid) = subgrid {
+                subgrid.session_fill_count()
+            } else {
+                0
+            }
+        })
+    }
+
+    /// Return the total number of events filled into this grid in the current session, which is
+    /// the sum of [`Grid::session_fill_counts`] and has the same limitations.
+    #[must_use]
+    pub fn session_total_events(&self) -> u64 {
+        self.session_fill_counts().sum()
+    }
+
     /// Return the channels for this `Grid`.
     #[must_use]
     pub fn channels(&self) -> &[Channel] {
         &self.channels
     }
 
+    /// Return the indices of all channels for which at least one entry has the particle ID `pid` in
+    /// the convolution with index `slot`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot` is larger than `1`.
+    #[must_use]
+    pub fn channels_with_pid(&self, slot: usize, pid: i32) -> Vec<usize> {
+        assert!(slot < 2, "slot must be `0` or `1`, is `{slot}`");
+
+        self.channels
+            .iter()
+            .enumerate()
+            .filter_map(|(index, channel)| {
+                channel
+                    .entry()
+                    .iter()
+                    .any(|&(a, b, _)| [a, b][slot] == pid)
+                    .then_some(index)
+            })
+            .collect()
+    }
+
+    /// Return the distinct particle IDs of all channel entries in the convolution with index
+    /// `slot`, sorted in ascending order. The particle IDs are given in the convention returned by
+    /// [`Grid::pid_basis`], and they are the ones that a convolution function for `slot` must
+    /// provide.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot` is larger than `1`.
+    #[must_use]
+    pub fn distinct_pids(&self, slot: usize) -> Vec<i32> {
+        assert!(slot < 2, "slot must be `0` or `1`, is `{slot}`");
+
+        self.channels
+            .iter()
+            .flat_map(|channel| channel.entry().iter().map(|&(a, b, _)| [a, b][slot]))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
     /// Merges the bins for the corresponding range together in a single one.
     ///
     /// # Errors
@@ -655,1423 +1699,6585 @@ impl Grid {
             }
         }
 
+        self.remove_bin_central_scales();
+        self.remove_reference();
+
         Ok(())
     }
 
-    /// Merges the non-empty `Subgrid`s contained in `other` into `self`.
+    /// Merges the bins of each range in `groups` into a single bin. In contrast to calling
+    /// [`Grid::merge_bins`] once for each range, all ranges refer to the bin indices before any
+    /// merging takes place, and all bins are merged in a single pass. Bins that are not part of any
+    /// range are kept unchanged.
     ///
     /// # Errors
     ///
-    /// If the bin limits of `self` and `other` are different and if the bin limits of `other` can
-    /// not be merged with `self` an error is returned.
-    ///
-    /// # Panics
-    ///
-    /// TODO
-    pub fn merge(&mut self, mut other: Self) -> Result<(), GridError> {
-        let mut new_orders: Vec<Order> = Vec::new();
-        let mut new_bins = 0;
-        let mut new_entries: Vec<Channel> = Vec::new();
+    /// Returns an error if a range is empty or outside the available bins, if the ranges overlap
+    /// or are not in increasing order, or if the bins of a range are not consecutive. In each case
+    /// the grid is not modified.
+    pub fn merge_bins_grouped(&mut self, groups: &[Range<usize>]) -> Result<(), GridError> {
+        let bins = self.bin_info().bins();
+
+        for group in groups {
+            if group.is_empty() {
+                return Err(GridError::MergeBinError(MergeBinError::EmptyRange(
+                    group.clone(),
+                )));
+            }
 
-        if self.bin_info() != other.bin_info() {
-            let lhs_bins = self.bin_info().bins();
-            new_bins = other.bin_info().bins();
+            if group.end > bins {
+                return Err(GridError::MergeBinError(MergeBinError::InvalidRange {
+                    range: group.clone(),
+                    bins,
+                }));
+            }
+        }
 
-            let lhs_remapper = self.remapper_mut();
-            let rhs_remapper = other.remapper();
+        if let Some(pair) = groups.windows(2).find(|pair| pair[1].start < pair[0].end) {
+            return Err(GridError::MergeBinError(MergeBinError::OverlappingRanges {
+                lhs: pair[0].clone(),
+                rhs: pair[1].clone(),
+            }));
+        }
 
-            if let Some(lhs) = lhs_remapper {
-                if let Some(rhs) = rhs_remapper {
-                    lhs.merge(rhs).map_err(GridError::MergeBinError)?;
+        let mut bin_limits = self.bin_limits.clone();
+        let mut remapper = self.remapper().cloned();
 
-                    let a = u32::try_from(lhs_bins).unwrap_or_else(|_| unreachable!());
-                    let b = u32::try_from(lhs_bins + new_bins).unwrap_or_else(|_| unreachable!());
+        // merging the last range first doesn't change the indices of the other ranges
+        for group in groups.iter().rev() {
+            bin_limits
+                .merge_bins(group.clone())
+                .map_err(GridError::MergeBinError)?;
 
-                    self.bin_limits = BinLimits::new((0..=b).map(f64::from).collect());
-                    other.bin_limits = BinLimits::new((a..=b).map(f64::from).collect());
-                } else {
-                    // Return an error
-                    todo!();
-                }
-            } else if rhs_remapper.is_none() {
-                self.bin_limits
-                    .merge(&other.bin_limits)
-                    .map_err(GridError::InvalidBinLimits)?;
-            } else {
-                // Return an error
-                todo!();
+            if let Some(remapper) = &mut remapper {
+                remapper
+                    .merge_bins(group.clone())
+                    .map_err(GridError::MergeBinError)?;
             }
         }
 
-        for ((i, _, k), _) in other
-            .subgrids
-            .indexed_iter_mut()
-            .filter(|((_, _, _), subgrid)| !subgrid.is_empty())
-        {
-            let other_order = &other.orders[i];
-            let other_entry = &other.channels[k];
+        // for each bin the index of the bin it is merged into
+        let indices: Vec<_> = (0..bins)
+            .scan(0, |next, bin| {
+                if !groups
+                    .iter()
+                    .any(|group| (group.start + 1..group.end).contains(&bin))
+                {
+                    *next += 1;
+                }
 
-            if !self
-                .orders
-                .iter()
-                .chain(new_orders.iter())
-                .any(|x| x == other_order)
-            {
-                new_orders.push(other_order.clone());
+                Some(*next - 1)
+            })
+            .collect();
+
+        let bin_count = bin_limits.bins();
+        self.bin_limits = bin_limits;
+
+        if let Some(remapper) = remapper {
+            self.set_remapper(remapper)?;
+        }
+
+        let mut old_subgrids = mem::replace(
+            &mut self.subgrids,
+            Array3::from_shape_simple_fn(
+                (self.orders.len(), bin_count, self.channels.len()),
+                || EmptySubgridV1.into(),
+            ),
+        );
+
+        for ((order, bin, channel), subgrid) in old_subgrids.indexed_iter_mut() {
+            if subgrid.is_empty() {
+                continue;
             }
 
-            if !self
-                .channels()
-                .iter()
-                .chain(new_entries.iter())
-                .any(|y| y == other_entry)
-            {
-                new_entries.push(other_entry.clone());
+            let new_subgrid = &mut self.subgrids[[order, indices[bin], channel]];
+
+            if new_subgrid.is_empty() {
+                mem::swap(new_subgrid, subgrid);
+            } else {
+                new_subgrid.merge(subgrid, false);
             }
         }
 
-        if !new_orders.is_empty() || !new_entries.is_empty() || (new_bins != 0) {
-            self.increase_shape(&(new_orders.len(), new_bins, new_entries.len()));
+        self.remove_bin_central_scales();
+        self.remove_reference();
+
+        Ok(())
+    }
+
+    /// Replaces the bins of this grid with the one-dimensional bins given by `new_fill_limits`.
+    /// Each subgrid is distributed onto the new bins that overlap with its bin, proportionally to
+    /// the length of the overlap divided by the width of its bin, which assumes that the cross
+    /// section is constant within each old bin. Parts of the old bins outside the new limits are
+    /// dropped. A one-dimensional bin remapper is removed, so that the normalizations of the new
+    /// bins are their widths.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bins of this grid have more than one dimension, if the new limits
+    /// are invalid (see [`Grid::try_new`]), or if they are not within the limits of the old bins.
+    /// In each case the grid is not modified.
+    pub fn rebin_to(&mut self, new_fill_limits: Vec<f64>) -> Result<(), GridError> {
+        let bin_info = self.bin_info();
+        let dimensions = bin_info.dimensions();
+
+        if dimensions != 1 {
+            return Err(GridError::MultiDimensionalBins { dimensions });
         }
 
-        self.orders.append(&mut new_orders);
-        self.channels.append(&mut new_entries);
+        Self::validate_bin_limits(&new_fill_limits)?;
 
-        let bin_indices: Vec<_> = (0..other.bin_info().bins())
-            .map(|bin| {
-                self.bin_info()
-                    .find_bin(&other.bin_info().bin_limits(bin))
-                    .unwrap_or_else(|| panic!("failed for {bin}"))
+        let old_limits: Vec<_> = bin_info
+            .limits()
+            .into_iter()
+            .map(|limits| limits[0])
+            .collect();
+        let old_min = old_limits
+            .iter()
+            .map(|&(left, _)| left)
+            .fold(f64::INFINITY, f64::min);
+        let old_max = old_limits
+            .iter()
+            .map(|&(_, right)| right)
+            .fold(f64::NEG_INFINITY, f64::max);
+        // `validate_bin_limits` makes sure there are at least two limits
+        let min = new_fill_limits[0];
+        let max = new_fill_limits[new_fill_limits.len() - 1];
+
+        if min < old_min || max > old_max {
+            return Err(GridError::BinLimitsOutOfRange {
+                min,
+                max,
+                old_min,
+                old_max,
+            });
+        }
+
+        // for each old bin the new bins it overlaps with and the fraction of the overlap
+        let fractions: Vec<Vec<_>> = old_limits
+            .iter()
+            .map(|&(left, right)| {
+                new_fill_limits
+                    .windows(2)
+                    .enumerate()
+                    .filter_map(|(new_bin, limits)| {
+                        let overlap = right.min(limits[1]) - left.max(limits[0]);
+                        (overlap > 0.0).then(|| (new_bin, overlap / (right - left)))
+                    })
+                    .collect()
             })
             .collect();
 
-        for ((i, j, k), subgrid) in other
-            .subgrids
-            .indexed_iter_mut()
-            .filter(|((_, _, _), subgrid)| !subgrid.is_empty())
-        {
-            let other_order = &other.orders[i];
-            let other_entry = &other.channels[k];
+        let bin_count = new_fill_limits.len() - 1;
+        self.bin_limits = BinLimits::new(new_fill_limits);
 
-            let self_i = self.orders.iter().position(|x| x == other_order).unwrap();
-            let self_j = bin_indices[j];
-            let self_k = self.channels.iter().position(|y| y == other_entry).unwrap();
+        match &mut self.more_members {
+            MoreMembers::V1(_) => {}
+            MoreMembers::V2(mmv2) => mmv2.remapper = None,
+            MoreMembers::V3(mmv3) => mmv3.remapper = None,
+        }
 
-            if self.subgrids[[self_i, self_j, self_k]].is_empty() {
-                mem::swap(&mut self.subgrids[[self_i, self_j, self_k]], subgrid);
-            } else {
-                self.subgrids[[self_i, self_j, self_k]].merge(&mut *subgrid, false);
+        let old_subgrids = mem::replace(
+            &mut self.subgrids,
+            Array3::from_shape_simple_fn(
+                (self.orders.len(), bin_count, self.channels.len()),
+                || EmptySubgridV1.into(),
+            ),
+        );
+
+        for ((order, bin, channel), subgrid) in old_subgrids.indexed_iter() {
+            if subgrid.is_empty() {
+                continue;
+            }
+
+            for &(new_bin, fraction) in &fractions[bin] {
+                let mut subgrid = subgrid.clone();
+                subgrid.scale(fraction);
+
+                let new_subgrid = &mut self.subgrids[[order, new_bin, channel]];
+
+                if new_subgrid.is_empty() {
+                    *new_subgrid = subgrid;
+                } else {
+                    new_subgrid.merge(&mut subgrid, false);
+                }
             }
         }
 
+        self.remove_bin_central_scales();
+        self.remove_reference();
+
         Ok(())
     }
 
-    /// Return a vector containing the type of convolutions performed with this grid.
+    /// Enables or disables the reweighting of the momentum fractions of the convolution with index
+    /// `convolution`. All subgrids of type [`LagrangeSubgridV2`] are rescaled such that the
+    /// results of convolutions do not change, and subgrids created by subsequent calls to
+    /// [`Grid::fill`] use the new setting. Other subgrid types are not changed, because they either
+    /// do not support reweighting or use the same setting for both convolutions.
     ///
     /// # Panics
     ///
-    /// Panics if the metadata key--value pairs `convolution_particle_1` and `convolution_type_1`,
-    /// or `convolution_particle_2` and `convolution_type_2` are not correctly set.
-    #[must_use]
-    pub fn convolutions(&self) -> Vec<Convolution> {
-        self.key_values().map_or_else(
-            // if there isn't any metadata, we assume two unpolarized proton-PDFs are used
-            || vec![Convolution::UnpolPDF(2212), Convolution::UnpolPDF(2212)],
-            |kv| {
-                // the current file format only supports exactly two convolutions
-                (1..=2)
-                    .map(|index| {
-                        // if there are key-value pairs `convolution_particle_1` and
-                        // `convolution_type_1` and the same with a higher index, we convert this
-                        // metadata into `Convolution`
-                        match (
-                            kv.get(&format!("convolution_particle_{index}"))
-                                .map(|s| s.parse::<i32>()),
-                            kv.get(&format!("convolution_type_{index}"))
-                                .map(String::as_str),
-                        ) {
-                            (_, Some("None")) => Convolution::None,
-                            (Some(Ok(pid)), Some("UnpolPDF")) => Convolution::UnpolPDF(pid),
-                            (Some(Ok(pid)), Some("PolPDF")) => Convolution::PolPDF(pid),
-                            (Some(Ok(pid)), Some("UnpolFF")) => Convolution::UnpolFF(pid),
-                            (Some(Ok(pid)), Some("PolFF")) => Convolution::PolFF(pid),
-                            (None, None) => {
-                                // if these key-value pairs are missing use the old metadata
-                                match kv
-                                    .get(&format!("initial_state_{index}"))
-                                    .map(|s| s.parse::<i32>())
-                                {
-                                    Some(Ok(pid)) => {
-                                        let condition = !self.channels().iter().all(|entry| {
-                                            entry.entry().iter().all(|&channels| match index {
-                                                1 => channels.0 == pid,
-                                                2 => channels.1 == pid,
-                                                _ => unreachable!(),
-                                            })
-                                        });
+    /// Panics if `convolution` is larger than `1`.
+    pub fn set_reweight(&mut self, convolution: usize, reweight: bool) {
+        assert!(
+            convolution < 2,
+            "convolution must be `0` or `1`, is `{convolution}`"
+        );
 
-                                        if condition {
-                                            Convolution::UnpolPDF(pid)
-                                        } else {
-                                            Convolution::None
-                                        }
-                                    }
-                                    None => Convolution::UnpolPDF(2212),
-                                    Some(Err(err)) => panic!("metadata 'initial_state_{index}' could not be parsed: {err}"),
-                                }
-                            }
-                            (None, Some(_)) => {
-                                panic!("metadata 'convolution_type_{index}' is missing")
-                            }
-                            (Some(_), None) => {
-                                panic!("metadata 'convolution_particle_{index}' is missing")
-                            }
-                            (Some(Ok(_)), Some(type_)) => {
-                                panic!("metadata 'convolution_type_{index} = {type_}' is unknown")
-                            }
-                            (Some(Err(err)), Some(_)) => panic!(
-                                "metadata 'convolution_particle_{index}' could not be parsed: {err}"
-                            ),
-                        }
-                    })
-                    .collect()
-            },
-        )
+        for subgrid in &mut self.subgrids {
+            if let SubgridEnum::LagrangeSubgridV2(subgrid) = subgrid {
+                subgrid.set_reweight(convolution, reweight);
+            }
+        }
+
+        if let MoreMembers::V3(Mmv3 {
+            subgrid_template: SubgridEnum::LagrangeSubgridV2(template),
+            ..
+        }) = &mut self.more_members
+        {
+            template.set_reweight(convolution, reweight);
+        }
     }
 
-    /// Set the convolution type for this grid for the corresponding `index`.
-    pub fn set_convolution(&mut self, index: usize, convolution: Convolution) {
-        // remove outdated metadata
-        self.key_values_mut()
-            .remove(&format!("initial_state_{}", index + 1));
+    /// Resamples every subgrid onto the interpolation nodes given by `subgrid_params`, which is
+    /// useful to harmonize grids with different interpolation parameters before merging them. Each
+    /// value of a subgrid is treated as an event at its node and interpolated onto the new nodes,
+    /// after which all subgrids are of type [`LagrangeSubgridV2`]. The new parameters are also used
+    /// for subgrids that are filled afterwards.
+    ///
+    /// Convolutions change by the interpolation error of the new nodes. For the default
+    /// [`SubgridParams`] the relative differences are typically smaller than `1e-4`. Resampling
+    /// onto the same nodes reproduces the convolutions up to rounding errors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the interpolation ranges in `subgrid_params` are invalid, if a node
+    /// lies outside of them, or if a subgrid has different renormalization and factorization
+    /// scales. In these cases the grid is left unchanged.
+    pub fn resample_nodes(&mut self, subgrid_params: &SubgridParams) -> Result<(), GridError> {
+        Self::validate(&self.bin_limits.limits(), subgrid_params)?;
 
-        let (type_, particle) = match convolution {
-            Convolution::UnpolPDF(pid) => ("UnpolPDF".to_owned(), pid.to_string()),
-            Convolution::PolPDF(pid) => ("PolPDF".to_owned(), pid.to_string()),
-            Convolution::UnpolFF(pid) => ("UnpolFF".to_owned(), pid.to_string()),
-            Convolution::PolFF(pid) => ("PolFF".to_owned(), pid.to_string()),
-            Convolution::None => ("None".to_owned(), String::new()),
-        };
+        let extra_params = ExtraSubgridParams::from(subgrid_params);
+        let mut subgrids =
+            Array3::from_shape_simple_fn(self.subgrids.dim(), || EmptySubgridV1.into());
 
-        self.set_key_value(&format!("convolution_type_{}", index + 1), &type_);
-        self.set_key_value(&format!("convolution_particle_{}", index + 1), &particle);
+        for (new_subgrid, subgrid) in subgrids.iter_mut().zip(&self.subgrids) {
+            if subgrid.is_empty() {
+                continue;
+            }
 
-        // update the remaining metadata
-        for (index, convolution) in self.convolutions().into_iter().enumerate() {
-            if self
-                .key_values()
-                // UNWRAP: we set some key-values before so there must be a storage
-                .unwrap_or_else(|| unreachable!())
-                .get(&format!("initial_state_{}", index + 1))
-                .is_some()
-            {
-                self.set_convolution(index, convolution);
+            let mut resampled = LagrangeSubgridV2::new(subgrid_params, &extra_params);
+            let mu2_grid = subgrid.mu2_grid();
+            let x1_grid = subgrid.x1_grid();
+            let x2_grid = subgrid.x2_grid();
+
+            for ((imu2, ix1, ix2), weight) in subgrid.indexed_iter() {
+                if weight == 0.0 {
+                    continue;
+                }
+
+                let Mu2 { ren, fac } = mu2_grid[imu2];
+
+                if !approx_eq!(f64, ren, fac, ulps = 4) {
+                    return Err(GridError::UnequalScales { ren, fac });
+                }
+
+                let ntuple = Ntuple {
+                    x1: x1_grid[ix1],
+                    x2: x2_grid[ix2],
+                    q2: fac,
+                    weight,
+                };
+                let fill_count = resampled.session_fill_count();
+
+                resampled.fill(&ntuple);
+
+                // nodes outside the interpolation range are silently dropped
+                if resampled.session_fill_count() == fill_count {
+                    return Err(GridError::NodeOutsideInterpolationRange {
+                        x1: ntuple.x1,
+                        x2: ntuple.x2,
+                        q2: ntuple.q2,
+                    });
+                }
             }
-        }
-    }
 
-    fn increase_shape(&mut self, new_dim: &(usize, usize, usize)) {
-        let old_dim = self.subgrids.raw_dim().into_pattern();
-        let mut new_subgrids = Array3::from_shape_simple_fn(
-            (
-                old_dim.0 + new_dim.0,
-                old_dim.1 + new_dim.1,
-                old_dim.2 + new_dim.2,
-            ),
-            || EmptySubgridV1.into(),
-        );
+            // the number of filled events shouldn't change
+            resampled.set_fill_count(
+                subgrid
+                    .as_lagrange_subgrid_v2()
+                    .map_or(0, LagrangeSubgridV2::session_fill_count),
+            );
 
-        for ((i, j, k), subgrid) in self.subgrids.indexed_iter_mut() {
-            mem::swap(&mut new_subgrids[[i, j, k]], subgrid);
+            *new_subgrid = resampled.into();
         }
 
-        self.subgrids = new_subgrids;
-    }
+        self.subgrids = subgrids;
+        self.subgrid_params = subgrid_params.clone();
 
-    /// Scale all subgrids by `factor`.
-    pub fn scale(&mut self, factor: f64) {
-        self.subgrids
-            .iter_mut()
-            .for_each(|subgrid| subgrid.scale(factor));
+        if let MoreMembers::V3(mmv3) = &mut self.more_members {
+            mmv3.subgrid_template = LagrangeSubgridV2::new(subgrid_params, &extra_params).into();
+        }
+
+        Ok(())
     }
 
-    /// Scales each subgrid by a factor which is the product of the given values `alphas`, `alpha`,
-    /// `logxir`, and `logxif`, each raised to the corresponding powers for each subgrid. In
-    /// addition, every subgrid is scaled by a factor `global` independently of its order.
+    /// Replaces the renormalization and factorization scales of every node with the values
+    /// returned by `scales`, which is called with the previous scales. This repairs grids whose
+    /// scales were filled with a wrong functional form, for instance `mu` instead of `mu2`. All
+    /// non-empty subgrids are converted to [`ImportOnlySubgridV2`], and subgrids that are filled
+    /// afterwards are not affected.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// TODO
-    pub fn scale_by_order(
-        &mut self,
-        alphas: f64,
-        alpha: f64,
-        logxir: f64,
-        logxif: f64,
-        global: f64,
-    ) {
-        for ((i, _, _), subgrid) in self.subgrids.indexed_iter_mut() {
-            let order = &self.orders[i];
-            let factor = global
-                * alphas.powi(order.alphas.try_into().unwrap())
-                * alpha.powi(order.alpha.try_into().unwrap())
-                * logxir.powi(order.logxir.try_into().unwrap())
-                * logxif.powi(order.logxif.try_into().unwrap());
-
-            subgrid.scale(factor);
-        }
-    }
+    /// Returns an error if `scales` returns a scale that is not positive and finite. In this case
+    /// the grid is left unchanged.
+    pub fn set_scales(&mut self, scales: &dyn Fn(&Mu2) -> Mu2) -> Result<(), GridError> {
+        let mut subgrids =
+            Array3::from_shape_simple_fn(self.subgrids.dim(), || EmptySubgridV1.into());
 
-    /// Scales each subgrid by a bin-dependent factor given in `factors`. If a bin does not have a
-    /// corresponding entry in `factors` it is not rescaled. If `factors` has more entries than
-    /// there are bins the superfluous entries do not have an effect.
-    pub fn scale_by_bin(&mut self, factors: &[f64]) {
-        for ((_, bin, _), subgrid) in self.subgrids.indexed_iter_mut() {
-            if let Some(&factor) = factors.get(bin) {
-                subgrid.scale(factor);
+        for (new_subgrid, subgrid) in subgrids.iter_mut().zip(&self.subgrids) {
+            if subgrid.is_empty() {
+                continue;
             }
-        }
-    }
 
-    /// Returns the subgrid parameters.
-    #[must_use]
-    pub fn orders(&self) -> &[Order] {
-        &self.orders
-    }
+            let mut converted = ImportOnlySubgridV2::from(subgrid);
 
-    /// Return a mutable reference to the subgrid parameters.
-    #[must_use]
-    pub fn orders_mut(&mut self) -> &mut [Order] {
-        &mut self.orders
-    }
+            for mu2 in converted.mu2_grid_mut() {
+                let Mu2 { ren, fac } = scales(mu2);
 
-    /// Return a mutable reference to the grid's channels.
-    pub fn channels_mut(&mut self) -> &mut [Channel] {
-        &mut self.channels
-    }
+                if !(ren.is_finite() && fac.is_finite() && (ren > 0.0) && (fac > 0.0)) {
+                    return Err(GridError::InvalidScale { ren, fac });
+                }
 
-    /// Return all subgrids as an `ArrayView3`.
-    #[must_use]
-    pub fn subgrids(&self) -> ArrayView3<SubgridEnum> {
-        self.subgrids.view()
-    }
+                *mu2 = Mu2 { ren, fac };
+            }
 
-    /// Return all subgrids as an `ArrayViewMut3`.
-    #[must_use]
-    pub fn subgrids_mut(&mut self) -> ArrayViewMut3<SubgridEnum> {
-        self.subgrids.view_mut()
+            *new_subgrid = converted.into();
+        }
+
+        self.subgrids = subgrids;
+
+        Ok(())
     }
 
-    /// Sets a remapper. A remapper can change the dimensions and limits of each bin in this grid.
-    /// This is useful because many Monte Carlo integrators and also `PineAPPL` do not support
-    /// multi-dimensional bins. To work around the problem the multi-dimensional bins can be
-    /// projected to one-dimensional bins, and the remapper can be used to restore the multi
-    /// dimensionality. Furthermore, it allows to normalize each bin separately, and independently
-    /// of the bin widths.
+    /// Replaces the renormalization and factorization scales of all nodes of the bin with index
+    /// `b` with `scales[b]`, for instance to reproduce a calculation that used a table of scales
+    /// which can not be derived from the kinematics. Contrary to [`Grid::set_scales`] the
+    /// predictions are changed consistently: the orders with logarithms of the scale factors are
+    /// evaluated with the ratios of the new to the previous scales and absorbed into the
+    /// corresponding orders without logarithms, and are then removed. The result is only exact
+    /// if the grid contains all orders with logarithms that are needed to vary the scales of the
+    /// orders selected later, and if the ratios are not so large that the perturbative expansion
+    /// breaks down. All non-empty subgrids are converted to [`ImportOnlySubgridV2`].
     ///
     /// # Errors
     ///
-    /// Returns an error if the number of bins in the grid and in the remapper do not agree.
+    /// Returns an error if the number of `scales` is different from the number of bins, or if a
+    /// scale is not positive and finite. In both cases the grid is left unchanged.
     ///
     /// # Panics
     ///
-    /// TODO
-    pub fn set_remapper(&mut self, remapper: BinRemapper) -> Result<(), GridError> {
-        if remapper.bins() != self.bin_info().bins() {
-            return Err(GridError::BinNumberMismatch {
+    /// Panics if the power of a logarithm of an order does not fit into an `i32`.
+    pub fn set_bin_scales(&mut self, scales: &[Mu2]) -> Result<(), GridError> {
+        if scales.len() != self.bin_info().bins() {
+            return Err(GridError::BinScaleMismatch {
                 grid_bins: self.bin_info().bins(),
-                remapper_bins: remapper.bins(),
+                scales: scales.len(),
             });
         }
 
-        self.more_members.upgrade();
-
-        match &mut self.more_members {
-            MoreMembers::V1(_) => unreachable!(),
-            MoreMembers::V2(mmv2) => mmv2.remapper = Some(remapper),
-            MoreMembers::V3(mmv3) => mmv3.remapper = Some(remapper),
+        if let Some(&Mu2 { ren, fac }) = scales.iter().find(|Mu2 { ren, fac }| {
+            !(ren.is_finite() && fac.is_finite() && (*ren > 0.0) && (*fac > 0.0))
+        }) {
+            return Err(GridError::InvalidScale { ren, fac });
         }
 
-        Ok(())
-    }
-
-    /// Return the currently set remapper, if there is any.
-    #[must_use]
-    pub const fn remapper(&self) -> Option<&BinRemapper> {
-        match &self.more_members {
-            MoreMembers::V1(_) => None,
-            MoreMembers::V2(mmv2) => mmv2.remapper.as_ref(),
-            MoreMembers::V3(mmv3) => mmv3.remapper.as_ref(),
+        // the order into which each order is absorbed; if there's no order without logarithms the
+        // first order with logarithms takes its place
+        let mut targets: Vec<usize> = Vec::with_capacity(self.orders.len());
+        for (index, order) in self.orders.iter().enumerate() {
+            let base = Order::with_couplings(order.alphas, order.alpha);
+            let target = self
+                .orders
+                .iter()
+                .position(|other| *other == base)
+                .or_else(|| {
+                    targets.iter().copied().find(|&target| {
+                        self.orders[target].alphas == order.alphas
+                            && self.orders[target].alpha == order.alpha
+                    })
+                })
+                .unwrap_or(index);
+            targets.push(target);
         }
-    }
 
-    fn remapper_mut(&mut self) -> Option<&mut BinRemapper> {
-        match &mut self.more_members {
-            MoreMembers::V1(_) => None,
-            MoreMembers::V2(mmv2) => mmv2.remapper.as_mut(),
-            MoreMembers::V3(mmv3) => mmv3.remapper.as_mut(),
-        }
-    }
+        let mut subgrids: Array3<SubgridEnum> =
+            Array3::from_shape_simple_fn(self.subgrids.dim(), || EmptySubgridV1.into());
 
-    /// Returns all information about the bins in this grid.
-    #[must_use]
-    pub const fn bin_info(&self) -> BinInfo {
-        BinInfo::new(&self.bin_limits, self.remapper())
-    }
+        for ((order, bin, channel), subgrid) in self.subgrids.indexed_iter() {
+            if subgrid.is_empty() {
+                continue;
+            }
 
-    /// Calls [`Self::optimize_using`] with all possible optimization options
-    /// ([`GridOptFlags::all`]).
-    pub fn optimize(&mut self) {
-        self.optimize_using(GridOptFlags::all());
-    }
-
-    /// Optimizes the internal datastructures for space efficiency. The parameter `flags`
-    /// determines which optimizations are applied, see [`GridOptFlags`].
-    pub fn optimize_using(&mut self, flags: GridOptFlags) {
-        if flags.contains(GridOptFlags::OPTIMIZE_SUBGRID_TYPE) {
-            let ssd = flags.contains(GridOptFlags::STATIC_SCALE_DETECTION);
-            self.optimize_subgrid_type(ssd);
-        }
-        if flags.contains(GridOptFlags::SYMMETRIZE_CHANNELS) {
-            self.symmetrize_channels();
-        }
-        if flags.contains(GridOptFlags::STRIP_EMPTY_ORDERS) {
-            self.strip_empty_orders();
-        }
-        if flags.contains(GridOptFlags::MERGE_SAME_CHANNELS) {
-            self.merge_same_channels();
-        }
-        if flags.contains(GridOptFlags::STRIP_EMPTY_CHANNELS) {
-            self.strip_empty_channels();
-        }
-    }
-
-    fn optimize_subgrid_type(&mut self, static_scale_detection: bool) {
-        for subgrid in &mut self.subgrids {
-            match subgrid {
-                // replace empty subgrids of any type with `EmptySubgridV1`
-                _ if subgrid.is_empty() => {
-                    *subgrid = EmptySubgridV1.into();
-                }
-                // can't be optimized without losing information
-                SubgridEnum::NtupleSubgridV1(_) => continue,
-                _ => {
-                    // TODO: this requires a `pub(crate)` in `LagrangeSubgridV2`; we should
-                    // replace this with a method
-                    if !static_scale_detection {
-                        if let SubgridEnum::LagrangeSubgridV2(subgrid) = subgrid {
-                            // disable static-scale detection
-                            subgrid.static_q2 = -1.0;
-                        }
-                    }
-
-                    *subgrid = ImportOnlySubgridV2::from(&*subgrid).into();
+            let Order { logxir, logxif, .. } = self.orders[order];
+            let Mu2 { ren, fac } = scales[bin];
+            let x1_grid = subgrid.x1_grid().into_owned();
+            let x2_grid = subgrid.x2_grid().into_owned();
+            let factors: Vec<_> = subgrid
+                .mu2_grid()
+                .iter()
+                .map(|mu2| {
+                    (ren / mu2.ren).ln().powi(logxir.try_into().unwrap())
+                        * (fac / mu2.fac).ln().powi(logxif.try_into().unwrap())
+                })
+                .collect();
+            let mut array = SparseArray3::new(1, x1_grid.len(), x2_grid.len());
+
+            for ((imu2, ix1, ix2), value) in subgrid.indexed_iter() {
+                let value = value * factors[imu2];
+
+                if value != 0.0 {
+                    array[[0, ix1, ix2]] += value;
                 }
             }
-        }
-    }
-
-    /// Try to deduplicate channels by detecting pairs of them that contain the same subgrids. The
-    /// numerical equality is tested using a tolerance of `ulps`, given in [units of least
-    /// precision](https://docs.rs/float-cmp/latest/float_cmp/index.html#some-explanation).
-    pub fn dedup_channels(&mut self, ulps: i64) {
-        let mut indices: Vec<usize> = (0..self.channels.len()).collect();
-
-        while let Some(index) = indices.pop() {
-            if let Some(other_index) = indices.iter().copied().find(|&other_index| {
-                let (mut a, mut b) = self
-                    .subgrids
-                    .multi_slice_mut((s![.., .., other_index], s![.., .., index]));
-
-                // TODO: use `Iterator::eq_by` once stablizied
-                for (lhs, rhs) in a.iter_mut().zip(b.iter_mut()) {
-                    let mut it_a = lhs.indexed_iter();
-                    let mut it_b = rhs.indexed_iter();
-
-                    loop {
-                        let a = it_a.next();
-                        let b = it_b.next();
-
-                        match (a, b) {
-                            (Some((tuple_a, value_a)), Some((tuple_b, value_b))) => {
-                                if tuple_a != tuple_b {
-                                    return false;
-                                }
-
-                                let u = ulps;
-                                if !approx_eq!(f64, value_a, value_b, ulps = u) {
-                                    return false;
-                                }
-                            }
-                            (None, None) => break,
-                            _ => return false,
-                        }
-                    }
-                }
 
-                true
-            }) {
-                let old_channel = self.channels.remove(index).entry().to_vec();
-                let mut new_channel = self.channels[other_index].entry().to_vec();
-                new_channel.extend(old_channel);
-                self.channels[other_index] = Channel::new(new_channel);
-                self.subgrids.remove_index(Axis(2), index);
+            if array.is_empty() {
+                continue;
             }
-        }
-    }
-
-    fn merge_same_channels(&mut self) {
-        let mut indices: Vec<_> = (0..self.channels.len()).rev().collect();
-
-        // merge channels that are the same
-        while let Some(index) = indices.pop() {
-            if let Some((other_index, factor)) = indices.iter().find_map(|&i| {
-                self.channels[i]
-                    .common_factor(&self.channels[index])
-                    .map(|factor| (i, factor))
-            }) {
-                let (mut a, mut b) = self
-                    .subgrids
-                    .multi_slice_mut((s![.., .., other_index], s![.., .., index]));
 
-                // check if in all cases the limits are compatible with merging
-                for (lhs, rhs) in a.iter_mut().zip(b.iter_mut()) {
-                    if !rhs.is_empty() {
-                        rhs.scale(1.0 / factor);
-                        if lhs.is_empty() {
-                            // we can't merge into an EmptySubgridV1
-                            *lhs = rhs.clone_empty();
-                        }
-                        lhs.merge(rhs, false);
+            let mut converted: SubgridEnum =
+                ImportOnlySubgridV2::new(array, vec![Mu2 { ren, fac }], x1_grid, x2_grid).into();
+            let target = &mut subgrids[[targets[order], bin, channel]];
 
-                        *rhs = EmptySubgridV1.into();
-                    }
-                }
+            if target.is_empty() {
+                *target = converted;
+            } else {
+                target.merge(&mut converted, false);
             }
         }
-    }
 
-    fn strip_empty_channels(&mut self) {
-        let mut indices: Vec<_> = (0..self.channels().len()).collect();
+        self.subgrids = subgrids;
 
-        while let Some(index) = indices.pop() {
-            if self
-                .subgrids
-                .slice(s![.., .., index])
-                .iter()
-                .all(Subgrid::is_empty)
-            {
-                self.channels.remove(index);
-                self.subgrids.remove_index(Axis(2), index);
+        let mut absorbed = Vec::new();
+        for (index, &target) in targets.iter().enumerate() {
+            if target == index {
+                self.orders[index].logxir = 0;
+                self.orders[index].logxif = 0;
+            } else {
+                absorbed.push(index);
             }
         }
-    }
+        self.delete_orders(&absorbed);
 
-    fn strip_empty_orders(&mut self) {
-        let mut indices: Vec<_> = (0..self.orders().len()).collect();
-
-        while let Some(index) = indices.pop() {
-            if self
-                .subgrids
-                .slice(s![index, .., ..])
-                .iter()
-                .all(Subgrid::is_empty)
-            {
-                self.orders.remove(index);
-                self.subgrids.remove_index(Axis(0), index);
-            }
-        }
+        Ok(())
     }
 
-    fn symmetrize_channels(&mut self) {
-        let convolutions = self.convolutions();
-        if convolutions[0] != convolutions[1] {
-            return;
-        }
-
-        let mut indices: Vec<usize> = (0..self.channels.len()).rev().collect();
+    /// Merges bins along the dimension with index `dimension`, which for multi-dimensional
+    /// distributions allows to merge bins that are not consecutive. See
+    /// [`BinRemapper::merge_dimension`] for the meaning of `groups`. If this `Grid` has a
+    /// remapper, its one-dimensional bin limits are replaced with the bin indices afterwards.
+    ///
+    /// # Errors
+    ///
+    /// If the bins can not be merged, an error is returned.
+    pub fn merge_bins_nd(
+        &mut self,
+        dimension: usize,
+        groups: &[Range<usize>],
+    ) -> Result<(), GridError> {
+        let remapper = self.remapper().cloned().unwrap_or_else(|| {
+            let limits = self.bin_limits.limits();
+
+            // UNWRAP: one-dimensional bin limits can not overlap
+            BinRemapper::new(
+                self.bin_limits.bin_sizes(),
+                limits.windows(2).map(|win| (win[0], win[1])).collect(),
+            )
+            .unwrap_or_else(|_| unreachable!())
+        });
+        let (remapper, indices) = remapper
+            .merge_dimension(dimension, groups)
+            .map_err(GridError::MergeBinError)?;
+        let bin_count = remapper.bins();
 
-        while let Some(index) = indices.pop() {
-            let channel_entry = &self.channels[index];
+        if self.remapper().is_some() {
+            self.bin_limits = BinLimits::new(
+                iter::successors(Some(0.0), |x| Some(x + 1.0))
+                    .take(bin_count + 1)
+                    .collect(),
+            );
+            self.set_remapper(remapper)?;
+        } else {
+            self.bin_limits = BinLimits::new(
+                remapper
+                    .limits()
+                    .iter()
+                    .map(|&(left, _)| left)
+                    .chain(remapper.limits().last().map(|&(_, right)| right))
+                    .collect(),
+            );
+        }
 
-            if *channel_entry == channel_entry.transpose() {
-                // check if in all cases the limits are compatible with merging
-                self.subgrids
-                    .slice_mut(s![.., .., index])
-                    .iter_mut()
-                    .for_each(|subgrid| {
-                        if !subgrid.is_empty() && (subgrid.x1_grid() == subgrid.x2_grid()) {
-                            subgrid.symmetrize();
-                        }
-                    });
-            } else if let Some((j, &other_index)) = indices
-                .iter()
-                .enumerate()
-                .find(|(_, i)| self.channels[**i] == channel_entry.transpose())
-            {
-                indices.remove(j);
+        let mut old_subgrids = mem::replace(
+            &mut self.subgrids,
+            Array3::from_shape_simple_fn(
+                (self.orders.len(), bin_count, self.channels.len()),
+                || EmptySubgridV1.into(),
+            ),
+        );
 
-                // check if in all cases the limits are compatible with merging
-                let (mut a, mut b) = self
-                    .subgrids
-                    .multi_slice_mut((s![.., .., index], s![.., .., other_index]));
+        for ((order, bin, channel), subgrid) in old_subgrids.indexed_iter_mut() {
+            if subgrid.is_empty() {
+                continue;
+            }
 
-                for (lhs, rhs) in a.iter_mut().zip(b.iter_mut()) {
-                    if !rhs.is_empty() {
-                        if lhs.is_empty() {
-                            // we can't merge into an EmptySubgridV1
-                            *lhs = rhs.clone_empty();
-                        }
+            let new_subgrid = &mut self.subgrids[[order, indices[bin], channel]];
 
-                        lhs.merge(rhs, true);
-                        *rhs = EmptySubgridV1.into();
-                    }
-                }
+            if new_subgrid.is_empty() {
+                mem::swap(new_subgrid, subgrid);
+            } else {
+                new_subgrid.merge(subgrid, false);
             }
         }
-    }
 
-    /// Upgrades the internal data structures to their latest versions.
-    pub fn upgrade(&mut self) {
-        self.more_members.upgrade();
-    }
+        self.remove_bin_central_scales();
+        self.remove_reference();
 
-    /// Returns a map with key-value pairs, if there are any stored in this grid.
-    #[must_use]
-    pub const fn key_values(&self) -> Option<&HashMap<String, String>> {
-        match &self.more_members {
-            MoreMembers::V3(mmv3) => Some(&mmv3.key_value_db),
-            MoreMembers::V2(mmv2) => Some(&mmv2.key_value_db),
-            MoreMembers::V1(_) => None,
-        }
+        Ok(())
     }
 
-    /// Returns a map with key-value pairs, if there are any stored in this grid.
+    /// Merges the non-empty `Subgrid`s contained in `other` into `self`. Metadata are merged with
+    /// [`MetadataMergePolicy::KeepFirst`], see [`Grid::merge_with_metadata`].
+    ///
+    /// # Errors
+    ///
+    /// If the bin limits of `self` and `other` are different and if the bin limits of `other` can
+    /// not be merged with `self` an error is returned. [`GridError::InvalidExtraCouplings`] is
+    /// returned if the additional couplings of one of the grids are malformed.
     ///
     /// # Panics
     ///
     /// TODO
-    #[must_use]
-    pub fn key_values_mut(&mut self) -> &mut HashMap<String, String> {
-        self.more_members.upgrade();
-
-        match &mut self.more_members {
-            MoreMembers::V1(_) => unreachable!(),
-            MoreMembers::V2(mmv2) => &mut mmv2.key_value_db,
-            MoreMembers::V3(mmv3) => &mut mmv3.key_value_db,
-        }
+    pub fn merge(&mut self, other: Self) -> Result<(), GridError> {
+        self.merge_with_metadata(other, MetadataMergePolicy::KeepFirst)
     }
 
-    /// Sets a specific key-value pair in this grid.
+    /// Merges the non-empty `Subgrid`s contained in `other` into `self` like [`Grid::merge`], and
+    /// merges the metadata of both grids according to `policy`. The powers of additional
+    /// couplings, see [`Grid::extra_couplings`], are not subject to `policy`, because they are
+    /// merged together with the orders.
+    ///
+    /// # Errors
+    ///
+    /// If the bin limits of `self` and `other` are different and if the bin limits of `other` can
+    /// not be merged with `self` an error is returned. If `policy` is
+    /// [`MetadataMergePolicy::ErrorOnConflict`] and a metadata key has different values in `self`
+    /// and `other`, [`GridError::MetadataConflict`] is returned before anything is merged.
+    /// [`GridError::InvalidExtraCouplings`] is returned if the additional couplings of one of the
+    /// grids are malformed.
     ///
     /// # Panics
     ///
     /// TODO
-    pub fn set_key_value(&mut self, key: &str, value: &str) {
-        self.key_values_mut()
-            .insert(key.to_owned(), value.to_owned());
-    }
-
-    /// Returns information for the generation of evolution operators that are being used in
-    /// [`Grid::evolve`] with the parameter `order_mask`.
-    #[must_use]
-    pub fn evolve_info(&self, order_mask: &[bool]) -> EvolveInfo {
-        use super::evolution::EVOLVE_INFO_TOL_ULPS;
+    pub fn merge_with_metadata(
+        &mut self,
+        mut other: Self,
+        policy: MetadataMergePolicy,
+    ) -> Result<(), GridError> {
+        let mut other_key_values: Vec<_> = other
+            .key_values()
+            .map(|kv| {
+                kv.iter()
+                    // the additional couplings are merged together with the orders, and the
+                    // central scales and the reference together with the bins
+                    .filter(|&(key, _)| {
+                        !matches!(
+                            key.as_str(),
+                            "extra_couplings" | "bin_central_scales" | "reference" | "results_pdf"
+                        )
+                    })
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        // sort to make the reported conflict deterministic
+        other_key_values.sort();
 
-        let has_pdf1 = self.convolutions()[0] != Convolution::None;
-        let has_pdf2 = self.convolutions()[1] != Convolution::None;
+        if policy == MetadataMergePolicy::ErrorOnConflict {
+            if let Some(self_key_values) = self.key_values() {
+                if let Some((key, rhs)) = other_key_values
+                    .iter()
+                    .find(|(key, value)| self_key_values.get(key).is_some_and(|lhs| lhs != value))
+                {
+                    return Err(GridError::MetadataConflict {
+                        key: key.clone(),
+                        lhs: self_key_values[key].clone(),
+                        rhs: rhs.clone(),
+                    });
+                }
+            }
+        }
 
-        let mut ren1 = Vec::new();
-        let mut fac1 = Vec::new();
-        let mut x1 = Vec::new();
-        let mut pids1 = Vec::new();
+        let mut new_orders: Vec<Order> = Vec::new();
+        let mut new_couplings: Vec<BTreeMap<String, u32>> = Vec::new();
+        let self_couplings = self.extra_couplings()?;
+        let other_couplings = other.extra_couplings()?;
+        let self_scales = self.bin_central_scales();
+        let other_scales = other.bin_central_scales();
+        let self_reference = self.reference().ok().flatten();
+        let other_reference = other.reference().ok().flatten();
+        let mut new_bins = 0;
+        let mut new_entries: Vec<Channel> = Vec::new();
 
-        for (channel, subgrid) in self
-            .subgrids()
-            .indexed_iter()
-            .filter_map(|(tuple, subgrid)| {
-                (!subgrid.is_empty() && (order_mask.is_empty() || order_mask[tuple.0]))
-                    .then_some((tuple.2, subgrid))
-            })
-        {
-            ren1.extend(subgrid.mu2_grid().iter().map(|Mu2 { ren, .. }| *ren));
-            ren1.sort_by(f64::total_cmp);
-            ren1.dedup_by(|a, b| approx_eq!(f64, *a, *b, ulps = EVOLVE_INFO_TOL_ULPS));
+        if self.bin_info() != other.bin_info() {
+            let lhs_bins = self.bin_info().bins();
+            new_bins = other.bin_info().bins();
 
-            fac1.extend(subgrid.mu2_grid().iter().map(|Mu2 { fac, .. }| *fac));
-            fac1.sort_by(f64::total_cmp);
-            fac1.dedup_by(|a, b| approx_eq!(f64, *a, *b, ulps = EVOLVE_INFO_TOL_ULPS));
+            let lhs_remapper = self.remapper_mut();
+            let rhs_remapper = other.remapper();
 
-            if has_pdf1 {
-                x1.extend(subgrid.x1_grid().iter().copied());
+            if let Some(lhs) = lhs_remapper {
+                if let Some(rhs) = rhs_remapper {
+                    lhs.merge(rhs).map_err(GridError::MergeBinError)?;
+
+                    let a = u32::try_from(lhs_bins).unwrap_or_else(|_| unreachable!());
+                    let b = u32::try_from(lhs_bins + new_bins).unwrap_or_else(|_| unreachable!());
+
+                    self.bin_limits = BinLimits::new((0..=b).map(f64::from).collect());
+                    other.bin_limits = BinLimits::new((a..=b).map(f64::from).collect());
+                } else {
+                    // Return an error
+                    todo!();
+                }
+            } else if rhs_remapper.is_none() {
+                self.bin_limits
+                    .merge(&other.bin_limits)
+                    .map_err(GridError::InvalidBinLimits)?;
+            } else {
+                // Return an error
+                todo!();
             }
-            if has_pdf2 {
-                x1.extend(subgrid.x2_grid().iter());
+        }
+
+        for ((i, _, k), _) in other
+            .subgrids
+            .indexed_iter_mut()
+            .filter(|((_, _, _), subgrid)| !subgrid.is_empty())
+        {
+            let other_order = &other.orders[i];
+            let other_coupling = &other_couplings[i];
+            let other_entry = &other.channels[k];
+
+            if !self
+                .orders
+                .iter()
+                .zip(&self_couplings)
+                .chain(new_orders.iter().zip(&new_couplings))
+                .any(|x| x == (other_order, other_coupling))
+            {
+                new_orders.push(other_order.clone());
+                new_couplings.push(other_coupling.clone());
             }
 
-            x1.sort_by(f64::total_cmp);
-            x1.dedup_by(|a, b| approx_eq!(f64, *a, *b, ulps = EVOLVE_INFO_TOL_ULPS));
+            if !self
+                .channels()
+                .iter()
+                .chain(new_entries.iter())
+                .any(|y| y == other_entry)
+            {
+                new_entries.push(other_entry.clone());
+            }
+        }
 
-            if has_pdf1 {
-                pids1.extend(self.channels()[channel].entry().iter().map(|(a, _, _)| a));
+        if !new_orders.is_empty() || !new_entries.is_empty() || (new_bins != 0) {
+            self.increase_shape(&(new_orders.len(), new_bins, new_entries.len()));
+        }
+
+        let merged_couplings: Vec<_> = self_couplings.into_iter().chain(new_couplings).collect();
+
+        self.orders.append(&mut new_orders);
+        self.channels.append(&mut new_entries);
+        self.write_extra_couplings(&merged_couplings);
+
+        for (key, value) in other_key_values {
+            let key_values = self.key_values_mut();
+
+            if (policy == MetadataMergePolicy::KeepLast) || !key_values.contains_key(&key) {
+                key_values.insert(key, value);
             }
-            if has_pdf2 {
-                pids1.extend(self.channels()[channel].entry().iter().map(|(_, b, _)| b));
+        }
+
+        match (self_scales, other_scales) {
+            // the bins of `other` were appended
+            (Some(mut lhs), Some(rhs)) if new_bins != 0 => {
+                lhs.extend(rhs);
+                self.set_bin_central_scales(&lhs);
             }
+            (Some(lhs), Some(rhs)) if lhs == rhs => {}
+            _ => self.remove_bin_central_scales(),
+        }
 
-            pids1.sort_unstable();
-            pids1.dedup();
+        match (self_reference, other_reference) {
+            // the bins of `other` were appended
+            (Some(mut lhs), Some(rhs)) if (new_bins != 0) && (lhs.pdf == rhs.pdf) => {
+                lhs.results.extend(rhs.results);
+                self.set_reference(&lhs)?;
+            }
+            // the predictions of both grids are added, which the reference doesn't know about
+            _ => self.remove_reference(),
         }
 
-        EvolveInfo {
-            fac1,
-            pids1,
-            x1,
-            ren1,
+        let bin_indices: Vec<_> = (0..other.bin_info().bins())
+            .map(|bin| {
+                self.bin_info()
+                    .find_bin(&other.bin_info().bin_limits(bin))
+                    .unwrap_or_else(|| panic!("failed for {bin}"))
+            })
+            .collect();
+
+        for ((i, j, k), subgrid) in other
+            .subgrids
+            .indexed_iter_mut()
+            .filter(|((_, _, _), subgrid)| !subgrid.is_empty())
+        {
+            let other_order = &other.orders[i];
+            let other_coupling = &other_couplings[i];
+            let other_entry = &other.channels[k];
+
+            let self_i = self
+                .orders
+                .iter()
+                .zip(&merged_couplings)
+                .position(|x| x == (other_order, other_coupling))
+                .unwrap();
+            let self_j = bin_indices[j];
+            let self_k = self.channels.iter().position(|y| y == other_entry).unwrap();
+
+            if self.subgrids[[self_i, self_j, self_k]].is_empty() {
+                mem::swap(&mut self.subgrids[[self_i, self_j, self_k]], subgrid);
+            } else {
+                self.subgrids[[self_i, self_j, self_k]].merge(&mut *subgrid, false);
+            }
         }
+
+        Ok(())
     }
 
-    /// Converts this `Grid` into an [`FkTable`] using an evolution kernel operator (EKO) given as
-    /// `operator`. The dimensions and properties of this operator must be described using `info`.
-    /// The parameter `order_mask` can be used to include or exclude orders from this operation,
-    /// and must correspond to the ordering given by [`Grid::orders`]. Orders that are not given
-    /// are enabled, and in particular if `order_mask` is empty all orders are activated.
+    /// Checks whether `other` can be merged into `self` with [`Grid::merge`] without modifying
+    /// either grid. Different orders and channels are always compatible, since they are added to
+    /// `self`. Incompatible are grids with different convolutions, grids whose bin limits can not
+    /// be merged, and grids with the same bins whose subgrids for the same order, bin and channel
+    /// use interpolations that can not be merged.
     ///
     /// # Errors
     ///
-    /// Returns a [`GridError::EvolutionFailure`] if either the `operator` or its `info` is
-    /// incompatible with this `Grid`.
-    #[deprecated(since = "0.7.4", note = "use evolve_with_slice_iter instead")]
-    pub fn evolve(
-        &self,
-        operator: ArrayView5<f64>,
-        info: &OperatorInfo,
-        order_mask: &[bool],
-    ) -> Result<FkTable, GridError> {
-        self.evolve_with_slice_iter(
-            info.fac1
+    /// Returns the first incompatibility that was found. For bin limits this is the error that
+    /// [`Grid::merge`] would return, otherwise it is [`GridError::IncompatibleGrids`]. Malformed
+    /// additional couplings are reported with [`GridError::InvalidExtraCouplings`].
+    pub fn check_mergeable(&self, other: &Self) -> Result<(), GridError> {
+        let lhs_convolutions = self.convolutions();
+        let rhs_convolutions = other.convolutions();
+
+        if lhs_convolutions != rhs_convolutions {
+            return Err(GridError::IncompatibleGrids {
+                property: "convolutions",
+                reason: format!(
+                    "{} and {}",
+                    lhs_convolutions.iter().join(","),
+                    rhs_convolutions.iter().join(",")
+                ),
+            });
+        }
+
+        if self.bin_info() != other.bin_info() {
+            match (self.remapper(), other.remapper()) {
+                (Some(lhs), Some(rhs)) => {
+                    lhs.clone().merge(rhs).map_err(GridError::MergeBinError)?;
+                }
+                (None, None) => {
+                    self.bin_limits
+                        .clone()
+                        .merge(&other.bin_limits)
+                        .map_err(GridError::InvalidBinLimits)?;
+                }
+                _ => {
+                    return Err(GridError::IncompatibleGrids {
+                        property: "bins",
+                        reason: "only one of the grids has a bin remapper".to_owned(),
+                    });
+                }
+            }
+
+            // the bins of `other` are appended, so no subgrids are merged with each other
+            return Ok(());
+        }
+
+        let self_couplings = self.extra_couplings()?;
+        let other_couplings = other.extra_couplings()?;
+
+        for ((order, bin, channel), subgrid) in other
+            .subgrids
+            .indexed_iter()
+            .filter(|(_, subgrid)| !subgrid.is_empty())
+        {
+            let Some(self_order) = self
+                .orders
                 .iter()
-                .zip(operator.axis_iter(Axis(0)))
-                .map(|(&fac1, op)| {
-                    Ok::<_, GridError>((
-                        OperatorSliceInfo {
-                            fac0: info.fac0,
-                            pids0: info.pids0.clone(),
-                            x0: info.x0.clone(),
-                            fac1,
-                            pids1: info.pids1.clone(),
-                            x1: info.x1.clone(),
-                            pid_basis: info.pid_basis,
-                        },
-                        CowArray::from(op),
-                    ))
-                }),
-            order_mask,
-            (info.xir, info.xif),
-            &AlphasTable {
-                ren1: info.ren1.clone(),
-                alphas: info.alphas.clone(),
-            },
-        )
+                .zip(&self_couplings)
+                .position(|x| x == (&other.orders[order], &other_couplings[order]))
+            else {
+                continue;
+            };
+            let Some(self_channel) = self
+                .channels
+                .iter()
+                .position(|c| *c == other.channels[channel])
+            else {
+                continue;
+            };
+            let self_subgrid = &self.subgrids[[self_order, bin, self_channel]];
+
+            if !self_subgrid.is_empty() && !Self::subgrids_mergeable(self_subgrid, subgrid) {
+                return Err(GridError::IncompatibleGrids {
+                    property: "subgrids",
+                    reason: format!(
+                        "the subgrids of order {}, bin {bin} and channel {self_channel} use \
+                         different interpolations",
+                        other.orders[order]
+                    ),
+                });
+            }
+        }
+
+        Ok(())
     }
 
-    // TODO:
-    // - try to find a better solution than to require that E must be convertible into
-    //   anyhow::Error
+    fn subgrids_mergeable(lhs: &SubgridEnum, rhs: &SubgridEnum) -> bool {
+        match (lhs, rhs) {
+            // these subgrids can be merged even if their nodes are different
+            (
+                SubgridEnum::ImportOnlySubgridV2(_) | SubgridEnum::ImportOnlySubgridF32(_),
+                SubgridEnum::ImportOnlySubgridV2(_) | SubgridEnum::ImportOnlySubgridF32(_),
+            ) => true,
+            // the filled scale ranges of these subgrids may differ, but not their interpolations
+            (SubgridEnum::LagrangeSubgridV1(lhs), SubgridEnum::LagrangeSubgridV1(rhs)) => {
+                lhs.same_interpolation(rhs)
+            }
+            (SubgridEnum::LagrangeSubgridV2(lhs), SubgridEnum::LagrangeSubgridV2(rhs)) => {
+                lhs.same_interpolation(rhs)
+            }
+            (
+                SubgridEnum::LagrangeSparseSubgridV1(lhs),
+                SubgridEnum::LagrangeSparseSubgridV1(rhs),
+            ) => lhs.same_interpolation(rhs),
+            _ => {
+                (mem::discriminant(lhs) == mem::discriminant(rhs))
+                    && (lhs.x1_grid() == rhs.x1_grid())
+                    && (lhs.x2_grid() == rhs.x2_grid())
+                    && (lhs.mu2_grid() == rhs.mu2_grid())
+            }
+        }
+    }
 
-    /// Converts this `Grid` into an [`FkTable`] using `slices` that must iterate over a [`Result`]
-    /// of tuples of an [`OperatorSliceInfo`] and the corresponding sliced operator. The parameter
-    /// `order_mask` can be used to include or exclude orders from this operation, and must
-    /// correspond to the ordering given by [`Grid::orders`]. Orders that are not given are
-    /// enabled, and in particular if `order_mask` is empty all orders are activated.
+    /// Return a vector containing the type of convolutions performed with this grid.
     ///
-    /// # Errors
+    /// # Panics
     ///
-    /// Returns a [`GridError::EvolutionFailure`] if either the `operator` or its `info` is
-    /// incompatible with this `Grid`. Returns a [`GridError::Other`] if the iterator from `slices`
-    /// return an error.
-    pub fn evolve_with_slice_iter<'a, E: Into<anyhow::Error>>(
-        &self,
-        slices: impl IntoIterator<Item = Result<(OperatorSliceInfo, CowArray<'a, f64, Ix4>), E>>,
-        order_mask: &[bool],
-        xi: (f64, f64),
-        alphas_table: &AlphasTable,
-    ) -> Result<FkTable, GridError> {
-        use super::evolution::EVOLVE_INFO_TOL_ULPS;
+    /// Panics if the metadata key--value pairs `convolution_particle_1` and `convolution_type_1`,
+    /// or `convolution_particle_2` and `convolution_type_2` are not correctly set.
+    #[must_use]
+    pub fn convolutions(&self) -> Vec<Convolution> {
+        self.key_values().map_or_else(
+            // if there isn't any metadata, we assume two unpolarized proton-PDFs are used
+            || vec![Convolution::UnpolPDF(2212), Convolution::UnpolPDF(2212)],
+            |kv| {
+                // the current file format only supports exactly two convolutions
+                (1..=2)
+                    .map(|index| {
+                        // if there are key-value pairs `convolution_particle_1` and
+                        // `convolution_type_1` and the same with a higher index, we convert this
+                        // metadata into `Convolution`
+                        match (
+                            kv.get(&format!("convolution_particle_{index}"))
+                                .map(|s| s.parse::<i32>()),
+                            kv.get(&format!("convolution_type_{index}"))
+                                .map(String::as_str),
+                        ) {
+                            (_, Some("None")) => Convolution::None,
+                            (Some(Ok(pid)), Some("UnpolPDF")) => Convolution::UnpolPDF(pid),
+                            (Some(Ok(pid)), Some("PolPDF")) => Convolution::PolPDF(pid),
+                            (Some(Ok(pid)), Some("UnpolFF")) => Convolution::UnpolFF(pid),
+                            (Some(Ok(pid)), Some("PolFF")) => Convolution::PolFF(pid),
+                            (None, None) => {
+                                // if these key-value pairs are missing use the old metadata
+                                match kv
+                                    .get(&format!("initial_state_{index}"))
+                                    .map(|s| s.parse::<i32>())
+                                {
+                                    Some(Ok(pid)) => {
+                                        let condition = !self.channels().iter().all(|entry| {
+                                            entry.entry().iter().all(|&channels| match index {
+                                                1 => channels.0 == pid,
+                                                2 => channels.1 == pid,
+                                                _ => unreachable!(),
+                                            })
+                                        });
+
+                                        if condition {
+                                            Convolution::UnpolPDF(pid)
+                                        } else {
+                                            Convolution::None
+                                        }
+                                    }
+                                    None => Convolution::UnpolPDF(2212),
+                                    Some(Err(err)) => panic!("metadata 'initial_state_{index}' could not be parsed: {err}"),
+                                }
+                            }
+                            (None, Some(_)) => {
+                                panic!("metadata 'convolution_type_{index}' is missing")
+                            }
+                            (Some(_), None) => {
+                                panic!("metadata 'convolution_particle_{index}' is missing")
+                            }
+                            (Some(Ok(_)), Some(type_)) => {
+                                panic!("metadata 'convolution_type_{index} = {type_}' is unknown")
+                            }
+                            (Some(Err(err)), Some(_)) => panic!(
+                                "metadata 'convolution_particle_{index}' could not be parsed: {err}"
+                            ),
+                        }
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Set the convolution type for this grid for the corresponding `index`.
+    pub fn set_convolution(&mut self, index: usize, convolution: Convolution) {
+        // remove outdated metadata
+        self.key_values_mut()
+            .remove(&format!("initial_state_{}", index + 1));
+
+        let (type_, particle) = match convolution {
+            Convolution::UnpolPDF(pid) => ("UnpolPDF".to_owned(), pid.to_string()),
+            Convolution::PolPDF(pid) => ("PolPDF".to_owned(), pid.to_string()),
+            Convolution::UnpolFF(pid) => ("UnpolFF".to_owned(), pid.to_string()),
+            Convolution::PolFF(pid) => ("PolFF".to_owned(), pid.to_string()),
+            Convolution::None => ("None".to_owned(), String::new()),
+        };
+
+        self.set_key_value(&format!("convolution_type_{}", index + 1), &type_);
+        self.set_key_value(&format!("convolution_particle_{}", index + 1), &particle);
+
+        // update the remaining metadata
+        for (index, convolution) in self.convolutions().into_iter().enumerate() {
+            if self
+                .key_values()
+                // UNWRAP: we set some key-values before so there must be a storage
+                .unwrap_or_else(|| unreachable!())
+                .get(&format!("initial_state_{}", index + 1))
+                .is_some()
+            {
+                self.set_convolution(index, convolution);
+            }
+        }
+    }
+
+    /// Same as [`Grid::set_convolution`], but checks first that `convolution` is compatible with
+    /// the channels of this grid. [`Convolution::None`] requires that all channels use the same
+    /// particle for the convolution with index `index`, and every other convolution requires that
+    /// the channels use only partons of the grid's [`PidBasis`] for it.
+    ///
+    /// # Errors
+    ///
+    /// If `index` is larger than `1` or if `convolution` is not compatible with the channels,
+    /// [`GridError::IncompatibleConvolution`] is returned and the grid is not modified.
+    pub fn try_set_convolution(
+        &mut self,
+        index: usize,
+        convolution: Convolution,
+    ) -> Result<(), GridError> {
+        let error = |reason| GridError::IncompatibleConvolution { index, reason };
+
+        if index >= 2 {
+            return Err(error("grids only have two convolutions".to_owned()));
+        }
+
+        let pid_basis = self.pid_basis();
+        let pids = self.distinct_pids(index);
+
+        if convolution == Convolution::None {
+            if pids.len() > 1 {
+                return Err(error(format!(
+                    "'{convolution}' requires a single particle, but the channels use the PIDs {}",
+                    pids.iter().join(", ")
+                )));
+            }
+        } else if let Some(pid) = pids.iter().find(|&&pid| !pid_basis.is_parton(pid)) {
+            return Err(error(format!(
+                "'{convolution}' requires partons, but the channels use the PID {pid}"
+            )));
+        }
+
+        self.set_convolution(index, convolution);
+
+        Ok(())
+    }
+
+    /// Returns the powers of additional couplings for each order, for instance of Wilson
+    /// coefficients in EFT calculations. The returned vector has as many entries as there are
+    /// orders, and orders without additional couplings have empty maps. These powers are stored
+    /// as metadata under the key `extra_couplings`, because the file format of [`Order`] can not
+    /// be extended.
+    ///
+    /// Convolutions treat the additional couplings as if their values were one; use
+    /// [`Grid::scale_by_coupling`] to set their values. Methods that remove or add orders, like
+    /// [`Grid::delete_orders`] or [`Grid::merge`], update the powers accordingly, and changing an
+    /// order with [`Grid::orders_mut`] keeps its powers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GridError::InvalidExtraCouplings`] if the metadata storing the additional
+    /// couplings is malformed or if it does not have an entry for each order.
+    pub fn extra_couplings(&self) -> Result<Vec<BTreeMap<String, u32>>, GridError> {
+        let Some(value) = self.key_values().and_then(|kv| kv.get("extra_couplings")) else {
+            return Ok(vec![BTreeMap::new(); self.orders.len()]);
+        };
+
+        let entries: Vec<_> = value.split(';').collect();
+
+        if entries.len() != self.orders.len() {
+            return Err(GridError::InvalidExtraCouplings(format!(
+                "found {} entries for {} orders",
+                entries.len(),
+                self.orders.len()
+            )));
+        }
+
+        entries
+            .into_iter()
+            .map(|entry| {
+                entry
+                    .split(',')
+                    .filter(|coupling| !coupling.is_empty())
+                    .map(|coupling| {
+                        coupling
+                            .split_once('=')
+                            .and_then(|(name, power)| Some((name.to_owned(), power.parse().ok()?)))
+                            .ok_or_else(|| {
+                                GridError::InvalidExtraCouplings(format!(
+                                    "malformed coupling '{coupling}'"
+                                ))
+                            })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Sets the powers of the additional couplings of the order with index `order`, replacing
+    /// previously set powers. See [`Grid::extra_couplings`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GridError::InvalidOrderIndex`] if `order` is larger or equal than the number of
+    /// orders, and [`GridError::InvalidExtraCouplings`] if the stored powers are malformed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a name of a coupling is empty or contains one of the characters `,`, `;` or `=`.
+    pub fn set_extra_couplings(
+        &mut self,
+        order: usize,
+        couplings: BTreeMap<String, u32>,
+    ) -> Result<(), GridError> {
+        assert!(
+            couplings
+                .keys()
+                .all(|name| !name.is_empty() && !name.contains([',', ';', '='])),
+            "names of couplings must not be empty or contain ',', ';' or '='"
+        );
+
+        let orders = self.orders.len();
+
+        if order >= orders {
+            return Err(GridError::InvalidOrderIndex { order, orders });
+        }
+
+        let mut extra_couplings = self.extra_couplings()?;
+        extra_couplings[order] = couplings;
+        self.write_extra_couplings(&extra_couplings);
+
+        Ok(())
+    }
+
+    fn write_extra_couplings(&mut self, extra_couplings: &[BTreeMap<String, u32>]) {
+        if extra_couplings.iter().all(BTreeMap::is_empty) {
+            // don't create metadata if there wasn't any before
+            if self
+                .key_values()
+                .is_some_and(|kv| kv.contains_key("extra_couplings"))
+            {
+                self.key_values_mut().remove("extra_couplings");
+            }
+        } else {
+            let value = extra_couplings
+                .iter()
+                .map(|couplings| {
+                    couplings
+                        .iter()
+                        .map(|(name, power)| format!("{name}={power}"))
+                        .join(",")
+                })
+                .join(";");
+
+            self.set_key_value("extra_couplings", &value);
+        }
+    }
+
+    fn delete_extra_couplings(&mut self, order: usize) {
+        // work on the raw entries, so that the remaining entries stay assigned to their orders
+        // even if one of them is malformed
+        let Some(value) = self.key_values().and_then(|kv| kv.get("extra_couplings")) else {
+            return;
+        };
+        let mut entries: Vec<_> = value.split(';').map(str::to_owned).collect();
+
+        if order < entries.len() {
+            entries.remove(order);
+        }
+
+        if entries.iter().all(String::is_empty) {
+            self.key_values_mut().remove("extra_couplings");
+        } else {
+            self.set_key_value("extra_couplings", &entries.join(";"));
+        }
+    }
+
+    fn increase_shape(&mut self, new_dim: &(usize, usize, usize)) {
+        let old_dim = self.subgrids.raw_dim().into_pattern();
+        let mut new_subgrids = Array3::from_shape_simple_fn(
+            (
+                old_dim.0 + new_dim.0,
+                old_dim.1 + new_dim.1,
+                old_dim.2 + new_dim.2,
+            ),
+            || EmptySubgridV1.into(),
+        );
+
+        for ((i, j, k), subgrid) in self.subgrids.indexed_iter_mut() {
+            mem::swap(&mut new_subgrids[[i, j, k]], subgrid);
+        }
+
+        self.subgrids = new_subgrids;
+    }
+
+    /// Scale all subgrids by `factor`.
+    pub fn scale(&mut self, factor: f64) {
+        self.subgrids
+            .iter_mut()
+            .for_each(|subgrid| subgrid.scale(factor));
+    }
+
+    /// Scales each subgrid by a factor which is the product of the given values `alphas`, `alpha`,
+    /// `logxir`, and `logxif`, each raised to the corresponding powers for each subgrid. In
+    /// addition, every subgrid is scaled by a factor `global` independently of its order.
+    ///
+    /// # Panics
+    ///
+    /// TODO
+    pub fn scale_by_order(
+        &mut self,
+        alphas: f64,
+        alpha: f64,
+        logxir: f64,
+        logxif: f64,
+        global: f64,
+    ) {
+        for ((i, _, _), subgrid) in self.subgrids.indexed_iter_mut() {
+            let order = &self.orders[i];
+            let factor = global
+                * alphas.powi(order.alphas.try_into().unwrap())
+                * alpha.powi(order.alpha.try_into().unwrap())
+                * logxir.powi(order.logxir.try_into().unwrap())
+                * logxif.powi(order.logxif.try_into().unwrap());
+
+            subgrid.scale(factor);
+        }
+    }
+
+    /// Scales each subgrid by `factor` raised to the power of the additional coupling `name` of
+    /// the subgrid's order, see [`Grid::extra_couplings`]. Subgrids whose orders do not contain
+    /// this coupling are not rescaled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GridError::InvalidExtraCouplings`] if the metadata storing the additional
+    /// couplings is malformed; in this case the grid is not modified.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a power does not fit into an `i32`.
+    pub fn scale_by_coupling(&mut self, name: &str, factor: f64) -> Result<(), GridError> {
+        let couplings = self.extra_couplings()?;
+
+        for ((i, _, _), subgrid) in self.subgrids.indexed_iter_mut() {
+            if let Some(&power) = couplings[i].get(name) {
+                subgrid.scale(factor.powi(power.try_into().unwrap()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Scales each subgrid by a bin-dependent factor given in `factors`. If a bin does not have a
+    /// corresponding entry in `factors` it is not rescaled. If `factors` has more entries than
+    /// there are bins the superfluous entries do not have an effect.
+    pub fn scale_by_bin(&mut self, factors: &[f64]) {
+        for ((_, bin, _), subgrid) in self.subgrids.indexed_iter_mut() {
+            if let Some(&factor) = factors.get(bin) {
+                subgrid.scale(factor);
+            }
+        }
+    }
+
+    /// Scales the subgrids of the order with index `order` by a bin-dependent factor given in
+    /// `factors`, which must have as many entries as there are bins. The orders with the same
+    /// powers of the couplings but different powers of the scale logarithms are rescaled with the
+    /// same factors, so that scale variations of the rescaled order stay consistent. In contrast
+    /// to [`Grid::scale_by_bin`] the subgrids of all other orders are not rescaled, which allows,
+    /// for instance, to apply a K-factor to a single order.
+    ///
+    /// # Errors
+    ///
+    /// If `order` is not a valid order index or if the number of `factors` does not match the
+    /// number of bins, an error is returned and the grid is not modified.
+    pub fn scale_order_by_bin(&mut self, order: usize, factors: &[f64]) -> Result<(), GridError> {
+        let orders = self.orders.len();
+        let grid_bins = self.bin_info().bins();
+
+        if order >= orders {
+            return Err(GridError::InvalidOrderIndex { order, orders });
+        }
+
+        if factors.len() != grid_bins {
+            return Err(GridError::BinFactorMismatch {
+                grid_bins,
+                factors: factors.len(),
+            });
+        }
+
+        let Order { alphas, alpha, .. } = self.orders[order];
+
+        for ((ord, bin, _), subgrid) in self.subgrids.indexed_iter_mut() {
+            let other = &self.orders[ord];
+
+            if (other.alphas == alphas) && (other.alpha == alpha) {
+                subgrid.scale(factors[bin]);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Scales each subgrid by a channel- and bin-dependent factor given in `factors`, which must be
+    /// a matrix with as many rows as there are channels and as many columns as there are bins. The
+    /// factor `factors[[channel, bin]]` multiplies all subgrids with the corresponding channel and
+    /// bin, independently of their order.
+    ///
+    /// # Errors
+    ///
+    /// If the shape of `factors` does not match the number of channels and bins of this grid, an
+    /// error is returned.
+    pub fn scale_by_channel_and_bin(&mut self, factors: &Array2<f64>) -> Result<(), GridError> {
+        let channels = self.channels.len();
+        let bins = self.bin_info().bins();
+
+        if factors.dim() != (channels, bins) {
+            return Err(GridError::FactorShapeMismatch {
+                shape: factors.dim(),
+                channels,
+                bins,
+            });
+        }
+
+        for ((_, bin, channel), subgrid) in self.subgrids.indexed_iter_mut() {
+            subgrid.scale(factors[[channel, bin]]);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the subgrid parameters.
+    #[must_use]
+    pub fn orders(&self) -> &[Order] {
+        &self.orders
+    }
+
+    /// Returns a mask for the orders of this grid that can be used as the `order_mask` of
+    /// [`Grid::convolve`]. An order is selected if its exponent of the strong coupling is at most
+    /// `max_alphas` and its exponent of the electromagnetic coupling is at most `max_alpha`, where
+    /// `None` doesn't restrict the corresponding exponent. If `only_born` is `true`, only the orders
+    /// with the smallest sum of both exponents are selected. In contrast to
+    /// [`Order::create_mask`], which counts perturbative orders relative to the leading order, the
+    /// criteria refer to the exponents themselves.
+    ///
+    /// Orders with logarithms of the scale factors are only selected if the order with the same
+    /// exponents but without logarithms is selected as well, which keeps scale variations
+    /// consistent.
+    #[must_use]
+    pub fn order_mask_for(
+        &self,
+        max_alphas: Option<u32>,
+        max_alpha: Option<u32>,
+        only_born: bool,
+    ) -> Vec<bool> {
+        let has_logs = |order: &Order| (order.logxir != 0) || (order.logxif != 0);
+        let born_power = self
+            .orders
+            .iter()
+            .filter(|order| !has_logs(order))
+            .map(|order| order.alphas + order.alpha)
+            .min();
+        let selected = |order: &Order| {
+            max_alphas.map_or(true, |max| order.alphas <= max)
+                && max_alpha.map_or(true, |max| order.alpha <= max)
+                && (!only_born || (Some(order.alphas + order.alpha) == born_power))
+                && (!has_logs(order)
+                    || self.orders.iter().any(|other| {
+                        !has_logs(other)
+                            && (other.alphas == order.alphas)
+                            && (other.alpha == order.alpha)
+                    }))
+        };
+
+        self.orders.iter().map(selected).collect()
+    }
+
+    /// Return a mutable reference to the orders. The powers of additional couplings, see
+    /// [`Grid::extra_couplings`], stay assigned to the orders with the same index.
+    #[must_use]
+    pub fn orders_mut(&mut self) -> &mut [Order] {
+        &mut self.orders
+    }
+
+    /// Return a mutable reference to the grid's channels.
+    pub fn channels_mut(&mut self) -> &mut [Channel] {
+        &mut self.channels
+    }
+
+    /// Return all subgrids as an `ArrayView3`.
+    #[must_use]
+    pub fn subgrids(&self) -> ArrayView3<SubgridEnum> {
+        self.subgrids.view()
+    }
+
+    /// Return the nodes of the subgrid with the indices `order`, `bin` and `channel`, each paired
+    /// with the kinematic variable it belongs to. The squared renormalization and factorization
+    /// scales are labeled with [`Kinematics::Scale`], the momentum fractions with
+    /// [`Kinematics::X`]. For empty subgrids the nodes are empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the indices is out of range.
+    #[must_use]
+    pub fn subgrid_nodes(
+        &self,
+        order: usize,
+        bin: usize,
+        channel: usize,
+    ) -> Vec<(Kinematics, Vec<f64>)> {
+        let subgrid = &self.subgrids[[order, bin, channel]];
+        let mu2_grid = subgrid.mu2_grid();
+
+        vec![
+            (
+                Kinematics::Scale(0),
+                mu2_grid.iter().map(|Mu2 { ren, .. }| *ren).collect(),
+            ),
+            (
+                Kinematics::Scale(1),
+                mu2_grid.iter().map(|Mu2 { fac, .. }| *fac).collect(),
+            ),
+            (Kinematics::X(0), subgrid.x1_grid().into_owned()),
+            (Kinematics::X(1), subgrid.x2_grid().into_owned()),
+        ]
+    }
+
+    /// Return the interpolation that is used to fill the kinematic variable `kinematics`, or `None`
+    /// if the grid does not have this variable. Both scales share the same interpolation. Subgrids
+    /// with the same nodes can only be merged if their interpolations are the same, see
+    /// [`Grid::check_mergeable`].
+    #[must_use]
+    pub fn interp_for_kinematic(&self, kinematics: Kinematics) -> Option<Interp> {
+        if let MoreMembers::V3(Mmv3 {
+            subgrid_template: SubgridEnum::LagrangeSubgridV2(template),
+            ..
+        }) = &self.more_members
+        {
+            return template.interp(kinematics);
+        }
+
+        // all other subgrid types interpolate both momentum fractions in the same way
+        let params = &self.subgrid_params;
+
+        match kinematics {
+            Kinematics::Scale(0 | 1) => Some(Interp::new(
+                params.q2_bins(),
+                params.q2_min(),
+                params.q2_max(),
+                params.q2_order(),
+                false,
+            )),
+            Kinematics::X(0 | 1) => Some(Interp::new(
+                params.x_bins(),
+                params.x_min(),
+                params.x_max(),
+                params.x_order(),
+                params.reweight(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Return the kinds of all subgrids, indexed in the same way as [`Grid::subgrids`].
+    #[must_use]
+    pub fn subgrid_kinds(&self) -> Array3<SubgridKind> {
+        self.subgrids.map(SubgridEnum::kind)
+    }
+
+    /// Return the fill density and estimated memory usage of every subgrid, ordered in the same
+    /// way as the elements of [`Grid::subgrids`]. Empty subgrids report zero entries, zero nodes
+    /// and zero bytes.
+    #[must_use]
+    pub fn subgrid_stats(&self) -> Vec<SubgridStats> {
+        self.subgrids
+            .indexed_iter()
+            .map(|((order, bin, channel), subgrid)| {
+                let stats = subgrid.stats();
+                let kind = subgrid.kind();
+                let mu2_len = subgrid.mu2_grid().len();
+                let x1_len = subgrid.x1_grid().len();
+                let x2_len = subgrid.x2_grid().len();
+
+                // interpolating subgrids compute their nodes from their parameters, import-only
+                // subgrids store them
+                let node_bytes = match kind {
+                    SubgridKind::ImportOnlySubgridV1 => {
+                        (mu2_len + x1_len + x2_len) * mem::size_of::<f64>()
+                    }
+                    SubgridKind::ImportOnlySubgridV2 | SubgridKind::ImportOnlySubgridF32 => {
+                        mu2_len * mem::size_of::<Mu2>() + (x1_len + x2_len) * mem::size_of::<f64>()
+                    }
+                    _ => 0,
+                };
+                // the overhead is measured in units of `f64`, independently of the value type
+                let bytes = stats.allocated * stats.bytes_per_value
+                    + stats.overhead * mem::size_of::<f64>()
+                    + node_bytes;
+
+                SubgridStats {
+                    order,
+                    bin,
+                    channel,
+                    kind,
+                    non_zeros: stats.allocated - stats.zeros,
+                    nodes: mu2_len * x1_len * x2_len,
+                    bytes,
+                }
+            })
+            .collect()
+    }
+
+    /// Replace the subgrid for the given `order`, `bin` and `channel` with `subgrid`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the indices is out of bounds.
+    pub fn set_subgrid(&mut self, order: usize, bin: usize, channel: usize, subgrid: SubgridEnum) {
+        self.subgrids[[order, bin, channel]] = subgrid;
+    }
+
+    /// Construct a `Grid` that stores `predictions`, which must be given for each bin in the same
+    /// way as [`Grid::convolve`] returns them. The new grid has the same bins and metadata as
+    /// `self`, but no convolutions, a single channel and a single order. Convolving it with any
+    /// PDFs therefore returns `predictions`, which makes it possible to store predictions in the
+    /// same format as grids.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `predictions` is not the same as the number of bins.
+    #[must_use]
+    pub fn predictions_grid(&self, predictions: &[f64]) -> Self {
+        let bin_info = self.bin_info();
+
+        assert_eq!(
+            predictions.len(),
+            bin_info.bins(),
+            "the number of predictions does not match the number of bins"
+        );
+
+        let mut grid = Self {
+            subgrids: Array3::from_shape_simple_fn((1, bin_info.bins(), 1), || {
+                EmptySubgridV1.into()
+            }),
+            // the PIDs are irrelevant, because the grid doesn't have convolutions
+            channels: vec![Channel::new(vec![(0, 0, 1.0)])],
+            bin_limits: self.bin_limits.clone(),
+            orders: vec![Order::new(0, 0, 0, 0)],
+            subgrid_params: SubgridParams::default(),
+            more_members: self.more_members.clone(),
+        };
+
+        grid.write_extra_couplings(&[]);
+        grid.set_convolution(0, Convolution::None);
+        grid.set_convolution(1, Convolution::None);
+
+        for (bin, (&prediction, normalization)) in predictions
+            .iter()
+            .zip(bin_info.normalizations())
+            .enumerate()
+        {
+            if prediction == 0.0 {
+                continue;
+            }
+
+            let mut array = SparseArray3::new(1, 1, 1);
+            array[[0, 0, 0]] = prediction * normalization;
+
+            grid.subgrids[[0, bin, 0]] = ImportOnlySubgridV2::new(
+                array,
+                vec![Mu2 { ren: 1e4, fac: 1e4 }],
+                vec![1.0],
+                vec![1.0],
+            )
+            .into();
+        }
+
+        grid
+    }
+
+    /// Return all subgrids as an `ArrayViewMut3`.
+    #[must_use]
+    pub fn subgrids_mut(&mut self) -> ArrayViewMut3<SubgridEnum> {
+        self.subgrids.view_mut()
+    }
+
+    /// Sets a remapper. A remapper can change the dimensions and limits of each bin in this grid.
+    /// This is useful because many Monte Carlo integrators and also `PineAPPL` do not support
+    /// multi-dimensional bins. To work around the problem the multi-dimensional bins can be
+    /// projected to one-dimensional bins, and the remapper can be used to restore the multi
+    /// dimensionality. Furthermore, it allows to normalize each bin separately, and independently
+    /// of the bin widths.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number of bins in the grid and in the remapper do not agree.
+    ///
+    /// # Panics
+    ///
+    /// TODO
+    pub fn set_remapper(&mut self, remapper: BinRemapper) -> Result<(), GridError> {
+        if remapper.bins() != self.bin_info().bins() {
+            return Err(GridError::BinNumberMismatch {
+                grid_bins: self.bin_info().bins(),
+                remapper_bins: remapper.bins(),
+            });
+        }
+
+        self.more_members.upgrade();
+
+        match &mut self.more_members {
+            MoreMembers::V1(_) => unreachable!(),
+            MoreMembers::V2(mmv2) => mmv2.remapper = Some(remapper),
+            MoreMembers::V3(mmv3) => mmv3.remapper = Some(remapper),
+        }
+
+        Ok(())
+    }
+
+    /// Return the currently set remapper, if there is any.
+    #[must_use]
+    pub const fn remapper(&self) -> Option<&BinRemapper> {
+        match &self.more_members {
+            MoreMembers::V1(_) => None,
+            MoreMembers::V2(mmv2) => mmv2.remapper.as_ref(),
+            MoreMembers::V3(mmv3) => mmv3.remapper.as_ref(),
+        }
+    }
+
+    fn remapper_mut(&mut self) -> Option<&mut BinRemapper> {
+        match &mut self.more_members {
+            MoreMembers::V1(_) => None,
+            MoreMembers::V2(mmv2) => mmv2.remapper.as_mut(),
+            MoreMembers::V3(mmv3) => mmv3.remapper.as_mut(),
+        }
+    }
+
+    /// Returns all information about the bins in this grid.
+    #[must_use]
+    pub const fn bin_info(&self) -> BinInfo {
+        BinInfo::new(&self.bin_limits, self.remapper())
+    }
+
+    /// Return the width of each bin, which is the product of the differences between the right
+    /// and left limits of the bin over all dimensions. For grids without a remapper the widths are
+    /// the same as the normalizations returned by [`BinInfo::normalizations`]. The normalizations
+    /// of a remapper, however, are set independently of its limits and therefore can differ from
+    /// the widths, for instance if the observable is not differential in every dimension.
+    #[must_use]
+    pub fn bin_widths(&self) -> Vec<f64> {
+        self.bin_info()
+            .limits()
+            .iter()
+            .map(|limits| limits.iter().map(|&(left, right)| right - left).product())
+            .collect()
+    }
+
+    /// Returns the distribution of the weights of every subgrid, indexed in the same way as
+    /// [`Grid::subgrids`]. Use [`WeightHistogram::is_f32_safe`] to find out which subgrids
+    /// [`Grid::compress_to_f32`] would convert. Empty subgrids have empty histograms.
+    #[must_use]
+    pub fn weight_histograms(&self) -> Array3<WeightHistogram> {
+        self.subgrids
+            .map(|subgrid| WeightHistogram::new(subgrid.indexed_iter().map(|(_, weight)| weight)))
+    }
+
+    /// Converts the non-empty subgrids to [`ImportOnlySubgridF32`], which stores the values with
+    /// single precision and therefore needs about half of the memory. A subgrid is only converted
+    /// if the sum of the absolute rounding errors of its values is at most `rel_tol` times the
+    /// absolute value of the sum of its values, see [`WeightHistogram::is_f32_safe`]; subgrids
+    /// with large cancellations between their values are therefore kept in double precision. The
+    /// returned array has the same shape as [`Grid::subgrids`] and is `true` for each subgrid
+    /// that is stored with single precision after this call. Converted subgrids can no longer be
+    /// filled.
+    pub fn compress_to_f32(&mut self, rel_tol: f64) -> Array3<bool> {
+        self.subgrids.map_mut(|subgrid| {
+            if subgrid.is_empty() {
+                return false;
+            }
+
+            if let SubgridEnum::ImportOnlySubgridF32(_) = subgrid {
+                return true;
+            }
+
+            let subgrid_f64 = ImportOnlySubgridV2::from(&*subgrid);
+
+            if WeightHistogram::new(subgrid_f64.indexed_iter().map(|(_, weight)| weight))
+                .is_f32_safe(rel_tol)
+            {
+                *subgrid = ImportOnlySubgridF32::from(&subgrid_f64).into();
+                true
+            } else {
+                false
+            }
+        })
+    }
+
+    /// Calls [`Self::optimize_using`] with all possible optimization options
+    /// ([`GridOptFlags::all`]).
+    pub fn optimize(&mut self) {
+        self.optimize_using(GridOptFlags::all());
+    }
+
+    /// Optimizes the internal datastructures for space efficiency. The parameter `flags`
+    /// determines which optimizations are applied, see [`GridOptFlags`].
+    pub fn optimize_using(&mut self, flags: GridOptFlags) {
+        let _ = self.optimize_with_counts(flags);
+    }
+
+    /// Same as [`Grid::optimize_using`], but returns a report of what the optimizations changed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of the serialized grid can not be determined.
+    pub fn optimize_using_report(&mut self, flags: GridOptFlags) -> OptimizeReport {
+        // UNWRAP: serializing a grid only fails when writing fails
+        let bytes_before = bincode::serialized_size(self).unwrap();
+        let report = self.optimize_with_counts(flags);
+        let bytes_after = bincode::serialized_size(self).unwrap();
+
+        OptimizeReport {
+            bytes_before,
+            bytes_after,
+            ..report
+        }
+    }
+
+    fn optimize_with_counts(&mut self, flags: GridOptFlags) -> OptimizeReport {
+        let mut report = OptimizeReport::default();
+
+        if flags.contains(GridOptFlags::OPTIMIZE_SUBGRID_TYPE) {
+            let ssd = flags.contains(GridOptFlags::STATIC_SCALE_DETECTION);
+            let kinds: Vec<_> = self.subgrids.iter().map(mem::discriminant).collect();
+            self.optimize_subgrid_type(ssd);
+            report.subgrids_converted = kinds
+                .into_iter()
+                .zip(self.subgrids.iter().map(mem::discriminant))
+                .filter(|(before, after)| before != after)
+                .count();
+        }
+        if flags.contains(GridOptFlags::SYMMETRIZE_CHANNELS) {
+            report.channels_symmetrized = self.symmetrize_channels();
+        }
+        if flags.contains(GridOptFlags::STRIP_EMPTY_ORDERS) {
+            report.orders_stripped = self.strip_empty_orders();
+        }
+        if flags.contains(GridOptFlags::MERGE_SAME_CHANNELS) {
+            report.channels_merged = self.merge_same_channels();
+        }
+        if flags.contains(GridOptFlags::STRIP_EMPTY_CHANNELS) {
+            report.channels_stripped = self.strip_empty_channels();
+        }
+
+        report
+    }
+
+    fn optimize_subgrid_type(&mut self, static_scale_detection: bool) {
+        for subgrid in &mut self.subgrids {
+            match subgrid {
+                // replace empty subgrids of any type with `EmptySubgridV1`
+                _ if subgrid.is_empty() => {
+                    *subgrid = EmptySubgridV1.into();
+                }
+                // can't be optimized without losing information
+                SubgridEnum::NtupleSubgridV1(_) => continue,
+                // keep the single-precision storage
+                SubgridEnum::ImportOnlySubgridF32(_) => {
+                    *subgrid =
+                        ImportOnlySubgridF32::from(&ImportOnlySubgridV2::from(&*subgrid)).into();
+                }
+                _ => {
+                    // TODO: this requires a `pub(crate)` in `LagrangeSubgridV2`; we should
+                    // replace this with a method
+                    if !static_scale_detection {
+                        if let SubgridEnum::LagrangeSubgridV2(subgrid) = subgrid {
+                            // disable static-scale detection
+                            subgrid.static_q2 = -1.0;
+                        }
+                    }
+
+                    *subgrid = ImportOnlySubgridV2::from(&*subgrid).into();
+                }
+            }
+        }
+    }
+
+    /// Try to deduplicate channels by detecting pairs of them that contain the same subgrids. The
+    /// numerical equality is tested using a tolerance of `ulps`, given in [units of least
+    /// precision](https://docs.rs/float-cmp/latest/float_cmp/index.html#some-explanation).
+    pub fn dedup_channels(&mut self, ulps: i64) {
+        let mut indices: Vec<usize> = (0..self.channels.len()).collect();
+
+        while let Some(index) = indices.pop() {
+            if let Some(other_index) = indices.iter().copied().find(|&other_index| {
+                let (mut a, mut b) = self
+                    .subgrids
+                    .multi_slice_mut((s![.., .., other_index], s![.., .., index]));
+
+                // TODO: use `Iterator::eq_by` once stablizied
+                for (lhs, rhs) in a.iter_mut().zip(b.iter_mut()) {
+                    let mut it_a = lhs.indexed_iter();
+                    let mut it_b = rhs.indexed_iter();
+
+                    loop {
+                        let a = it_a.next();
+                        let b = it_b.next();
+
+                        match (a, b) {
+                            (Some((tuple_a, value_a)), Some((tuple_b, value_b))) => {
+                                if tuple_a != tuple_b {
+                                    return false;
+                                }
+
+                                let u = ulps;
+                                if !approx_eq!(f64, value_a, value_b, ulps = u) {
+                                    return false;
+                                }
+                            }
+                            (None, None) => break,
+                            _ => return false,
+                        }
+                    }
+                }
+
+                true
+            }) {
+                let old_channel = self.channels.remove(index).entry().to_vec();
+                let mut new_channel = self.channels[other_index].entry().to_vec();
+                new_channel.extend(old_channel);
+                self.channels[other_index] = Channel::new(new_channel);
+                self.subgrids.remove_index(Axis(2), index);
+            }
+        }
+    }
+
+    /// Returns the channels that [`GridOptFlags::MERGE_SAME_CHANNELS`] merges, in the order in
+    /// which they are merged. Each tuple `(index, other_index, factor)` means that the subgrids of
+    /// the channel with index `index` are divided by `factor` and merged into the ones of the
+    /// channel with index `other_index`, which leaves the former channel empty.
+    #[must_use]
+    pub fn mergeable_channels(&self) -> Vec<(usize, usize, f64)> {
+        let mut indices: Vec<_> = (0..self.channels.len()).rev().collect();
+        let mut mergeable = Vec::new();
+
+        while let Some(index) = indices.pop() {
+            if let Some((other_index, factor)) = indices.iter().find_map(|&i| {
+                self.channels[i]
+                    .common_factor(&self.channels[index])
+                    .map(|factor| (i, factor))
+            }) {
+                mergeable.push((index, other_index, factor));
+            }
+        }
+
+        mergeable
+    }
+
+    /// Returns the number of channels that were merged into other channels.
+    fn merge_same_channels(&mut self) -> usize {
+        let mergeable = self.mergeable_channels();
+
+        // merge channels that are the same
+        for &(index, other_index, factor) in &mergeable {
+            let (mut a, mut b) = self
+                .subgrids
+                .multi_slice_mut((s![.., .., other_index], s![.., .., index]));
+
+            // check if in all cases the limits are compatible with merging
+            for (lhs, rhs) in a.iter_mut().zip(b.iter_mut()) {
+                if !rhs.is_empty() {
+                    rhs.scale(1.0 / factor);
+                    if lhs.is_empty() {
+                        // we can't merge into an EmptySubgridV1
+                        *lhs = rhs.clone_empty();
+                    }
+                    lhs.merge(rhs, false);
+
+                    *rhs = EmptySubgridV1.into();
+                }
+            }
+        }
+
+        mergeable.len()
+    }
+
+    /// Removes all channels whose subgrids are empty for every order and bin, and returns how many
+    /// channels were removed. In contrast to [`Grid::optimize_using`] with
+    /// [`GridOptFlags::STRIP_EMPTY_CHANNELS`] no other optimization is performed, in particular
+    /// the types of the subgrids stay the same. This is useful after [`Grid::delete_bins`] or
+    /// [`Grid::delete_orders`], which may leave channels without any contributions.
+    pub fn strip_empty_channels(&mut self) -> usize {
+        let channels = self.channels.len();
+        let mut indices: Vec<_> = (0..self.channels().len()).collect();
+
+        while let Some(index) = indices.pop() {
+            if self
+                .subgrids
+                .slice(s![.., .., index])
+                .iter()
+                .all(Subgrid::is_empty)
+            {
+                self.channels.remove(index);
+                self.subgrids.remove_index(Axis(2), index);
+            }
+        }
+
+        channels - self.channels.len()
+    }
+
+    /// Removes all orders whose subgrids are empty for every bin and channel, and returns how many
+    /// orders were removed. In contrast to [`Grid::optimize_using`] with
+    /// [`GridOptFlags::STRIP_EMPTY_ORDERS`] no other optimization is performed, in particular the
+    /// types of the subgrids stay the same.
+    pub fn strip_empty_orders(&mut self) -> usize {
+        let orders = self.orders.len();
+        let mut indices: Vec<_> = (0..self.orders().len()).collect();
+
+        while let Some(index) = indices.pop() {
+            if self
+                .subgrids
+                .slice(s![index, .., ..])
+                .iter()
+                .all(Subgrid::is_empty)
+            {
+                self.delete_extra_couplings(index);
+                self.orders.remove(index);
+                self.subgrids.remove_index(Axis(0), index);
+            }
+        }
+
+        orders - self.orders.len()
+    }
+
+    /// Returns the pairs of channels that [`GridOptFlags::SYMMETRIZE_CHANNELS`] merges. Each
+    /// tuple `(index, other_index)` means that the subgrids of the channel with index
+    /// `other_index`, which is the transpose of the channel with index `index`, are transposed and
+    /// merged into the ones of the latter, which leaves the channel with index `other_index`
+    /// empty. If the two convolutions of this grid are different, no channels are returned.
+    #[must_use]
+    pub fn symmetrizable_channels(&self) -> Vec<(usize, usize)> {
+        let convolutions = self.convolutions();
+        if convolutions[0] != convolutions[1] {
+            return Vec::new();
+        }
+
+        let mut indices: Vec<usize> = (0..self.channels.len()).rev().collect();
+        let mut symmetrizable = Vec::new();
+
+        while let Some(index) = indices.pop() {
+            let channel_entry = &self.channels[index];
+
+            // channels that are their own transpose are symmetrized, but not merged
+            if channel_entry.is_transpose_of(channel_entry, 0, 1) {
+                continue;
+            }
+
+            if let Some((j, &other_index)) = indices
+                .iter()
+                .enumerate()
+                .find(|(_, i)| channel_entry.is_transpose_of(&self.channels[**i], 0, 1))
+            {
+                indices.remove(j);
+                symmetrizable.push((index, other_index));
+            }
+        }
+
+        symmetrizable
+    }
+
+    /// Returns the number of channels that were merged into their transposed channels.
+    fn symmetrize_channels(&mut self) -> usize {
+        let convolutions = self.convolutions();
+        if convolutions[0] != convolutions[1] {
+            return 0;
+        }
+
+        for index in 0..self.channels.len() {
+            let channel_entry = &self.channels[index];
+
+            if channel_entry.is_transpose_of(channel_entry, 0, 1) {
+                // check if in all cases the limits are compatible with merging
+                self.subgrids
+                    .slice_mut(s![.., .., index])
+                    .iter_mut()
+                    .for_each(|subgrid| {
+                        if !subgrid.is_empty() && (subgrid.x1_grid() == subgrid.x2_grid()) {
+                            subgrid.symmetrize();
+                        }
+                    });
+            }
+        }
+
+        let symmetrizable = self.symmetrizable_channels();
+
+        for &(index, other_index) in &symmetrizable {
+            // check if in all cases the limits are compatible with merging
+            let (mut a, mut b) = self
+                .subgrids
+                .multi_slice_mut((s![.., .., index], s![.., .., other_index]));
+
+            for (lhs, rhs) in a.iter_mut().zip(b.iter_mut()) {
+                if !rhs.is_empty() {
+                    if lhs.is_empty() {
+                        // we can't merge into an EmptySubgridV1
+                        *lhs = rhs.clone_empty();
+                    }
+
+                    lhs.merge(rhs, true);
+                    *rhs = EmptySubgridV1.into();
+                }
+            }
+        }
+
+        symmetrizable.len()
+    }
+
+    /// Upgrades the internal data structures to their latest versions.
+    pub fn upgrade(&mut self) {
+        self.more_members.upgrade();
+    }
+
+    /// Returns a map with key-value pairs, if there are any stored in this grid.
+    #[must_use]
+    pub const fn key_values(&self) -> Option<&HashMap<String, String>> {
+        match &self.more_members {
+            MoreMembers::V3(mmv3) => Some(&mmv3.key_value_db),
+            MoreMembers::V2(mmv2) => Some(&mmv2.key_value_db),
+            MoreMembers::V1(_) => None,
+        }
+    }
+
+    /// Returns a map with key-value pairs, if there are any stored in this grid.
+    ///
+    /// # Panics
+    ///
+    /// TODO
+    #[must_use]
+    pub fn key_values_mut(&mut self) -> &mut HashMap<String, String> {
+        self.more_members.upgrade();
+
+        match &mut self.more_members {
+            MoreMembers::V1(_) => unreachable!(),
+            MoreMembers::V2(mmv2) => &mut mmv2.key_value_db,
+            MoreMembers::V3(mmv3) => &mut mmv3.key_value_db,
+        }
+    }
+
+    /// Sets a specific key-value pair in this grid.
+    ///
+    /// # Panics
+    ///
+    /// TODO
+    pub fn set_key_value(&mut self, key: &str, value: &str) {
+        self.key_values_mut()
+            .insert(key.to_owned(), value.to_owned());
+    }
+
+    /// Returns the labels of each bin dimension, see [`DimLabel`].
+    #[must_use]
+    pub fn bin_dimension_labels(&self) -> Vec<DimLabel> {
+        let key_values = self.key_values();
+        let get = |key: String| {
+            key_values
+                .and_then(|kv| kv.get(&key))
+                .cloned()
+                .unwrap_or_default()
+        };
+
+        (1..=self.bin_info().dimensions())
+            .map(|d| DimLabel {
+                label: get(format!("x{d}_label")),
+                label_tex: get(format!("x{d}_label_tex")),
+                unit: get(format!("x{d}_unit")),
+            })
+            .collect()
+    }
+
+    /// Returns the reference results stored in the metadata, see [`Reference`]. If either of the
+    /// keys is missing or the results can not be parsed, `None` is returned.
+    ///
+    /// The reference is kept when bins are deleted, and when grids with different bins are merged
+    /// and both store a reference for the same PDF. Merging bins, rebinning, and any other merge
+    /// of grids removes it, because the results of the new bins are not known.
+    ///
+    /// # Errors
+    ///
+    /// If the number of stored results does not match the number of bins of this grid, an error
+    /// is returned.
+    pub fn reference(&self) -> Result<Option<Reference>, GridError> {
+        let Some(reference) = self.key_values().and_then(|key_values| {
+            let pdf = key_values.get("results_pdf")?.clone();
+            let results = key_values
+                .get("reference")?
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .ok()?;
+
+            Some(Reference { pdf, results })
+        }) else {
+            return Ok(None);
+        };
+
+        if reference.results.len() != self.bin_info().bins() {
+            return Err(GridError::ReferenceBinMismatch {
+                grid_bins: self.bin_info().bins(),
+                reference_bins: reference.results.len(),
+            });
+        }
+
+        Ok(Some(reference))
+    }
+
+    /// Stores `reference` in the metadata, see [`Reference`], replacing a previously stored one.
+    ///
+    /// # Errors
+    ///
+    /// If the number of results in `reference` does not match the number of bins of this grid,
+    /// an error is returned.
+    pub fn set_reference(&mut self, reference: &Reference) -> Result<(), GridError> {
+        if reference.results.len() != self.bin_info().bins() {
+            return Err(GridError::ReferenceBinMismatch {
+                grid_bins: self.bin_info().bins(),
+                reference_bins: reference.results.len(),
+            });
+        }
+
+        self.set_key_value("results_pdf", &reference.pdf);
+        self.set_key_value(
+            "reference",
+            &reference
+                .results
+                .iter()
+                .map(|result| format!("{result:e}"))
+                .join(" "),
+        );
+
+        Ok(())
+    }
+
+    fn remove_reference(&mut self) {
+        if self
+            .key_values()
+            .is_some_and(|kv| kv.contains_key("reference") || kv.contains_key("results_pdf"))
+        {
+            let key_values = self.key_values_mut();
+            key_values.remove("reference");
+            key_values.remove("results_pdf");
+        }
+    }
+
+    /// Scales this grid such that its predictions match the stored [`Reference`], and returns the
+    /// factor that was applied. This fixes grids that differ from their reference by a constant
+    /// factor, for instance because of a mismatch of units. The PDFs and the strong coupling in
+    /// `lumi_cache` must be the ones of [`Reference::pdf`]. The ratios of the reference to the
+    /// predictions must agree within the relative `tolerance` for all bins; bins where both
+    /// numbers are zero are ignored.
+    ///
+    /// # Errors
+    ///
+    /// If this grid does not store a reference, or its number of results is different from the
+    /// number of bins, or if the ratios are not the same for all bins, an error is returned and
+    /// this grid is left unchanged.
+    pub fn calibrate_to_reference(
+        &mut self,
+        lumi_cache: &mut LumiCache,
+        tolerance: f64,
+    ) -> Result<f64, GridError> {
+        let reference = self.reference()?.ok_or(GridError::MissingReference)?;
+
+        let results = self.convolve(lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+        let ratios: Vec<_> = reference
+            .results
+            .iter()
+            .zip(&results)
+            .enumerate()
+            .filter(|(_, (&reference, &result))| (reference != 0.0) || (result != 0.0))
+            .map(|(bin, (reference, result))| (bin, reference / result))
+            .collect();
+
+        if ratios.is_empty() {
+            return Ok(1.0);
+        }
+
+        // the bins are weighted equally, so that no bin dominates the factor
+        #[allow(clippy::cast_precision_loss)]
+        let factor = ratios.iter().map(|&(_, ratio)| ratio).sum::<f64>() / ratios.len() as f64;
+
+        if let Some(&(bin, ratio)) = ratios
+            .iter()
+            .find(|(_, ratio)| !ratio.is_finite() || ((ratio / factor - 1.0).abs() > tolerance))
+        {
+            return Err(GridError::InconsistentReference { bin, ratio, factor });
+        }
+
+        self.scale(factor);
+
+        Ok(factor)
+    }
+
+    /// Returns information for the generation of evolution operators that are being used in
+    /// [`Grid::evolve`] with the parameter `order_mask`.
+    #[must_use]
+    pub fn evolve_info(&self, order_mask: &[bool]) -> EvolveInfo {
+        use super::evolution::EVOLVE_INFO_TOL_ULPS;
+
+        let has_pdf1 = self.convolutions()[0] != Convolution::None;
+        let has_pdf2 = self.convolutions()[1] != Convolution::None;
+
+        let mut ren1 = Vec::new();
+        let mut fac1 = Vec::new();
+        let mut x1 = Vec::new();
+        let mut pids1 = Vec::new();
+
+        for (channel, subgrid) in self
+            .subgrids()
+            .indexed_iter()
+            .filter_map(|(tuple, subgrid)| {
+                (!subgrid.is_empty() && (order_mask.is_empty() || order_mask[tuple.0]))
+                    .then_some((tuple.2, subgrid))
+            })
+        {
+            ren1.extend(subgrid.mu2_grid().iter().map(|Mu2 { ren, .. }| *ren));
+            ren1.sort_by(f64::total_cmp);
+            ren1.dedup_by(|a, b| approx_eq!(f64, *a, *b, ulps = EVOLVE_INFO_TOL_ULPS));
+
+            fac1.extend(subgrid.mu2_grid().iter().map(|Mu2 { fac, .. }| *fac));
+            fac1.sort_by(f64::total_cmp);
+            fac1.dedup_by(|a, b| approx_eq!(f64, *a, *b, ulps = EVOLVE_INFO_TOL_ULPS));
+
+            if has_pdf1 {
+                x1.extend(subgrid.x1_grid().iter().copied());
+            }
+            if has_pdf2 {
+                x1.extend(subgrid.x2_grid().iter());
+            }
+
+            x1.sort_by(f64::total_cmp);
+            x1.dedup_by(|a, b| approx_eq!(f64, *a, *b, ulps = EVOLVE_INFO_TOL_ULPS));
+
+            if has_pdf1 {
+                pids1.extend(self.channels()[channel].entry().iter().map(|(a, _, _)| a));
+            }
+            if has_pdf2 {
+                pids1.extend(self.channels()[channel].entry().iter().map(|(_, b, _)| b));
+            }
+
+            pids1.sort_unstable();
+            pids1.dedup();
+        }
+
+        EvolveInfo {
+            fac1,
+            pids1,
+            x1,
+            ren1,
+        }
+    }
+
+    /// Converts this `Grid` into an [`FkTable`] using an evolution kernel operator (EKO) given as
+    /// `operator`. The dimensions and properties of this operator must be described using `info`.
+    /// The parameter `order_mask` can be used to include or exclude orders from this operation,
+    /// and must correspond to the ordering given by [`Grid::orders`]. Orders that are not given
+    /// are enabled, and in particular if `order_mask` is empty all orders are activated.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GridError::EvolutionFailure`] if either the `operator` or its `info` is
+    /// incompatible with this `Grid`.
+    #[deprecated(since = "0.7.4", note = "use evolve_with_slice_iter instead")]
+    pub fn evolve(
+        &self,
+        operator: ArrayView5<f64>,
+        info: &OperatorInfo,
+        order_mask: &[bool],
+    ) -> Result<FkTable, GridError> {
+        self.evolve_with_slice_iter(
+            info.fac1
+                .iter()
+                .zip(operator.axis_iter(Axis(0)))
+                .map(|(&fac1, op)| {
+                    Ok::<_, GridError>((
+                        OperatorSliceInfo {
+                            fac0: info.fac0,
+                            pids0: info.pids0.clone(),
+                            x0: info.x0.clone(),
+                            fac1,
+                            pids1: info.pids1.clone(),
+                            x1: info.x1.clone(),
+                            pid_basis: info.pid_basis,
+                        },
+                        CowArray::from(op),
+                    ))
+                }),
+            order_mask,
+            (info.xir, info.xif),
+            &AlphasTable {
+                ren1: info.ren1.clone(),
+                alphas: info.alphas.clone(),
+            },
+        )
+    }
+
+    // TODO:
+    // - try to find a better solution than to require that E must be convertible into
+    //   anyhow::Error
+
+    /// Converts this `Grid` into an [`FkTable`] using `slices` that must iterate over a [`Result`]
+    /// of tuples of an [`OperatorSliceInfo`] and the corresponding sliced operator. The parameter
+    /// `order_mask` can be used to include or exclude orders from this operation, and must
+    /// correspond to the ordering given by [`Grid::orders`]. Orders that are not given are
+    /// enabled, and in particular if `order_mask` is empty all orders are activated.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GridError::EvolutionFailure`] if either the `operator` or its `info` is
+    /// incompatible with this `Grid`. Returns a [`GridError::Other`] if the iterator from `slices`
+    /// return an error.
+    pub fn evolve_with_slice_iter<'a, E: Into<anyhow::Error>>(
+        &self,
+        slices: impl IntoIterator<Item = Result<(OperatorSliceInfo, CowArray<'a, f64, Ix4>), E>>,
+        order_mask: &[bool],
+        xi: (f64, f64),
+        alphas_table: &AlphasTable,
+    ) -> Result<FkTable, GridError> {
+        self.evolve_with_slice_iter_with_progress(slices, order_mask, xi, alphas_table, |_, _| {})
+    }
+
+    /// Same as [`Grid::evolve_with_slice_iter`], but calls `progress` after each evolved slice
+    /// with the number of slices evolved so far and the total number of slices needed by this
+    /// `Grid`. The callback has no influence on the result.
+    ///
+    /// # Errors
+    ///
+    /// See [`Grid::evolve_with_slice_iter`].
+    pub fn evolve_with_slice_iter_with_progress<'a, E: Into<anyhow::Error>>(
+        &self,
+        slices: impl IntoIterator<Item = Result<(OperatorSliceInfo, CowArray<'a, f64, Ix4>), E>>,
+        order_mask: &[bool],
+        xi: (f64, f64),
+        alphas_table: &AlphasTable,
+        progress: impl FnMut(usize, usize),
+    ) -> Result<FkTable, GridError> {
+        let mut fk_tables = self.evolve_groups(
+            slices
+                .into_iter()
+                .map(|result| result.map(|slice| vec![slice])),
+            order_mask,
+            xi,
+            alphas_table,
+            progress,
+        )?;
+
+        // TODO: convert this unwrap into error
+        Ok(fk_tables.pop().unwrap())
+    }
+
+    /// Converts this `Grid` into one [`FkTable`] for each group of operators, for instance for
+    /// different starting scales. Each item of `slices` must contain one [`OperatorSliceInfo`]
+    /// and the corresponding sliced operator for every group, all with the same
+    /// [`OperatorSliceInfo::fac1`], and the slices of the same group must all have the same
+    /// [`OperatorSliceInfo::fac0`]. The returned FK tables are in the order of the groups in each
+    /// item. Compared to calling [`Grid::evolve_with_slice_iter`] once for each group, the
+    /// subgrids are traversed only once for each `fac1`; the summed subgrids of all bins and
+    /// channels for a single `fac1` are kept in memory while the operators of all groups are
+    /// applied. The parameters `order_mask`, `xi` and `alphas_table` have the same meaning as for
+    /// [`Grid::evolve_with_slice_iter`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GridError::EvolutionFailure`] if either an operator or its info is
+    /// incompatible with this `Grid`, if an item of `slices` doesn't contain a slice for each
+    /// group, or if the slices violate the conditions on `fac0` and `fac1` given above. Returns a
+    /// [`GridError::Other`] if the iterator from `slices` returns an error.
+    pub fn evolve_with_slice_iter_groups<'a, E: Into<anyhow::Error>>(
+        &self,
+        slices: impl IntoIterator<Item = Result<Vec<(OperatorSliceInfo, CowArray<'a, f64, Ix4>)>, E>>,
+        order_mask: &[bool],
+        xi: (f64, f64),
+        alphas_table: &AlphasTable,
+    ) -> Result<Vec<FkTable>, GridError> {
+        self.evolve_groups(slices, order_mask, xi, alphas_table, |_, _| {})
+    }
+
+    fn evolve_groups<'a, E: Into<anyhow::Error>>(
+        &self,
+        slices: impl IntoIterator<Item = Result<Vec<(OperatorSliceInfo, CowArray<'a, f64, Ix4>)>, E>>,
+        order_mask: &[bool],
+        xi: (f64, f64),
+        alphas_table: &AlphasTable,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<Vec<FkTable>, GridError> {
+        use super::evolution::EVOLVE_INFO_TOL_ULPS;
+
+        // one (partial) FK table for each group
+        let mut lhs: Vec<Option<Self>> = Vec::new();
+        // the starting scale of each group, which is set by the first item of `slices`
+        let mut groups_fac0: Vec<f64> = Vec::new();
+        // Q2 slices we use
+        let mut used_op_fac1 = Vec::new();
+        // Q2 slices we encounter, but possibly don't use
+        let mut op_fac1 = Vec::new();
+        // Q2 slices needed by the grid
+        let grid_fac1: Vec<_> = self
+            .evolve_info(order_mask)
+            .fac1
+            .into_iter()
+            .map(|fac| xi.1 * xi.1 * fac)
+            .collect();
+        // this only depends on the grid, so don't recompute it for every slice
+        let gluon_has_pid_zero = evolution::gluon_has_pid_zero(self);
+
+        for result in slices {
+            let group_slices = result.map_err(|err| GridError::Other(err.into()))?;
+
+            if op_fac1.is_empty() {
+                groups_fac0 = group_slices.iter().map(|(info, _)| info.fac0).collect();
+                lhs = vec![None; group_slices.len()];
+            } else if group_slices.len() != groups_fac0.len() {
+                return Err(GridError::EvolutionFailure(format!(
+                    "expected {} operator slices, one for each group, but got {}",
+                    groups_fac0.len(),
+                    group_slices.len()
+                )));
+            }
+
+            let Some((first, _)) = group_slices.first() else {
+                return Err(GridError::EvolutionFailure(
+                    "no operator slices were given".to_owned(),
+                ));
+            };
+            let fac1 = first.fac1;
+
+            for (index, ((info, _), &fac0)) in group_slices.iter().zip(&groups_fac0).enumerate() {
+                if !approx_eq!(f64, info.fac0, fac0, ulps = EVOLVE_INFO_TOL_ULPS) {
+                    return Err(GridError::EvolutionFailure(format!(
+                        "the operator slice of group {index} has fac0 = {}, but the group started \
+                         with fac0 = {fac0}",
+                        info.fac0
+                    )));
+                }
+
+                if !approx_eq!(f64, info.fac1, fac1, ulps = EVOLVE_INFO_TOL_ULPS) {
+                    return Err(GridError::EvolutionFailure(format!(
+                        "the operator slice of group {index} has fac1 = {}, but the slice of \
+                         group 0 has fac1 = {fac1}",
+                        info.fac1
+                    )));
+                }
+            }
+
+            op_fac1.push(fac1);
+
+            // it's possible that due to small numerical differences we get two slices which are
+            // almost the same. We have to skip those in order not to evolve the 'same' slice twice
+            if used_op_fac1
+                .iter()
+                .any(|&fac| approx_eq!(f64, fac, fac1, ulps = EVOLVE_INFO_TOL_ULPS))
+            {
+                continue;
+            }
+
+            // skip slices that the grid doesn't use
+            if !grid_fac1
+                .iter()
+                .any(|&fac| approx_eq!(f64, fac, fac1, ulps = EVOLVE_INFO_TOL_ULPS))
+            {
+                continue;
+            }
+
+            // the traversal of the subgrids is shared by all groups
+            let grid_slice = evolution::grid_slice(self, fac1, order_mask, xi, alphas_table)?;
+
+            for ((info, operator), lhs) in group_slices.iter().zip(&mut lhs) {
+                let rhs = self.evolve_slice(info, operator, &grid_slice, gluon_has_pid_zero)?;
+
+                if let Some(lhs) = lhs {
+                    lhs.merge(rhs)?;
+                } else {
+                    *lhs = Some(rhs);
+                }
+            }
+
+            used_op_fac1.push(fac1);
+
+            progress(used_op_fac1.len(), grid_fac1.len());
+        }
+
+        // UNWRAP: if we can't compare two numbers there's a bug
+        op_fac1.sort_by(|a, b| a.partial_cmp(b).unwrap_or_else(|| unreachable!()));
+
+        // make sure we've evolved all slices
+        if let Some(muf2) = grid_fac1.into_iter().find(|&grid_mu2| {
+            !used_op_fac1
+                .iter()
+                .any(|&eko_mu2| approx_eq!(f64, grid_mu2, eko_mu2, ulps = EVOLVE_INFO_TOL_ULPS))
+        }) {
+            return Err(GridError::EvolutionFailure(format!(
+                "no operator for muf2 = {muf2} found in {op_fac1:?}"
+            )));
+        }
+
+        let bin_central_scales = self.central_fac_scales(order_mask, xi.1);
+
+        Ok(lhs
+            .into_iter()
+            .map(|lhs| {
+                // TODO: convert this unwrap into error
+                let mut grid = lhs.unwrap();
+
+                grid.set_bin_central_scales(&bin_central_scales);
+
+                // UNWRAP: merging evolved slices should be a proper FkTable again
+                FkTable::try_from(grid).unwrap_or_else(|_| unreachable!())
+            })
+            .collect())
+    }
+
+    /// Evolves the slice of this `Grid` described by `info` with `operator`.
+    fn evolve_slice(
+        &self,
+        info: &OperatorSliceInfo,
+        operator: &CowArray<f64, Ix4>,
+        grid_slice: &evolution::GridSlice,
+        gluon_has_pid_zero: bool,
+    ) -> Result<Self, GridError> {
+        let op_info_dim = (
+            info.pids1.len(),
+            info.x1.len(),
+            info.pids0.len(),
+            info.x0.len(),
+        );
+
+        assert_eq!(
+            operator.dim(),
+            op_info_dim,
+            "operator information {:?} does not match the operator's dimensions: {:?}",
+            op_info_dim,
+            operator.dim(),
+        );
+
+        let view = operator.view();
+
+        let (subgrids, channels) = if self.convolutions()[0] != Convolution::None
+            && self.convolutions()[1] != Convolution::None
+        {
+            evolution::evolve_slice_with_two(self, &view, info, grid_slice, gluon_has_pid_zero)
+        } else {
+            evolution::evolve_slice_with_one(self, &view, info, grid_slice, gluon_has_pid_zero)
+        }?;
+
+        let mut rhs = Self {
+            subgrids,
+            channels,
+            bin_limits: self.bin_limits.clone(),
+            orders: vec![Order::new(0, 0, 0, 0)],
+            subgrid_params: SubgridParams::default(),
+            more_members: self.more_members.clone(),
+        };
+
+        // the evolved orders are summed, their additional couplings are therefore lost
+        rhs.write_extra_couplings(&[]);
+        // TODO: use a new constructor to set this information
+        rhs.set_pid_basis(info.pid_basis);
+
+        Ok(rhs)
+    }
+
+    /// Converts this `Grid` into an [`FkTable`] using `slices` that must iterate over a [`Result`]
+    /// of tuples of an [`OperatorSliceInfo`] and the corresponding sliced operator. The parameter
+    /// `order_mask` can be used to include or exclude orders from this operation, and must
+    /// correspond to the ordering given by [`Grid::orders`]. Orders that are not given are
+    /// enabled, and in particular if `order_mask` is empty all orders are activated.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GridError::EvolutionFailure`] if either the `operator` or its `info` is
+    /// incompatible with this `Grid`. Returns a [`GridError::Other`] if the iterator from `slices`
+    /// return an error.
+    pub fn evolve_with_slice_iter2<'a, E: Into<anyhow::Error>>(
+        &self,
+        slices_a: impl IntoIterator<Item = Result<(OperatorSliceInfo, CowArray<'a, f64, Ix4>), E>>,
+        slices_b: impl IntoIterator<Item = Result<(OperatorSliceInfo, CowArray<'a, f64, Ix4>), E>>,
+        order_mask: &[bool],
+        xi: (f64, f64),
+        alphas_table: &AlphasTable,
+    ) -> Result<FkTable, GridError> {
+        self.evolve_with_slice_iter2_with_progress(
+            slices_a,
+            slices_b,
+            order_mask,
+            xi,
+            alphas_table,
+            |_, _| {},
+        )
+    }
+
+    /// Same as [`Grid::evolve_with_slice_iter2`], but calls `progress` after each evolved slice
+    /// with the number of slices evolved so far and the total number of slices needed by this
+    /// `Grid`. The callback has no influence on the result.
+    ///
+    /// # Errors
+    ///
+    /// See [`Grid::evolve_with_slice_iter2`].
+    pub fn evolve_with_slice_iter2_with_progress<'a, E: Into<anyhow::Error>>(
+        &self,
+        slices_a: impl IntoIterator<Item = Result<(OperatorSliceInfo, CowArray<'a, f64, Ix4>), E>>,
+        slices_b: impl IntoIterator<Item = Result<(OperatorSliceInfo, CowArray<'a, f64, Ix4>), E>>,
+        order_mask: &[bool],
+        xi: (f64, f64),
+        alphas_table: &AlphasTable,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<FkTable, GridError> {
+        use super::evolution::EVOLVE_INFO_TOL_ULPS;
+        use itertools::izip;
+
+        let mut lhs: Option<Self> = None;
+        // Q2 slices we use
+        let mut used_op_fac1 = Vec::new();
+        // Q2 slices we encounter, but possibly don't use
+        let mut op_fac1 = Vec::new();
+        // Q2 slices needed by the grid
+        let grid_fac1: Vec<_> = self
+            .evolve_info(order_mask)
+            .fac1
+            .into_iter()
+            .map(|fac| xi.1 * xi.1 * fac)
+            .collect();
+        // this only depends on the grid, so don't recompute it for every slice
+        let gluon_has_pid_zero = evolution::gluon_has_pid_zero(self);
+
+        // TODO: simplify the ugly repetition below by offloading some ops into fn
+        for (result_a, result_b) in izip!(slices_a, slices_b) {
+            // Operate on `slices_a`
+            let (info_a, operator_a) = result_a.map_err(|err| GridError::Other(err.into()))?;
+            // Operate on `slices_b`
+            let (info_b, operator_b) = result_b.map_err(|err| GridError::Other(err.into()))?;
+
+            // TODO: what if the scales of the EKOs don't agree? Is there an ordering problem?
+            assert_approx_eq!(f64, info_a.fac1, info_b.fac1, ulps = EVOLVE_INFO_TOL_ULPS);
+
+            // also the PID bases must be the same
+            assert_eq!(info_a.pid_basis, info_b.pid_basis);
+
+            op_fac1.push(info_a.fac1);
+
+            // it's possible that due to small numerical differences we get two slices which are
+            // almost the same. We have to skip those in order not to evolve the 'same' slice twice
+            if used_op_fac1
+                .iter()
+                .any(|&fac| approx_eq!(f64, fac, info_a.fac1, ulps = EVOLVE_INFO_TOL_ULPS))
+            {
+                continue;
+            }
+
+            // skip slices that the grid doesn't use
+            if !grid_fac1
+                .iter()
+                .any(|&fac| approx_eq!(f64, fac, info_a.fac1, ulps = EVOLVE_INFO_TOL_ULPS))
+            {
+                continue;
+            }
+
+            let op_info_dim_a = (
+                info_a.pids1.len(),
+                info_a.x1.len(),
+                info_a.pids0.len(),
+                info_a.x0.len(),
+            );
+
+            assert_eq!(
+                operator_a.dim(),
+                op_info_dim_a,
+                "operator information {:?} does not match the operator's dimensions: {:?}",
+                op_info_dim_a,
+                operator_a.dim(),
+            );
+
+            let op_info_dim_b = (
+                info_b.pids1.len(),
+                info_b.x1.len(),
+                info_b.pids0.len(),
+                info_b.x0.len(),
+            );
+
+            assert_eq!(
+                operator_b.dim(),
+                op_info_dim_b,
+                "operator information {:?} does not match the operator's dimensions: {:?}",
+                op_info_dim_b,
+                operator_b.dim(),
+            );
+
+            let views = [operator_a.view(), operator_b.view()];
+            let infos = [info_a, info_b];
+
+            assert!(
+                (self.convolutions()[0] != Convolution::None)
+                    && (self.convolutions()[1] != Convolution::None),
+                "only one convolution found, use `Grid::evolve_with_slice_iter` instead"
+            );
+
+            let (subgrids, channels) = evolution::evolve_slice_with_two2(
+                self,
+                &views,
+                &infos,
+                order_mask,
+                xi,
+                alphas_table,
+                gluon_has_pid_zero,
+            )?;
+
+            let mut rhs = Self {
+                subgrids,
+                channels,
+                bin_limits: self.bin_limits.clone(),
+                orders: vec![Order::new(0, 0, 0, 0)],
+                subgrid_params: SubgridParams::default(),
+                more_members: self.more_members.clone(),
+            };
+
+            // the evolved orders are summed, their additional couplings are therefore lost
+            rhs.write_extra_couplings(&[]);
+            // TODO: use a new constructor to set this information
+            rhs.set_pid_basis(infos[0].pid_basis);
+
+            if let Some(lhs) = &mut lhs {
+                lhs.merge(rhs)?;
+            } else {
+                lhs = Some(rhs);
+            }
+
+            used_op_fac1.push(infos[0].fac1);
+
+            progress(used_op_fac1.len(), grid_fac1.len());
+        }
+
+        // UNWRAP: if we can't compare two numbers there's a bug
+        op_fac1.sort_by(|a, b| a.partial_cmp(b).unwrap_or_else(|| unreachable!()));
+
+        // make sure we've evolved all slices
+        if let Some(muf2) = grid_fac1.into_iter().find(|&grid_mu2| {
+            !used_op_fac1
+                .iter()
+                .any(|&eko_mu2| approx_eq!(f64, grid_mu2, eko_mu2, ulps = EVOLVE_INFO_TOL_ULPS))
+        }) {
+            return Err(GridError::EvolutionFailure(format!(
+                "no operator for muf2 = {muf2} found in {op_fac1:?}"
+            )));
+        }
+
+        // TODO: convert this unwrap into error
+        let mut grid = lhs.unwrap();
+
+        grid.set_bin_central_scales(&self.central_fac_scales(order_mask, xi.1));
+
+        // UNWRAP: merging evolved slices should be a proper FkTable again
+        Ok(FkTable::try_from(grid).unwrap_or_else(|_| unreachable!()))
+    }
+
+    /// Returns the (squared) central factorization scale of each bin, if it was recorded. This is
+    /// the case for FK tables produced by [`Grid::evolve_with_slice_iter`] and
+    /// [`Grid::evolve_with_slice_iter2`], for which each value is the average of the factorization
+    /// scales of the evolved grid, weighted with the absolute values of its subgrids. Bins without
+    /// any contribution have a central scale of zero.
+    ///
+    /// The scales are kept when bins are deleted, and when grids with different bins are merged
+    /// and both record the scales. Merging bins, rebinning, and merging grids with the same bins
+    /// but different scales removes them, because the central scales of the new bins are not
+    /// known.
+    #[must_use]
+    pub fn bin_central_scales(&self) -> Option<Vec<f64>> {
+        self.key_values()?
+            .get("bin_central_scales")?
+            .split(',')
+            .map(|scale| scale.parse().ok())
+            .collect()
+    }
+
+    fn set_bin_central_scales(&mut self, scales: &[f64]) {
+        self.set_key_value(
+            "bin_central_scales",
+            &scales.iter().map(ToString::to_string).join(","),
+        );
+    }
+
+    fn remove_bin_central_scales(&mut self) {
+        if self
+            .key_values()
+            .is_some_and(|kv| kv.contains_key("bin_central_scales"))
+        {
+            self.key_values_mut().remove("bin_central_scales");
+        }
+    }
+
+    fn central_fac_scales(&self, order_mask: &[bool], xif: f64) -> Vec<f64> {
+        let mut sums = vec![(0.0, 0.0); self.bin_info().bins()];
+
+        for ((order, bin, _), subgrid) in self.subgrids.indexed_iter() {
+            if !order_mask.is_empty() && !order_mask[order] {
+                continue;
+            }
+
+            let mu2_grid = subgrid.mu2_grid();
+
+            for ((imu2, _, _), value) in subgrid.indexed_iter() {
+                sums[bin].0 += value.abs() * xif * xif * mu2_grid[imu2].fac;
+                sums[bin].1 += value.abs();
+            }
+        }
+
+        sums.into_iter()
+            .map(|(scale, norm)| if norm == 0.0 { 0.0 } else { scale / norm })
+            .collect()
+    }
+
+    /// Deletes bins with the corresponding `bin_indices`. Repeated indices and indices larger or
+    /// equal the bin length are ignored.
+    pub fn delete_bins(&mut self, bin_indices: &[usize]) {
+        let mut bin_indices: Vec<_> = bin_indices
+            .iter()
+            .copied()
+            // ignore indices corresponding to bin that don't exist
+            .filter(|&index| index < self.bin_info().bins())
+            .collect();
+
+        // sort and remove repeated indices
+        bin_indices.sort_unstable();
+        bin_indices.dedup();
+        let bin_indices = bin_indices;
+
+        // the central scales of the remaining bins stay valid
+        let central_scales = self
+            .bin_central_scales()
+            .filter(|scales| scales.len() == self.bin_info().bins());
+        self.remove_bin_central_scales();
+        // the same holds for the reference results
+        let reference = self.reference().ok().flatten();
+        self.remove_reference();
+
+        let mut bin_ranges: Vec<Range<_>> = Vec::new();
+
+        // convert indices into consecutive ranges
+        for &bin_index in &bin_indices {
+            match bin_ranges.last_mut() {
+                Some(range) if range.end == bin_index => range.end += 1,
+                _ => bin_ranges.push(bin_index..(bin_index + 1)),
+            }
+        }
+
+        let bin_ranges = bin_ranges;
+        let mut ranges = bin_ranges.as_slice();
+        let old_limits = self.bin_limits.limits();
+
+        // remove the bins from the right first, so as not to invalidate any indices
+        if let Some((range, remainder)) = ranges.split_last() {
+            if range.end == self.bin_info().bins() {
+                self.bin_limits.delete_bins_right(range.end - range.start);
+                ranges = remainder;
+            }
+        }
+
+        // indices on the left aren't affected by removal of bins to their right
+        if let Some((range, remainder)) = ranges.split_first() {
+            if range.start == 0 {
+                self.bin_limits.delete_bins_left(range.end);
+                ranges = remainder;
+            }
+        }
+
+        if !ranges.is_empty() {
+            // if there's no remapper we need to store the bin limits in a new remapper
+            if self.remapper_mut().is_none() {
+                self.set_remapper(
+                    BinRemapper::new(
+                        old_limits.windows(2).map(|win| win[1] - win[0]).collect(),
+                        old_limits.windows(2).map(|win| (win[0], win[1])).collect(),
+                    )
+                    .unwrap_or_else(|_| unreachable!()),
+                )
+                .unwrap_or_else(|_| unreachable!());
+            }
+
+            // the following should not be needed, but let's set these limits to integer values
+            self.bin_limits = BinLimits::new(
+                iter::successors(Some(0.0), |x| Some(x + 1.0))
+                    .take(old_limits.len() - bin_indices.len())
+                    .collect(),
+            );
+        }
+
+        if let Some(remapper) = self.remapper_mut() {
+            remapper.delete_bins(&bin_ranges);
+        }
+
+        for &bin_index in bin_indices.iter().rev() {
+            self.subgrids.remove_index(Axis(1), bin_index);
+        }
+
+        if let Some(scales) = central_scales {
+            self.set_bin_central_scales(
+                &scales
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(bin, scale)| {
+                        bin_indices.binary_search(&bin).is_err().then_some(scale)
+                    })
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        if let Some(Reference { pdf, results }) = reference {
+            self.set_reference(&Reference {
+                pdf,
+                results: results
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(bin, result)| {
+                        bin_indices.binary_search(&bin).is_err().then_some(result)
+                    })
+                    .collect(),
+            })
+            .unwrap_or_else(|_| unreachable!());
+        }
+    }
+
+    /// Change the particle ID convention.
+    pub fn rotate_pid_basis(&mut self, pid_basis: PidBasis) {
+        let current_basis = self.pid_basis();
+
+        if current_basis == pid_basis {
+            // here's nothing to do
+            return;
+        }
+
+        self.channels = self
+            .channels()
+            .iter()
+            .map(|channel| current_basis.translate_channel(pid_basis, channel))
+            .collect();
+
+        self.set_pid_basis(pid_basis);
+    }
+
+    /// Deletes channels with the corresponding `channel_indices`. Repeated indices and indices
+    /// larger or equal than the number of channels are ignored.
+    pub fn delete_channels(&mut self, channel_indices: &[usize]) {
+        let mut channel_indices: Vec<_> = channel_indices
+            .iter()
+            .copied()
+            // ignore indices corresponding to bin that don't exist
+            .filter(|&index| index < self.channels().len())
+            .collect();
+
+        // sort and remove repeated indices
+        channel_indices.sort_unstable();
+        channel_indices.dedup();
+        channel_indices.reverse();
+        let channel_indices = channel_indices;
+
+        for index in channel_indices {
+            self.channels.remove(index);
+            self.subgrids.remove_index(Axis(2), index);
+        }
+    }
+
+    /// Removes the contributions of channels with photons, which have the particle ID `22`. Every
+    /// channel entry with a photon is removed, and channels that are left without entries are
+    /// deleted together with their subgrids. The removed contributions can only be recovered by
+    /// reading the grid again; if they are needed later, remove them from a clone instead.
+    pub fn remove_photon_channels(&mut self) {
+        // only look at the particle IDs of convolutions that exist
+        let has_pdf1 = self.convolutions()[0] != Convolution::None;
+        let has_pdf2 = self.convolutions()[1] != Convolution::None;
+        let mut empty_channels = Vec::new();
+
+        for (index, channel) in self.channels.iter_mut().enumerate() {
+            let entry: Vec<_> = channel
+                .entry()
+                .iter()
+                .copied()
+                .filter(|&(a, b, _)| !((has_pdf1 && (a == 22)) || (has_pdf2 && (b == 22))))
+                .collect();
+
+            if entry.is_empty() {
+                empty_channels.push(index);
+            } else {
+                *channel = Channel::new(entry);
+            }
+        }
+
+        self.delete_channels(&empty_channels);
+    }
+
+    /// Delete orders with the corresponding `order_indices`. Repeated indices and indices larger
+    /// or equal than the number of orders are ignored.
+    pub fn delete_orders(&mut self, order_indices: &[usize]) {
+        let mut order_indices: Vec<_> = order_indices
+            .iter()
+            .copied()
+            // ignore indices corresponding to orders that don't exist
+            .filter(|&index| index < self.orders().len())
+            .collect();
+
+        // sort and remove repeated indices
+        order_indices.sort_unstable();
+        order_indices.dedup();
+        order_indices.reverse();
+        let order_indices = order_indices;
+
+        for index in order_indices {
+            self.delete_extra_couplings(index);
+            self.orders.remove(index);
+            self.subgrids.remove_index(Axis(0), index);
+        }
+    }
+
+    pub(crate) fn rewrite_channels(&mut self, add: &[(i32, i32)], del: &[i32]) {
+        self.channels = self
+            .channels()
+            .iter()
+            .map(|entry| {
+                Channel::new(
+                    entry
+                        .entry()
+                        .iter()
+                        .map(|(a, b, f)| {
+                            (
+                                // if `a` is to be added to another pid replace it with this pid
+                                add.iter().fold(
+                                    *a,
+                                    |id, &(source, target)| if id == source { target } else { id },
+                                ),
+                                // if `b` is to be added to another pid replace it with this pid
+                                add.iter().fold(
+                                    *b,
+                                    |id, &(source, target)| if id == source { target } else { id },
+                                ),
+                                // if any of the pids `a` or `b` are to b deleted set the factor to
+                                // zero
+                                if del.iter().any(|id| id == a || id == b) {
+                                    0.0
+                                } else {
+                                    *f
+                                },
+                            )
+                        })
+                        .collect(),
+                )
+            })
+            .collect();
+    }
+
+    /// Splits the grid such that each channel contains only a single tuple of PIDs.
+    pub fn split_channels(&mut self) {
+        self.split_channels_limited(1);
+    }
+
+    /// Splits channels with more than `max_combinations` tuples of PIDs such that each of the new
+    /// channels contains only a single tuple, and leaves all other channels unchanged. This limits
+    /// the growth of the number of channels compared to [`Grid::split_channels`].
+    pub fn split_channels_limited(&mut self, max_combinations: usize) {
+        let split = |channel: &Channel| channel.entry().len() > max_combinations;
+
+        let indices: Vec<_> = self
+            .channels()
+            .iter()
+            .enumerate()
+            .flat_map(|(index, channel)| {
+                iter::repeat(index).take(if split(channel) {
+                    channel.entry().len()
+                } else {
+                    1
+                })
+            })
+            .collect();
+
+        self.subgrids = self.subgrids.select(Axis(2), &indices);
+        self.channels = self
+            .channels()
+            .iter()
+            .flat_map(|channel| {
+                if split(channel) {
+                    channel
+                        .entry()
+                        .iter()
+                        .copied()
+                        .map(|entry| Channel::new(vec![entry]))
+                        .collect()
+                } else {
+                    vec![channel.clone()]
+                }
+            })
+            .collect();
+    }
+
+    /// Sorts the channels by their [`Channel::canonical_key`] and reorders the subgrids
+    /// accordingly. Afterwards the order of the channels only depends on their contents, and not
+    /// on the order in which they were added to the grid, which makes it possible to compare grids
+    /// from different generators channel by channel. The predictions of the grid are not changed.
+    pub fn canonicalize_channels(&mut self) {
+        let mut indices: Vec<_> = (0..self.channels.len()).collect();
+        indices.sort_by_cached_key(|&index| self.channels[index].canonical_key());
+
+        self.subgrids = self.subgrids.select(Axis(2), &indices);
+        self.channels = indices
+            .iter()
+            .map(|&index| self.channels[index].clone())
+            .collect();
+    }
+
+    /// Combines channels with a single tuple of PIDs into other channels whose subgrids are
+    /// proportional to them, which inverts [`Grid::split_channels`]. If the subgrids of a channel
+    /// are `r` times the subgrids of another channel, the channel is removed and its PID tuple is
+    /// added to the other channel with its factor multiplied by `r`.
+    ///
+    /// The inverse is exact for grids produced by [`Grid::split_channels`], because the split
+    /// channels share identical subgrids and therefore `r = 1`. For channels that were split in
+    /// other ways, for example by a Monte Carlo generator, the subgrids are only proportional up to
+    /// rounding errors; these channels are combined if all ratios agree within 64 [units of least
+    /// precision](https://docs.rs/float-cmp/latest/float_cmp/index.html#some-explanation), and the
+    /// result is then only approximately equal to the original grid. Channels whose subgrids use
+    /// different `x` or scale nodes are never combined.
+    pub fn combine_channels_by_pids(&mut self) {
+        const ULPS: i64 = 64;
+
+        let mut indices: Vec<usize> = (0..self.channels.len()).collect();
+
+        while let Some(index) = indices.pop() {
+            if self.channels[index].entry().len() != 1 {
+                continue;
+            }
+
+            if let Some((other_index, ratio)) = indices.iter().find_map(|&other_index| {
+                let mut ratio: Option<f64> = None;
+
+                for (lhs, rhs) in self
+                    .subgrids
+                    .slice(s![.., .., other_index])
+                    .iter()
+                    .zip(self.subgrids.slice(s![.., .., index]).iter())
+                {
+                    // equal indices only describe the same kinematics if the nodes agree
+                    if !lhs.is_empty()
+                        && !rhs.is_empty()
+                        && ((lhs.x1_grid() != rhs.x1_grid())
+                            || (lhs.x2_grid() != rhs.x2_grid())
+                            || (lhs.mu2_grid() != rhs.mu2_grid()))
+                    {
+                        return None;
+                    }
+
+                    let mut it_a = lhs.indexed_iter();
+                    let mut it_b = rhs.indexed_iter();
+
+                    loop {
+                        match (it_a.next(), it_b.next()) {
+                            (Some((tuple_a, value_a)), Some((tuple_b, value_b))) => {
+                                if (tuple_a != tuple_b) || (value_a == 0.0) {
+                                    return None;
+                                }
+
+                                let r = value_b / value_a;
+
+                                if let Some(ratio) = ratio {
+                                    if !approx_eq!(f64, ratio, r, ulps = ULPS) {
+                                        return None;
+                                    }
+                                } else {
+                                    ratio = Some(r);
+                                }
+                            }
+                            (None, None) => break,
+                            _ => return None,
+                        }
+                    }
+                }
+
+                // if both channels are empty there's no ratio
+                ratio.map(|ratio| (other_index, ratio))
+            }) {
+                let (a, b, factor) = self.channels.remove(index).entry()[0];
+                let mut new_channel = self.channels[other_index].entry().to_vec();
+                new_channel.push((a, b, factor * ratio));
+                self.channels[other_index] = Channel::new(new_channel);
+                self.subgrids.remove_index(Axis(2), index);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bin::MergeBinError;
+    use crate::channel;
+    use ndarray::Array4;
+    use std::cell::Cell;
+    use std::fs::File;
+
+    #[test]
+    fn grid_with_subgrid_type() {
+        let subgrid_type = String::from("Idontexist");
+        let result = Grid::with_subgrid_type(
+            vec![],
+            vec![],
+            vec![],
+            SubgridParams::default(),
+            ExtraSubgridParams::default(),
+            &subgrid_type,
+        );
+
+        matches!(result, Err(GridError::UnknownSubgridType(x)) if x == subgrid_type);
+    }
+
+    #[test]
+    fn grid_try_new() {
+        let try_new = |bin_limits: Vec<f64>, subgrid_params| {
+            Grid::try_new(
+                vec![channel![2, 2, 1.0]],
+                vec![Order::new(0, 2, 0, 0)],
+                bin_limits,
+                subgrid_params,
+            )
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+        };
+
+        assert_eq!(
+            try_new(vec![0.0, 1.0, 2.0], SubgridParams::default()),
+            Ok(())
+        );
+        assert_eq!(
+            try_new(vec![0.0, 2.0, 1.0], SubgridParams::default()),
+            Err("the bin limit with index 2 is smaller than its predecessor: 1".to_owned())
+        );
+        assert_eq!(
+            try_new(vec![0.0], SubgridParams::default()),
+            Err("at least two bin limits are required, but 1 were given".to_owned())
+        );
+        assert_eq!(
+            try_new(vec![0.0, f64::NAN], SubgridParams::default()),
+            Err("the bin limit with index 1 is not finite: NaN".to_owned())
+        );
+        assert_eq!(
+            try_new(vec![0.0, 1.0, 0.0], SubgridParams::default()),
+            Err("the bin limit with index 2 is the same as the one with index 0: 0".to_owned())
+        );
+        assert_eq!(
+            Grid::try_new(
+                vec![
+                    channel![2, 2, 1.0],
+                    channel![1, -1, 1.0; 2, -2, f64::INFINITY]
+                ],
+                vec![Order::new(0, 2, 0, 0)],
+                vec![0.0, 1.0],
+                SubgridParams::default(),
+            )
+            .map(|_| ())
+            .map_err(|err| err.to_string()),
+            Err("the factor of entry 1 of the channel with index 1 is not finite: inf".to_owned())
+        );
+
+        let mut subgrid_params = SubgridParams::default();
+        subgrid_params.set_x_min(0.0);
+
+        assert_eq!(
+            try_new(vec![0.0, 1.0], subgrid_params),
+            Err("the interpolation range of x from 0 to 1 is invalid".to_owned())
+        );
+
+        let mut subgrid_params = SubgridParams::default();
+        subgrid_params.set_q2_min(1e9);
+
+        assert_eq!(
+            try_new(vec![0.0, 1.0], subgrid_params),
+            Err("the interpolation range of q2 from 1000000000 to 100000000 is invalid".to_owned())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at least two bin limits are required, but 0 were given")]
+    fn grid_new_panic() {
+        let _ = Grid::new(vec![], vec![], vec![], SubgridParams::default());
+    }
+
+    #[test]
+    fn grid_merge_empty_subgrids() {
+        let mut grid = Grid::new(
+            vec![
+                channel![2, 2, 1.0; 4, 4, 1.0],
+                channel![1, 1, 1.0; 3, 3, 1.0],
+            ],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 0.25, 0.5, 0.75, 1.0],
+            SubgridParams::default(),
+        );
+
+        assert_eq!(grid.bin_info().bins(), 4);
+        assert_eq!(grid.channels().len(), 2);
+        assert_eq!(grid.orders().len(), 1);
+
+        let other = Grid::new(
+            vec![
+                // differently ordered than `grid`
+                channel![1, 1, 1.0; 3, 3, 1.0],
+                channel![2, 2, 1.0; 4, 4, 1.0],
+            ],
+            vec![Order::new(1, 2, 0, 0), Order::new(1, 2, 0, 1)],
+            vec![0.0, 0.25, 0.5, 0.75, 1.0],
+            SubgridParams::default(),
+        );
+
+        // merging with empty subgrids should not change the grid
+        grid.merge(other).unwrap();
+
+        assert_eq!(grid.bin_info().bins(), 4);
+        assert_eq!(grid.channels().len(), 2);
+        assert_eq!(grid.orders().len(), 1);
+    }
+
+    #[test]
+    fn grid_merge_orders() {
+        let mut grid = Grid::new(
+            vec![
+                channel![2, 2, 1.0; 4, 4, 1.0],
+                channel![1, 1, 1.0; 3, 3, 1.0],
+            ],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 0.25, 0.5, 0.75, 1.0],
+            SubgridParams::default(),
+        );
+
+        assert_eq!(grid.bin_info().bins(), 4);
+        assert_eq!(grid.channels().len(), 2);
+        assert_eq!(grid.orders().len(), 1);
+
+        let mut other = Grid::new(
+            vec![
+                channel![2, 2, 1.0; 4, 4, 1.0],
+                channel![1, 1, 1.0; 3, 3, 1.0],
+            ],
+            vec![
+                Order::new(1, 2, 0, 0),
+                Order::new(1, 2, 0, 1),
+                Order::new(0, 2, 0, 0),
+            ],
+            vec![0.0, 0.25, 0.5, 0.75, 1.0],
+            SubgridParams::default(),
+        );
+
+        other.fill_all(
+            0,
+            0.1,
+            &Ntuple {
+                x1: 0.1,
+                x2: 0.2,
+                q2: 90.0_f64.powi(2),
+                weight: (),
+            },
+            &[1.0, 2.0],
+        );
+        other.fill_all(
+            1,
+            0.1,
+            &Ntuple {
+                x1: 0.1,
+                x2: 0.2,
+                q2: 90.0_f64.powi(2),
+                weight: (),
+            },
+            &[1.0, 2.0],
+        );
+
+        // merge with four non-empty subgrids
+        grid.merge(other).unwrap();
+
+        assert_eq!(grid.bin_info().bins(), 4);
+        assert_eq!(grid.channels().len(), 2);
+        assert_eq!(grid.orders().len(), 3);
+    }
+
+    #[test]
+    fn grid_merge_channels_entries() {
+        let mut grid = Grid::new(
+            vec![
+                channel![2, 2, 1.0; 4, 4, 1.0],
+                channel![1, 1, 1.0; 3, 3, 1.0],
+            ],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 0.25, 0.5, 0.75, 1.0],
+            SubgridParams::default(),
+        );
+
+        assert_eq!(grid.bin_info().bins(), 4);
+        assert_eq!(grid.channels().len(), 2);
+        assert_eq!(grid.orders().len(), 1);
+
+        let mut other = Grid::new(
+            vec![channel![22, 22, 1.0], channel![2, 2, 1.0; 4, 4, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 0.25, 0.5, 0.75, 1.0],
+            SubgridParams::default(),
+        );
+
+        // fill the photon-photon entry
+        other.fill(
+            0,
+            0.1,
+            0,
+            &Ntuple {
+                x1: 0.1,
+                x2: 0.2,
+                q2: 90.0_f64.powi(2),
+                weight: 3.0,
+            },
+        );
+
+        grid.merge(other).unwrap();
+
+        assert_eq!(grid.bin_info().bins(), 4);
+        assert_eq!(grid.channels().len(), 3);
+        assert_eq!(grid.orders().len(), 1);
+    }
+
+    #[test]
+    fn grid_check_mergeable() {
+        let new_grid = |bin_limits: Vec<f64>, subgrid_params: SubgridParams| {
+            let mut grid = Grid::new(
+                vec![channel![2, 2, 1.0; 4, 4, 1.0]],
+                vec![Order::new(0, 2, 0, 0)],
+                bin_limits,
+                subgrid_params,
+            );
+
+            grid.fill(
+                0,
+                0.1,
+                0,
+                &Ntuple {
+                    x1: 0.1,
+                    x2: 0.2,
+                    q2: 90.0_f64.powi(2),
+                    weight: 1.0,
+                },
+            );
+
+            grid
+        };
+
+        let grid = new_grid(vec![0.0, 0.25, 0.5], SubgridParams::default());
+
+        assert!(grid
+            .check_mergeable(&new_grid(vec![0.0, 0.25, 0.5], SubgridParams::default()))
+            .is_ok());
+        // consecutive bins are appended
+        assert!(grid
+            .check_mergeable(&new_grid(vec![0.5, 0.75], SubgridParams::default()))
+            .is_ok());
+        // grids with different orders or channels are compatible
+        assert!(grid
+            .check_mergeable(&Grid::new(
+                vec![channel![1, 1, 1.0]],
+                vec![Order::new(1, 2, 0, 0)],
+                vec![0.0, 0.25, 0.5],
+                SubgridParams::default(),
+            ))
+            .is_ok());
+
+        assert!(matches!(
+            grid.check_mergeable(&new_grid(vec![1.0, 2.0], SubgridParams::default())),
+            Err(GridError::InvalidBinLimits(_))
+        ));
+
+        let mut other = new_grid(vec![0.0, 0.25, 0.5], SubgridParams::default());
+        other.set_convolution(0, Convolution::UnpolPDF(-2212));
+
+        assert_eq!(
+            other.check_mergeable(&grid).unwrap_err().to_string(),
+            "the convolutions are incompatible: unpolpdf:-2212,unpolpdf:2212 and \
+             unpolpdf:2212,unpolpdf:2212"
+        );
+
+        let mut subgrid_params = SubgridParams::default();
+        subgrid_params.set_x_min(1e-3);
+
+        let mut q2_params = SubgridParams::default();
+        q2_params.set_q2_min(200.0);
+
+        // the interpolation of the scales must be the same as well
+        assert_eq!(
+            grid.check_mergeable(&new_grid(vec![0.0, 0.25, 0.5], q2_params))
+                .unwrap_err()
+                .to_string(),
+            "the subgrids are incompatible: the subgrids of order as0a2, bin 0 and channel 0 use \
+             different interpolations"
+        );
+
+        assert_eq!(
+            grid.check_mergeable(&new_grid(vec![0.0, 0.25, 0.5], subgrid_params))
+                .unwrap_err()
+                .to_string(),
+            "the subgrids are incompatible: the subgrids of order as0a2, bin 0 and channel 0 use \
+             different interpolations"
+        );
+    }
+
+    #[test]
+    fn grid_canonicalize_channels() {
+        let mut grid = Grid::new(
+            vec![
+                channel![2, 2, 1.0],
+                channel![1, -1, 1.0; 3, -3, 1.0],
+                channel![1, -1, 0.5],
+            ],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 0.25, 0.5],
+            SubgridParams::default(),
+        );
+
+        for (channel, weight) in [(0, 1.0), (1, 2.0), (2, 4.0)] {
+            grid.fill(
+                0,
+                0.1,
+                channel,
+                &Ntuple {
+                    x1: 0.1,
+                    x2: 0.2,
+                    q2: 90.0_f64.powi(2),
+                    weight,
+                },
+            );
+        }
+
+        let mut xfx = |id, x, _| if id == 2 { 2.0 * x } else { x };
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+        let channel_results: Vec<_> = (0..3)
+            .map(|channel| {
+                let mut channel_mask = vec![false; 3];
+                channel_mask[channel] = true;
+                grid.convolve(&mut lumi_cache, &[], &[], &channel_mask, &[(1.0, 1.0)])
+            })
+            .collect();
+
+        grid.canonicalize_channels();
+
+        assert_eq!(
+            grid.channels(),
+            [
+                channel![1, -1, 0.5],
+                channel![1, -1, 1.0; 3, -3, 1.0],
+                channel![2, 2, 1.0],
+            ]
+        );
+
+        for (channel, old_channel) in [2, 1, 0].into_iter().enumerate() {
+            let mut channel_mask = vec![false; 3];
+            channel_mask[channel] = true;
+
+            assert_eq!(
+                grid.convolve(&mut lumi_cache, &[], &[], &channel_mask, &[(1.0, 1.0)]),
+                channel_results[old_channel]
+            );
+        }
+    }
+
+    #[test]
+    fn grid_convolve_reduction_order() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0], channel![1, 1, 1.0]],
+            vec![Order::new(0, 2, 0, 0), Order::new(0, 3, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        // summing these in a different order gives a different result
+        for ((order, channel), value) in [
+            ((0, 0), 1e16),
+            ((0, 1), 1.0),
+            ((1, 0), -1e16),
+            ((1, 1), 1.0),
+        ] {
+            grid.set_subgrid(
+                order,
+                0,
+                channel,
+                ImportOnlySubgridV2::single_node(value, 0.5).into(),
+            );
+        }
+
+        let mut xfx = |_, x, _| x;
+        let mut alphas = |_| 0.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        // order 0: 1e16 + 1 rounds to 1e16, order 1: 1e16 - 1e16 + 1
+        assert_eq!(
+            grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]),
+            [1.0]
+        );
+
+        // pin the results of a grid with many nodes
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0], channel![1, -1, 1.0; 3, -3, 0.5]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0, 3.0],
+            SubgridParams::default(),
+        );
+
+        for ((_, bin, channel), subgrid) in grid.subgrids_mut().indexed_iter_mut() {
+            let mut array = SparseArray3::new(2, 3, 3);
+            for (index, value) in [0.3, -1.7, 2.9, 0.1, 4.1, -0.7, 1.3]
+                .into_iter()
+                .enumerate()
+            {
+                array[[(index + bin) % 2, index % 3, (index * 2 + channel) % 3]] += value;
+            }
+
+            *subgrid = ImportOnlySubgridV2::new(
+                array,
+                vec![
+                    Mu2 {
+                        ren: 10.0,
+                        fac: 10.0,
+                    },
+                    Mu2 {
+                        ren: 100.0,
+                        fac: 100.0,
+                    },
+                ],
+                vec![0.1, 0.3, 0.7],
+                vec![0.2, 0.4, 0.8],
+            )
+            .into();
+        }
+
+        // only use operations whose results are exactly specified by IEEE 754
+        let mut xfx = |id: i32, x: f64, q2: f64| f64::from(id) * x * (1.0 - x) * q2;
+        let mut alphas = |_| 0.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        assert_eq!(
+            grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]),
+            [39085.499999999985, -46524.899999999994]
+        );
+    }
+
+    // creates a grid with the channels `u u + c c` and `d dbar`, the given `orders` and two bins,
+    // which are filled with two events each for every order and channel for which `weight`
+    // returns a non-zero weight
+    fn convolve_test_grid(orders: Vec<Order>, weight: impl Fn(usize, usize) -> f64) -> Grid {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0; 4, 4, 1.0], channel![1, -1, 1.0]],
+            orders,
+            vec![0.0, 0.25, 0.5],
+            SubgridParams::default(),
+        );
+
+        for order in 0..grid.orders().len() {
+            for channel in 0..grid.channels().len() {
+                let weight = weight(order, channel);
+
+                if weight == 0.0 {
+                    continue;
+                }
+
+                for (observable, x1) in [(0.1, 0.1), (0.3, 0.4)] {
+                    grid.fill(
+                        order,
+                        observable,
+                        channel,
+                        &Ntuple {
+                            x1,
+                            x2: 0.2,
+                            q2: 90.0_f64.powi(2),
+                            weight,
+                        },
+                    );
+                }
+            }
+        }
+
+        grid
+    }
+
+    // the PDF used with `convolve_test_grid`
+    fn convolve_test_xfx(id: i32, x: f64, _: f64) -> f64 {
+        if id == 2 {
+            2.0 * x
+        } else {
+            x
+        }
+    }
+
+    #[test]
+    fn grid_convolve_full() {
+        let grid = convolve_test_grid(
+            vec![
+                Order::new(0, 2, 0, 0),
+                Order::new(1, 2, 0, 0),
+                Order::new(1, 2, 0, 1),
+            ],
+            |order, channel| 1.0 + f64::from(u32::try_from(order + channel).unwrap()),
+        );
+
+        let mut xfx = convolve_test_xfx;
+        let mut alphas = |_| 0.1;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        for xi in [(1.0, 1.0), (2.0, 0.5)] {
+            let full = grid.convolve_full(&mut lumi_cache, xi);
+
+            assert_eq!(full.dim(), (3, 2, 2));
+
+            let results = grid.convolve(&mut lumi_cache, &[], &[], &[], &[xi]);
+            let sums = full.sum_axis(Axis(0)).sum_axis(Axis(0));
+
+            for (sum, result) in sums.iter().zip(&results) {
+                assert_approx_eq!(f64, *sum, *result, ulps = 4);
+            }
+
+            for order in 0..3 {
+                let mut order_mask = vec![false; 3];
+                order_mask[order] = true;
+
+                for channel in 0..2 {
+                    let mut channel_mask = vec![false; 2];
+                    channel_mask[channel] = true;
+
+                    let results =
+                        grid.convolve(&mut lumi_cache, &order_mask, &[], &channel_mask, &[xi]);
+
+                    assert_eq!(full.slice(s![order, channel, ..]).to_vec(), results);
+                }
+            }
+        }
+
+        // the scale-log order only contributes if the factorization scale is varied
+        let full = grid.convolve_full(&mut lumi_cache, (1.0, 1.0));
+
+        assert!(full.slice(s![2, .., ..]).iter().all(|&value| value == 0.0));
+    }
+
+    #[test]
+    fn grid_convolve_with_options_reweight() {
+        let grid = convolve_test_grid(
+            vec![Order::new(0, 2, 0, 0), Order::new(1, 2, 0, 0)],
+            |_, _| 1.0,
+        );
+
+        let mut xfx = convolve_test_xfx;
+        let mut alphas = |_| 0.1;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+        let xi = [(1.0, 1.0), (2.0, 0.5)];
+
+        let results = grid.convolve(&mut lumi_cache, &[], &[], &[], &xi);
+        let reweighted = grid.convolve_with_options(
+            slice::from_mut(&mut lumi_cache),
+            &ConvolveOptions {
+                xi: &xi,
+                reweight: Some(&|_, _, _, _| 2.0),
+                ..ConvolveOptions::default()
+            },
+        );
+
+        for (reweighted, result) in reweighted.iter().zip(&results) {
+            assert_approx_eq!(f64, *reweighted, 2.0 * result, ulps = 4);
+        }
+
+        // a weight of zero for the second channel is the same as a channel mask
+        assert_eq!(
+            grid.convolve_with_options(
+                slice::from_mut(&mut lumi_cache),
+                &ConvolveOptions {
+                    xi: &xi,
+                    reweight: Some(&|channel, _, _, _| if channel == 0 { 1.0 } else { 0.0 }),
+                    ..ConvolveOptions::default()
+                }
+            ),
+            grid.convolve(&mut lumi_cache, &[], &[], &[true, false], &xi)
+        );
+
+        // a kinematic weight multiplies each node
+        let reweighted = grid.convolve_with_options(
+            slice::from_mut(&mut lumi_cache),
+            &ConvolveOptions {
+                reweight: Some(&|_, _, x1, _| x1),
+                ..ConvolveOptions::default()
+            },
+        );
+
+        assert_eq!(reweighted.len(), 2);
+
+        for (bin, reweighted) in reweighted.iter().enumerate() {
+            let mut expected = 0.0;
+
+            for order in 0..2 {
+                for channel in 0..2 {
+                    let array =
+                        grid.convolve_subgrid(&mut lumi_cache, order, bin, channel, 1.0, 1.0);
+                    let x1_grid = grid.subgrids()[[order, bin, channel]]
+                        .x1_grid()
+                        .into_owned();
+
+                    for ((_, ix1, _), value) in array.indexed_iter() {
+                        expected += value * x1_grid[ix1];
+                    }
+                }
+            }
+
+            assert_approx_eq!(f64, *reweighted, expected, ulps = 16);
+        }
+    }
+
+    #[test]
+    fn grid_convolve_with_tables() {
+        let grid = convolve_test_grid(
+            vec![Order::new(0, 2, 0, 0), Order::new(1, 2, 0, 0)],
+            |_, _| 1.0,
+        );
+
+        // unlike `convolve_test_xfx` this PDF depends on the scale, so that looking up a value at
+        // the wrong scale is noticed
+        let xfx = |id, x: f64, q2: f64| f64::from(id) * x * (1.0 - x) * q2.sqrt();
+        let alphas = |q2: f64| 1.0 / q2.ln();
+        let xi = [(1.0, 1.0), (2.0, 0.5)];
+
+        let xfx_table = XfxTable::from_grid(&grid, 2212, &xi, &xfx);
+        // the scales of the table must be sorted
+        let mut scales_alphas: Vec<_> = xi
+            .iter()
+            .flat_map(|&(xir, _)| {
+                let table = AlphasTable::from_grid(&grid, xir, &alphas);
+                table.ren1.into_iter().zip(table.alphas)
+            })
+            .collect();
+        scales_alphas.sort_by(|(lhs, _), (rhs, _)| lhs.total_cmp(rhs));
+        let (ren1, alphas_values) = scales_alphas.into_iter().unzip();
+        let alphas_table = AlphasTable {
+            ren1,
+            alphas: alphas_values,
+        };
+
+        // the anti-quarks are needed for anti-protons
+        assert_eq!(xfx_table.pids, [-4, -2, -1, 1, 2, 4]);
+
+        let mut xfx_fun = xfx;
+        let mut alphas_fun = alphas;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx_fun, &mut alphas_fun);
+        let results = grid.convolve(&mut lumi_cache, &[], &[], &[], &xi);
+        let options = ConvolveOptions {
+            xi: &xi,
+            ..ConvolveOptions::default()
+        };
+
+        assert_eq!(
+            grid.convolve_with_tables(slice::from_ref(&xfx_table), &alphas_table, &options)
+                .unwrap(),
+            results
+        );
+
+        // a table without the scale variation doesn't contain all factorization scales ...
+        let xfx_table = XfxTable::from_grid(&grid, 2212, &[(1.0, 1.0)], &xfx);
+
+        assert!(matches!(
+            grid.convolve_with_tables(&[xfx_table], &alphas_table, &options),
+            Err(GridError::IncompleteTables(what)) if what.starts_with("PDF at Q2 = ")
+        ));
+
+        let xfx_table = XfxTable::from_grid(&grid, 2212, &xi, &xfx);
+        let alphas_table = AlphasTable::from_grid(&grid, 1.0, &alphas);
+
+        // ... and a table without the scale variation not all renormalization scales
+        assert!(matches!(
+            grid.convolve_with_tables(slice::from_ref(&xfx_table), &alphas_table, &options),
+            Err(GridError::IncompleteTables(what)) if what.starts_with("strong coupling at Q2 = ")
+        ));
+
+        let alphas_table = AlphasTable::from_grid(&grid, 1.0, &alphas);
+
+        assert!(matches!(
+            grid.convolve_with_tables(&[], &alphas_table, &ConvolveOptions::default()),
+            Err(GridError::TableCountMismatch { tables: 0 })
+        ));
+
+        let mut pion_table = XfxTable::from_grid(&grid, 2212, &[(1.0, 1.0)], &xfx);
+        pion_table.pdg = 211;
+
+        assert!(matches!(
+            grid.convolve_with_tables(
+                &[xfx_table, pion_table],
+                &alphas_table,
+                &ConvolveOptions::default()
+            ),
+            Err(GridError::HadronMismatch {
+                index: 1,
+                pdg: 211,
+                convolution: Convolution::UnpolPDF(2212)
+            })
+        ));
+
+        // a table for a grid with different channels doesn't contain all partons
+        let xfx_table = XfxTable::from_grid(&grid, 2212, &[(1.0, 1.0)], &xfx);
+        let mut other = grid.clone();
+        other.channels_mut()[1] = channel![3, -3, 1.0];
+
+        assert!(matches!(
+            other.convolve_with_tables(&[xfx_table], &alphas_table, &ConvolveOptions::default()),
+            Err(GridError::IncompleteTables(what)) if what == "PDF of particle 3"
+        ));
+    }
+
+    #[test]
+    fn grid_convolve_subgrid() {
+        let grid = convolve_test_grid(
+            vec![Order::new(0, 2, 0, 0), Order::new(0, 2, 1, 0)],
+            |_, channel| if channel == 0 { 1.0 } else { 0.0 },
+        );
+
+        let mut xfx = convolve_test_xfx;
+        let mut alphas = |_| 0.1;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        // the second channel was never filled
+        assert_eq!(
+            grid.convolve_subgrid(&mut lumi_cache, 0, 0, 1, 1.0, 1.0)
+                .dim(),
+            (0, 0, 0)
+        );
+
+        for xi in [(1.0, 1.0), (2.0, 1.0)] {
+            let full = grid.convolve_full(&mut lumi_cache, xi);
+
+            for order in 0..2 {
+                for bin in 0..2 {
+                    let array = grid.convolve_subgrid(&mut lumi_cache, order, bin, 0, xi.0, xi.1);
+                    let subgrid = &grid.subgrids()[[order, bin, 0]];
+
+                    assert_eq!(
+                        array.dim(),
+                        (
+                            subgrid.mu2_grid().len(),
+                            subgrid.x1_grid().len(),
+                            subgrid.x2_grid().len()
+                        )
+                    );
+                    assert_approx_eq!(f64, array.sum(), full[[order, 0, bin]], ulps = 16);
+                }
+            }
+        }
+
+        // the renormalization scale-log order vanishes without scale variation
+        let array = grid.convolve_subgrid(&mut lumi_cache, 1, 0, 0, 1.0, 1.0);
+
+        assert!(!array.is_empty());
+        assert!(array.iter().all(|&value| value == 0.0));
+    }
+
+    #[test]
+    fn grid_strip_empty() {
+        let mut grid = Grid::new(
+            vec![
+                channel![2, 2, 1.0],
+                channel![1, -1, 1.0],
+                channel![21, 21, 1.0],
+            ],
+            vec![
+                Order::new(0, 2, 0, 0),
+                Order::new(1, 2, 0, 0),
+                Order::new(1, 2, 0, 1),
+            ],
+            vec![0.0, 0.25, 0.5],
+            SubgridParams::default(),
+        );
+
+        let ntuple = Ntuple {
+            x1: 0.1,
+            x2: 0.2,
+            q2: 90.0_f64.powi(2),
+            weight: 1.0,
+        };
+
+        grid.fill(0, 0.1, 0, &ntuple);
+        grid.fill(0, 0.1, 2, &ntuple);
+        grid.fill(1, 0.3, 1, &ntuple);
+        grid.fill(2, 0.1, 2, &ntuple);
+
+        // nothing is empty yet
+        assert_eq!(grid.strip_empty_orders(), 0);
+        assert_eq!(grid.strip_empty_channels(), 0);
+
+        // the second order and channel only contribute to the second bin
+        grid.delete_bins(&[1]);
+
+        assert_eq!(grid.strip_empty_channels(), 1);
+        assert_eq!(
+            grid.channels(),
+            [channel![2, 2, 1.0], channel![21, 21, 1.0]]
+        );
+        assert_eq!(grid.strip_empty_orders(), 1);
+        assert_eq!(
+            grid.orders(),
+            [Order::new(0, 2, 0, 0), Order::new(1, 2, 0, 1)]
+        );
+        assert_eq!(grid.subgrids().dim(), (2, 1, 2));
+
+        // the remaining subgrids are not converted
+        assert!(matches!(
+            grid.subgrids()[[0, 0, 0]],
+            SubgridEnum::LagrangeSubgridV2(_)
+        ));
+        assert!(grid.subgrids()[[1, 0, 0]].is_empty());
+    }
+
+    #[test]
+    fn grid_merge_bins() {
+        let mut grid = Grid::new(
+            vec![
+                channel![2, 2, 1.0; 4, 4, 1.0],
+                channel![1, 1, 1.0; 3, 3, 1.0],
+            ],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 0.25, 0.5],
+            SubgridParams::default(),
+        );
+
+        assert_eq!(grid.bin_info().bins(), 2);
+        assert_eq!(grid.channels().len(), 2);
+        assert_eq!(grid.orders().len(), 1);
+
+        let mut other = Grid::new(
+            vec![
+                // channels are differently sorted
+                channel![1, 1, 1.0; 3, 3, 1.0],
+                channel![2, 2, 1.0; 4, 4, 1.0],
+            ],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.5, 0.75, 1.0],
+            SubgridParams::default(),
+        );
+
+        other.fill_all(
+            0,
+            0.1,
+            &Ntuple {
+                x1: 0.1,
+                x2: 0.2,
+                q2: 90.0_f64.powi(2),
+                weight: (),
+            },
+            &[2.0, 3.0],
+        );
+
+        grid.merge(other).unwrap();
+
+        assert_eq!(grid.bin_info().bins(), 4);
+        assert_eq!(grid.channels().len(), 2);
+        assert_eq!(grid.orders().len(), 1);
+    }
+
+    #[test]
+    fn grid_reference() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 0, 0, 0)],
+            vec![0.0, 0.5, 1.0],
+            SubgridParams::default(),
+        );
+
+        assert_eq!(grid.reference().unwrap(), None);
+
+        let reference = Reference {
+            pdf: "NNPDF31_nlo_as_0118_luxqed".to_owned(),
+            results: vec![1.0 / 3.0, -2.5e-12],
+        };
+
+        grid.set_reference(&reference).unwrap();
+
+        assert_eq!(grid.reference().unwrap(), Some(reference.clone()));
+
+        let result = grid.set_reference(&Reference {
+            pdf: "NNPDF31_nlo_as_0118_luxqed".to_owned(),
+            results: vec![1.0],
+        });
+
+        assert!(matches!(
+            result,
+            Err(GridError::ReferenceBinMismatch {
+                grid_bins: 2,
+                reference_bins: 1
+            })
+        ));
+
+        let mut other = grid.clone();
+        other.delete_bins(&[0]);
+
+        assert_eq!(
+            other.reference().unwrap(),
+            Some(Reference {
+                pdf: reference.pdf.clone(),
+                results: vec![-2.5e-12],
+            })
+        );
+
+        // merging grids with different bins concatenates the references
+        let mut merged = grid.clone();
+        merged.delete_bins(&[1]);
+        merged.merge(other.clone()).unwrap();
+
+        assert_eq!(merged.reference().unwrap(), Some(reference.clone()));
+
+        // merging grids with the same bins adds the predictions and removes the reference
+        let mut merged = grid.clone();
+        merged.merge(grid.clone()).unwrap();
+
+        assert_eq!(merged.reference().unwrap(), None);
+
+        let mut merged = grid.clone();
+        merged.merge_bins(0..2).unwrap();
+
+        assert_eq!(merged.reference().unwrap(), None);
+
+        grid.set_key_value("reference", "1.0");
+
+        assert!(matches!(
+            grid.reference(),
+            Err(GridError::ReferenceBinMismatch {
+                grid_bins: 2,
+                reference_bins: 1
+            })
+        ));
+
+        grid.set_key_value("reference", "1.0 x");
+
+        assert_eq!(grid.reference().unwrap(), None);
+    }
+
+    #[test]
+    fn grid_calibrate_to_reference() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 0.5, 1.0],
+            SubgridParams::default(),
+        );
+        grid.fill(
+            0,
+            0.25,
+            0,
+            &Ntuple {
+                x1: 0.1,
+                x2: 0.2,
+                q2: 100.0,
+                weight: 1.0,
+            },
+        );
+        grid.fill(
+            0,
+            0.75,
+            0,
+            &Ntuple {
+                x1: 0.3,
+                x2: 0.4,
+                q2: 100.0,
+                weight: 3.0,
+            },
+        );
+
+        let mut xfx = |_, x, _| x;
+        let mut alphas = |_| 0.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        assert!(matches!(
+            grid.calibrate_to_reference(&mut lumi_cache, 1e-10),
+            Err(GridError::MissingReference)
+        ));
+
+        let results = grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+
+        grid.set_reference(&Reference {
+            pdf: "x".to_owned(),
+            results: results.iter().map(|result| 1000.0 * result).collect(),
+        })
+        .unwrap();
+
+        assert_approx_eq!(
+            f64,
+            grid.calibrate_to_reference(&mut lumi_cache, 1e-10).unwrap(),
+            1000.0,
+            ulps = 4
+        );
+
+        let calibrated = grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+
+        for (calibrated, result) in calibrated.iter().zip(&results) {
+            assert_approx_eq!(f64, *calibrated, 1000.0 * result, ulps = 4);
+        }
+
+        grid.set_reference(&Reference {
+            pdf: "x".to_owned(),
+            results: vec![calibrated[0], 2.0 * calibrated[1]],
+        })
+        .unwrap();
+
+        assert!(matches!(
+            grid.calibrate_to_reference(&mut lumi_cache, 1e-10),
+            Err(GridError::InconsistentReference { bin: 0, .. })
+        ));
+
+        // the failed calibration leaves the grid unchanged
+        let unchanged = grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+
+        assert_eq!(unchanged, calibrated);
+    }
+
+    #[test]
+    fn grid_bin_dimension_labels() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 0, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        grid.set_remapper(BinRemapper::new(vec![1.0], vec![(0.0, 1.0), (2.0, 3.0)]).unwrap())
+            .unwrap();
+        grid.set_key_value("x1_label", "yll");
+        grid.set_key_value("x1_label_tex", "$y_{\\ell\\ell}$");
+        grid.set_key_value("x2_unit", "GeV");
+
+        assert_eq!(
+            grid.bin_dimension_labels(),
+            [
+                DimLabel {
+                    label: "yll".to_owned(),
+                    label_tex: "$y_{\\ell\\ell}$".to_owned(),
+                    unit: String::new(),
+                },
+                DimLabel {
+                    label: String::new(),
+                    label_tex: String::new(),
+                    unit: "GeV".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn grid_optimize_using_report() {
+        let mut grid = Grid::new(
+            vec![
+                channel![2, 2, 1.0],
+                channel![2, 2, 1.0],
+                channel![1, 2, 1.0],
+                channel![2, 1, 1.0],
+                channel![21, 21, 1.0],
+            ],
+            vec![Order::new(0, 2, 0, 0), Order::new(1, 2, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        for channel in 0..4 {
+            grid.fill(
+                0,
+                0.5,
+                channel,
+                &Ntuple {
+                    x1: 0.1,
+                    x2: 0.2,
+                    q2: 8000.0,
+                    weight: 1.0,
+                },
+            );
+        }
+
+        assert_eq!(grid.symmetrizable_channels(), [(2, 3)]);
+        assert_eq!(grid.mergeable_channels(), [(0, 1, 1.0)]);
+
+        let mut optimized = grid.clone();
+        optimized.optimize();
+
+        let report = grid.optimize_using_report(GridOptFlags::all());
+
+        assert_eq!(report.subgrids_converted, 4);
+        assert_eq!(report.channels_symmetrized, 1);
+        assert_eq!(report.orders_stripped, 1);
+        assert_eq!(report.channels_merged, 1);
+        // the empty channel and the ones emptied by the symmetrization and merging
+        assert_eq!(report.channels_stripped, 3);
+        assert!(report.bytes_after < report.bytes_before);
+
+        // the report doesn't change what is optimized
+        assert_eq!(grid.orders(), optimized.orders());
+        assert_eq!(grid.channels(), optimized.channels());
+        assert_eq!(grid.channels().len(), 2);
+    }
+
+    #[test]
+    fn grid_merge_with_metadata() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+        grid.set_key_value("runcard", "first");
+        grid.set_key_value("first_only", "1");
+
+        let mut other = grid.clone();
+        other.key_values_mut().remove("first_only");
+        other.set_key_value("runcard", "second");
+        other.set_key_value("second_only", "2");
+
+        let key_values = |grid: &Grid| {
+            let key_values = grid.key_values().unwrap();
+            (
+                key_values.get("runcard").cloned(),
+                key_values.get("first_only").cloned(),
+                key_values.get("second_only").cloned(),
+            )
+        };
+
+        let mut keep_first = grid.clone();
+        keep_first.merge(other.clone()).unwrap();
+        assert_eq!(
+            key_values(&keep_first),
+            (Some("first".into()), Some("1".into()), Some("2".into()))
+        );
+
+        let mut keep_last = grid.clone();
+        keep_last
+            .merge_with_metadata(other.clone(), MetadataMergePolicy::KeepLast)
+            .unwrap();
+        assert_eq!(
+            key_values(&keep_last),
+            (Some("second".into()), Some("1".into()), Some("2".into()))
+        );
+
+        let mut error = grid.clone();
+        assert!(matches!(
+            error.merge_with_metadata(other.clone(), MetadataMergePolicy::ErrorOnConflict),
+            Err(GridError::MetadataConflict { key, lhs, rhs })
+                if (key == "runcard") && (lhs == "first") && (rhs == "second")
+        ));
+        // nothing was merged
+        assert_eq!(key_values(&error), key_values(&grid));
+
+        // without conflicts merging succeeds
+        other.set_key_value("runcard", "first");
+        error
+            .merge_with_metadata(other, MetadataMergePolicy::ErrorOnConflict)
+            .unwrap();
+        assert_eq!(
+            key_values(&error),
+            (Some("first".into()), Some("1".into()), Some("2".into()))
+        );
+    }
+
+    #[test]
+    fn grid_scale_by_channel_and_bin() {
+        let mut grid = Grid::new(
+            vec![
+                channel![2, 2, 1.0; 4, 4, 1.0],
+                channel![1, 1, 1.0; 3, 3, 1.0],
+            ],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 0.5, 1.0],
+            SubgridParams::default(),
+        );
+
+        for observable in [0.25, 0.75] {
+            grid.fill_all(
+                0,
+                observable,
+                &Ntuple {
+                    x1: 0.1,
+                    x2: 0.2,
+                    q2: 90.0_f64.powi(2),
+                    weight: (),
+                },
+                &[1.0, 1.0],
+            );
+        }
+
+        let sum = |grid: &Grid, bin: usize, channel: usize| -> f64 {
+            grid.subgrids()[[0, bin, channel]]
+                .indexed_iter()
+                .map(|(_, value)| value)
+                .sum()
+        };
+        let unscaled: Vec<_> = (0..2)
+            .flat_map(|bin| (0..2).map(move |channel| (bin, channel)))
+            .map(|(bin, channel)| sum(&grid, bin, channel))
+            .collect();
+
+        assert!(matches!(
+            grid.scale_by_channel_and_bin(&Array2::ones((2, 3))),
+            Err(GridError::FactorShapeMismatch {
+                shape: (2, 3),
+                channels: 2,
+                bins: 2
+            })
+        ));
+
+        grid.scale_by_channel_and_bin(&ndarray::arr2(&[[1.0, 2.0], [3.0, 4.0]]))
+            .unwrap();
+
+        assert_approx_eq!(f64, sum(&grid, 0, 0), unscaled[0], ulps = 4);
+        assert_approx_eq!(f64, sum(&grid, 0, 1), 3.0 * unscaled[1], ulps = 4);
+        assert_approx_eq!(f64, sum(&grid, 1, 0), 2.0 * unscaled[2], ulps = 4);
+        assert_approx_eq!(f64, sum(&grid, 1, 1), 4.0 * unscaled[3], ulps = 4);
+    }
+
+    #[test]
+    fn grid_set_bin_scales() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0; 1, -1, 0.5]],
+            vec![
+                Order::new(1, 0, 0, 0),
+                Order::new(1, 0, 1, 0),
+                Order::new(1, 0, 0, 1),
+            ],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
+
+        for ((order, bin, _), subgrid) in grid.subgrids_mut().indexed_iter_mut() {
+            let mut array = SparseArray3::new(1, 2, 2);
+            array[[0, 0, 0]] = 1.0 + f64::from(u32::try_from(order).unwrap());
+            array[[0, 1, 0]] = -0.5;
+            array[[0, 1, 1]] = 2.0 * f64::from(u32::try_from(bin + 1).unwrap());
+            let q2 = if bin == 0 { 100.0 } else { 400.0 };
+
+            *subgrid = ImportOnlySubgridV2::new(
+                array,
+                vec![Mu2 { ren: q2, fac: q2 }],
+                vec![0.1, 0.5],
+                vec![0.2, 0.6],
+            )
+            .into();
+        }
+
+        let mut xfx = |_, x: f64, q2: f64| x * q2.ln();
+        let mut alphas = |q2: f64| 1.0 / q2.ln();
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        // for subgrids with a single scale this is the same as a scale variation
+        let expected = grid.convolve(&mut lumi_cache, &[], &[], &[], &[(2.0, 0.5)]);
+
+        assert!(matches!(
+            grid.set_bin_scales(&[Mu2 { ren: 1.0, fac: 1.0 }]),
+            Err(GridError::BinScaleMismatch {
+                grid_bins: 2,
+                scales: 1
+            })
+        ));
+        assert!(matches!(
+            grid.set_bin_scales(&[Mu2 { ren: 1.0, fac: 1.0 }, Mu2 { ren: 1.0, fac: 0.0 }]),
+            Err(GridError::InvalidScale { .. })
+        ));
+        assert_eq!(grid.orders().len(), 3);
+
+        grid.set_bin_scales(&[
+            Mu2 {
+                ren: 400.0,
+                fac: 25.0,
+            },
+            Mu2 {
+                ren: 1600.0,
+                fac: 100.0,
+            },
+        ])
+        .unwrap();
+
+        assert_eq!(grid.orders(), [Order::new(1, 0, 0, 0)]);
+        assert_eq!(
+            grid.subgrids()[[0, 1, 0]].mu2_grid().as_ref(),
+            [Mu2 {
+                ren: 1600.0,
+                fac: 100.0
+            }]
+        );
+
+        let results = grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+
+        for (result, expected) in results.iter().zip(&expected) {
+            assert_approx_eq!(f64, *result, *expected, ulps = 16);
+        }
+    }
+
+    #[test]
+    fn grid_set_scales() {
+        let grid_with_scales = |scales: &[f64]| {
+            let mut grid = Grid::new(
+                vec![channel![2, 2, 1.0]],
+                vec![Order::new(1, 0, 0, 0)],
+                vec![0.0, 1.0],
+                SubgridParams::default(),
+            );
+            let mut array = SparseArray3::new(scales.len(), 1, 1);
+
+            for (index, _) in scales.iter().enumerate() {
+                array[[index, 0, 0]] = 1.0 + f64::from(u32::try_from(index).unwrap());
+            }
+
+            grid.subgrids_mut()[[0, 0, 0]] = ImportOnlySubgridV2::new(
+                array,
+                scales
+                    .iter()
+                    .map(|&scale| Mu2 {
+                        ren: scale,
+                        fac: scale,
+                    })
+                    .collect(),
+                vec![0.5],
+                vec![0.5],
+            )
+            .into();
+            grid
+        };
+
+        let mut xfx = |_, x: f64, q2: f64| x * q2.ln();
+        let mut alphas = |q2: f64| 1.0 / q2.ln();
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        // the scales were filled as `mu` instead of `mu2`
+        let mut grid = grid_with_scales(&[10.0, 20.0]);
+        let expected = grid_with_scales(&[100.0, 400.0]).convolve(
+            &mut lumi_cache,
+            &[],
+            &[],
+            &[],
+            &[(1.0, 1.0)],
+        );
+
+        let result = grid.set_scales(&|&Mu2 { ren, fac }| Mu2 {
+            ren: -ren,
+            fac: fac * fac,
+        });
+
+        assert!(matches!(
+            result,
+            Err(GridError::InvalidScale { ren, fac })
+                if approx_eq!(f64, ren, -10.0, ulps = 4) && approx_eq!(f64, fac, 100.0, ulps = 4)
+        ));
+        // the failed call didn't change the grid
+        assert_eq!(
+            grid.subgrids()[[0, 0, 0]].mu2_grid()[1],
+            Mu2 {
+                ren: 20.0,
+                fac: 20.0
+            }
+        );
+
+        grid.set_scales(&|&Mu2 { ren, fac }| Mu2 {
+            ren: ren * ren,
+            fac: fac * fac,
+        })
+        .unwrap();
+
+        assert_eq!(
+            grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]),
+            expected
+        );
+    }
+
+    #[test]
+    fn grid_convolve_with_options_compensated() {
+        let mut grid = Grid::new(
+            vec![
+                channel![2, 2, 1e16],
+                channel![2, 2, 1.0],
+                channel![2, 2, -1e16],
+            ],
+            vec![Order::new(0, 0, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        for channel in 0..3 {
+            grid.subgrids_mut()[[0, 0, channel]] =
+                ImportOnlySubgridV2::single_node(1.0, 0.5).into();
+        }
+
+        let mut xfx = |_, x, _| x;
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        // the contribution of the second channel is lost in the rounding of the first one
+        assert_eq!(
+            grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]),
+            [0.0]
+        );
+        assert_eq!(
+            grid.convolve_with_options(
+                slice::from_mut(&mut lumi_cache),
+                &ConvolveOptions {
+                    compensated: true,
+                    ..ConvolveOptions::default()
+                }
+            ),
+            [1.0]
+        );
+    }
+
+    #[test]
+    fn grid_convolve_with_options_bin_caches() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 0, 0, 0)],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
+
+        for bin in 0..2 {
+            grid.set_subgrid(0, bin, 0, ImportOnlySubgridV2::single_node(1.0, 0.5).into());
+        }
+
+        let mut xfx1 = |_, x, _| x;
+        let mut alphas1 = |_| 1.0;
+        let mut xfx2 = |_, x, _| 2.0 * x;
+        let mut alphas2 = |_| 1.0;
+        let mut lumi_caches = [
+            LumiCache::with_one(2212, &mut xfx1, &mut alphas1),
+            LumiCache::with_one(2212, &mut xfx2, &mut alphas2),
+        ];
+
+        assert_eq!(
+            grid.convolve_with_options(&mut lumi_caches, &ConvolveOptions::default()),
+            [1.0, 1.0]
+        );
+        assert_eq!(
+            grid.convolve_with_options(
+                &mut lumi_caches,
+                &ConvolveOptions {
+                    bin_caches: &[0, 1],
+                    ..ConvolveOptions::default()
+                }
+            ),
+            [1.0, 4.0]
+        );
+        assert_eq!(
+            grid.convolve_with_options(
+                &mut lumi_caches,
+                &ConvolveOptions {
+                    bin_caches: &[1, 0],
+                    bin_indices: &[1],
+                    ..ConvolveOptions::default()
+                }
+            ),
+            [1.0]
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`bin_caches` must have an entry for each of the 1 bins, but it has 2"
+    )]
+    fn grid_convolve_with_options_bin_caches_panic() {
+        let grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 0, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        let mut xfx = |_, x, _| x;
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        let _ = grid.convolve_with_options(
+            slice::from_mut(&mut lumi_cache),
+            &ConvolveOptions {
+                bin_caches: &[0, 0],
+                ..ConvolveOptions::default()
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "`lumi_caches` must contain at least one cache")]
+    fn grid_convolve_with_options_empty_caches() {
+        let grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 0, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        let _ = grid.convolve_with_options(&mut [], &ConvolveOptions::default());
+    }
+
+    #[test]
+    fn lumi_cache_set_alphas_and_set_pdf() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(2, 0, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        let mut array = SparseArray3::new(1, 2, 2);
+        array[[0, 0, 0]] = 1.0;
+        array[[0, 1, 1]] = 2.0;
+
+        grid.set_subgrid(
+            0,
+            0,
+            0,
+            ImportOnlySubgridV2::new(
+                array,
+                vec![Mu2 {
+                    ren: 100.0,
+                    fac: 100.0,
+                }],
+                vec![0.25, 0.5],
+                vec![0.25, 0.5],
+            )
+            .into(),
+        );
+
+        let pdf_evaluations = Cell::new(0);
+        let alphas_evaluations = Cell::new(0);
+        let mut xfx1 = |_, x, _| {
+            pdf_evaluations.set(pdf_evaluations.get() + 1);
+            x
+        };
+        let mut xfx2 = |_, x: f64, _| {
+            pdf_evaluations.set(pdf_evaluations.get() + 1);
+            2.0 * x
+        };
+        let mut alphas1 = |_| {
+            alphas_evaluations.set(alphas_evaluations.get() + 1);
+            1.0
+        };
+        let mut alphas2 = |_| {
+            alphas_evaluations.set(alphas_evaluations.get() + 1);
+            0.5
+        };
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx1, &mut alphas1);
+
+        let results = grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+        assert_approx_eq!(f64, results[0], 3.0, ulps = 4);
+        assert_eq!(pdf_evaluations.get(), 2);
+        assert_eq!(alphas_evaluations.get(), 1);
+
+        // swapping the strong coupling keeps the PDF values
+        lumi_cache.set_alphas(&mut alphas2);
+
+        let results = grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+        assert_approx_eq!(f64, results[0], 0.75, ulps = 4);
+        assert_eq!(pdf_evaluations.get(), 2);
+        assert_eq!(alphas_evaluations.get(), 2);
+
+        // swapping the PDF keeps the values of the strong coupling
+        lumi_cache.set_pdf(2212, &mut xfx2);
+
+        let results = grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+        assert_approx_eq!(f64, results[0], 3.0, ulps = 4);
+        assert_eq!(pdf_evaluations.get(), 4);
+        assert_eq!(alphas_evaluations.get(), 2);
+    }
+
+    #[test]
+    fn grid_fill_at_bin() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0], channel![21, 21, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
+        let mut other = grid.clone();
+
+        for (observable, bin, channel, x1) in
+            [(0.5, 0, 0, 0.1), (1.5, 1, 1, 0.2), (1.25, 1, 0, 0.3)]
+        {
+            let ntuple = Ntuple {
+                x1,
+                x2: 0.4,
+                q2: 1000.0,
+                weight: 1.0,
+            };
+
+            grid.fill(0, observable, channel, &ntuple);
+            other.fill_at_bin(0, bin, channel, &ntuple);
+        }
+
+        let mut xfx = |_, x, _| x;
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        let results = grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+        let other_results = other.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+
+        assert!(results.iter().all(|&result| result > 0.0));
+
+        for (result, other_result) in results.into_iter().zip(other_results) {
+            assert_approx_eq!(f64, result, other_result, ulps = 4);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn grid_fill_at_bin_invalid_bin() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        grid.fill_at_bin(
+            0,
+            1,
+            0,
+            &Ntuple {
+                x1: 0.1,
+                x2: 0.2,
+                q2: 1000.0,
+                weight: 1.0,
+            },
+        );
+    }
+
+    #[test]
+    fn grid_convolve_with_options_channel_mask() {
+        let mut grid = Grid::new(
+            vec![
+                channel![2, 2, 1.0],
+                channel![4, 4, 2.0],
+                channel![21, 21, 1.0],
+            ],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
+
+        for (bin, channel, x) in [(0, 0, 0.5), (1, 0, 0.25), (0, 1, 0.125), (1, 2, 0.75)] {
+            grid.set_subgrid(
+                0,
+                bin,
+                channel,
+                ImportOnlySubgridV2::single_node(1.0, x).into(),
+            );
+        }
+
+        let mut xfx = |_, x, _| x;
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+        let xi = [(1.0, 1.0), (2.0, 2.0)];
+
+        for channel in 0..grid.channels().len() {
+            let mut channel_mask = vec![false; grid.channels().len()];
+            channel_mask[channel] = true;
+
+            let masked = grid.convolve_with_options(
+                slice::from_mut(&mut lumi_cache),
+                &ConvolveOptions {
+                    channel_mask: &channel_mask,
+                    xi: &xi,
+                    ..ConvolveOptions::default()
+                },
+            );
+
+            assert_eq!(masked.len(), 4);
+
+            // the results of the masked channel are the ones of its subgrids
+            for (index, masked) in masked.into_iter().enumerate() {
+                let (bin, xi_index) = (index / xi.len(), index % xi.len());
+                let (xir, xif) = xi[xi_index];
+                let expected = grid
+                    .convolve_subgrid(&mut lumi_cache, 0, bin, channel, xir, xif)
+                    .sum();
+
+                assert_approx_eq!(f64, masked, expected, ulps = 4);
+            }
+        }
+
+        // selecting bins works as well
+        let options = ConvolveOptions {
+            channel_mask: &[false, true, false],
+            xi: &xi,
+            ..ConvolveOptions::default()
+        };
+        let results = grid.convolve_with_options(
+            slice::from_mut(&mut lumi_cache),
+            &ConvolveOptions {
+                bin_indices: &[0],
+                ..options
+            },
+        );
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|&result| result > 0.0));
+        let results = grid.convolve_with_options(
+            slice::from_mut(&mut lumi_cache),
+            &ConvolveOptions {
+                bin_indices: &[1],
+                ..options
+            },
+        );
+        assert!(results.iter().all(|&result| result == 0.0));
+    }
+
+    #[test]
+    fn grid_convolve_log_orders() {
+        let orders = vec![
+            Order::new(0, 2, 0, 0),
+            Order::new(0, 2, 1, 0),
+            Order::new(0, 2, 0, 1),
+            Order::new(0, 2, 1, 1),
+            Order::new(0, 2, 2, 0),
+        ];
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            orders.clone(),
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        for order in 0..orders.len() {
+            grid.set_subgrid(
+                order,
+                0,
+                0,
+                ImportOnlySubgridV2::single_node(1.0, 0.5).into(),
+            );
+        }
+
+        // with these functions every subgrid contributes exactly one times its logarithms
+        let mut xfx = |_, x, _| x;
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        let xi = [(1.0, 1.0), (2.0, 1.0), (1.0, 2.0), (2.0, 2.0), (0.5, 2.0)];
+        let lr = |xir: f64| (xir * xir).ln();
+        let lf = |xif: f64| (xif * xif).ln();
+
+        for (index, order) in orders.iter().enumerate() {
+            let mut order_mask = vec![false; orders.len()];
+            order_mask[index] = true;
+
+            let results = grid.convolve(&mut lumi_cache, &order_mask, &[], &[], &xi);
+
+            for (&result, &(xir, xif)) in results.iter().zip(&xi) {
+                // the skipped log orders are exactly the ones whose logarithms vanish
+                let expected = match (order.logxir, order.logxif) {
+                    (0, 0) => 1.0,
+                    (1, 0) => lr(xir),
+                    (0, 1) => lf(xif),
+                    (1, 1) => lr(xir) * lf(xif),
+                    (2, 0) => lr(xir).powi(2),
+                    _ => unreachable!(),
+                };
+
+                assert_approx_eq!(f64, result, expected, ulps = 4);
+            }
+        }
+    }
+
+    #[test]
+    fn grid_convolve_with_options_bin_corrections() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 0, 0, 0)],
+            vec![0.0, 1.0, 3.0],
+            SubgridParams::default(),
+        );
+
+        for bin in 0..2 {
+            grid.set_subgrid(0, bin, 0, ImportOnlySubgridV2::single_node(1.0, 0.5).into());
+        }
+
+        let mut xfx = |_, x, _| x;
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        assert_eq!(
+            grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]),
+            [1.0, 0.5]
+        );
+        assert_eq!(
+            grid.convolve_with_options(
+                slice::from_mut(&mut lumi_cache),
+                &ConvolveOptions {
+                    bin_corrections: &[2.0, 3.0],
+                    ..ConvolveOptions::default()
+                }
+            ),
+            [2.0, 1.5]
+        );
+        assert_eq!(
+            grid.convolve_with_options(
+                slice::from_mut(&mut lumi_cache),
+                &ConvolveOptions {
+                    bin_corrections: &[2.0, 3.0],
+                    bin_indices: &[1],
+                    xi: &[(1.0, 1.0), (2.0, 2.0)],
+                    ..ConvolveOptions::default()
+                }
+            ),
+            [1.5, 1.5]
+        );
+        assert!(grid
+            .convolve_with_options(
+                slice::from_mut(&mut lumi_cache),
+                &ConvolveOptions {
+                    bin_corrections: &[2.0, 3.0],
+                    xi: &[],
+                    ..ConvolveOptions::default()
+                }
+            )
+            .is_empty());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`bin_corrections` must have an entry for each of the 1 bins, but it has 2"
+    )]
+    fn grid_convolve_with_options_bin_corrections_panic() {
+        let grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 0, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        let mut xfx = |_, x, _| x;
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        let _ = grid.convolve_with_options(
+            slice::from_mut(&mut lumi_cache),
+            &ConvolveOptions {
+                bin_corrections: &[1.0, 1.0],
+                ..ConvolveOptions::default()
+            },
+        );
+    }
+
+    #[test]
+    fn grid_compress_to_f32() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0], channel![21, 21, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        for i in 1..100 {
+            let x1 = f64::from(i) / 101.0;
+
+            for j in 1..10 {
+                let x2 = f64::from(j) / 11.0;
+
+                grid.fill(
+                    0,
+                    0.5,
+                    0,
+                    &Ntuple {
+                        x1,
+                        x2,
+                        q2: 100.0,
+                        weight: x1 * x2,
+                    },
+                );
+            }
+        }
+
+        // these two events almost cancel each other
+        for (x2, weight) in [(0.2, 1.0), (0.200_001, -1.0)] {
+            grid.fill(
+                0,
+                0.5,
+                1,
+                &Ntuple {
+                    x1: 0.1,
+                    x2,
+                    q2: 100.0,
+                    weight,
+                },
+            );
+        }
+
+        grid.optimize();
+
+        let mut xfx = |_, x: f64, _| x * (1.0 - x).powi(3);
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+        let reference = grid.convolve(&mut lumi_cache, &[], &[], &[true, false], &[(1.0, 1.0)]);
+
+        let mut buffer = Vec::new();
+        grid.write(&mut buffer).unwrap();
+        let size_f64 = buffer.len();
+
+        let histograms = grid.weight_histograms();
+        let converted = grid.compress_to_f32(1e-6);
+
+        assert_eq!(
+            histograms.map(|histogram| histogram.is_f32_safe(1e-6)),
+            converted
+        );
+        assert_eq!(
+            converted,
+            Array3::from_shape_vec((1, 1, 2), vec![true, false]).unwrap()
+        );
+        assert_eq!(
+            grid.subgrid_kinds(),
+            Array3::from_shape_vec(
+                (1, 1, 2),
+                vec![
+                    SubgridKind::ImportOnlySubgridF32,
+                    SubgridKind::ImportOnlySubgridV2
+                ]
+            )
+            .unwrap()
+        );
+
+        let mut buffer = Vec::new();
+        grid.write(&mut buffer).unwrap();
+        let size_f32 = buffer.len();
+
+        // the indices of the sparse arrays are not compressed
+        assert!(size_f32 < size_f64 * 3 / 4);
+
+        let grid = Grid::read(buffer.as_slice()).unwrap();
+        let result = grid.convolve(&mut lumi_cache, &[], &[], &[true, false], &[(1.0, 1.0)]);
+
+        assert_approx_eq!(
+            f64,
+            result[0],
+            reference[0],
+            epsilon = 1e-6 * reference[0].abs()
+        );
+    }
+
+    #[test]
+    fn evolve_with_slice_iter_with_progress() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 0, 0, 0)],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
+
+        for (bin, fac) in [(0, 100.0), (1, 1000.0)] {
+            let mut array = SparseArray3::new(1, 1, 1);
+            array[[0, 0, 0]] = f64::from(u8::try_from(bin).unwrap()) + 1.0;
+
+            grid.set_subgrid(
+                0,
+                bin,
+                0,
+                ImportOnlySubgridV2::new(array, vec![Mu2 { ren: fac, fac }], vec![0.5], vec![0.5])
+                    .into(),
+            );
+        }
+
+        // the slice with `fac1 = 10` isn't needed by the grid and doesn't count as progress
+        let slices = || {
+            [10.0, 100.0, 1000.0].map(|fac1| {
+                Ok::<_, GridError>((
+                    OperatorSliceInfo {
+                        fac0: 1.0,
+                        pids0: vec![2],
+                        x0: vec![0.5],
+                        fac1,
+                        pids1: vec![2],
+                        x1: vec![0.5],
+                        pid_basis: PidBasis::Pdg,
+                    },
+                    CowArray::from(Array4::from_elem((1, 1, 1, 1), 2.0)),
+                ))
+            })
+        };
+        let alphas_table = AlphasTable {
+            ren1: vec![],
+            alphas: vec![],
+        };
+        let mut calls = Vec::new();
+
+        let fk_table = grid
+            .evolve_with_slice_iter_with_progress(
+                slices(),
+                &[],
+                (1.0, 1.0),
+                &alphas_table,
+                |evolved, total| calls.push((evolved, total)),
+            )
+            .unwrap();
+
+        assert_eq!(calls, [(1, 2), (2, 2)]);
+
+        let reference = grid
+            .evolve_with_slice_iter(slices(), &[], (1.0, 1.0), &alphas_table)
+            .unwrap();
+        let mut xfx = |_, _, _| 1.0;
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        assert_eq!(
+            fk_table.convolve(&mut lumi_cache, &[], &[]),
+            reference.convolve(&mut lumi_cache, &[], &[])
+        );
+        // each bin is multiplied by the two operators and the PDFs `f(x) = xfx / x = 2`
+        assert_eq!(reference.convolve(&mut lumi_cache, &[], &[]), [16.0, 32.0]);
+    }
+
+    #[test]
+    fn grid_evolve_with_slice_iter_groups() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 0, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+        grid.set_subgrid(0, 0, 0, ImportOnlySubgridV2::single_node(1.0, 0.5).into());
+
+        let slice = |fac0: f64, fac1: f64, factor: f64| {
+            (
+                OperatorSliceInfo {
+                    fac0,
+                    pids0: vec![2],
+                    x0: vec![0.5],
+                    fac1,
+                    pids1: vec![2],
+                    x1: vec![0.5],
+                    pid_basis: PidBasis::Pdg,
+                },
+                CowArray::from(Array4::from_elem((1, 1, 1, 1), factor)),
+            )
+        };
+        let alphas_table = AlphasTable {
+            ren1: vec![],
+            alphas: vec![],
+        };
+
+        let fk_tables = grid
+            .evolve_with_slice_iter_groups(
+                [
+                    // slices that the grid doesn't need are skipped
+                    Ok::<_, GridError>(vec![slice(1.0, 50.0, 5.0), slice(4.0, 50.0, 7.0)]),
+                    Ok(vec![slice(1.0, 100.0, 2.0), slice(4.0, 100.0, 3.0)]),
+                ],
+                &[],
+                (1.0, 1.0),
+                &alphas_table,
+            )
+            .unwrap();
+
+        assert_eq!(fk_tables.len(), 2);
+
+        let mut xfx = |_, x, _| x;
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+        let reference = grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+
+        // the FK tables are in the order of the groups
+        for (fk_table, (fac0, factor)) in fk_tables.iter().zip([(1.0, 2.0), (4.0, 3.0)]) {
+            assert_approx_eq!(f64, fk_table.muf2(), fac0, ulps = 4);
+            assert_approx_eq!(
+                f64,
+                fk_table.convolve(&mut lumi_cache, &[], &[])[0],
+                factor * factor * reference[0],
+                ulps = 4
+            );
+
+            // each group gives the same result as `evolve_with_slice_iter`
+            let single = grid
+                .evolve_with_slice_iter(
+                    [Ok::<_, GridError>(slice(fac0, 100.0, factor))],
+                    &[],
+                    (1.0, 1.0),
+                    &alphas_table,
+                )
+                .unwrap();
+
+            assert_eq!(
+                single.convolve(&mut lumi_cache, &[], &[]),
+                fk_table.convolve(&mut lumi_cache, &[], &[])
+            );
+        }
+
+        for (slices, message) in [
+            (
+                vec![
+                    vec![slice(1.0, 50.0, 1.0)],
+                    vec![slice(2.0, 100.0, 1.0)],
+                ],
+                "the operator slice of group 0 has fac0 = 2, but the group started with fac0 = 1",
+            ),
+            (
+                vec![vec![slice(1.0, 100.0, 1.0), slice(2.0, 50.0, 1.0)]],
+                "the operator slice of group 1 has fac1 = 50, but the slice of group 0 has fac1 = 100",
+            ),
+            (
+                vec![
+                    vec![slice(1.0, 50.0, 1.0), slice(2.0, 50.0, 1.0)],
+                    vec![slice(1.0, 100.0, 1.0)],
+                ],
+                "expected 2 operator slices, one for each group, but got 1",
+            ),
+        ] {
+            let result = grid.evolve_with_slice_iter_groups(
+                slices.into_iter().map(Ok::<_, GridError>),
+                &[],
+                (1.0, 1.0),
+                &alphas_table,
+            );
+
+            assert!(
+                matches!(&result, Err(GridError::EvolutionFailure(msg)) if msg == message),
+                "{message}"
+            );
+        }
+    }
+
+    #[test]
+    fn grid_weight_histograms() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0, 2.0, 3.0],
+            SubgridParams::default(),
+        );
+
+        grid.set_subgrid(0, 0, 0, ImportOnlySubgridV2::single_node(0.5, 0.5).into());
+        grid.set_subgrid(
+            0,
+            1,
+            0,
+            ImportOnlySubgridV2::single_node(-250.1, 0.5).into(),
+        );
+
+        let histograms = grid.weight_histograms();
+
+        assert_eq!(histograms.dim(), (1, 3, 1));
+        assert_eq!(
+            histograms[[0, 0, 0]],
+            WeightHistogram {
+                decades: BTreeMap::from([(-1, 1)]),
+                sum: 0.5,
+                f32_error: 0.0,
+            }
+        );
+        assert_eq!(histograms[[0, 1, 0]].decades, BTreeMap::from([(2, 1)]));
+        assert_approx_eq!(f64, histograms[[0, 1, 0]].sum, -250.1, ulps = 4);
+        assert!(histograms[[0, 1, 0]].f32_error > 0.0);
+        assert!(histograms[[0, 1, 0]].is_f32_safe(1e-6));
+        assert!(!histograms[[0, 1, 0]].is_f32_safe(1e-12));
+        assert_eq!(histograms[[0, 2, 0]], WeightHistogram::default());
+        assert!(!histograms[[0, 2, 0]].is_f32_safe(1.0));
+    }
+
+    #[test]
+    fn grid_set_reweight() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0; 21, 21, 0.5]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        for (x1, x2, q2) in [(0.1, 0.2, 100.0), (0.01, 0.5, 1000.0), (0.3, 0.001, 50.0)] {
+            grid.fill(
+                0,
+                0.5,
+                0,
+                &Ntuple {
+                    x1,
+                    x2,
+                    q2,
+                    weight: 1.0,
+                },
+            );
+        }
+
+        let mut xfx = |_, x: f64, _| x * (1.0 - x).powi(3);
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+        let reference = grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+
+        for (convolution, reweight) in [(0, false), (1, false), (0, true), (1, true)] {
+            grid.set_reweight(convolution, reweight);
+
+            let result = grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+            assert_approx_eq!(f64, result[0], reference[0], ulps = 16);
+        }
+
+        // subgrids filled after changing the reweighting must use the new setting
+        let mut subgrid_params = SubgridParams::default();
+        subgrid_params.set_reweight(false);
+        let mut extra = ExtraSubgridParams::from(&subgrid_params);
+        extra.set_reweight2(false);
+
+        let mut other = Grid::with_subgrid_type(
+            grid.channels().to_vec(),
+            grid.orders().to_vec(),
+            vec![0.0, 1.0],
+            subgrid_params,
+            extra,
+            "LagrangeSubgrid",
+        )
+        .unwrap();
+        let mut grid = Grid::new(
+            grid.channels().to_vec(),
+            grid.orders().to_vec(),
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+        grid.set_reweight(0, false);
+        grid.set_reweight(1, false);
+
+        let ntuple = Ntuple {
+            x1: 0.1,
+            x2: 0.2,
+            q2: 100.0,
+            weight: 1.0,
+        };
+        grid.fill(0, 0.5, 0, &ntuple);
+        other.fill(0, 0.5, 0, &ntuple);
+
+        assert_eq!(
+            grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]),
+            other.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)])
+        );
+    }
+
+    #[test]
+    fn grid_predictions_grid() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 0.5, 2.0, 3.0],
+            SubgridParams::default(),
+        );
+        grid.set_key_value("y_label", "dsig");
+
+        let predictions = grid.predictions_grid(&[1.0, 0.0, -3.5]);
+
+        assert_eq!(predictions.bin_info(), grid.bin_info());
+        assert_eq!(predictions.orders(), [Order::new(0, 0, 0, 0)]);
+        assert_eq!(predictions.channels().len(), 1);
+        assert_eq!(
+            predictions.convolutions(),
+            [Convolution::None, Convolution::None]
+        );
+        assert_eq!(predictions.key_values().unwrap()["y_label"], "dsig");
+
+        let mut xfx = |_, x: f64, _| x * (1.0 - x);
+        let mut alphas = |_| 0.118;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        assert_eq!(
+            predictions.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]),
+            [1.0, 0.0, -3.5]
+        );
+    }
+
+    #[test]
+    fn grid_bin_central_scales() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 0, 0, 0)],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
+
+        assert_eq!(grid.bin_central_scales(), None);
+
+        let mut array = SparseArray3::new(2, 1, 1);
+        array[[0, 0, 0]] = 1.0;
+        array[[1, 0, 0]] = -3.0;
+
+        grid.subgrids_mut()[[0, 0, 0]] = ImportOnlySubgridV2::new(
+            array,
+            vec![
+                Mu2 {
+                    ren: 100.0,
+                    fac: 100.0,
+                },
+                Mu2 {
+                    ren: 200.0,
+                    fac: 200.0,
+                },
+            ],
+            vec![0.5],
+            vec![0.5],
+        )
+        .into();
+
+        let scales = grid.central_fac_scales(&[], 2.0);
+        grid.set_bin_central_scales(&scales);
+
+        assert_eq!(grid.bin_central_scales(), Some(vec![700.0, 0.0]));
+
+        // deleting bins keeps the scales of the other bins
+        let mut deleted = grid.clone();
+        deleted.delete_bins(&[0]);
+
+        assert_eq!(deleted.bin_central_scales(), Some(vec![0.0]));
+
+        // merging grids with the same bins and scales keeps them ...
+        let mut merged = grid.clone();
+        merged.merge(grid.clone()).unwrap();
+
+        assert_eq!(merged.bin_central_scales(), Some(vec![700.0, 0.0]));
+
+        // ... and appending bins appends their scales
+        let mut other = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 0, 0, 0)],
+            vec![2.0, 3.0],
+            SubgridParams::default(),
+        );
+        other.set_bin_central_scales(&[5.0]);
+        merged.merge(other.clone()).unwrap();
+
+        assert_eq!(merged.bin_central_scales(), Some(vec![700.0, 0.0, 5.0]));
+
+        // the scales of merged bins are not known
+        merged.merge_bins(1..3).unwrap();
+
+        assert_eq!(merged.bin_central_scales(), None);
+
+        other.set_bin_central_scales(&[6.0]);
+        merged = other.clone();
+        other.set_bin_central_scales(&[7.0]);
+        merged.merge(other).unwrap();
+
+        assert_eq!(merged.bin_central_scales(), None);
+
+        grid.rebin_to(vec![0.0, 2.0]).unwrap();
+
+        assert_eq!(grid.bin_central_scales(), None);
+    }
+
+    #[test]
+    fn grid_channels_with_pid() {
+        let grid = Grid::new(
+            vec![
+                channel![2, 2, 1.0; 4, 4, 1.0],
+                channel![1, 21, 1.0; 3, 21, 1.0],
+                channel![21, 2, 1.0],
+            ],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        assert_eq!(grid.channels_with_pid(0, 2), [0]);
+        assert_eq!(grid.channels_with_pid(1, 2), [0, 2]);
+        assert_eq!(grid.channels_with_pid(0, 3), [1]);
+        assert_eq!(grid.channels_with_pid(1, 21), [1]);
+        assert!(grid.channels_with_pid(0, 22).is_empty());
+    }
+
+    #[test]
+    fn grid_remove_photon_channels() {
+        let mut grid = Grid::new(
+            vec![
+                channel![2, 2, 1.0; 4, 4, 1.0],
+                channel![22, 2, 1.0; 2, 22, 1.0; 21, 2, 1.0],
+                channel![22, 22, 1.0],
+            ],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        grid.remove_photon_channels();
+        assert_eq!(
+            grid.channels(),
+            [channel![2, 2, 1.0; 4, 4, 1.0], channel![21, 2, 1.0]]
+        );
+        assert_eq!(grid.subgrids().dim(), (1, 1, 2));
+    }
+
+    #[test]
+    fn grid_distinct_pids() {
+        let grid = Grid::new(
+            vec![
+                channel![2, 2, 1.0; 4, 4, 1.0],
+                channel![1, 21, 1.0; 3, 21, 1.0],
+                channel![21, 2, 1.0],
+            ],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        assert_eq!(grid.distinct_pids(0), [1, 2, 3, 4, 21]);
+        assert_eq!(grid.distinct_pids(1), [2, 4, 21]);
+    }
+
+    #[test]
+    fn grid_combine_channels_by_pids() {
+        let mut grid = Grid::new(
+            vec![
+                channel![2, 2, 1.0; 4, 4, 2.0],
+                channel![1, 1, 1.0],
+                channel![3, 3, 1.0],
+                channel![21, 21, 1.0],
+            ],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
+
+        let ntuple = Ntuple {
+            x1: 0.5,
+            x2: 0.25,
+            q2: 1000.0,
+            weight: 1.0,
+        };
+
+        grid.fill(0, 0.5, 0, &ntuple);
+        grid.fill(0, 1.5, 0, &ntuple);
+        grid.fill(0, 0.5, 1, &ntuple);
+        // the third channel is proportional to the second one ...
+        grid.fill(
+            0,
+            0.5,
+            2,
+            &Ntuple {
+                weight: 3.0,
+                ..ntuple
+            },
+        );
+        // ... but the fourth one isn't
+        grid.fill(0, 1.5, 3, &ntuple);
+
+        grid.combine_channels_by_pids();
+
+        assert_eq!(grid.channels().len(), 3);
+        assert_eq!(grid.channels()[0], channel![2, 2, 1.0; 4, 4, 2.0]);
+        assert_eq!(grid.channels()[2], channel![21, 21, 1.0]);
+
+        let entry = grid.channels()[1].entry();
+        assert_eq!(entry.len(), 2);
+        assert_eq!((entry[0].0, entry[0].1, entry[0].2), (1, 1, 1.0));
+        assert_eq!((entry[1].0, entry[1].1), (3, 3));
+        assert_approx_eq!(f64, entry[1].2, 3.0, ulps = 4);
+
+        // splitting and combining again gives back the same channels
+        let mut split = grid.clone();
+        split.split_channels();
+        assert_eq!(split.channels().len(), 5);
+        split.combine_channels_by_pids();
+        assert_eq!(split.channels(), grid.channels());
+    }
+
+    #[test]
+    fn grid_combine_channels_by_pids_different_nodes() {
+        let mut grid = Grid::new(
+            vec![channel![1, 1, 1.0], channel![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        for (channel, x) in [(0, 0.5), (1, 0.25)] {
+            grid.set_subgrid(
+                0,
+                0,
+                channel,
+                ImportOnlySubgridV2::single_node(1.0, x).into(),
+            );
+        }
 
-        let mut lhs: Option<Self> = None;
-        // Q2 slices we use
-        let mut used_op_fac1 = Vec::new();
-        // Q2 slices we encounter, but possibly don't use
-        let mut op_fac1 = Vec::new();
-        // Q2 slices needed by the grid
-        let grid_fac1: Vec<_> = self
-            .evolve_info(order_mask)
-            .fac1
-            .into_iter()
-            .map(|fac| xi.1 * xi.1 * fac)
-            .collect();
+        // the subgrids have the same indices and values, but describe different kinematics
+        grid.combine_channels_by_pids();
 
-        for result in slices {
-            let (info, operator) = result.map_err(|err| GridError::Other(err.into()))?;
+        assert_eq!(grid.channels(), [channel![1, 1, 1.0], channel![2, 2, 1.0]]);
+    }
 
-            op_fac1.push(info.fac1);
+    #[test]
+    fn grid_split_channels_limited() {
+        let mut grid = Grid::new(
+            vec![
+                channel![2, 2, 1.0; 4, 4, 2.0; 1, 1, 1.0],
+                channel![2, 21, 1.0; 4, 21, 1.0],
+                channel![21, 21, 1.0],
+            ],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
 
-            // it's possible that due to small numerical differences we get two slices which are
-            // almost the same. We have to skip those in order not to evolve the 'same' slice twice
-            if used_op_fac1
-                .iter()
-                .any(|&fac| approx_eq!(f64, fac, info.fac1, ulps = EVOLVE_INFO_TOL_ULPS))
-            {
-                continue;
-            }
+        let ntuple = Ntuple {
+            x1: 0.5,
+            x2: 0.25,
+            q2: 1000.0,
+            weight: 1.0,
+        };
 
-            // skip slices that the grid doesn't use
-            if !grid_fac1
-                .iter()
-                .any(|&fac| approx_eq!(f64, fac, info.fac1, ulps = EVOLVE_INFO_TOL_ULPS))
-            {
-                continue;
-            }
+        grid.fill(0, 0.5, 0, &ntuple);
+        grid.fill(0, 1.5, 1, &ntuple);
+        grid.fill(0, 0.5, 2, &ntuple);
 
-            let op_info_dim = (
-                info.pids1.len(),
-                info.x1.len(),
-                info.pids0.len(),
-                info.x0.len(),
-            );
+        let mut split = grid.clone();
+        split.split_channels_limited(2);
 
-            assert_eq!(
-                operator.dim(),
-                op_info_dim,
-                "operator information {:?} does not match the operator's dimensions: {:?}",
-                op_info_dim,
-                operator.dim(),
-            );
+        assert_eq!(split.channels().len(), 5);
+        assert_eq!(split.channels()[0], channel![1, 1, 1.0]);
+        assert_eq!(split.channels()[1], channel![2, 2, 1.0]);
+        assert_eq!(split.channels()[2], channel![4, 4, 2.0]);
+        assert_eq!(split.channels()[3], grid.channels()[1]);
+        assert_eq!(split.channels()[4], grid.channels()[2]);
 
-            let view = operator.view();
+        // the first three channels share the subgrids of the original first channel
+        for channel in 0..3 {
+            assert!(!split.subgrids()[[0, 0, channel]].is_empty());
+            assert!(split.subgrids()[[0, 1, channel]].is_empty());
+        }
+        assert!(!split.subgrids()[[0, 1, 3]].is_empty());
+        assert!(!split.subgrids()[[0, 0, 4]].is_empty());
+
+        // a limit that no channel exceeds doesn't change anything
+        let mut unchanged = grid.clone();
+        unchanged.split_channels_limited(3);
+        assert_eq!(unchanged.channels(), grid.channels());
+
+        // a limit of one is the same as splitting all channels
+        let mut all = grid.clone();
+        all.split_channels_limited(1);
+        grid.split_channels();
+        assert_eq!(all.channels(), grid.channels());
+    }
 
-            let (subgrids, channels) = if self.convolutions()[0] != Convolution::None
-                && self.convolutions()[1] != Convolution::None
-            {
-                evolution::evolve_slice_with_two(self, &view, &info, order_mask, xi, alphas_table)
-            } else {
-                evolution::evolve_slice_with_one(self, &view, &info, order_mask, xi, alphas_table)
-            }?;
+    #[test]
+    fn grid_resample_nodes() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0], channel![21, 21, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
 
-            let mut rhs = Self {
-                subgrids,
-                channels,
-                bin_limits: self.bin_limits.clone(),
-                orders: vec![Order::new(0, 0, 0, 0)],
-                subgrid_params: SubgridParams::default(),
-                more_members: self.more_members.clone(),
+        for (i, &(x1, x2, q2)) in [
+            (0.1, 0.2, 100.0),
+            (0.01, 0.3, 1000.0),
+            (0.5, 0.001, 90.0),
+            (0.05, 0.05, 8000.0),
+        ]
+        .iter()
+        .enumerate()
+        {
+            let ntuple = Ntuple {
+                x1,
+                x2,
+                q2,
+                weight: 1.0,
             };
 
-            // TODO: use a new constructor to set this information
-            rhs.set_pid_basis(info.pid_basis);
+            grid.fill(0, 0.5, i % 2, &ntuple);
+            grid.fill(0, 1.5, (i + 1) % 2, &ntuple);
+        }
 
-            if let Some(lhs) = &mut lhs {
-                lhs.merge(rhs)?;
-            } else {
-                lhs = Some(rhs);
-            }
+        let fill_counts = grid.session_fill_counts();
+
+        let mut xfx =
+            |id, x: f64, _| x.sqrt() * (1.0 - x).powi(3) * if id == 21 { 2.0 } else { 1.0 };
+        let mut alphas = |q2: f64| 1.0 / q2.ln();
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+        let reference = grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
 
-            used_op_fac1.push(info.fac1);
+        // resampling onto the same nodes doesn't change anything
+        let mut same = grid.clone();
+        same.resample_nodes(&SubgridParams::default()).unwrap();
+
+        for (&lhs, &rhs) in same
+            .convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)])
+            .iter()
+            .zip(&reference)
+        {
+            assert_approx_eq!(f64, lhs, rhs, epsilon = 1e-12 * rhs.abs());
         }
 
-        // UNWRAP: if we can't compare two numbers there's a bug
-        op_fac1.sort_by(|a, b| a.partial_cmp(b).unwrap_or_else(|| unreachable!()));
+        let mut subgrid_params = SubgridParams::default();
+        subgrid_params.set_x_bins(40);
+        subgrid_params.set_q2_bins(30);
 
-        // make sure we've evolved all slices
-        if let Some(muf2) = grid_fac1.into_iter().find(|&grid_mu2| {
-            !used_op_fac1
-                .iter()
-                .any(|&eko_mu2| approx_eq!(f64, grid_mu2, eko_mu2, ulps = EVOLVE_INFO_TOL_ULPS))
-        }) {
-            return Err(GridError::EvolutionFailure(format!(
-                "no operator for muf2 = {muf2} found in {op_fac1:?}"
-            )));
+        grid.resample_nodes(&subgrid_params).unwrap();
+
+        for (&lhs, &rhs) in grid
+            .convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)])
+            .iter()
+            .zip(&reference)
+        {
+            assert_approx_eq!(f64, lhs, rhs, epsilon = 1e-4 * rhs.abs());
         }
 
-        // TODO: convert this unwrap into error
-        let grid = lhs.unwrap();
+        assert_eq!(grid.session_fill_counts(), fill_counts);
+        assert_eq!(grid.subgrid_params.x_bins(), 40);
 
-        // UNWRAP: merging evolved slices should be a proper FkTable again
-        Ok(FkTable::try_from(grid).unwrap_or_else(|_| unreachable!()))
+        // nodes outside of the interpolation range can't be resampled
+        subgrid_params.set_x_min(0.01);
+
+        assert!(matches!(
+            grid.resample_nodes(&subgrid_params),
+            Err(GridError::NodeOutsideInterpolationRange { .. })
+        ));
     }
 
-    /// Converts this `Grid` into an [`FkTable`] using `slices` that must iterate over a [`Result`]
-    /// of tuples of an [`OperatorSliceInfo`] and the corresponding sliced operator. The parameter
-    /// `order_mask` can be used to include or exclude orders from this operation, and must
-    /// correspond to the ordering given by [`Grid::orders`]. Orders that are not given are
-    /// enabled, and in particular if `order_mask` is empty all orders are activated.
-    ///
-    /// # Errors
-    ///
-    /// Returns a [`GridError::EvolutionFailure`] if either the `operator` or its `info` is
-    /// incompatible with this `Grid`. Returns a [`GridError::Other`] if the iterator from `slices`
-    /// return an error.
-    pub fn evolve_with_slice_iter2<'a, E: Into<anyhow::Error>>(
-        &self,
-        slices_a: impl IntoIterator<Item = Result<(OperatorSliceInfo, CowArray<'a, f64, Ix4>), E>>,
-        slices_b: impl IntoIterator<Item = Result<(OperatorSliceInfo, CowArray<'a, f64, Ix4>), E>>,
-        order_mask: &[bool],
-        xi: (f64, f64),
-        alphas_table: &AlphasTable,
-    ) -> Result<FkTable, GridError> {
-        use super::evolution::EVOLVE_INFO_TOL_ULPS;
-        use itertools::izip;
+    #[test]
+    fn grid_session_fill_counts() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0], channel![21, 21, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
 
-        let mut lhs: Option<Self> = None;
-        // Q2 slices we use
-        let mut used_op_fac1 = Vec::new();
-        // Q2 slices we encounter, but possibly don't use
-        let mut op_fac1 = Vec::new();
-        // Q2 slices needed by the grid
-        let grid_fac1: Vec<_> = self
-            .evolve_info(order_mask)
-            .fac1
-            .into_iter()
-            .map(|fac| xi.1 * xi.1 * fac)
-            .collect();
+        let ntuple = Ntuple {
+            x1: 0.5,
+            x2: 0.5,
+            q2: 1000.0,
+            weight: 1.0,
+        };
 
-        // TODO: simplify the ugly repetition below by offloading some ops into fn
-        for (result_a, result_b) in izip!(slices_a, slices_b) {
-            // Operate on `slices_a`
-            let (info_a, operator_a) = result_a.map_err(|err| GridError::Other(err.into()))?;
-            // Operate on `slices_b`
-            let (info_b, operator_b) = result_b.map_err(|err| GridError::Other(err.into()))?;
+        grid.fill(0, 0.5, 0, &ntuple);
+        grid.fill(0, 0.5, 0, &ntuple);
+        grid.fill(0, 1.5, 1, &ntuple);
+        // events with zero weight are not counted
+        grid.fill(
+            0,
+            1.5,
+            0,
+            &Ntuple {
+                weight: 0.0,
+                ..ntuple
+            },
+        );
+        // events outside the bins are not counted either
+        grid.fill(0, 2.5, 0, &ntuple);
 
-            // TODO: what if the scales of the EKOs don't agree? Is there an ordering problem?
-            assert_approx_eq!(f64, info_a.fac1, info_b.fac1, ulps = EVOLVE_INFO_TOL_ULPS);
+        assert_eq!(
+            grid.session_fill_counts(),
+            Array3::from_shape_vec((1, 2, 2), vec![2, 0, 0, 1]).unwrap()
+        );
 
-            // also the PID bases must be the same
-            assert_eq!(info_a.pid_basis, info_b.pid_basis);
+        let mut other = grid.clone();
+        grid.merge(other.clone()).unwrap();
+        other.fill(0, 0.5, 1, &ntuple);
+        grid.merge(other).unwrap();
 
-            op_fac1.push(info_a.fac1);
+        assert_eq!(
+            grid.session_fill_counts(),
+            Array3::from_shape_vec((1, 2, 2), vec![6, 1, 0, 3]).unwrap()
+        );
+        assert_eq!(grid.session_total_events(), 10);
 
-            // it's possible that due to small numerical differences we get two slices which are
-            // almost the same. We have to skip those in order not to evolve the 'same' slice twice
-            if used_op_fac1
-                .iter()
-                .any(|&fac| approx_eq!(f64, fac, info_a.fac1, ulps = EVOLVE_INFO_TOL_ULPS))
-            {
-                continue;
-            }
+        // the counts are not written to disk
+        let mut buffer = Vec::new();
+        grid.write(&mut buffer).unwrap();
 
-            // skip slices that the grid doesn't use
-            if !grid_fac1
-                .iter()
-                .any(|&fac| approx_eq!(f64, fac, info_a.fac1, ulps = EVOLVE_INFO_TOL_ULPS))
-            {
-                continue;
-            }
+        assert_eq!(
+            Grid::read(buffer.as_slice())
+                .unwrap()
+                .session_total_events(),
+            0
+        );
+    }
 
-            let op_info_dim_a = (
-                info_a.pids1.len(),
-                info_a.x1.len(),
-                info_a.pids0.len(),
-                info_a.x0.len(),
-            );
+    #[test]
+    fn grid_subgrid_kinds() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
 
-            assert_eq!(
-                operator_a.dim(),
-                op_info_dim_a,
-                "operator information {:?} does not match the operator's dimensions: {:?}",
-                op_info_dim_a,
-                operator_a.dim(),
-            );
+        grid.fill(
+            0,
+            0.5,
+            0,
+            &Ntuple {
+                x1: 0.5,
+                x2: 0.5,
+                q2: 1000.0,
+                weight: 1.0,
+            },
+        );
 
-            let op_info_dim_b = (
-                info_b.pids1.len(),
-                info_b.x1.len(),
-                info_b.pids0.len(),
-                info_b.x0.len(),
-            );
+        let kinds = grid.subgrid_kinds();
 
-            assert_eq!(
-                operator_b.dim(),
-                op_info_dim_b,
-                "operator information {:?} does not match the operator's dimensions: {:?}",
-                op_info_dim_b,
-                operator_b.dim(),
-            );
+        assert_eq!(kinds.dim(), (1, 2, 1));
+        assert_eq!(kinds[[0, 0, 0]], SubgridKind::LagrangeSubgridV2);
+        assert_eq!(kinds[[0, 1, 0]], SubgridKind::EmptySubgridV1);
+        assert!(kinds[[0, 0, 0]].is_interpolating());
+        assert!(!kinds[[0, 1, 0]].is_import_only());
+        assert_eq!(kinds[[0, 0, 0]].to_string(), "LagrangeSubgridV2");
 
-            let views = [operator_a.view(), operator_b.view()];
-            let infos = [info_a, info_b];
+        grid.optimize_using(GridOptFlags::OPTIMIZE_SUBGRID_TYPE);
 
-            assert!(
-                (self.convolutions()[0] != Convolution::None)
-                    && (self.convolutions()[1] != Convolution::None),
-                "only one convolution found, use `Grid::evolve_with_slice_iter` instead"
-            );
+        assert!(grid.subgrid_kinds()[[0, 0, 0]].is_import_only());
+    }
 
-            let (subgrids, channels) = evolution::evolve_slice_with_two2(
-                self,
-                &views,
-                &infos,
-                order_mask,
-                xi,
-                alphas_table,
-            )?;
+    #[test]
+    fn grid_subgrid_stats() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
 
-            let mut rhs = Self {
-                subgrids,
-                channels,
-                bin_limits: self.bin_limits.clone(),
-                orders: vec![Order::new(0, 0, 0, 0)],
-                subgrid_params: SubgridParams::default(),
-                more_members: self.more_members.clone(),
-            };
+        grid.fill(
+            0,
+            0.5,
+            0,
+            &Ntuple {
+                x1: 0.5,
+                x2: 0.5,
+                q2: 1000.0,
+                weight: 1.0,
+            },
+        );
+
+        let stats = grid.subgrid_stats();
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(
+            stats[1],
+            SubgridStats {
+                order: 0,
+                bin: 1,
+                channel: 0,
+                kind: SubgridKind::EmptySubgridV1,
+                non_zeros: 0,
+                nodes: 0,
+                bytes: 0,
+            }
+        );
+
+        let interpolated = &stats[0];
+
+        assert_eq!(interpolated.kind, SubgridKind::LagrangeSubgridV2);
+        assert_eq!(interpolated.nodes, 40 * 50 * 50);
+        assert!(interpolated.non_zeros > 0);
+        assert!(interpolated.bytes >= interpolated.non_zeros * mem::size_of::<f64>());
+
+        grid.optimize_using(GridOptFlags::OPTIMIZE_SUBGRID_TYPE);
+
+        let stats = grid.subgrid_stats();
+        let imported = &stats[0];
+
+        assert_eq!(imported.kind, SubgridKind::ImportOnlySubgridV2);
+        assert_eq!(imported.non_zeros, interpolated.non_zeros);
+        assert!(imported.nodes < interpolated.nodes);
+        assert!(imported.bytes < interpolated.bytes);
+    }
+
+    #[test]
+    fn grid_read_header() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0], channel![21, 21, 1.0]],
+            vec![Order::new(0, 2, 0, 0), Order::new(1, 2, 0, 0)],
+            vec![0.0, 1.0, 2.0],
+            SubgridParams::default(),
+        );
+
+        grid.fill(
+            1,
+            0.5,
+            1,
+            &Ntuple {
+                x1: 0.5,
+                x2: 0.5,
+                q2: 1000.0,
+                weight: 1.0,
+            },
+        );
+        grid.set_key_value("description", "test grid");
 
-            // TODO: use a new constructor to set this information
-            rhs.set_pid_basis(infos[0].pid_basis);
+        let mut uncompressed = Vec::new();
+        grid.write(&mut uncompressed).unwrap();
+        let mut compressed = Vec::new();
+        grid.write_lz4(&mut compressed).unwrap();
+        // the file version 0 stores the entire grid after the file header
+        let mut version0 = b"PineAPPL\0\0\0\0\0\0\0\0".to_vec();
+        bincode::serialize_into(&mut version0, &grid).unwrap();
 
-            if let Some(lhs) = &mut lhs {
-                lhs.merge(rhs)?;
-            } else {
-                lhs = Some(rhs);
-            }
+        assert_eq!(uncompressed[8..16], 1_u64.to_le_bytes());
 
-            used_op_fac1.push(infos[0].fac1);
-        }
+        for buffer in [uncompressed.clone(), compressed, version0] {
+            let read = Grid::read(buffer.as_slice()).unwrap();
 
-        // UNWRAP: if we can't compare two numbers there's a bug
-        op_fac1.sort_by(|a, b| a.partial_cmp(b).unwrap_or_else(|| unreachable!()));
+            assert_eq!(read.subgrid_kinds(), grid.subgrid_kinds());
+            assert_eq!(read.orders(), grid.orders());
+            assert_eq!(read.key_values(), grid.key_values());
 
-        // make sure we've evolved all slices
-        if let Some(muf2) = grid_fac1.into_iter().find(|&grid_mu2| {
-            !used_op_fac1
-                .iter()
-                .any(|&eko_mu2| approx_eq!(f64, grid_mu2, eko_mu2, ulps = EVOLVE_INFO_TOL_ULPS))
-        }) {
-            return Err(GridError::EvolutionFailure(format!(
-                "no operator for muf2 = {muf2} found in {op_fac1:?}"
-            )));
+            let header = Grid::read_header(buffer.as_slice()).unwrap();
+
+            assert_eq!(header.orders(), grid.orders());
+            assert_eq!(header.channels(), grid.channels());
+            assert_eq!(header.bin_info(), grid.bin_info());
+            assert_eq!(header.key_values(), grid.key_values());
+
+            let header_grid = header.into_grid();
+
+            assert_eq!(header_grid.subgrids().dim(), (2, 2, 2));
+            assert!(header_grid.subgrids().iter().all(Subgrid::is_empty));
         }
 
-        // TODO: convert this unwrap into error
-        let grid = lhs.unwrap();
+        // the header is stored before the subgrids, which `Grid::read_header` doesn't read
+        let header_len = 16
+            + bincode::serialized_size(&GridHeaderV1 {
+                channels: grid.channels(),
+                bin_limits: &grid.bin_limits,
+                orders: grid.orders(),
+                subgrid_params: &grid.subgrid_params,
+                more_members: &grid.more_members,
+            })
+            .unwrap();
+        let truncated = &uncompressed[..usize::try_from(header_len).unwrap()];
 
-        // UNWRAP: merging evolved slices should be a proper FkTable again
-        Ok(FkTable::try_from(grid).unwrap_or_else(|_| unreachable!()))
-    }
+        assert!(Grid::read(truncated).is_err());
+        assert_eq!(
+            Grid::read_header(truncated).unwrap().orders(),
+            grid.orders()
+        );
 
-    /// Deletes bins with the corresponding `bin_indices`. Repeated indices and indices larger or
-    /// equal the bin length are ignored.
-    pub fn delete_bins(&mut self, bin_indices: &[usize]) {
-        let mut bin_indices: Vec<_> = bin_indices
-            .iter()
-            .copied()
-            // ignore indices corresponding to bin that don't exist
-            .filter(|&index| index < self.bin_info().bins())
-            .collect();
+        // newer file versions are rejected
+        let mut version2 = uncompressed;
+        version2[8..16].copy_from_slice(&2_u64.to_le_bytes());
 
-        // sort and remove repeated indices
-        bin_indices.sort_unstable();
-        bin_indices.dedup();
-        let bin_indices = bin_indices;
+        assert!(matches!(
+            Grid::read(version2.as_slice()),
+            Err(GridError::FileVersionMismatch {
+                file_version: 2,
+                supported_version: 1
+            })
+        ));
+    }
 
-        let mut bin_ranges: Vec<Range<_>> = Vec::new();
+    #[test]
+    fn grid_extra_couplings() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![
+                Order::new(0, 2, 0, 0),
+                Order::new(0, 2, 0, 0),
+                Order::new(0, 2, 0, 0),
+            ],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
 
-        // convert indices into consecutive ranges
-        for &bin_index in &bin_indices {
-            match bin_ranges.last_mut() {
-                Some(range) if range.end == bin_index => range.end += 1,
-                _ => bin_ranges.push(bin_index..(bin_index + 1)),
-            }
+        assert_eq!(grid.extra_couplings().unwrap(), vec![BTreeMap::new(); 3]);
+        assert!(grid.key_values().unwrap().get("extra_couplings").is_none());
+
+        for order in 0..3 {
+            grid.set_subgrid(
+                order,
+                0,
+                0,
+                ImportOnlySubgridV2::single_node(1.0, 0.5).into(),
+            );
         }
 
-        let bin_ranges = bin_ranges;
-        let mut ranges = bin_ranges.as_slice();
-        let old_limits = self.bin_limits.limits();
+        grid.set_extra_couplings(1, [("cW".to_owned(), 1)].into())
+            .unwrap();
+        grid.set_extra_couplings(2, [("cW".to_owned(), 2), ("cHq".to_owned(), 1)].into())
+            .unwrap();
 
-        // remove the bins from the right first, so as not to invalidate any indices
-        if let Some((range, remainder)) = ranges.split_last() {
-            if range.end == self.bin_info().bins() {
-                self.bin_limits.delete_bins_right(range.end - range.start);
-                ranges = remainder;
-            }
-        }
+        assert!(matches!(
+            grid.set_extra_couplings(3, BTreeMap::new()),
+            Err(GridError::InvalidOrderIndex {
+                order: 3,
+                orders: 3
+            })
+        ));
 
-        // indices on the left aren't affected by removal of bins to their right
-        if let Some((range, remainder)) = ranges.split_first() {
-            if range.start == 0 {
-                self.bin_limits.delete_bins_left(range.end);
-                ranges = remainder;
-            }
-        }
+        assert_eq!(
+            grid.key_values().unwrap()["extra_couplings"],
+            ";cW=1;cHq=1,cW=2"
+        );
 
-        if !ranges.is_empty() {
-            // if there's no remapper we need to store the bin limits in a new remapper
-            if self.remapper_mut().is_none() {
-                self.set_remapper(
-                    BinRemapper::new(
-                        old_limits.windows(2).map(|win| win[1] - win[0]).collect(),
-                        old_limits.windows(2).map(|win| (win[0], win[1])).collect(),
-                    )
-                    .unwrap_or_else(|_| unreachable!()),
-                )
-                .unwrap_or_else(|_| unreachable!());
-            }
+        let mut xfx = |_, x, _| x;
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
 
-            // the following should not be needed, but let's set these limits to integer values
-            self.bin_limits = BinLimits::new(
-                iter::successors(Some(0.0), |x| Some(x + 1.0))
-                    .take(old_limits.len() - bin_indices.len())
-                    .collect(),
-            );
-        }
+        grid.scale_by_coupling("cW", 2.0).unwrap();
+        grid.scale_by_coupling("cHq", 3.0).unwrap();
 
-        if let Some(remapper) = self.remapper_mut() {
-            remapper.delete_bins(&bin_ranges);
-        }
+        assert_eq!(
+            grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]),
+            [1.0 + 2.0 + 12.0]
+        );
 
-        for &bin_index in bin_indices.iter().rev() {
-            self.subgrids.remove_index(Axis(1), bin_index);
-        }
-    }
+        // orders with the same powers of the SM couplings are kept apart when merging
+        let other = grid.clone();
+        grid.merge(other).unwrap();
 
-    /// Change the particle ID convention.
-    pub fn rotate_pid_basis(&mut self, pid_basis: PidBasis) {
-        match (self.pid_basis(), pid_basis) {
-            (PidBasis::Pdg, PidBasis::Evol) => {
-                self.channels = self
-                    .channels()
-                    .iter()
-                    .map(|channel| Channel::translate(channel, &pids::pdg_mc_pids_to_evol))
-                    .collect();
+        assert_eq!(grid.orders().len(), 3);
+        assert_eq!(
+            grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]),
+            [2.0 * (1.0 + 2.0 + 12.0)]
+        );
 
-                self.set_pid_basis(PidBasis::Evol);
-            }
-            (PidBasis::Evol, PidBasis::Pdg) => {
-                self.channels = self
-                    .channels()
-                    .iter()
-                    .map(|channel| Channel::translate(channel, &pids::evol_to_pdg_mc_ids))
-                    .collect();
+        // changing an order keeps its additional couplings
+        grid.orders_mut()[1] = Order::new(1, 2, 0, 0);
 
-                self.set_pid_basis(PidBasis::Pdg);
-            }
-            (PidBasis::Evol, PidBasis::Evol) | (PidBasis::Pdg, PidBasis::Pdg) => {
-                // here's nothing to do
-            }
-        }
-    }
+        assert_eq!(
+            grid.extra_couplings().unwrap()[1],
+            [("cW".to_owned(), 1)].into()
+        );
 
-    /// Deletes channels with the corresponding `channel_indices`. Repeated indices and indices
-    /// larger or equal than the number of channels are ignored.
-    pub fn delete_channels(&mut self, channel_indices: &[usize]) {
-        let mut channel_indices: Vec<_> = channel_indices
-            .iter()
-            .copied()
-            // ignore indices corresponding to bin that don't exist
-            .filter(|&index| index < self.channels().len())
-            .collect();
+        grid.delete_orders(&[1]);
 
-        // sort and remove repeated indices
-        channel_indices.sort_unstable();
-        channel_indices.dedup();
-        channel_indices.reverse();
-        let channel_indices = channel_indices;
+        assert_eq!(
+            grid.extra_couplings().unwrap(),
+            [
+                BTreeMap::new(),
+                [("cW".to_owned(), 2), ("cHq".to_owned(), 1)].into()
+            ]
+        );
 
-        for index in channel_indices {
-            self.channels.remove(index);
-            self.subgrids.remove_index(Axis(2), index);
-        }
-    }
+        grid.delete_orders(&[1]);
 
-    /// Delete orders with the corresponding `order_indices`. Repeated indices and indices larger
-    /// or equal than the number of orders are ignored.
-    pub fn delete_orders(&mut self, order_indices: &[usize]) {
-        let mut order_indices: Vec<_> = order_indices
-            .iter()
-            .copied()
-            // ignore indices corresponding to orders that don't exist
-            .filter(|&index| index < self.orders().len())
-            .collect();
+        assert!(grid.key_values().unwrap().get("extra_couplings").is_none());
 
-        // sort and remove repeated indices
-        order_indices.sort_unstable();
-        order_indices.dedup();
-        order_indices.reverse();
-        let order_indices = order_indices;
+        // malformed metadata is reported instead of panicking ...
+        grid.set_key_value("extra_couplings", "cW");
 
-        for index in order_indices {
-            self.orders.remove(index);
-            self.subgrids.remove_index(Axis(0), index);
-        }
-    }
+        assert!(matches!(
+            grid.extra_couplings(),
+            Err(GridError::InvalidExtraCouplings(reason)) if reason == "malformed coupling 'cW'"
+        ));
+        assert!(grid.scale_by_coupling("cW", 2.0).is_err());
 
-    pub(crate) fn rewrite_channels(&mut self, add: &[(i32, i32)], del: &[i32]) {
-        self.channels = self
-            .channels()
-            .iter()
-            .map(|entry| {
-                Channel::new(
-                    entry
-                        .entry()
-                        .iter()
-                        .map(|(a, b, f)| {
-                            (
-                                // if `a` is to be added to another pid replace it with this pid
-                                add.iter().fold(
-                                    *a,
-                                    |id, &(source, target)| if id == source { target } else { id },
-                                ),
-                                // if `b` is to be added to another pid replace it with this pid
-                                add.iter().fold(
-                                    *b,
-                                    |id, &(source, target)| if id == source { target } else { id },
-                                ),
-                                // if any of the pids `a` or `b` are to b deleted set the factor to
-                                // zero
-                                if del.iter().any(|id| id == a || id == b) {
-                                    0.0
-                                } else {
-                                    *f
-                                },
-                            )
-                        })
-                        .collect(),
-                )
-            })
-            .collect();
+        // ... as are entries that don't match the orders
+        grid.set_key_value("extra_couplings", "cW=1;cW=2");
+
+        assert!(matches!(
+            grid.extra_couplings(),
+            Err(GridError::InvalidExtraCouplings(reason)) if reason == "found 2 entries for 1 orders"
+        ));
+        assert!(grid.clone().merge(grid.clone()).is_err());
     }
 
-    /// Splits the grid such that each channel contains only a single tuple of PIDs.
-    pub fn split_channels(&mut self) {
-        let indices: Vec<_> = self
-            .channels()
-            .iter()
-            .enumerate()
-            .flat_map(|(index, entry)| iter::repeat(index).take(entry.entry().len()))
-            .collect();
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn grid_merge_bins_nd() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 0, 0, 0)],
+            vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            SubgridParams::default(),
+        );
 
-        self.subgrids = self.subgrids.select(Axis(2), &indices);
-        self.channels = self
-            .channels()
-            .iter()
-            .flat_map(|entry| {
-                entry
-                    .entry()
-                    .iter()
-                    .copied()
-                    .map(move |entry| Channel::new(vec![entry]))
-            })
-            .collect();
-    }
-}
+        grid.set_remapper(
+            BinRemapper::new(
+                vec![1.0; 4],
+                vec![
+                    (0.0, 1.0),
+                    (0.0, 1.0),
+                    (0.0, 1.0),
+                    (1.0, 2.0),
+                    (1.0, 2.0),
+                    (0.0, 1.0),
+                    (1.0, 2.0),
+                    (1.0, 2.0),
+                ],
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        for (bin, value) in [1.0, 2.0, 3.0, 4.0].into_iter().enumerate() {
+            grid.set_subgrid(
+                0,
+                bin,
+                0,
+                ImportOnlySubgridV2::single_node(value, 0.5).into(),
+            );
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::channel;
-    use std::fs::File;
+        let mut xfx = |_, x, _| x;
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        let mut other = grid.clone();
+
+        // merge the bins of the first dimension
+        grid.merge_bins_nd(0, &[0..2]).unwrap();
+
+        assert_eq!(
+            grid.bin_info().limits(),
+            [[(0.0, 2.0), (0.0, 1.0)], [(0.0, 2.0), (1.0, 2.0)]]
+        );
+        assert_eq!(
+            grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]),
+            [2.0, 3.0]
+        );
+
+        // merge the bins of the second dimension
+        other.merge_bins_nd(1, &[0..2]).unwrap();
+
+        assert_eq!(
+            other.bin_info().limits(),
+            [[(0.0, 1.0), (0.0, 2.0)], [(1.0, 2.0), (0.0, 2.0)]]
+        );
+        assert_eq!(
+            other.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]),
+            [1.5, 3.5]
+        );
+    }
 
     #[test]
-    fn grid_with_subgrid_type() {
-        let subgrid_type = String::from("Idontexist");
-        let result = Grid::with_subgrid_type(
-            vec![],
-            vec![],
-            vec![],
+    #[allow(clippy::single_range_in_vec_init)]
+    fn grid_merge_bins_nd_without_remapper() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 0, 0, 0)],
+            vec![0.0, 0.5, 1.0, 2.0, 4.0],
             SubgridParams::default(),
-            ExtraSubgridParams::default(),
-            &subgrid_type,
         );
 
-        matches!(result, Err(GridError::UnknownSubgridType(x)) if x == subgrid_type);
+        grid.merge_bins_nd(0, &[0..2, 2..4]).unwrap();
+
+        assert_eq!(grid.bin_info().bins(), 2);
+        assert_eq!(grid.bin_info().limits(), [[(0.0, 1.0)], [(1.0, 4.0)]]);
+        assert!(grid.remapper().is_none());
+        assert!(matches!(
+            grid.merge_bins_nd(1, &[0..2]),
+            Err(GridError::MergeBinError(MergeBinError::InvalidDimension {
+                dimension: 1,
+                dimensions: 1
+            }))
+        ));
     }
 
     #[test]
-    fn grid_merge_empty_subgrids() {
+    fn grid_scale_order_by_bin() {
         let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
             vec![
-                channel![2, 2, 1.0; 4, 4, 1.0],
-                channel![1, 1, 1.0; 3, 3, 1.0],
+                Order::new(0, 2, 0, 0),
+                Order::new(1, 2, 0, 0),
+                Order::new(1, 2, 1, 0),
             ],
-            vec![Order::new(0, 2, 0, 0)],
-            vec![0.0, 0.25, 0.5, 0.75, 1.0],
+            vec![0.0, 1.0, 2.0],
             SubgridParams::default(),
         );
 
-        assert_eq!(grid.bin_info().bins(), 4);
-        assert_eq!(grid.channels().len(), 2);
-        assert_eq!(grid.orders().len(), 1);
+        for order in 0..3 {
+            for observable in [0.5, 1.5] {
+                grid.fill(
+                    order,
+                    observable,
+                    0,
+                    &Ntuple {
+                        x1: 0.5,
+                        x2: 0.5,
+                        q2: 1000.0,
+                        weight: 1.0,
+                    },
+                );
+            }
+        }
 
-        let other = Grid::new(
-            vec![
-                // differently ordered than `grid`
-                channel![1, 1, 1.0; 3, 3, 1.0],
-                channel![2, 2, 1.0; 4, 4, 1.0],
-            ],
-            vec![Order::new(1, 2, 0, 0), Order::new(1, 2, 0, 1)],
-            vec![0.0, 0.25, 0.5, 0.75, 1.0],
-            SubgridParams::default(),
+        let mut xfx = |_, _, _| 1.0;
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        // the scale logarithm only contributes with a varied renormalization scale
+        let xi = [(1.0, 1.0), (2.0, 1.0)];
+        let lo = grid.convolve(&mut lumi_cache, &[true, false, false], &[], &[], &xi);
+        let nlo = grid.convolve(&mut lumi_cache, &[false, true, true], &[], &[], &xi);
+
+        assert!(matches!(
+            grid.scale_order_by_bin(3, &[1.0, 1.0]),
+            Err(GridError::InvalidOrderIndex {
+                order: 3,
+                orders: 3
+            })
+        ));
+        assert!(matches!(
+            grid.scale_order_by_bin(1, &[1.0]),
+            Err(GridError::BinFactorMismatch {
+                grid_bins: 2,
+                factors: 1
+            })
+        ));
+
+        grid.scale_order_by_bin(1, &[2.0, 3.0]).unwrap();
+
+        assert_eq!(
+            grid.convolve(&mut lumi_cache, &[true, false, false], &[], &[], &xi),
+            lo
         );
 
-        // merging with empty subgrids should not change the grid
-        grid.merge(other).unwrap();
+        // the scale-log order is rescaled together with its physical order
+        let scaled = grid.convolve(&mut lumi_cache, &[false, true, true], &[], &[], &xi);
 
-        assert_eq!(grid.bin_info().bins(), 4);
-        assert_eq!(grid.channels().len(), 2);
-        assert_eq!(grid.orders().len(), 1);
+        for (index, (scaled, nlo)) in scaled.into_iter().zip(nlo).enumerate() {
+            let factor = [2.0, 3.0][index / xi.len()];
+            assert_approx_eq!(f64, scaled, factor * nlo, ulps = 16);
+        }
     }
 
     #[test]
-    fn grid_merge_orders() {
+    fn grid_bin_widths() {
         let mut grid = Grid::new(
-            vec![
-                channel![2, 2, 1.0; 4, 4, 1.0],
-                channel![1, 1, 1.0; 3, 3, 1.0],
-            ],
+            vec![channel![2, 2, 1.0]],
             vec![Order::new(0, 2, 0, 0)],
-            vec![0.0, 0.25, 0.5, 0.75, 1.0],
+            vec![0.0, 0.5, 2.0],
             SubgridParams::default(),
         );
 
-        assert_eq!(grid.bin_info().bins(), 4);
-        assert_eq!(grid.channels().len(), 2);
-        assert_eq!(grid.orders().len(), 1);
+        assert_eq!(grid.bin_widths(), [0.5, 1.5]);
+        assert_eq!(grid.bin_widths(), grid.bin_info().normalizations());
 
-        let mut other = Grid::new(
-            vec![
-                channel![2, 2, 1.0; 4, 4, 1.0],
-                channel![1, 1, 1.0; 3, 3, 1.0],
-            ],
-            vec![
-                Order::new(1, 2, 0, 0),
-                Order::new(1, 2, 0, 1),
-                Order::new(0, 2, 0, 0),
-            ],
-            vec![0.0, 0.25, 0.5, 0.75, 1.0],
+        grid.set_remapper(
+            BinRemapper::new(
+                vec![1.0, 1.0],
+                vec![(0.0, 2.0), (1.0, 4.0), (2.0, 3.0), (1.0, 1.5)],
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(grid.bin_widths(), [6.0, 0.5]);
+        assert_eq!(grid.bin_info().normalizations(), [1.0, 1.0]);
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn grid_merge_bins_grouped() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
             SubgridParams::default(),
         );
 
-        other.fill_all(
-            0,
-            0.1,
-            &Ntuple {
-                x1: 0.1,
-                x2: 0.2,
-                q2: 90.0_f64.powi(2),
-                weight: (),
-            },
-            &[1.0, 2.0],
-        );
-        other.fill_all(
-            1,
-            0.1,
-            &Ntuple {
-                x1: 0.1,
-                x2: 0.2,
-                q2: 90.0_f64.powi(2),
-                weight: (),
-            },
-            &[1.0, 2.0],
-        );
+        for bin in 0..5 {
+            grid.fill(
+                0,
+                f64::from(bin) + 0.5,
+                0,
+                &Ntuple {
+                    x1: 0.5,
+                    x2: 0.5,
+                    q2: 1000.0,
+                    weight: f64::from(bin + 1),
+                },
+            );
+        }
 
-        // merge with four non-empty subgrids
-        grid.merge(other).unwrap();
+        let mut xfx = |_, _, _| 1.0;
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
 
-        assert_eq!(grid.bin_info().bins(), 4);
-        assert_eq!(grid.channels().len(), 2);
-        assert_eq!(grid.orders().len(), 3);
+        let mut expected = grid.clone();
+        expected.merge_bins(3..5).unwrap();
+        expected.merge_bins(0..2).unwrap();
+
+        for groups in [vec![1..1], vec![4..6], vec![0..2, 1..3], vec![3..5, 0..2]] {
+            assert!(grid.merge_bins_grouped(&groups).is_err());
+            assert_eq!(grid.bin_info().bins(), 5);
+        }
+
+        grid.merge_bins_grouped(&[0..2, 3..5]).unwrap();
+
+        assert_eq!(grid.bin_info().bins(), 3);
+        assert_eq!(
+            grid.bin_info().limits(),
+            [[(0.0, 2.0)], [(2.0, 3.0)], [(3.0, 5.0)]]
+        );
+        assert_eq!(
+            grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]),
+            expected.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)])
+        );
     }
 
     #[test]
-    fn grid_merge_channels_entries() {
-        let mut grid = Grid::new(
+    fn grid_order_mask_for() {
+        let grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
             vec![
-                channel![2, 2, 1.0; 4, 4, 1.0],
-                channel![1, 1, 1.0; 3, 3, 1.0],
+                Order::new(0, 2, 0, 0),
+                Order::new(1, 2, 0, 0),
+                Order::new(1, 2, 1, 0),
+                Order::new(0, 3, 0, 0),
+                Order::new(0, 3, 0, 1),
+                Order::new(2, 2, 0, 0),
+                Order::new(1, 3, 1, 0),
             ],
-            vec![Order::new(0, 2, 0, 0)],
-            vec![0.0, 0.25, 0.5, 0.75, 1.0],
+            vec![0.0, 1.0],
             SubgridParams::default(),
         );
 
-        assert_eq!(grid.bin_info().bins(), 4);
-        assert_eq!(grid.channels().len(), 2);
-        assert_eq!(grid.orders().len(), 1);
+        // the last order has logarithms, but there's no corresponding order without them
+        assert_eq!(
+            grid.order_mask_for(None, None, false),
+            [true, true, true, true, true, true, false]
+        );
+        assert_eq!(
+            grid.order_mask_for(Some(1), None, false),
+            [true, true, true, true, true, false, false]
+        );
+        assert_eq!(
+            grid.order_mask_for(Some(0), Some(2), false),
+            [true, false, false, false, false, false, false]
+        );
+        assert_eq!(
+            grid.order_mask_for(None, Some(2), false),
+            [true, true, true, false, false, true, false]
+        );
+        assert_eq!(
+            grid.order_mask_for(None, None, true),
+            [true, false, false, false, false, false, false]
+        );
+        assert_eq!(grid.order_mask_for(Some(0), Some(1), false), [false; 7]);
+    }
 
-        let mut other = Grid::new(
-            vec![channel![22, 22, 1.0], channel![2, 2, 1.0; 4, 4, 1.0]],
+    #[test]
+    fn grid_subgrid_nodes() {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0], channel![21, 21, 1.0]],
             vec![Order::new(0, 2, 0, 0)],
-            vec![0.0, 0.25, 0.5, 0.75, 1.0],
+            vec![0.0, 1.0],
             SubgridParams::default(),
         );
 
-        // fill the photon-photon entry
-        other.fill(
+        let mut array = SparseArray3::new(2, 3, 1);
+        array[[0, 0, 0]] = 1.0;
+
+        grid.set_subgrid(
             0,
-            0.1,
             0,
-            &Ntuple {
-                x1: 0.1,
-                x2: 0.2,
-                q2: 90.0_f64.powi(2),
-                weight: 3.0,
-            },
+            0,
+            ImportOnlySubgridV2::new(
+                array,
+                vec![
+                    Mu2 {
+                        ren: 10.0,
+                        fac: 20.0,
+                    },
+                    Mu2 {
+                        ren: 30.0,
+                        fac: 40.0,
+                    },
+                ],
+                vec![0.1, 0.2, 0.3],
+                vec![1.0],
+            )
+            .into(),
         );
 
-        grid.merge(other).unwrap();
+        assert_eq!(
+            grid.subgrid_nodes(0, 0, 0),
+            [
+                (Kinematics::Scale(0), vec![10.0, 30.0]),
+                (Kinematics::Scale(1), vec![20.0, 40.0]),
+                (Kinematics::X(0), vec![0.1, 0.2, 0.3]),
+                (Kinematics::X(1), vec![1.0]),
+            ]
+        );
+        assert_eq!(
+            grid.subgrid_nodes(0, 0, 1),
+            [
+                (Kinematics::Scale(0), vec![]),
+                (Kinematics::Scale(1), vec![]),
+                (Kinematics::X(0), vec![]),
+                (Kinematics::X(1), vec![]),
+            ]
+        );
+    }
 
-        assert_eq!(grid.bin_info().bins(), 4);
-        assert_eq!(grid.channels().len(), 3);
-        assert_eq!(grid.orders().len(), 1);
+    #[test]
+    fn grid_interp_for_kinematic() {
+        let mut extra = ExtraSubgridParams::default();
+        extra.set_reweight2(false);
+        extra.set_x2_bins(30);
+        extra.set_x2_min(1e-3);
+
+        let grid = Grid::with_subgrid_type(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+            extra,
+            "LagrangeSubgridV2",
+        )
+        .unwrap();
+
+        let scale = grid.interp_for_kinematic(Kinematics::Scale(0)).unwrap();
+
+        assert_eq!(scale.nodes(), 40);
+        assert_approx_eq!(f64, scale.min(), 1e2, ulps = 64);
+        assert_approx_eq!(f64, scale.max(), 1e8, ulps = 64);
+        assert_eq!(scale.order(), 3);
+        assert!(!scale.reweight());
+        assert_eq!(grid.interp_for_kinematic(Kinematics::Scale(1)), Some(scale));
+
+        let x1 = grid.interp_for_kinematic(Kinematics::X(0)).unwrap();
+
+        assert_eq!(x1.nodes(), 50);
+        assert_approx_eq!(f64, x1.min(), 2e-7, ulps = 64);
+        assert_approx_eq!(f64, x1.max(), 1.0, ulps = 64);
+        assert_eq!(x1.order(), 3);
+        assert!(x1.reweight());
+
+        // the second momentum fraction uses the extra parameters
+        let x2 = grid.interp_for_kinematic(Kinematics::X(1)).unwrap();
+
+        assert_eq!(x2.nodes(), 30);
+        assert_approx_eq!(f64, x2.min(), 1e-3, ulps = 64);
+        assert_eq!(x2.order(), 3);
+        assert!(!x2.reweight());
+
+        assert_eq!(grid.interp_for_kinematic(Kinematics::X(2)), None);
+
+        // without a `LagrangeSubgridV2` template both momentum fractions share the interpolation
+        let grid = Grid::with_subgrid_type(
+            vec![channel![2, 2, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+            ExtraSubgridParams::default(),
+            "LagrangeSubgridV1",
+        )
+        .unwrap();
+
+        assert_eq!(
+            grid.interp_for_kinematic(Kinematics::X(1)),
+            Some(Interp::new(50, 2e-7, 1.0, 3, true))
+        );
     }
 
     #[test]
-    fn grid_merge_bins() {
+    fn grid_rebin_to() {
         let mut grid = Grid::new(
-            vec![
-                channel![2, 2, 1.0; 4, 4, 1.0],
-                channel![1, 1, 1.0; 3, 3, 1.0],
-            ],
+            vec![channel![2, 2, 1.0]],
             vec![Order::new(0, 2, 0, 0)],
-            vec![0.0, 0.25, 0.5],
+            vec![0.0, 1.0, 2.0, 3.0],
             SubgridParams::default(),
         );
 
-        assert_eq!(grid.bin_info().bins(), 2);
-        assert_eq!(grid.channels().len(), 2);
-        assert_eq!(grid.orders().len(), 1);
+        for bin in 0..3 {
+            grid.fill(
+                0,
+                f64::from(bin) + 0.5,
+                0,
+                &Ntuple {
+                    x1: 0.5,
+                    x2: 0.5,
+                    q2: 1000.0,
+                    weight: f64::from(1 << bin),
+                },
+            );
+        }
 
-        let mut other = Grid::new(
-            vec![
-                // channels are differently sorted
-                channel![1, 1, 1.0; 3, 3, 1.0],
-                channel![2, 2, 1.0; 4, 4, 1.0],
-            ],
+        let mut xfx = |_, _, _| 1.0;
+        let mut alphas = |_| 1.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+
+        let old = grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+
+        assert_eq!(
+            grid.rebin_to(vec![-0.5, 1.0]).unwrap_err().to_string(),
+            "the new bin limits from -0.5 to 1 are not within the old bin limits from 0 to 3"
+        );
+        assert_eq!(
+            grid.rebin_to(vec![1.0]).unwrap_err().to_string(),
+            "at least two bin limits are required, but 1 were given"
+        );
+
+        grid.rebin_to(vec![0.5, 1.5, 3.0]).unwrap();
+
+        assert_eq!(grid.bin_info().limits(), [[(0.5, 1.5)], [(1.5, 3.0)]]);
+
+        // half of the first and second bin, and half of the second and the full third bin
+        let new = grid.convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+        assert_approx_eq!(f64, new[0], 0.5 * (old[0] + old[1]), ulps = 16);
+        assert_approx_eq!(f64, new[1], 0.5f64.mul_add(old[1], old[2]) / 1.5, ulps = 16);
+
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0]],
             vec![Order::new(0, 2, 0, 0)],
-            vec![0.5, 0.75, 1.0],
+            vec![0.0, 1.0],
             SubgridParams::default(),
         );
+        grid.set_remapper(BinRemapper::new(vec![1.0], vec![(0.0, 1.0), (0.0, 1.0)]).unwrap())
+            .unwrap();
 
-        other.fill_all(
-            0,
-            0.1,
-            &Ntuple {
-                x1: 0.1,
-                x2: 0.2,
-                q2: 90.0_f64.powi(2),
-                weight: (),
-            },
-            &[2.0, 3.0],
+        assert_eq!(
+            grid.rebin_to(vec![0.0, 1.0]).unwrap_err().to_string(),
+            "rebinning requires one-dimensional bins, but the bins have 2 dimensions"
         );
-
-        grid.merge(other).unwrap();
-
-        assert_eq!(grid.bin_info().bins(), 4);
-        assert_eq!(grid.channels().len(), 2);
-        assert_eq!(grid.orders().len(), 1);
     }
 
-    // TODO: convolve_subgrid, merge_bins, subgrid, set_subgrid
+    // TODO: merge_bins, subgrid, set_subgrid
 
     #[test]
     fn grid_convolutions() {
@@ -2102,6 +8308,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn grid_try_set_convolution() {
+        let mut grid = Grid::new(
+            vec![channel![2, 11, 1.0], channel![21, 11, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 1.0],
+            SubgridParams::default(),
+        );
+
+        grid.set_convolution(1, Convolution::None);
+
+        grid.try_set_convolution(0, Convolution::UnpolFF(211))
+            .unwrap();
+        assert_eq!(
+            grid.convolutions(),
+            [Convolution::UnpolFF(211), Convolution::None]
+        );
+
+        for (index, convolution, reason) in [
+            (
+                2,
+                Convolution::UnpolPDF(2212),
+                "grids only have two convolutions",
+            ),
+            (
+                0,
+                Convolution::None,
+                "'none' requires a single particle, but the channels use the PIDs 2, 21",
+            ),
+            (
+                1,
+                Convolution::UnpolPDF(2212),
+                "'unpolpdf:2212' requires partons, but the channels use the PID 11",
+            ),
+        ] {
+            let err = grid.try_set_convolution(index, convolution).unwrap_err();
+
+            assert_eq!(
+                err.to_string(),
+                format!("the convolution with index {index} can not be set: {reason}")
+            );
+        }
+
+        assert_eq!(
+            grid.convolutions(),
+            [Convolution::UnpolFF(211), Convolution::None]
+        );
+    }
+
     #[test]
     fn evolve_info() {
         let grid =
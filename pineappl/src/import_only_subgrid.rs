@@ -3,6 +3,7 @@
 use super::grid::Ntuple;
 use super::sparse_array3::SparseArray3;
 use super::subgrid::{Mu2, Stats, Subgrid, SubgridEnum, SubgridIndexedIter};
+use ndarray::ArrayView3;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::mem;
@@ -177,7 +178,13 @@ impl Subgrid for ImportOnlySubgridV1 {
     }
 }
 
-/// TODO
+/// Subgrid that stores precomputed values on fixed nodes.
+///
+/// This is the type to use when a grid is constructed from interpolation tables that were computed
+/// elsewhere, for example when converting grids of other formats. The values are stored in a
+/// three-dimensional array, whose axes correspond to the scales given by `mu2_grid`, and the
+/// momentum fractions given by `x1_grid` and `x2_grid`, in this order. Values must not be
+/// multiplied by any reweighting functions.
 #[derive(Clone, Deserialize, Serialize)]
 pub struct ImportOnlySubgridV2 {
     array: SparseArray3<f64>,
@@ -187,7 +194,8 @@ pub struct ImportOnlySubgridV2 {
 }
 
 impl ImportOnlySubgridV2 {
-    /// Constructor.
+    /// Constructor. The dimensions of `array` must be the lengths of `mu2_grid`, `x1_grid` and
+    /// `x2_grid`, in this order.
     #[must_use]
     pub fn new(
         array: SparseArray3<f64>,
@@ -203,10 +211,65 @@ impl ImportOnlySubgridV2 {
         }
     }
 
+    /// Constructs a subgrid from the dense `array`, whose shape must be given by the lengths of
+    /// `mu2_grid`, `x1_grid` and `x2_grid`, in this order. Only the non-zero values of `array` are
+    /// stored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shape of `array` does not match the lengths of the node values.
+    #[must_use]
+    pub fn from_array(
+        array: ArrayView3<f64>,
+        mu2_grid: Vec<Mu2>,
+        x1_grid: Vec<f64>,
+        x2_grid: Vec<f64>,
+    ) -> Self {
+        let shape = (mu2_grid.len(), x1_grid.len(), x2_grid.len());
+
+        assert_eq!(
+            array.dim(),
+            shape,
+            "the shape of the array does not match the lengths of the node values"
+        );
+
+        let mut sparse_array = SparseArray3::new(shape.0, shape.1, shape.2);
+
+        for ((imu2, ix1, ix2), &value) in array.indexed_iter().filter(|(_, &value)| value != 0.0) {
+            sparse_array[[imu2, ix1, ix2]] = value;
+        }
+
+        Self::new(sparse_array, mu2_grid, x1_grid, x2_grid)
+    }
+
     /// Return the array containing the numerical values of the grid.
     pub fn array_mut(&mut self) -> &mut SparseArray3<f64> {
         &mut self.array
     }
+
+    /// Return the renormalization and factorization scales of the nodes.
+    pub fn mu2_grid_mut(&mut self) -> &mut [Mu2] {
+        &mut self.mu2_grid
+    }
+}
+
+#[cfg(test)]
+impl ImportOnlySubgridV2 {
+    // creates a subgrid with the single node `x1 = x2 = x` and `mu2 = 100`, whose value is `value`
+    pub(crate) fn single_node(value: f64, x: f64) -> Self {
+        let mut array = SparseArray3::new(1, 1, 1);
+        array[[0, 0, 0]] = value;
+
+        Self::new(
+            array,
+            vec![Mu2 {
+                ren: 100.0,
+                fac: 100.0,
+            }],
+            vec![x],
+            vec![x],
+        )
+    }
 }
 
 impl Subgrid for ImportOnlySubgridV2 {
@@ -244,7 +307,10 @@ impl Subgrid for ImportOnlySubgridV2 {
     }
 
     fn merge(&mut self, other: &mut SubgridEnum, transpose: bool) {
-        if let SubgridEnum::ImportOnlySubgridV2(other_grid) = other {
+        if let SubgridEnum::ImportOnlySubgridF32(other_grid) = other {
+            let mut other: SubgridEnum = other_grid.to_f64().into();
+            self.merge(&mut other, transpose);
+        } else if let SubgridEnum::ImportOnlySubgridV2(other_grid) = other {
             if self.array.is_empty() && !transpose {
                 mem::swap(&mut self.array, &mut other_grid.array);
             } else {
@@ -433,6 +499,184 @@ impl From<&SubgridEnum> for ImportOnlySubgridV2 {
     }
 }
 
+/// Same as [`ImportOnlySubgridV2`], but storing the values with single precision.
+///
+/// This halves the memory needed for the values. They are converted to `f64` before they are used
+/// in a convolution, and operations that change them, like merging or scaling, round their results
+/// to `f32`.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct ImportOnlySubgridF32 {
+    array: SparseArray3<f32>,
+    mu2_grid: Vec<Mu2>,
+    x1_grid: Vec<f64>,
+    x2_grid: Vec<f64>,
+}
+
+impl ImportOnlySubgridF32 {
+    /// Constructor. The dimensions of `array` must be the lengths of `mu2_grid`, `x1_grid` and
+    /// `x2_grid`, in this order.
+    #[must_use]
+    pub const fn new(
+        array: SparseArray3<f32>,
+        mu2_grid: Vec<Mu2>,
+        x1_grid: Vec<f64>,
+        x2_grid: Vec<f64>,
+    ) -> Self {
+        Self {
+            array,
+            mu2_grid,
+            x1_grid,
+            x2_grid,
+        }
+    }
+
+    /// Return the array containing the numerical values of the grid.
+    pub fn array_mut(&mut self) -> &mut SparseArray3<f32> {
+        &mut self.array
+    }
+
+    /// Return a copy of this subgrid storing the values with double precision.
+    #[must_use]
+    pub fn to_f64(&self) -> ImportOnlySubgridV2 {
+        let (mu2_len, x1_len, x2_len) = self.array.dimensions();
+        let mut array = SparseArray3::new(mu2_len, x1_len, x2_len);
+
+        for ((imu2, ix1, ix2), value) in self.array.indexed_iter() {
+            array[[imu2, ix1, ix2]] = f64::from(value);
+        }
+
+        ImportOnlySubgridV2::new(
+            array,
+            self.mu2_grid.clone(),
+            self.x1_grid.clone(),
+            self.x2_grid.clone(),
+        )
+    }
+}
+
+impl Subgrid for ImportOnlySubgridF32 {
+    fn convolve(
+        &self,
+        _: &[f64],
+        _: &[f64],
+        _: &[Mu2],
+        lumi: &mut dyn FnMut(usize, usize, usize) -> f64,
+    ) -> f64 {
+        self.array
+            .indexed_iter()
+            .map(|((imu2, ix1, ix2), sigma)| f64::from(sigma) * lumi(ix1, ix2, imu2))
+            .sum()
+    }
+
+    fn fill(&mut self, _: &Ntuple<f64>) {
+        panic!("ImportOnlySubgridF32 doesn't support the fill operation");
+    }
+
+    fn mu2_grid(&self) -> Cow<[Mu2]> {
+        Cow::Borrowed(&self.mu2_grid)
+    }
+
+    fn x1_grid(&self) -> Cow<[f64]> {
+        Cow::Borrowed(&self.x1_grid)
+    }
+
+    fn x2_grid(&self) -> Cow<[f64]> {
+        Cow::Borrowed(&self.x2_grid)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    fn merge(&mut self, other: &mut SubgridEnum, transpose: bool) {
+        // merge in double precision and round the result afterwards
+        let mut subgrid = self.to_f64();
+        subgrid.merge(other, transpose);
+        *self = Self::from(&subgrid);
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn scale(&mut self, factor: f64) {
+        if factor == 0.0 {
+            self.array.clear();
+        } else {
+            self.array
+                .iter_mut()
+                .for_each(|x| *x = (f64::from(*x) * factor) as f32);
+        }
+    }
+
+    fn symmetrize(&mut self) {
+        let mut new_array =
+            SparseArray3::new(self.mu2_grid.len(), self.x1_grid.len(), self.x2_grid.len());
+
+        for ((i, j, k), sigma) in self.array.indexed_iter().filter(|((_, j, k), _)| k >= j) {
+            new_array[[i, j, k]] = sigma;
+        }
+        // do not change the diagonal entries (k==j)
+        for ((i, j, k), sigma) in self.array.indexed_iter().filter(|((_, j, k), _)| k < j) {
+            new_array[[i, k, j]] += sigma;
+        }
+
+        mem::swap(&mut self.array, &mut new_array);
+    }
+
+    fn clone_empty(&self) -> SubgridEnum {
+        Self {
+            array: SparseArray3::new(self.mu2_grid.len(), self.x1_grid.len(), self.x2_grid.len()),
+            mu2_grid: self.mu2_grid.clone(),
+            x1_grid: self.x1_grid.clone(),
+            x2_grid: self.x2_grid.clone(),
+        }
+        .into()
+    }
+
+    fn indexed_iter(&self) -> SubgridIndexedIter {
+        Box::new(
+            self.array
+                .indexed_iter()
+                .map(|(indices, value)| (indices, f64::from(value))),
+        )
+    }
+
+    fn stats(&self) -> Stats {
+        Stats {
+            total: self.mu2_grid.len() * self.x1_grid.len() * self.x2_grid.len(),
+            allocated: self.array.len() + self.array.zeros(),
+            zeros: self.array.zeros(),
+            overhead: self.array.overhead(),
+            bytes_per_value: mem::size_of::<f32>(),
+        }
+    }
+
+    fn static_scale(&self) -> Option<Mu2> {
+        if let [static_scale] = self.mu2_grid.as_slice() {
+            Some(static_scale.clone())
+        } else {
+            None
+        }
+    }
+}
+
+impl From<&ImportOnlySubgridV2> for ImportOnlySubgridF32 {
+    #[allow(clippy::cast_possible_truncation)]
+    fn from(subgrid: &ImportOnlySubgridV2) -> Self {
+        let (mu2_len, x1_len, x2_len) = subgrid.array.dimensions();
+        let mut array = SparseArray3::new(mu2_len, x1_len, x2_len);
+
+        for ((imu2, ix1, ix2), value) in subgrid.array.indexed_iter() {
+            array[[imu2, ix1, ix2]] = value as f32;
+        }
+
+        Self {
+            array,
+            mu2_grid: subgrid.mu2_grid.clone(),
+            x1_grid: subgrid.x1_grid.clone(),
+            x2_grid: subgrid.x2_grid.clone(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -551,6 +795,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_f32() {
+        let x = vec![0.125, 0.25, 0.5, 1.0];
+        let mu2 = vec![Mu2 { ren: 0.0, fac: 0.0 }];
+        let mut array = SparseArray3::new(1, 4, 4);
+
+        // only use exactly representable numbers here so that we can avoid using approx_eq
+        array[[0, 0, 1]] = 1.0;
+        array[[0, 2, 3]] = 2.0;
+        array[[0, 3, 2]] = 4.0;
+
+        let grid_f64 = ImportOnlySubgridV2::new(array, mu2.clone(), x.clone(), x.clone());
+        let mut grid1: SubgridEnum = ImportOnlySubgridF32::from(&grid_f64).into();
+
+        assert_eq!(
+            grid1.stats(),
+            Stats {
+                total: 16,
+                allocated: 3,
+                zeros: 0,
+                overhead: 10,
+                bytes_per_value: 4,
+            }
+        );
+        assert!(grid1.indexed_iter().eq(grid_f64.indexed_iter()));
+
+        let lumi =
+            &mut (|ix1, ix2, _| x[ix1] * x[ix2]) as &mut dyn FnMut(usize, usize, usize) -> f64;
+        let result = grid_f64.convolve(&x, &x, &mu2, lumi);
+
+        assert_eq!(grid1.convolve(&x, &x, &mu2, lumi), result);
+
+        // merge with a subgrid of the same type and one with double precision
+        let mut grid2 = grid1.clone();
+        grid1.merge(&mut grid2, false);
+        grid1.merge(&mut grid_f64.clone().into(), true);
+
+        assert!(matches!(grid1, SubgridEnum::ImportOnlySubgridF32(_)));
+        assert_eq!(grid1.convolve(&x, &x, &mu2, lumi), 3.0 * result);
+
+        grid1.scale(0.5);
+        grid1.symmetrize();
+
+        assert_eq!(grid1.convolve(&x, &x, &mu2, lumi), 1.5 * result);
+
+        let mut grid3: SubgridEnum = grid_f64.into();
+        grid3.merge(&mut grid1, false);
+
+        assert!(matches!(grid3, SubgridEnum::ImportOnlySubgridV2(_)));
+        assert_eq!(grid3.convolve(&x, &x, &mu2, lumi), 2.5 * result);
+    }
+
     #[test]
     fn test_v2() {
         let x = vec![
@@ -680,6 +976,38 @@ mod tests {
         });
     }
 
+    #[test]
+    fn from_array_v2() {
+        let mut array = ndarray::Array3::zeros((2, 3, 1));
+        array[[0, 1, 0]] = 1.0;
+        array[[1, 2, 0]] = 2.0;
+
+        let grid = ImportOnlySubgridV2::from_array(
+            array.view(),
+            vec![Mu2 { ren: 1.0, fac: 1.0 }, Mu2 { ren: 2.0, fac: 2.0 }],
+            vec![0.1, 0.2, 0.3],
+            vec![1.0],
+        );
+
+        assert_eq!(
+            grid.indexed_iter().collect::<Vec<_>>(),
+            [((0, 1, 0), 1.0), ((1, 2, 0), 2.0)]
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "the shape of the array does not match the lengths of the node values"
+    )]
+    fn from_array_v2_panic() {
+        let _ = ImportOnlySubgridV2::from_array(
+            ndarray::Array3::zeros((1, 2, 2)).view(),
+            vec![Mu2 { ren: 1.0, fac: 1.0 }],
+            vec![1.0],
+            vec![1.0],
+        );
+    }
+
     #[test]
     fn from_lagrange_subgrid_v2() {
         let mut lagrange =
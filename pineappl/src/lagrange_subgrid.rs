@@ -1,10 +1,11 @@
 //! Module containing the Lagrange-interpolation subgrid.
 
+use super::boc::Kinematics;
 use super::convert::{f64_from_usize, usize_from_f64};
 use super::grid::Ntuple;
 use super::sparse_array3::SparseArray3;
 use super::subgrid::{
-    ExtraSubgridParams, Mu2, Stats, Subgrid, SubgridEnum, SubgridIndexedIter, SubgridParams,
+    ExtraSubgridParams, Interp, Mu2, Stats, Subgrid, SubgridEnum, SubgridIndexedIter, SubgridParams,
 };
 use arrayvec::ArrayVec;
 use ndarray::Array3;
@@ -112,6 +113,25 @@ impl LagrangeSubgridV1 {
         f64_from_usize(iy).mul_add(self.deltatau(), self.taumin)
     }
 
+    // returns whether `other` interpolates with the same nodes and orders, which `Subgrid::merge`
+    // requires
+    pub(crate) fn same_interpolation(&self, other: &Self) -> bool {
+        (
+            self.ntau,
+            self.ny,
+            self.yorder,
+            self.tauorder,
+            self.reweight,
+        ) == (
+            other.ntau,
+            other.ny,
+            other.yorder,
+            other.tauorder,
+            other.reweight,
+        ) && (self.ymin, self.ymax, self.taumin, self.taumax)
+            == (other.ymin, other.ymax, other.taumin, other.taumax)
+    }
+
     fn increase_tau(&mut self, new_itaumin: usize, new_itaumax: usize) {
         let min_diff = self.itaumin - new_itaumin;
 
@@ -410,6 +430,10 @@ pub struct LagrangeSubgridV2 {
     taumin: f64,
     taumax: f64,
     pub(crate) static_q2: f64,
+    // the counter costs eight bytes per subgrid; it isn't serialized to keep the file format
+    // unchanged, which means that it is reset to zero when a grid is read
+    #[serde(skip)]
+    fill_count: u64,
 }
 
 impl LagrangeSubgridV2 {
@@ -435,6 +459,135 @@ impl LagrangeSubgridV2 {
             taumin: ftau(subgrid_params.q2_min()),
             taumax: ftau(subgrid_params.q2_max()),
             static_q2: 0.0,
+            fill_count: 0,
+        }
+    }
+
+    /// Return the number of events that were filled into this subgrid in the current session, not
+    /// counting events with zero weight or events outside the interpolation range. The counter is
+    /// kept in memory only: it is not written to disk, and is therefore zero for subgrids that
+    /// were read from a file.
+    #[must_use]
+    pub const fn session_fill_count(&self) -> u64 {
+        self.fill_count
+    }
+
+    pub(crate) fn set_fill_count(&mut self, fill_count: u64) {
+        self.fill_count = fill_count;
+    }
+
+    // returns whether `other` interpolates with the same nodes and orders, which `Subgrid::merge`
+    // requires; different reweightings are converted when merging
+    pub(crate) fn same_interpolation(&self, other: &Self) -> bool {
+        (
+            self.ntau,
+            self.ny1,
+            self.ny2,
+            self.y1order,
+            self.y2order,
+            self.tauorder,
+        ) == (
+            other.ntau,
+            other.ny1,
+            other.ny2,
+            other.y1order,
+            other.y2order,
+            other.tauorder,
+        ) && (self.y1min, self.y1max, self.y2min, self.y2max)
+            == (other.y1min, other.y1max, other.y2min, other.y2max)
+            && (self.taumin, self.taumax) == (other.taumin, other.taumax)
+    }
+
+    // returns the interpolation of `kinematics`, or `None` if this subgrid doesn't interpolate it
+    pub(crate) fn interp(&self, kinematics: Kinematics) -> Option<Interp> {
+        match kinematics {
+            Kinematics::Scale(0 | 1) => Some(Interp::new(
+                self.ntau,
+                fq2(self.taumin),
+                fq2(self.taumax),
+                self.tauorder,
+                false,
+            )),
+            Kinematics::X(0) => Some(Interp::new(
+                self.ny1,
+                fx(self.y1max),
+                fx(self.y1min),
+                self.y1order,
+                self.reweight1,
+            )),
+            Kinematics::X(1) => Some(Interp::new(
+                self.ny2,
+                fx(self.y2max),
+                fx(self.y2min),
+                self.y2order,
+                self.reweight2,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the momentum fractions of the convolution with index `convolution` are
+    /// reweighted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `convolution` is larger than `1`.
+    #[must_use]
+    pub fn reweight(&self, convolution: usize) -> bool {
+        assert!(
+            convolution < 2,
+            "convolution must be `0` or `1`, is `{convolution}`"
+        );
+
+        if convolution == 0 {
+            self.reweight1
+        } else {
+            self.reweight2
+        }
+    }
+
+    /// Enables or disables the reweighting of the momentum fractions of the convolution with index
+    /// `convolution`. The stored values are rescaled such that the results of convolutions do not
+    /// change.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `convolution` is larger than `1`.
+    pub fn set_reweight(&mut self, convolution: usize, reweight: bool) {
+        assert!(
+            convolution < 2,
+            "convolution must be `0` or `1`, is `{convolution}`"
+        );
+
+        let current = if convolution == 0 {
+            &mut self.reweight1
+        } else {
+            &mut self.reweight2
+        };
+
+        if *current == reweight {
+            return;
+        }
+
+        *current = reweight;
+
+        let x_grid = if convolution == 0 {
+            self.x1_grid()
+        } else {
+            self.x2_grid()
+        }
+        .into_owned();
+
+        if let Some(grid) = &mut self.grid {
+            for ((_, ix1, ix2), value) in grid.indexed_iter_mut() {
+                let weight = weightfun(x_grid[if convolution == 0 { ix1 } else { ix2 }]);
+
+                if reweight {
+                    *value /= weight;
+                } else {
+                    *value *= weight;
+                }
+            }
         }
     }
 
@@ -546,6 +699,8 @@ impl Subgrid for LagrangeSubgridV2 {
             return;
         }
 
+        self.fill_count += 1;
+
         let k1 =
             usize_from_f64((y1 - self.y1min) / self.deltay1() - f64_from_usize(self.y1order / 2))
                 .min(self.ny1 - 1 - self.y1order);
@@ -635,6 +790,17 @@ impl Subgrid for LagrangeSubgridV2 {
         let x2_equal = self.x2_grid() == other.x2_grid();
 
         if let SubgridEnum::LagrangeSubgridV2(other_grid) = other {
+            self.fill_count += other_grid.fill_count;
+
+            // the stored values can only be added if they use the same reweighting
+            let (reweight1, reweight2) = if transpose {
+                (self.reweight2, self.reweight1)
+            } else {
+                (self.reweight1, self.reweight2)
+            };
+            other_grid.set_reweight(0, reweight1);
+            other_grid.set_reweight(1, reweight2);
+
             if let Some(other_grid_grid) = &mut other_grid.grid {
                 if self.grid.is_some() {
                     // TODO: the general case isn't implemented
@@ -725,6 +891,7 @@ impl Subgrid for LagrangeSubgridV2 {
             taumin: self.taumin,
             taumax: self.taumax,
             static_q2: 0.0,
+            fill_count: 0,
         }
         .into()
     }
@@ -836,6 +1003,25 @@ impl LagrangeSparseSubgridV1 {
     fn gettau(&self, iy: usize) -> f64 {
         f64_from_usize(iy).mul_add(self.deltatau(), self.taumin)
     }
+
+    // returns whether `other` interpolates with the same nodes and orders, which `Subgrid::merge`
+    // requires
+    pub(crate) fn same_interpolation(&self, other: &Self) -> bool {
+        (
+            self.ntau,
+            self.ny,
+            self.yorder,
+            self.tauorder,
+            self.reweight,
+        ) == (
+            other.ntau,
+            other.ny,
+            other.yorder,
+            other.tauorder,
+            other.reweight,
+        ) && (self.ymin, self.ymax, self.taumin, self.taumax)
+            == (other.ymin, other.ymax, other.taumin, other.taumax)
+    }
 }
 
 impl Subgrid for LagrangeSparseSubgridV1 {
@@ -1059,6 +1245,7 @@ impl From<&LagrangeSubgridV1> for LagrangeSparseSubgridV1 {
 mod tests {
     use super::*;
     use float_cmp::assert_approx_eq;
+    use std::collections::HashMap;
 
     fn test_q2_slice_methods<G: Subgrid>(mut grid: G) -> G {
         grid.fill(&Ntuple {
@@ -1339,6 +1526,54 @@ mod tests {
         assert_eq!(subgrid.indexed_iter().count(), 0);
     }
 
+    #[test]
+    fn merge_v2_with_different_reweight() {
+        for transpose in [false, true] {
+            let mut subgrid =
+                LagrangeSubgridV2::new(&SubgridParams::default(), &ExtraSubgridParams::default());
+            let mut other = subgrid.clone();
+            other.set_reweight(0, false);
+
+            assert!(subgrid.reweight(0));
+            assert!(!other.reweight(0));
+            assert!(other.reweight(1));
+
+            subgrid.fill(&Ntuple {
+                x1: 0.1,
+                x2: 0.2,
+                q2: 1000.0,
+                weight: 1.0,
+            });
+            other.fill(&Ntuple {
+                x1: 0.3,
+                x2: 0.05,
+                q2: 1000.0,
+                weight: 2.0,
+            });
+
+            // `indexed_iter` undoes the reweighting, so the merged values must be the sums
+            let mut expected: HashMap<_, f64> = subgrid.indexed_iter().collect();
+            for ((imu2, ix1, ix2), value) in other.indexed_iter() {
+                let index = if transpose {
+                    (imu2, ix2, ix1)
+                } else {
+                    (imu2, ix1, ix2)
+                };
+                *expected.entry(index).or_default() += value;
+            }
+
+            subgrid.merge(&mut other.into(), transpose);
+
+            assert!(subgrid.reweight(0));
+            assert!(subgrid.reweight(1));
+            assert_eq!(subgrid.indexed_iter().count(), expected.len());
+
+            for (index, value) in subgrid.indexed_iter() {
+                assert_approx_eq!(f64, value, expected[&index], ulps = 8);
+            }
+        }
+    }
+
     #[test]
     fn from() {
         // check conversion of empty grids
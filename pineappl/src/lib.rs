@@ -47,5 +47,7 @@ pub mod lagrange_subgrid;
 pub mod ntuple_subgrid;
 pub mod packed_array;
 pub mod pids;
+#[cfg(feature = "cache")]
+pub mod result_cache;
 pub mod sparse_array3;
 pub mod subgrid;
@@ -1,5 +1,6 @@
 //! TODO
 
+use super::boc::Channel;
 use std::str::FromStr;
 use thiserror::Error;
 
@@ -47,6 +48,64 @@ impl PidBasis {
         }
     }
 
+    /// Return `true` if `pid`, given in the basis of `self`, denotes a parton, which is a quark, a
+    /// gluon, a photon or, in the evolution basis, one of its combinations of quarks and gluons.
+    #[must_use]
+    pub(crate) const fn is_parton(self, pid: i32) -> bool {
+        matches!(
+            (self, pid),
+            (Self::Pdg | Self::Evol, -6..=-1 | 1..=6 | 21 | 22)
+                | (
+                    Self::Evol,
+                    100 | 103 | 108 | 115 | 124 | 135 | 200 | 203 | 208 | 215 | 224 | 235
+                )
+        )
+    }
+
+    /// Translate the particle ID `pid`, given in the basis of `self`, into the basis `target`. The
+    /// result is a linear combination of particle IDs, each paired with its factor.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use pineappl::pids::PidBasis;
+    ///
+    /// assert_eq!(
+    ///     PidBasis::Evol.translate(PidBasis::Pdg, 103),
+    ///     [(2, 1.0), (-2, 1.0), (1, -1.0), (-1, -1.0)]
+    /// );
+    /// assert_eq!(PidBasis::Evol.translate(PidBasis::Evol, 103), [(103, 1.0)]);
+    /// ```
+    #[must_use]
+    pub fn translate(&self, target: Self, pid: i32) -> Vec<(i32, f64)> {
+        match (*self, target) {
+            (Self::Pdg, Self::Evol) => pdg_mc_pids_to_evol(pid),
+            (Self::Evol, Self::Pdg) => evol_to_pdg_mc_ids(pid),
+            (Self::Pdg, Self::Pdg) | (Self::Evol, Self::Evol) => vec![(pid, 1.0)],
+        }
+    }
+
+    /// Translate `channel`, whose particle IDs are given in the basis of `self`, into the basis
+    /// `target`. This is the translation [`Grid::rotate_pid_basis`] applies to each channel.
+    ///
+    /// [`Grid::rotate_pid_basis`]: super::grid::Grid::rotate_pid_basis
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use pineappl::channel;
+    /// use pineappl::pids::PidBasis;
+    ///
+    /// assert_eq!(
+    ///     PidBasis::Evol.translate_channel(PidBasis::Pdg, &channel![103, 21, 2.0]),
+    ///     channel![2, 21, 2.0; -2, 21, 2.0; 1, 21, -2.0; -1, 21, -2.0]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn translate_channel(&self, target: Self, channel: &Channel) -> Channel {
+        Channel::translate(channel, &|pid| self.translate(target, pid))
+    }
+
     /// Given the particle IDs in `pids`, guess the [`PidBasis`].
     #[must_use]
     pub fn guess(pids: &[i32]) -> Self {
@@ -937,6 +996,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn pid_basis_translate() {
+        assert_eq!(PidBasis::Pdg.translate(PidBasis::Pdg, 2), [(2, 1.0)]);
+        assert_eq!(PidBasis::Evol.translate(PidBasis::Evol, 100), [(100, 1.0)]);
+        assert_eq!(
+            PidBasis::Pdg.translate(PidBasis::Evol, 2),
+            pdg_mc_pids_to_evol(2)
+        );
+        assert_eq!(
+            PidBasis::Evol.translate(PidBasis::Pdg, 100),
+            evol_to_pdg_mc_ids(100)
+        );
+        assert_eq!(PidBasis::Pdg.translate(PidBasis::Evol, 21), [(21, 1.0)]);
+        assert_eq!(PidBasis::Pdg.translate(PidBasis::Evol, 22), [(22, 1.0)]);
+    }
+
+    #[test]
+    fn pid_basis_translate_channel_round_trip() {
+        for channel in [
+            channel![21, 21, 1.0],
+            channel![22, 22, 1.0],
+            channel![21, 22, 1.0; 22, 21, 1.0],
+            channel![2, -2, 1.0; 4, -4, 1.0],
+            channel![1, 21, 0.5; -3, 22, 2.0],
+            channel![-6, 5, 1.0; 3, -3, -1.0; 21, 2, 0.25],
+        ] {
+            let evol = PidBasis::Pdg.translate_channel(PidBasis::Evol, &channel);
+            let pdg = PidBasis::Evol.translate_channel(PidBasis::Pdg, &evol);
+
+            assert_eq!(pdg.entry().len(), channel.entry().len());
+
+            for (&(a, b, factor), &(ref_a, ref_b, ref_factor)) in
+                pdg.entry().iter().zip(channel.entry())
+            {
+                assert_eq!((a, b), (ref_a, ref_b));
+                assert_approx_eq!(f64, factor, ref_factor, ulps = 16);
+            }
+        }
+    }
+
     #[test]
     fn pid_basis_charge_conjugate() {
         assert_eq!(PidBasis::Evol.charge_conjugate(100), (100, 1.0));
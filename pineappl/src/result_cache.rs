@@ -0,0 +1,369 @@
+//! Module containing a cache that memoizes the results of [`Grid::convolve`].
+
+use super::convolutions::LumiCache;
+use super::grid::Grid;
+use std::collections::{BTreeMap, HashMap};
+
+/// Default number of results stored by [`ResultCache::default`].
+pub const DEFAULT_CAPACITY: usize = 64;
+
+/// Statistics of a [`ResultCache`], which are returned by [`ResultCache::stats`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ResultCacheStats {
+    /// Number of convolutions whose results were found in the cache.
+    pub hits: u64,
+    /// Number of convolutions whose results were not found in the cache. Each miss calls
+    /// [`Grid::convolve`] once.
+    pub misses: u64,
+    /// Number of results that were removed from the cache to stay within its capacity.
+    pub evictions: u64,
+}
+
+/// Identifier of a grid stored in a [`ResultCache`], which is returned by
+/// [`ResultCache::insert_grid`]. Identifiers are never reused by the same cache.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct GridId(u64);
+
+#[derive(Clone, Eq, Hash, PartialEq)]
+struct Key {
+    grid: GridId,
+    pdf: String,
+    order_mask: Vec<bool>,
+    bin_indices: Vec<usize>,
+    channel_mask: Vec<bool>,
+    xi: Vec<(u64, u64)>,
+}
+
+struct Entry {
+    results: Vec<f64>,
+    // value of `ResultCache::clock` when these results were last used
+    used: u64,
+}
+
+/// A cache memoizing the results of [`Grid::convolve`], which is useful for interactive
+/// applications that request the same convolutions over and over again.
+///
+/// The cache owns the grids whose convolutions it memoizes: a grid is moved into the cache with
+/// [`ResultCache::insert_grid`], which returns its [`GridId`], and moved out again with
+/// [`ResultCache::remove_grid`], which also removes all results cached for it. Since a grid can
+/// not be modified while it is stored in the cache, its identifier uniquely determines its
+/// contents, and a modified grid must be inserted again, which gives it a new identifier.
+///
+/// Results are keyed by the identifier of the grid, an identifier of the PDFs, and the
+/// `order_mask`, `bin_indices`, `channel_mask` and `xi` arguments of [`Grid::convolve`], which are
+/// compared exactly. The PDFs and the strong coupling themselves can not be inspected, and
+/// therefore the caller must guarantee that the identifier, for example the name of the PDF set
+/// and the member, uniquely determines the contents of the [`LumiCache`].
+///
+/// The cache stores at most [`ResultCache::capacity`] results. If it is full, the least-recently
+/// used result is evicted.
+pub struct ResultCache {
+    capacity: usize,
+    grids: HashMap<GridId, Grid>,
+    next_id: u64,
+    results: HashMap<Key, Entry>,
+    // keys of `results` ordered by their last use
+    lru: BTreeMap<u64, Key>,
+    clock: u64,
+    stats: ResultCacheStats,
+}
+
+impl Default for ResultCache {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl ResultCache {
+    /// Create an empty cache that stores at most `capacity` results. A `capacity` of zero disables
+    /// the caching.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            grids: HashMap::new(),
+            next_id: 0,
+            results: HashMap::new(),
+            lru: BTreeMap::new(),
+            clock: 0,
+            stats: ResultCacheStats::default(),
+        }
+    }
+
+    /// Return the maximum number of results stored in this cache.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the maximum number of results stored in this cache. If there are more results than
+    /// `capacity`, the least-recently used ones are evicted.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    /// Return the number of results stored in this cache.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Return `true` if this cache does not store any results.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Remove all results from this cache. The grids and the statistics are kept.
+    pub fn clear(&mut self) {
+        self.results.clear();
+        self.lru.clear();
+    }
+
+    /// Return the statistics of this cache.
+    #[must_use]
+    pub const fn stats(&self) -> ResultCacheStats {
+        self.stats
+    }
+
+    /// Move `grid` into this cache and return the identifier with which its convolutions are
+    /// requested.
+    pub fn insert_grid(&mut self, grid: Grid) -> GridId {
+        let id = GridId(self.next_id);
+        self.next_id += 1;
+        self.grids.insert(id, grid);
+        id
+    }
+
+    /// Return the grid with the identifier `id`, or `None` if there is no such grid in this cache.
+    #[must_use]
+    pub fn grid(&self, id: GridId) -> Option<&Grid> {
+        self.grids.get(&id)
+    }
+
+    /// Move the grid with the identifier `id` out of this cache and remove all results cached for
+    /// it. Returns `None` if there is no such grid in this cache.
+    pub fn remove_grid(&mut self, id: GridId) -> Option<Grid> {
+        let grid = self.grids.remove(&id)?;
+
+        self.results.retain(|key, _| key.grid != id);
+        self.lru.retain(|_, key| key.grid != id);
+
+        Some(grid)
+    }
+
+    /// Same as [`Grid::convolve`] for the grid with the identifier `grid`, but the results are
+    /// looked up in this cache first and only calculated if they are not found. The string `pdf`
+    /// must uniquely identify the PDFs and the strong coupling of `lumi_cache`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no grid with the identifier `grid` in this cache, or if
+    /// [`Grid::convolve`] panics.
+    #[allow(clippy::too_many_arguments)]
+    pub fn convolve(
+        &mut self,
+        grid: GridId,
+        lumi_cache: &mut LumiCache,
+        pdf: &str,
+        order_mask: &[bool],
+        bin_indices: &[usize],
+        channel_mask: &[bool],
+        xi: &[(f64, f64)],
+    ) -> Vec<f64> {
+        let key = Key {
+            grid,
+            pdf: pdf.to_owned(),
+            order_mask: order_mask.to_vec(),
+            bin_indices: bin_indices.to_vec(),
+            channel_mask: channel_mask.to_vec(),
+            xi: xi
+                .iter()
+                .map(|&(xir, xif)| (xir.to_bits(), xif.to_bits()))
+                .collect(),
+        };
+
+        self.clock += 1;
+
+        if let Some(entry) = self.results.get_mut(&key) {
+            self.stats.hits += 1;
+
+            // UNWRAP: every entry in `results` has its key in `lru`
+            let key = self.lru.remove(&entry.used).unwrap();
+            entry.used = self.clock;
+            self.lru.insert(self.clock, key);
+
+            return entry.results.clone();
+        }
+
+        self.stats.misses += 1;
+
+        let results = self
+            .grids
+            .get(&grid)
+            .unwrap_or_else(|| panic!("grid with identifier {grid:?} not found"))
+            .convolve(lumi_cache, order_mask, bin_indices, channel_mask, xi);
+
+        if self.capacity > 0 {
+            self.results.insert(
+                key.clone(),
+                Entry {
+                    results: results.clone(),
+                    used: self.clock,
+                },
+            );
+            self.lru.insert(self.clock, key);
+            self.evict();
+        }
+
+        results
+    }
+
+    fn evict(&mut self) {
+        while self.results.len() > self.capacity {
+            // UNWRAP: `lru` has as many keys as `results`, which is not empty
+            let (_, key) = self.lru.pop_first().unwrap();
+            self.results.remove(&key);
+            self.stats.evictions += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boc::Order;
+    use crate::channel;
+    use crate::grid::Ntuple;
+    use crate::subgrid::SubgridParams;
+
+    fn grid() -> Grid {
+        let mut grid = Grid::new(
+            vec![channel![2, 2, 1.0; 4, 4, 1.0]],
+            vec![Order::new(0, 2, 0, 0)],
+            vec![0.0, 0.25, 0.5],
+            SubgridParams::default(),
+        );
+
+        grid.fill(
+            0,
+            0.1,
+            0,
+            &Ntuple {
+                x1: 0.1,
+                x2: 0.2,
+                q2: 100.0,
+                weight: 1.0,
+            },
+        );
+        grid.fill(
+            0,
+            0.3,
+            0,
+            &Ntuple {
+                x1: 0.3,
+                x2: 0.4,
+                q2: 100.0,
+                weight: 2.0,
+            },
+        );
+
+        grid
+    }
+
+    #[test]
+    fn result_cache() {
+        let mut xfx = |_, x, _| x;
+        let mut alphas = |_| 0.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+        let mut cache = ResultCache::with_capacity(2);
+        let id = cache.insert_grid(grid());
+
+        let reference = grid().convolve(&mut lumi_cache, &[], &[], &[], &[(1.0, 1.0)]);
+        let results = cache.convolve(id, &mut lumi_cache, "x", &[], &[], &[], &[(1.0, 1.0)]);
+
+        assert_eq!(results, reference);
+        assert_eq!(
+            cache.stats(),
+            ResultCacheStats {
+                hits: 0,
+                misses: 1,
+                evictions: 0
+            }
+        );
+
+        let results = cache.convolve(id, &mut lumi_cache, "x", &[], &[], &[], &[(1.0, 1.0)]);
+
+        assert_eq!(results, reference);
+        assert_eq!(cache.stats().hits, 1);
+        assert_eq!(cache.len(), 1);
+
+        // a different PDF identifier is a different result
+        cache.convolve(id, &mut lumi_cache, "y", &[], &[], &[], &[(1.0, 1.0)]);
+
+        assert_eq!(cache.stats().misses, 2);
+        assert_eq!(cache.len(), 2);
+
+        // 'x' was used less recently than 'y' and is evicted first
+        cache.convolve(id, &mut lumi_cache, "z", &[], &[], &[], &[(1.0, 1.0)]);
+        cache.convolve(id, &mut lumi_cache, "y", &[], &[], &[], &[(1.0, 1.0)]);
+
+        assert_eq!(
+            cache.stats(),
+            ResultCacheStats {
+                hits: 2,
+                misses: 3,
+                evictions: 1
+            }
+        );
+
+        // removing the grid removes its results
+        let mut grid = cache.remove_grid(id).unwrap();
+
+        assert!(cache.is_empty());
+        assert!(cache.grid(id).is_none());
+        assert!(cache.remove_grid(id).is_none());
+
+        // a modified grid gets a new identifier
+        grid.scale(2.0);
+        let new_id = cache.insert_grid(grid);
+
+        assert_ne!(new_id, id);
+
+        let results = cache.convolve(new_id, &mut lumi_cache, "x", &[], &[], &[], &[(1.0, 1.0)]);
+
+        assert_eq!(
+            results,
+            reference
+                .iter()
+                .map(|value| 2.0 * value)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(cache.stats().misses, 4);
+        assert_eq!(cache.len(), 1);
+
+        cache.set_capacity(0);
+
+        assert!(cache.is_empty());
+        assert_eq!(cache.stats().evictions, 2);
+
+        cache.convolve(new_id, &mut lumi_cache, "x", &[], &[], &[], &[(1.0, 1.0)]);
+
+        assert!(cache.is_empty());
+        assert!(cache.grid(new_id).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "grid with identifier GridId(0) not found")]
+    fn result_cache_unknown_grid() {
+        let mut xfx = |_, x, _| x;
+        let mut alphas = |_| 0.0;
+        let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+        let mut other = ResultCache::default();
+        let id = other.insert_grid(grid());
+        let mut cache = ResultCache::default();
+
+        cache.convolve(id, &mut lumi_cache, "x", &[], &[], &[], &[(1.0, 1.0)]);
+    }
+}
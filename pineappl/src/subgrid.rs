@@ -2,13 +2,14 @@
 
 use super::empty_subgrid::EmptySubgridV1;
 use super::grid::Ntuple;
-use super::import_only_subgrid::{ImportOnlySubgridV1, ImportOnlySubgridV2};
+use super::import_only_subgrid::{ImportOnlySubgridF32, ImportOnlySubgridV1, ImportOnlySubgridV2};
 use super::lagrange_subgrid::{LagrangeSparseSubgridV1, LagrangeSubgridV1, LagrangeSubgridV2};
 use super::ntuple_subgrid::NtupleSubgridV1;
 use enum_dispatch::enum_dispatch;
 use ndarray::Array3;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::fmt;
 
 /// Enum which lists all possible `Subgrid` variants possible.
 #[enum_dispatch(Subgrid)]
@@ -30,6 +31,139 @@ pub enum SubgridEnum {
     /// Same as [`ImportOnlySubgridV1`], but with support for different renormalization and
     /// factorization scales choices.
     ImportOnlySubgridV2,
+    /// Same as [`ImportOnlySubgridV2`], but storing the values with single precision.
+    ImportOnlySubgridF32,
+}
+
+impl SubgridEnum {
+    /// Return the kind of this subgrid, which identifies its type without its data.
+    #[must_use]
+    pub const fn kind(&self) -> SubgridKind {
+        match self {
+            Self::LagrangeSubgridV1(_) => SubgridKind::LagrangeSubgridV1,
+            Self::NtupleSubgridV1(_) => SubgridKind::NtupleSubgridV1,
+            Self::LagrangeSparseSubgridV1(_) => SubgridKind::LagrangeSparseSubgridV1,
+            Self::LagrangeSubgridV2(_) => SubgridKind::LagrangeSubgridV2,
+            Self::ImportOnlySubgridV1(_) => SubgridKind::ImportOnlySubgridV1,
+            Self::EmptySubgridV1(_) => SubgridKind::EmptySubgridV1,
+            Self::ImportOnlySubgridV2(_) => SubgridKind::ImportOnlySubgridV2,
+            Self::ImportOnlySubgridF32(_) => SubgridKind::ImportOnlySubgridF32,
+        }
+    }
+
+    /// Return the subgrid if it is a [`LagrangeSubgridV2`], otherwise `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use pineappl::lagrange_subgrid::LagrangeSubgridV2;
+    /// use pineappl::subgrid::{ExtraSubgridParams, SubgridEnum, SubgridParams};
+    ///
+    /// let params = SubgridParams::default();
+    /// let subgrid: SubgridEnum =
+    ///     LagrangeSubgridV2::new(&params, &ExtraSubgridParams::from(&params)).into();
+    ///
+    /// assert!(subgrid.as_lagrange_subgrid_v2().is_some());
+    /// assert!(subgrid.as_import_only_subgrid_v2().is_none());
+    /// ```
+    #[must_use]
+    pub const fn as_lagrange_subgrid_v2(&self) -> Option<&LagrangeSubgridV2> {
+        if let Self::LagrangeSubgridV2(subgrid) = self {
+            Some(subgrid)
+        } else {
+            None
+        }
+    }
+
+    /// Return the subgrid if it is an [`ImportOnlySubgridV2`], otherwise `None`.
+    #[must_use]
+    pub const fn as_import_only_subgrid_v2(&self) -> Option<&ImportOnlySubgridV2> {
+        if let Self::ImportOnlySubgridV2(subgrid) = self {
+            Some(subgrid)
+        } else {
+            None
+        }
+    }
+
+    /// Return the subgrid if it is an [`ImportOnlySubgridF32`], otherwise `None`.
+    #[must_use]
+    pub const fn as_import_only_subgrid_f32(&self) -> Option<&ImportOnlySubgridF32> {
+        if let Self::ImportOnlySubgridF32(subgrid) = self {
+            Some(subgrid)
+        } else {
+            None
+        }
+    }
+
+    /// Same as [`SubgridEnum::as_import_only_subgrid_v2`], but returns a mutable reference.
+    pub fn as_import_only_subgrid_v2_mut(&mut self) -> Option<&mut ImportOnlySubgridV2> {
+        if let Self::ImportOnlySubgridV2(subgrid) = self {
+            Some(subgrid)
+        } else {
+            None
+        }
+    }
+}
+
+/// Type of a [`SubgridEnum`], with one variant for each variant of the latter. The
+/// [`Display`](fmt::Display) implementation prints the name of the variant.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SubgridKind {
+    /// See [`SubgridEnum::LagrangeSubgridV1`].
+    LagrangeSubgridV1,
+    /// See [`SubgridEnum::NtupleSubgridV1`].
+    NtupleSubgridV1,
+    /// See [`SubgridEnum::LagrangeSparseSubgridV1`].
+    LagrangeSparseSubgridV1,
+    /// See [`SubgridEnum::LagrangeSubgridV2`].
+    LagrangeSubgridV2,
+    /// See [`SubgridEnum::ImportOnlySubgridV1`].
+    ImportOnlySubgridV1,
+    /// See [`SubgridEnum::EmptySubgridV1`].
+    EmptySubgridV1,
+    /// See [`SubgridEnum::ImportOnlySubgridV2`].
+    ImportOnlySubgridV2,
+    /// See [`SubgridEnum::ImportOnlySubgridF32`].
+    ImportOnlySubgridF32,
+}
+
+impl SubgridKind {
+    /// Return `true` if subgrids of this kind interpolate events that are filled into them.
+    #[must_use]
+    pub const fn is_interpolating(&self) -> bool {
+        matches!(
+            self,
+            Self::LagrangeSubgridV1 | Self::LagrangeSparseSubgridV1 | Self::LagrangeSubgridV2
+        )
+    }
+
+    /// Return `true` if subgrids of this kind can only be imported, not filled.
+    #[must_use]
+    pub const fn is_import_only(&self) -> bool {
+        matches!(
+            self,
+            Self::ImportOnlySubgridV1 | Self::ImportOnlySubgridV2 | Self::ImportOnlySubgridF32
+        )
+    }
+}
+
+impl fmt::Display for SubgridKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::LagrangeSubgridV1 => "LagrangeSubgridV1",
+                Self::NtupleSubgridV1 => "NtupleSubgridV1",
+                Self::LagrangeSparseSubgridV1 => "LagrangeSparseSubgridV1",
+                Self::LagrangeSubgridV2 => "LagrangeSubgridV2",
+                Self::ImportOnlySubgridV1 => "ImportOnlySubgridV1",
+                Self::EmptySubgridV1 => "EmptySubgridV1",
+                Self::ImportOnlySubgridV2 => "ImportOnlySubgridV2",
+                Self::ImportOnlySubgridF32 => "ImportOnlySubgridF32",
+            }
+        )
+    }
 }
 
 /// Structure denoting renormalization and factorization scale values.
@@ -61,6 +195,28 @@ pub struct Stats {
     pub bytes_per_value: usize,
 }
 
+/// Fill density and memory usage of a single subgrid of a [`Grid`](super::grid::Grid), as
+/// returned by [`Grid::subgrid_stats`](super::grid::Grid::subgrid_stats).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubgridStats {
+    /// Index of the order of the subgrid.
+    pub order: usize,
+    /// Index of the bin of the subgrid.
+    pub bin: usize,
+    /// Index of the channel of the subgrid.
+    pub channel: usize,
+    /// Type of the subgrid.
+    pub kind: SubgridKind,
+    /// Number of non-zero entries stored in the subgrid.
+    pub non_zeros: usize,
+    /// Number of nodes of the subgrid, which is the product of the lengths of the slices returned
+    /// by [`Subgrid::mu2_grid`], [`Subgrid::x1_grid`] and [`Subgrid::x2_grid`].
+    pub nodes: usize,
+    /// Estimated number of bytes used by the subgrid. This includes allocated zeros, the indices
+    /// of sparse subgrids and the nodes that import-only subgrids store explicitly.
+    pub bytes: usize,
+}
+
 /// Trait each subgrid must implement.
 #[enum_dispatch]
 pub trait Subgrid {
@@ -359,3 +515,64 @@ impl ExtraSubgridParams {
         self.x2_order
     }
 }
+
+/// Interpolation of a single kinematic variable, which is returned by
+/// [`Grid::interp_for_kinematic`].
+///
+/// [`Grid::interp_for_kinematic`]: super::grid::Grid::interp_for_kinematic
+#[derive(Clone, Debug, PartialEq)]
+pub struct Interp {
+    nodes: usize,
+    min: f64,
+    max: f64,
+    order: usize,
+    reweight: bool,
+}
+
+impl Interp {
+    pub(crate) const fn new(
+        nodes: usize,
+        min: f64,
+        max: f64,
+        order: usize,
+        reweight: bool,
+    ) -> Self {
+        Self {
+            nodes,
+            min,
+            max,
+            order,
+            reweight,
+        }
+    }
+
+    /// Returns the number of interpolation nodes.
+    #[must_use]
+    pub const fn nodes(&self) -> usize {
+        self.nodes
+    }
+
+    /// Returns the lower limit of the interpolation range.
+    #[must_use]
+    pub const fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// Returns the upper limit of the interpolation range.
+    #[must_use]
+    pub const fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// Returns the interpolation order.
+    #[must_use]
+    pub const fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Returns whether the interpolated values are reweighted.
+    #[must_use]
+    pub const fn reweight(&self) -> bool {
+        self.reweight
+    }
+}
@@ -0,0 +1,116 @@
+//! Checks that convolutions with an `FkTableCache` do not allocate memory.
+
+use float_cmp::assert_approx_eq;
+use ndarray::Array2;
+use pineappl::boc::Order;
+use pineappl::channel;
+use pineappl::convolutions::{Convolution, LumiCache};
+use pineappl::fk_table::FkTable;
+use pineappl::grid::Grid;
+use pineappl::import_only_subgrid::ImportOnlySubgridV2;
+use pineappl::sparse_array3::SparseArray3;
+use pineappl::subgrid::{Mu2, SubgridParams};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// counts the allocations of the current thread, so that tests running in parallel do not interfere
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|allocations| allocations.set(allocations.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn dis_fk_table(bins: usize, x: &[f64]) -> FkTable {
+    let pids = [-3, -2, -1, 1, 2, 3, 21];
+    let mut grid = Grid::new(
+        pids.iter().map(|&pid| channel![pid, 11, 1.0]).collect(),
+        vec![Order::new(0, 0, 0, 0)],
+        (0..=bins).map(|limit| limit as f64).collect(),
+        SubgridParams::default(),
+    );
+    grid.set_convolution(1, Convolution::None);
+
+    for bin in 0..bins {
+        for channel in 0..pids.len() {
+            let mut array = SparseArray3::new(1, x.len(), 1);
+
+            for ix in 0..x.len() {
+                array[[0, ix, 0]] = ((bin + 1) * (channel + 2) + ix) as f64;
+            }
+
+            grid.set_subgrid(
+                0,
+                bin,
+                channel,
+                ImportOnlySubgridV2::new(
+                    array,
+                    vec![Mu2 {
+                        ren: 10.0,
+                        fac: 10.0,
+                    }],
+                    x.to_vec(),
+                    vec![1.0],
+                )
+                .into(),
+            );
+        }
+    }
+
+    FkTable::try_from(grid).unwrap()
+}
+
+#[test]
+fn convolve_into_does_not_allocate() {
+    let x: Vec<_> = (0..50)
+        .map(|i| 1e-5_f64.powf(f64::from(i) / 49.0))
+        .collect();
+    let fk_table = dis_fk_table(20, &x);
+    let cache = fk_table.grid_cache();
+
+    assert_eq!(cache.pids(), [-3, -2, -1, 1, 2, 3, 21]);
+
+    let xfx = |pid: i32, x: f64| x.sqrt() * (1.0 - x).powi(3) * f64::from(pid.abs() + 1);
+    let pdfs = Array2::from_shape_fn((cache.pids().len(), cache.x_grid().len()), |(ipid, ix)| {
+        xfx(cache.pids()[ipid], cache.x_grid()[ix])
+    });
+    let mut results = vec![0.0; 20];
+
+    // warm-up
+    cache.convolve_into(pdfs.view(), &mut results);
+
+    let before = allocations();
+
+    for _ in 0..10_000 {
+        cache.convolve_into(pdfs.view(), &mut results);
+    }
+
+    assert_eq!(allocations() - before, 0);
+
+    let mut xfx = |pid, x, _| xfx(pid, x);
+    let mut alphas = |_| 1.0;
+    let mut lumi_cache = LumiCache::with_one(2212, &mut xfx, &mut alphas);
+    let reference = fk_table.convolve(&mut lumi_cache, &[], &[]);
+
+    for (result, reference) in results.iter().zip(&reference) {
+        assert_approx_eq!(f64, *result, *reference, ulps = 16);
+    }
+}
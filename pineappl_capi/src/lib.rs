@@ -58,9 +58,10 @@
 use itertools::izip;
 use pineappl::bin::BinRemapper;
 use pineappl::boc::{Channel, Order};
-use pineappl::convolutions::LumiCache;
+use pineappl::convolutions::{Convolution, LumiCache};
+use pineappl::fk_table::FkTable;
 use pineappl::grid::{Grid, GridOptFlags, Ntuple};
-use pineappl::subgrid::{ExtraSubgridParams, SubgridParams};
+use pineappl::subgrid::{ExtraSubgridParams, Subgrid, SubgridParams};
 use std::collections::HashMap;
 use std::ffi::{CStr, CString};
 use std::fs::File;
@@ -483,6 +484,88 @@ pub unsafe extern "C" fn pineappl_grid_convolve_with_two(
     ));
 }
 
+/// Write the shape of the array returned by `pineappl_grid_convolve_subgrid` for the subgrid with
+/// indices `order`, `bin` and `channel` into `shape`.
+///
+/// The three numbers are the number of scale values and of x-grid values for the first and second
+/// initial state. For an empty subgrid all three numbers are `0`.
+///
+/// # Safety
+///
+/// If `grid` does not point to a valid `Grid` object, for example when `grid` is the null pointer,
+/// this function is not safe to call. The indices `order`, `bin` and `channel` must be smaller
+/// than the number of orders, bins and channels of `grid`, respectively. The parameter `shape`
+/// must point to an array with three elements.
+#[no_mangle]
+pub unsafe extern "C" fn pineappl_grid_convolve_subgrid_shape(
+    grid: *const Grid,
+    order: usize,
+    bin: usize,
+    channel: usize,
+    shape: *mut usize,
+) {
+    let grid = unsafe { &*grid };
+    let subgrid = &grid.subgrids()[[order, bin, channel]];
+    let shape = unsafe { slice::from_raw_parts_mut(shape, 3) };
+
+    shape.copy_from_slice(&if subgrid.is_empty() {
+        [0, 0, 0]
+    } else {
+        [
+            subgrid.mu2_grid().len(),
+            subgrid.x1_grid().len(),
+            subgrid.x2_grid().len(),
+        ]
+    });
+}
+
+/// Convolutes the single subgrid of `grid` with indices `order`, `bin` and `channel`.
+///
+/// The PDFs `xfx1` and `xfx2` and the strong coupling `alphas` have the same meaning as in
+/// `pineappl_grid_convolve_with_two`. Instead of summing the result, the contribution of each
+/// node of the subgrid is written into `results`, which is a three-dimensional array indexed by
+/// the scale and the x-grid values of the first and second initial state, stored in row-major
+/// order with the last index varying fastest. Its shape is given by
+/// `pineappl_grid_convolve_subgrid_shape`. The sum of all values is the contribution of this
+/// subgrid to the differential cross section of `bin`.
+///
+/// # Safety
+///
+/// If `grid` does not point to a valid `Grid` object, for example when `grid` is the null pointer,
+/// this function is not safe to call. The indices `order`, `bin` and `channel` must be smaller
+/// than the number of orders, bins and channels of `grid`, respectively. The function pointers
+/// `xfx1`, `xfx2`, and `alphas` must not be null pointers and point to valid functions. Finally,
+/// `results` must be as long as the product of the numbers written by
+/// `pineappl_grid_convolve_subgrid_shape`.
+#[no_mangle]
+pub unsafe extern "C" fn pineappl_grid_convolve_subgrid(
+    grid: *const Grid,
+    order: usize,
+    bin: usize,
+    channel: usize,
+    pdg_id1: i32,
+    xfx1: extern "C" fn(pdg_id: i32, x: f64, q2: f64, state: *mut c_void) -> f64,
+    pdg_id2: i32,
+    xfx2: extern "C" fn(pdg_id: i32, x: f64, q2: f64, state: *mut c_void) -> f64,
+    alphas: extern "C" fn(q2: f64, state: *mut c_void) -> f64,
+    state: *mut c_void,
+    xi_ren: f64,
+    xi_fac: f64,
+    results: *mut f64,
+) {
+    let grid = unsafe { &*grid };
+    let mut pdf1 = |id, x, q2| xfx1(id, x, q2, state);
+    let mut pdf2 = |id, x, q2| xfx2(id, x, q2, state);
+    let mut als = |q2| alphas(q2, state);
+    let mut lumi_cache = LumiCache::with_two(pdg_id1, &mut pdf1, pdg_id2, &mut pdf2, &mut als);
+    let array = grid.convolve_subgrid(&mut lumi_cache, order, bin, channel, xi_ren, xi_fac);
+    let results = unsafe { slice::from_raw_parts_mut(results, array.len()) };
+
+    for (to, from) in results.iter_mut().zip(array.iter()) {
+        *to = *from;
+    }
+}
+
 /// Try to deduplicate channels of `grid` by detecting pairs of them that contain the same
 /// subgrids. The numerical equality is tested using a tolerance of `ulps`, given in [units of
 /// least precision](https://docs.rs/float-cmp/latest/float_cmp/index.html#some-explanation).
@@ -680,12 +763,7 @@ pub unsafe extern "C" fn pineappl_grid_new(
     let order_params = unsafe { slice::from_raw_parts(order_params, 4 * orders) };
     let orders: Vec<_> = order_params
         .chunks(4)
-        .map(|s| Order {
-            alphas: s[0],
-            alpha: s[1],
-            logxir: s[2],
-            logxif: s[3],
-        })
+        .map(|s| Order::new(s[0], s[1], s[2], s[3]))
         .collect();
 
     let key_vals = unsafe { key_vals.as_ref() };
@@ -991,6 +1069,166 @@ pub unsafe extern "C" fn pineappl_grid_write(grid: *const Grid, filename: *const
     }
 }
 
+/// Delete an FK table previously created with `pineappl_fktable_read`.
+#[no_mangle]
+#[allow(unused_variables)]
+pub extern "C" fn pineappl_fktable_delete(fktable: Option<Box<FkTable>>) {}
+
+/// Read an FK table from a file with name `filename`.
+///
+/// # Safety
+///
+/// The parameter `filename` must be a C string pointing to an existing grid file.
+///
+/// # Panics
+///
+/// This function panics if the file can not be read or if the grid is not an FK table.
+#[no_mangle]
+#[must_use]
+pub unsafe extern "C" fn pineappl_fktable_read(filename: *const c_char) -> Box<FkTable> {
+    let filename = unsafe { CStr::from_ptr(filename) };
+    let filename = filename.to_string_lossy();
+    let reader = File::open(filename.as_ref()).unwrap();
+
+    Box::new(FkTable::try_from(Grid::read(reader).unwrap()).unwrap())
+}
+
+/// Returns the number of flavor pairs of `fktable`, which is the number of channels.
+///
+/// # Safety
+///
+/// The parameter `fktable` must point to a valid `FkTable` object created by
+/// `pineappl_fktable_read`.
+#[no_mangle]
+#[must_use]
+pub unsafe extern "C" fn pineappl_fktable_flavors_len(fktable: *const FkTable) -> usize {
+    let fktable = unsafe { &*fktable };
+
+    fktable.grid().channels().len()
+}
+
+/// Write the flavors of `fktable` into `pdg_ids`. For each channel the PDG ids of the first and
+/// second initial state are written, in this order.
+///
+/// # Safety
+///
+/// The parameter `fktable` must point to a valid `FkTable` object created by
+/// `pineappl_fktable_read`. The parameter `pdg_ids` must point to an array that is twice as long
+/// as the value returned by `pineappl_fktable_flavors_len`.
+#[no_mangle]
+pub unsafe extern "C" fn pineappl_fktable_flavors(fktable: *const FkTable, pdg_ids: *mut i32) {
+    let fktable = unsafe { &*fktable };
+    let channels = fktable.channels();
+    let pdg_ids = unsafe { slice::from_raw_parts_mut(pdg_ids, 2 * channels.len()) };
+
+    for (ids, (id1, id2)) in pdg_ids.chunks_exact_mut(2).zip(channels) {
+        ids[0] = id1;
+        ids[1] = id2;
+    }
+}
+
+/// Returns the number of x-grid values of `fktable`.
+///
+/// # Safety
+///
+/// The parameter `fktable` must point to a valid `FkTable` object created by
+/// `pineappl_fktable_read`.
+#[no_mangle]
+#[must_use]
+pub unsafe extern "C" fn pineappl_fktable_x_grid_len(fktable: *const FkTable) -> usize {
+    let fktable = unsafe { &*fktable };
+
+    fktable.x_grid().len()
+}
+
+/// Write the x grid, which is shared by all subgrids of `fktable`, into `x_grid`.
+///
+/// # Safety
+///
+/// The parameter `fktable` must point to a valid `FkTable` object created by
+/// `pineappl_fktable_read`. The parameter `x_grid` must point to an array that is as long as the
+/// value returned by `pineappl_fktable_x_grid_len`.
+#[no_mangle]
+pub unsafe extern "C" fn pineappl_fktable_x_grid(fktable: *const FkTable, x_grid: *mut f64) {
+    let fktable = unsafe { &*fktable };
+    let values = fktable.x_grid();
+    let x_grid = unsafe { slice::from_raw_parts_mut(x_grid, values.len()) };
+
+    x_grid.copy_from_slice(&values);
+}
+
+fn fktable_table_shape(fktable: &FkTable) -> [usize; 4] {
+    let grid = fktable.grid();
+    let x_grid_len = fktable.x_grid().len();
+    let [x1_len, x2_len] = [0, 1].map(|index| {
+        if grid.convolutions()[index] == Convolution::None {
+            1
+        } else {
+            x_grid_len
+        }
+    });
+
+    [
+        grid.bin_info().bins(),
+        grid.channels().len(),
+        x1_len,
+        x2_len,
+    ]
+}
+
+/// Write the shape of the array returned by `pineappl_fktable_table` into `shape`.
+///
+/// The four numbers are the number of bins, of channels, and of x-grid values for the first and
+/// second initial state. If an initial state is not convolved, the corresponding number is `1`.
+///
+/// # Safety
+///
+/// The parameter `fktable` must point to a valid `FkTable` object created by
+/// `pineappl_fktable_read`. The parameter `shape` must point to an array with four elements.
+#[no_mangle]
+pub unsafe extern "C" fn pineappl_fktable_table_shape(fktable: *const FkTable, shape: *mut usize) {
+    let fktable = unsafe { &*fktable };
+    let shape = unsafe { slice::from_raw_parts_mut(shape, 4) };
+
+    shape.copy_from_slice(&fktable_table_shape(fktable));
+}
+
+/// Returns the number of elements of the array returned by `pineappl_fktable_table`, which is the
+/// product of the numbers returned by `pineappl_fktable_table_shape`.
+///
+/// # Safety
+///
+/// The parameter `fktable` must point to a valid `FkTable` object created by
+/// `pineappl_fktable_read`.
+#[no_mangle]
+#[must_use]
+pub unsafe extern "C" fn pineappl_fktable_table_len(fktable: *const FkTable) -> usize {
+    let fktable = unsafe { &*fktable };
+
+    fktable_table_shape(fktable).iter().product()
+}
+
+/// Write the FK table as a four-dimensional array into `table`.
+///
+/// The array is indexed by bin, channel, and the x-grid indices of the first and second initial
+/// state, and stored in row-major order, with the last index varying fastest.
+///
+/// # Safety
+///
+/// The parameter `fktable` must point to a valid `FkTable` object created by
+/// `pineappl_fktable_read`. The parameter `table` must point to an array that is as long as the
+/// value returned by `pineappl_fktable_table_len`.
+#[no_mangle]
+pub unsafe extern "C" fn pineappl_fktable_table(fktable: *const FkTable, table: *mut f64) {
+    let fktable = unsafe { &*fktable };
+    let array = fktable.table();
+    let table = unsafe { slice::from_raw_parts_mut(table, array.len()) };
+
+    for (to, from) in table.iter_mut().zip(array.iter()) {
+        *to = *from;
+    }
+}
+
 /// Adds a linear combination of initial states to the luminosity function `lumi`.
 ///
 /// # Safety
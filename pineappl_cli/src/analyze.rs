@@ -3,7 +3,10 @@ use super::{GlobalConfiguration, Subcommand};
 use anyhow::Result;
 use clap::builder::TypedValueParser;
 use clap::{value_parser, Parser, ValueHint};
-use prettytable::{cell, Row};
+use pineappl::grid::GridOptFlags;
+use prettytable::{cell, row, Row};
+use std::fs;
+use std::io::{self, Write};
 use std::path::PathBuf;
 use std::process::ExitCode;
 
@@ -23,12 +26,14 @@ impl Subcommand for Opts {
 #[derive(Parser)]
 enum SubcommandEnum {
     Ckf(CkfOpts),
+    OptimizeGain(OptimizeGainOpts),
 }
 
 impl Subcommand for SubcommandEnum {
     fn run(&self, cfg: &GlobalConfiguration) -> Result<ExitCode> {
         match self {
             Self::Ckf(opts) => opts.run(cfg),
+            Self::OptimizeGain(opts) => opts.run(cfg),
         }
     }
 }
@@ -186,3 +191,70 @@ impl Subcommand for CkfOpts {
         Ok(ExitCode::SUCCESS)
     }
 }
+
+/// Estimate the reduction of the file size achieved by optimizing a grid, without writing it.
+#[derive(Parser)]
+pub struct OptimizeGainOpts {
+    /// Path to the input grid.
+    #[arg(value_hint = ValueHint::FilePath)]
+    input: PathBuf,
+    /// Set the number of fractional digits shown for relative numbers.
+    #[arg(default_value_t = 2, long, value_name = "REL")]
+    digits_rel: usize,
+}
+
+/// Counts the bytes written into it and discards them.
+#[derive(Default)]
+struct ByteCounter(u64);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // UNWRAP: a buffer can not be larger than `u64::MAX`
+        self.0 += u64::try_from(buf.len()).unwrap();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Subcommand for OptimizeGainOpts {
+    fn run(&self, _: &GlobalConfiguration) -> Result<ExitCode> {
+        let file_before = fs::metadata(&self.input)?.len();
+        let mut grid = helpers::read_grid(&self.input)?;
+        let report = grid.optimize_using_report(GridOptFlags::all());
+
+        // estimate the size of the file using the same compression as the input
+        let mut counter = ByteCounter::default();
+        if self.input.extension().is_some_and(|ext| ext == "lz4") {
+            grid.write_lz4(&mut counter)?;
+        } else {
+            grid.write(&mut counter)?;
+        }
+        let file_after = counter.0;
+
+        let mut table = helpers::create_table();
+        table.set_titles(row![c => "size", "current", "optimized", "gain"]);
+
+        for (label, before, after) in [
+            ("file", file_before, file_after),
+            ("uncompressed", report.bytes_before, report.bytes_after),
+        ] {
+            // the precision loss only matters for files larger than 2^52 bytes
+            #[allow(clippy::cast_precision_loss)]
+            let gain = (1.0 - after as f64 / before as f64) * 100.0;
+
+            table.add_row(row![
+                l->label,
+                r->before,
+                r->after,
+                r->format!("{:.*}%", self.digits_rel, gain)
+            ]);
+        }
+
+        table.printstd();
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
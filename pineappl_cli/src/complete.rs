@@ -0,0 +1,26 @@
+use super::{GlobalConfiguration, Subcommand};
+use anyhow::Result;
+use clap::{CommandFactory, Parser};
+use clap_complete::Shell;
+use std::io;
+use std::process::ExitCode;
+
+/// Generate shell completions for all subcommands.
+#[derive(Parser)]
+pub struct Opts {
+    /// Shell for which the completions are generated.
+    shell: Shell,
+}
+
+impl Subcommand for Opts {
+    fn run(&self, _: &GlobalConfiguration) -> Result<ExitCode> {
+        clap_complete::generate(
+            self.shell,
+            &mut super::Opts::command(),
+            "pineappl",
+            &mut io::stdout(),
+        );
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
@@ -1,10 +1,21 @@
 use super::helpers::{self, ConvFuns, ConvoluteMode};
 use super::{GlobalConfiguration, Subcommand};
-use anyhow::Result;
+use anyhow::{anyhow, bail, Context, Result};
 use clap::{Parser, ValueHint};
+use float_cmp::approx_eq;
+use itertools::Itertools;
+use ndarray::Axis;
+use pineappl::convolutions::Convolution;
+use pineappl::grid::{Grid, Reference};
+use pineappl::pids;
+use pineappl::subgrid::Mu2;
 use prettytable::{cell, Row};
+use rayon::{prelude::*, ThreadPoolBuilder};
+use std::borrow::Cow;
+use std::fs;
+use std::num::NonZeroUsize;
 use std::ops::RangeInclusive;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
 /// Convolutes a PineAPPL grid with a PDF set.
@@ -14,8 +25,8 @@ pub struct Opts {
     /// Path of the input grid.
     #[arg(value_hint = ValueHint::FilePath)]
     input: PathBuf,
-    /// LHAPDF id(s) or name of the PDF set(s).
-    #[arg(required = true)]
+    /// LHAPDF id(s) or name of the PDF set(s). If none is given, the PDF set stored in the
+    /// metadata key `results_pdf` is used.
     conv_funs: Vec<ConvFuns>,
     /// Selects a subset of bins.
     #[arg(
@@ -38,40 +49,239 @@ pub struct Opts {
         value_parser = helpers::parse_order
     )]
     orders: Vec<(u32, u32)>,
+    /// Select the orders whose power of the strong coupling is at most MAX.
+    #[arg(conflicts_with = "orders", long, value_name = "MAX")]
+    max_as: Option<u32>,
+    /// Select the orders whose power of the electromagnetic coupling is at most MAX.
+    #[arg(conflicts_with = "orders", long, value_name = "MAX")]
+    max_a: Option<u32>,
     /// Set the variation of the renormalization scale.
     #[arg(default_value = "1.0", long, num_args = 1)]
     xir: f64,
     /// Set the variation of the factorization scale.
     #[arg(default_value = "1.0", long, num_args = 1)]
     xif: f64,
+    /// Convolve with all combinations of FACTORS for the renormalization and factorization scale.
+    #[arg(
+        conflicts_with_all = ["xir", "xif", "write_predictions"],
+        long,
+        num_args = 1,
+        value_delimiter = ',',
+        value_name = "FACTORS"
+    )]
+    scale_scan: Vec<f64>,
+    /// Convolve with the factors for the renormalization and factorization scale given in FILE.
+    #[arg(
+        conflicts_with_all = ["xir", "xif", "scale_scan", "write_predictions", "embed_reference"],
+        long,
+        value_hint = ValueHint::FilePath,
+        value_name = "FILE"
+    )]
+    scales_from_file: Option<PathBuf>,
+    /// Replace the renormalization and factorization scale of each bin with the values in FILE.
+    #[arg(
+        conflicts_with_all = ["xir", "xif", "scale_scan", "scales_from_file", "embed_reference"],
+        long,
+        value_hint = ValueHint::FilePath,
+        value_name = "FILE"
+    )]
+    scale_table: Option<PathBuf>,
+    /// Multiply the normalized result of each bin with the corresponding factor in FILE.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "FILE")]
+    bin_corrections: Option<PathBuf>,
     /// Set the number of fractional digits shown for absolute numbers.
     #[arg(default_value_t = 7, long, value_name = "ABS")]
     digits_abs: usize,
     /// Set the number of fractional digits shown for relative numbers.
     #[arg(default_value_t = 2, long, value_name = "REL")]
     digits_rel: usize,
+    /// Write the predictions of the first PDF set as a grid into FILE.
+    #[arg(
+        conflicts_with = "bins",
+        long,
+        value_hint = ValueHint::FilePath,
+        value_name = "FILE"
+    )]
+    write_predictions: Option<PathBuf>,
+    /// Store the results of the first PDF set as reference in the metadata of the grid and write
+    /// it into FILE.
+    #[arg(
+        // the reference must be the unmodified prediction of all orders with the central scales
+        conflicts_with_all = [
+            "orders",
+            "max_as",
+            "max_a",
+            "xir",
+            "xif",
+            "bins",
+            "bin_corrections",
+            "scale_scan",
+            "fill_nan_with",
+        ],
+        long,
+        value_hint = ValueHint::FilePath,
+        value_name = "FILE"
+    )]
+    embed_reference: Option<PathBuf>,
+    /// Write the contributions of each order, channel and bin of the first PDF set as an array
+    /// with the axes in this order into the `.npy` FILE.
+    #[arg(
+        conflicts_with_all = [
+            "bins",
+            "bin_corrections",
+            "scale_scan",
+            "scales_from_file",
+            "scale_table"
+        ],
+        long,
+        value_hint = ValueHint::FilePath,
+        value_name = "FILE"
+    )]
+    full_decomposition: Option<PathBuf>,
+    /// Replace non-finite PDF values, for instance outside the PDF's region of validity, with
+    /// VALUE.
+    #[arg(allow_hyphen_values = true, long, value_name = "VALUE")]
+    fill_nan_with: Option<f64>,
+    /// Number of threads to utilize.
+    #[arg(default_value_t = 1, long)]
+    threads: usize,
+    /// Number of bins convolved by a single thread at a time. If not given, the bins are
+    /// distributed evenly over the threads.
+    #[arg(long, value_name = "BINS")]
+    chunk_bins: Option<NonZeroUsize>,
+    /// Abort instead of warning when the particle of a PDF set does not match the hadron of the
+    /// corresponding convolution of the grid.
+    #[arg(long)]
+    strict: bool,
+    /// Show the cumulative results of the leading and each higher order, and their ratios.
+    #[arg(
+        conflicts_with_all = [
+            "orders",
+            "max_as",
+            "max_a",
+            "scale_scan",
+            "scales_from_file",
+            "bin_corrections",
+            "write_predictions",
+            "embed_reference",
+            "full_decomposition"
+        ],
+        long
+    )]
+    convergence: bool,
+    /// Evaluate the PDFs and the strong coupling at all nodes of the grid before convolving it.
+    #[arg(conflicts_with = "convergence", long)]
+    precompute_tables: bool,
 }
 
 impl Subcommand for Opts {
     fn run(&self, cfg: &GlobalConfiguration) -> Result<ExitCode> {
-        let grid = helpers::read_grid(&self.input)?;
-        let mut conv_funs_0 = helpers::create_conv_funs(&self.conv_funs[0])?;
+        let mut grid = helpers::read_grid(&self.input)?;
+        let conv_funs = if self.conv_funs.is_empty() {
+            let name = grid
+                .key_values()
+                .and_then(|key_values| key_values.get("results_pdf"))
+                .ok_or_else(|| {
+                    anyhow!("no PDF set given and the grid does not have the metadata key `results_pdf`")
+                })?;
+
+            eprintln!("using PDF set '{name}' from the metadata key `results_pdf`");
+
+            vec![name.parse()?]
+        } else {
+            self.conv_funs.clone()
+        };
+
+        if !self.scale_scan.is_empty() && (conv_funs.len() > 1) {
+            bail!("`--scale-scan` can only be used with a single PDF set");
+        }
+
+        if self.convergence && (conv_funs.len() > 1) {
+            bail!("`--convergence` can only be used with a single PDF set");
+        }
+
+        if self.scales_from_file.is_some() && (conv_funs.len() > 1) {
+            bail!("`--scales-from-file` can only be used with a single PDF set");
+        }
+
+        let orders = if self.max_as.is_none() && self.max_a.is_none() {
+            self.orders.clone()
+        } else {
+            helpers::orders_with_max_powers(&grid, self.max_as, self.max_a)?
+        };
+
+        let scales: Vec<_> = if let Some(path) = &self.scales_from_file {
+            read_scale_factors(path)?
+        } else if self.scale_scan.is_empty() {
+            vec![(self.xir, self.xif)]
+        } else {
+            self.scale_scan
+                .iter()
+                .copied()
+                .cartesian_product(self.scale_scan.iter().copied())
+                .collect()
+        };
+
+        for scale in scales
+            .iter()
+            .flat_map(|&(xir, xif)| missing_scale_logs(&grid, &orders, xir, xif))
+            .unique()
+        {
+            eprintln!(
+                "warning: the {scale} scale is varied, but the selected orders do not contain the \
+                 logarithms needed for an exact variation"
+            );
+        }
+
+        if let Some(path) = &self.scale_table {
+            for scale in missing_scale_logs(&grid, &orders, 2.0, 2.0) {
+                eprintln!(
+                    "warning: the {scale} scale is replaced by the scale table, but the selected \
+                     orders do not contain the logarithms needed for an exact replacement"
+                );
+            }
+
+            grid.set_bin_scales(&read_scale_table(path)?)?;
+        }
+
         let bins: Vec<_> = self.bins.iter().cloned().flatten().collect();
+        let bin_corrections = self
+            .bin_corrections
+            .as_deref()
+            .map_or_else(|| Ok(Vec::new()), read_bin_corrections)?;
 
-        let results = helpers::convolve_scales(
-            &grid,
-            &mut conv_funs_0,
-            &self.orders,
+        if !bin_corrections.is_empty() && (bin_corrections.len() != grid.bin_info().bins()) {
+            bail!(
+                "the grid has {} bins, but {} bin corrections were given",
+                grid.bin_info().bins(),
+                bin_corrections.len()
+            );
+        }
+
+        // the grids whose convolutions match the particles of each PDF set
+        let checked_grids = conv_funs
+            .iter()
+            .map(|conv_funs| self.check_particles(&grid, conv_funs))
+            .collect::<Result<Vec<_>>>()?;
+
+        if self.convergence {
+            return self.print_convergence(&checked_grids[0], &conv_funs[0], &bins, cfg);
+        }
+
+        ThreadPoolBuilder::new()
+            .num_threads(self.threads)
+            .build_global()
+            .unwrap();
+
+        let results = self.convolve_chunked(
+            &checked_grids[0],
+            &conv_funs[0],
+            &orders,
             &bins,
-            &[],
-            &[(self.xir, self.xif)],
-            if self.integrated {
-                ConvoluteMode::Integrated
-            } else {
-                ConvoluteMode::Normal
-            },
+            &scales,
+            &bin_corrections,
             cfg,
-        );
+        )?;
         let limits = helpers::convolve_limits(
             &grid,
             &bins,
@@ -83,25 +293,94 @@ impl Subcommand for Opts {
         );
         let bin_count = limits.len();
 
-        let other_results: Vec<_> = self.conv_funs[1..]
+        if let Some(output) = &self.write_predictions {
+            let mut predictions_grid = if self.integrated {
+                grid.predictions_grid(
+                    &results
+                        .iter()
+                        .zip(grid.bin_info().normalizations())
+                        .map(|(result, normalization)| result / normalization)
+                        .collect::<Vec<_>>(),
+                )
+            } else {
+                grid.predictions_grid(&results)
+            };
+            predictions_grid.set_key_value("results_pdf", &conv_funs[0].label);
+            predictions_grid.set_key_value("prediction_xir", &self.xir.to_string());
+            predictions_grid.set_key_value("prediction_xif", &self.xif.to_string());
+
+            if !orders.is_empty() {
+                predictions_grid.set_key_value(
+                    "prediction_orders",
+                    &orders
+                        .iter()
+                        .map(|(alphas, alpha)| format!("as{alphas}a{alpha}"))
+                        .join(","),
+                );
+            }
+
+            helpers::write_grid(output, &predictions_grid)?;
+        }
+
+        if let Some(output) = &self.embed_reference {
+            let mut grid = grid.clone();
+            let results = if self.integrated {
+                results
+                    .iter()
+                    .zip(grid.bin_info().normalizations())
+                    .map(|(result, normalization)| result / normalization)
+                    .collect()
+            } else {
+                results.clone()
+            };
+
+            grid.set_reference(&Reference {
+                pdf: conv_funs[0].label.clone(),
+                results,
+            })?;
+
+            helpers::write_grid(output, &grid)?;
+        }
+
+        if let Some(output) = &self.full_decomposition {
+            let grid = &checked_grids[0];
+            let mut full = helpers::convolve_full(
+                grid,
+                &mut helpers::create_conv_funs(&conv_funs[0])?,
+                (self.xir, self.xif),
+                self.fill_nan_with,
+                cfg,
+            );
+
+            if self.integrated {
+                for (mut lane, normalization) in full
+                    .axis_iter_mut(Axis(2))
+                    .zip(grid.bin_info().normalizations())
+                {
+                    lane *= normalization;
+                }
+            }
+
+            helpers::write_npy(output, &full)?;
+        }
+
+        let other_results: Vec<_> = conv_funs[1..]
             .iter()
-            .flat_map(|conv_funs| {
-                let mut conv_funs = helpers::create_conv_funs(conv_funs).unwrap();
-                helpers::convolve(
-                    &grid,
-                    &mut conv_funs,
-                    &self.orders,
+            .zip(&checked_grids[1..])
+            .map(|(conv_funs, grid)| {
+                self.convolve_chunked(
+                    grid,
+                    conv_funs,
+                    &orders,
                     &bins,
-                    &[],
-                    1,
-                    if self.integrated {
-                        ConvoluteMode::Integrated
-                    } else {
-                        ConvoluteMode::Normal
-                    },
+                    &scales,
+                    &bin_corrections,
                     cfg,
                 )
             })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
             .collect();
 
         let (x, y_label, y_unit) = helpers::labels_and_units(&grid, self.integrated);
@@ -112,9 +391,15 @@ impl Subcommand for Opts {
             cell.set_hspan(2);
             title.add_cell(cell);
         }
-        title.add_cell(cell!(c->format!("{y_label}\n[{y_unit}]")));
+        if self.scale_scan.is_empty() && self.scales_from_file.is_none() {
+            title.add_cell(cell!(c->format!("{y_label}\n[{y_unit}]")));
+        } else {
+            for (xir, xif) in &scales {
+                title.add_cell(cell!(c->format!("(r={xir},f={xif})\n[{y_unit}]")));
+            }
+        }
 
-        for other in self.conv_funs[1..].iter().map(|conv_funs| &conv_funs.label) {
+        for other in conv_funs[1..].iter().map(|conv_funs| &conv_funs.label) {
             let mut cell = cell!(c->format!("{other}\n[{y_unit}] [%]"));
             cell.set_hspan(2);
             title.add_cell(cell);
@@ -123,7 +408,11 @@ impl Subcommand for Opts {
         let mut table = helpers::create_table();
         table.set_titles(title);
 
-        for (index, (limits, value)) in limits.into_iter().zip(results.iter()).enumerate() {
+        for (index, (limits, values)) in limits
+            .into_iter()
+            .zip(results.chunks_exact(scales.len()))
+            .enumerate()
+        {
             let bin = if bins.is_empty() { index } else { bins[index] };
 
             let row = table.add_empty_row();
@@ -133,12 +422,14 @@ impl Subcommand for Opts {
                 row.add_cell(cell!(r->format!("{left}")));
                 row.add_cell(cell!(r->format!("{right}")));
             }
-            row.add_cell(cell!(r->format!("{:.*e}", self.digits_abs, value)));
+            for value in values {
+                row.add_cell(cell!(r->format!("{:.*e}", self.digits_abs, value)));
+            }
 
             for &other in other_results.iter().skip(index).step_by(bin_count) {
                 row.add_cell(cell!(r->format!("{:.*e}", self.digits_abs, other)));
                 row.add_cell(
-                    cell!(r->format!("{:.*}", self.digits_rel, (other / value - 1.0) * 100.0)),
+                    cell!(r->format!("{:.*}", self.digits_rel, (other / values[0] - 1.0) * 100.0)),
                 );
             }
         }
@@ -148,3 +439,359 @@ impl Subcommand for Opts {
         Ok(ExitCode::SUCCESS)
     }
 }
+
+impl Opts {
+    /// Compare the particles of the PDF sets in `conv_funs`, given by the LHAPDF field `Particle`,
+    /// with the hadrons of the convolutions of `grid`. A mismatch is an error if `--strict` is
+    /// given, otherwise a warning is printed and a copy of `grid` is returned whose convolutions
+    /// are changed to the particles of the PDF sets.
+    fn check_particles<'a>(&self, grid: &'a Grid, conv_funs: &ConvFuns) -> Result<Cow<'a, Grid>> {
+        let pdfs = helpers::create_conv_funs(conv_funs)?;
+        let mut grid = Cow::Borrowed(grid);
+
+        for (index, convolution) in grid.convolutions().into_iter().enumerate() {
+            let Some(pid) = convolution.pid() else {
+                continue;
+            };
+            // a single PDF set is used for all convolutions
+            let pdf_index = index.min(pdfs.len() - 1);
+            let particle = helpers::pdf_particle(&pdfs[pdf_index])?;
+
+            if (particle == pid) || (particle == pids::charge_conjugate_pdg_pid(pid)) {
+                continue;
+            }
+
+            let message = format!(
+                "the PDF set '{}' is for particle {particle}, but convolution {index} of the grid \
+                 expects {pid}",
+                conv_funs.lhapdf_names[pdf_index]
+            );
+
+            if self.strict {
+                bail!(message);
+            }
+
+            eprintln!("warning: {message}");
+
+            let convolution = match convolution {
+                Convolution::None => unreachable!(),
+                Convolution::UnpolPDF(_) => Convolution::UnpolPDF(particle),
+                Convolution::PolPDF(_) => Convolution::PolPDF(particle),
+                Convolution::UnpolFF(_) => Convolution::UnpolFF(particle),
+                Convolution::PolFF(_) => Convolution::PolFF(particle),
+            };
+            grid.to_mut().set_convolution(index, convolution);
+        }
+
+        Ok(grid)
+    }
+
+    /// Print, for each of the bins `bins` of `grid`, or all of them if `bins` is empty, the
+    /// cumulative results of the leading and each higher order convolved with `conv_funs`, and
+    /// the ratios of successive ones. The orders are grouped by the sum of their coupling powers.
+    fn print_convergence(
+        &self,
+        grid: &Grid,
+        conv_funs: &ConvFuns,
+        bins: &[usize],
+        cfg: &GlobalConfiguration,
+    ) -> Result<ExitCode> {
+        let full = helpers::convolve_full(
+            grid,
+            &mut helpers::create_conv_funs(conv_funs)?,
+            (self.xir, self.xif),
+            self.fill_nan_with,
+            cfg,
+        );
+        // the results of each order and bin, summed over all channels
+        let results = full.sum_axis(Axis(1));
+
+        let powers: Vec<_> = grid
+            .orders()
+            .iter()
+            .map(|order| order.alphas + order.alpha)
+            .collect();
+        let levels: Vec<_> = powers.iter().copied().sorted().dedup().collect();
+        let (Some(&lo_power), Some(&max_power)) = (levels.first(), levels.last()) else {
+            bail!("the grid does not contain any orders");
+        };
+
+        for power in (lo_power..=max_power).filter(|power| !levels.contains(power)) {
+            eprintln!(
+                "warning: the grid does not contain orders with a coupling power of {power}, which \
+                 are skipped"
+            );
+        }
+
+        let order_label = |power: u32| match power - lo_power {
+            0 => "LO".to_owned(),
+            1 => "NLO".to_owned(),
+            2 => "NNLO".to_owned(),
+            n => format!("N{n}LO"),
+        };
+
+        let mode = if self.integrated {
+            ConvoluteMode::Integrated
+        } else {
+            ConvoluteMode::Normal
+        };
+        let normalizations = grid.bin_info().normalizations();
+        let (x, _, y_unit) = helpers::labels_and_units(grid, self.integrated);
+        let mut title = Row::empty();
+        title.add_cell(cell!(c->"b"));
+        for (x_label, x_unit) in x {
+            let mut cell = cell!(c->format!("{x_label}\n[{x_unit}]"));
+            cell.set_hspan(2);
+            title.add_cell(cell);
+        }
+        for &power in &levels {
+            title.add_cell(cell!(c->format!("{}\n[{y_unit}]", order_label(power))));
+        }
+        for (&lower, &higher) in levels.iter().tuple_windows() {
+            title.add_cell(cell!(c->format!("{}/{}\n[]", order_label(higher), order_label(lower))));
+        }
+
+        let mut table = helpers::create_table();
+        table.set_titles(title);
+
+        for (bin, limits) in (0..grid.bin_info().bins())
+            .filter(|bin| bins.is_empty() || bins.contains(bin))
+            .zip(helpers::convolve_limits(grid, bins, mode))
+        {
+            let factor = if self.integrated {
+                normalizations[bin]
+            } else {
+                1.0
+            };
+            let cumulative: Vec<_> = levels
+                .iter()
+                .map(|&level| {
+                    powers
+                        .iter()
+                        .enumerate()
+                        .filter(|&(_, &power)| power <= level)
+                        .map(|(order, _)| results[[order, bin]])
+                        .sum::<f64>()
+                        * factor
+                })
+                .collect();
+
+            let row = table.add_empty_row();
+            row.add_cell(cell!(r->format!("{bin}")));
+            for (left, right) in limits {
+                row.add_cell(cell!(r->format!("{left}")));
+                row.add_cell(cell!(r->format!("{right}")));
+            }
+            for value in &cumulative {
+                row.add_cell(cell!(r->format!("{:.*e}", self.digits_abs, value)));
+            }
+            for (lower, higher) in cumulative.iter().tuple_windows() {
+                row.add_cell(cell!(r->format!("{:.*}", self.digits_rel, higher / lower)));
+            }
+        }
+
+        table.printstd();
+
+        Ok(ExitCode::SUCCESS)
+    }
+
+    /// Convolves the orders `orders` and bins `bins` of `grid`, all of them if they are empty,
+    /// with `conv_funs`. The bins are split into chunks that are convolved in parallel, each with
+    /// its own instances of the convolution functions. The results are ordered as the ones of
+    /// [`helpers::convolve_scales`].
+    fn convolve_chunked(
+        &self,
+        grid: &Grid,
+        conv_funs: &ConvFuns,
+        orders: &[(u32, u32)],
+        bins: &[usize],
+        scales: &[(f64, f64)],
+        bin_corrections: &[f64],
+        cfg: &GlobalConfiguration,
+    ) -> Result<Vec<f64>> {
+        let mode = if self.integrated {
+            ConvoluteMode::Integrated
+        } else {
+            ConvoluteMode::Normal
+        };
+
+        if (self.threads == 1) && self.chunk_bins.is_none() {
+            let mut conv_funs = helpers::create_conv_funs(conv_funs)?;
+
+            return Ok(helpers::convolve_scales(
+                grid,
+                &mut conv_funs,
+                orders,
+                bins,
+                &[],
+                scales,
+                bin_corrections,
+                mode,
+                self.fill_nan_with,
+                self.precompute_tables,
+                cfg,
+            ));
+        }
+
+        let bins: Vec<_> = if bins.is_empty() {
+            (0..grid.bin_info().bins()).collect()
+        } else {
+            bins.to_vec()
+        };
+        let threads = self.threads.max(1);
+        let chunk_size = self.chunk_bins.map_or_else(
+            || ((bins.len() + threads - 1) / threads).max(1),
+            NonZeroUsize::get,
+        );
+
+        Ok(bins
+            .par_chunks(chunk_size)
+            .map(|chunk| {
+                let mut conv_funs = helpers::create_conv_funs(conv_funs)?;
+
+                Ok(helpers::convolve_scales(
+                    grid,
+                    &mut conv_funs,
+                    orders,
+                    chunk,
+                    &[],
+                    scales,
+                    bin_corrections,
+                    mode,
+                    self.fill_nan_with,
+                    self.precompute_tables,
+                    cfg,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+}
+
+/// Read the bin corrections from the file `path`, which contains numbers separated by whitespace.
+fn read_bin_corrections(path: &Path) -> Result<Vec<f64>> {
+    fs::read_to_string(path)
+        .context(format!("unable to read '{}'", path.display()))?
+        .split_whitespace()
+        .map(|factor| {
+            factor
+                .parse()
+                .context(format!("unable to parse bin correction '{factor}'"))
+        })
+        .collect()
+}
+
+/// Read the scale factors from the file `path`. Every non-empty line that isn't a comment starting
+/// with `#` contains the factors of the renormalization, the factorization and optionally the
+/// fragmentation scale, separated by commas. Since grids don't support a separate fragmentation
+/// scale, its factor must be `1`.
+fn read_scale_factors(path: &Path) -> Result<Vec<(f64, f64)>> {
+    let scales = fs::read_to_string(path)
+        .context(format!("unable to read '{}'", path.display()))?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let factors = line
+                .split(',')
+                .map(|factor| factor.trim().parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .context(format!("unable to parse scale factors '{line}'"))?;
+
+            match factors[..] {
+                [xir, xif] => Ok((xir, xif)),
+                [xir, xif, xia] if approx_eq!(f64, xia, 1.0, ulps = 4) => Ok((xir, xif)),
+                [_, _, _] => bail!(
+                    "unable to vary the fragmentation scale in '{line}', because grids don't \
+                     support it"
+                ),
+                _ => bail!("expected two or three scale factors in '{line}'"),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if scales.is_empty() {
+        bail!("no scale factors found in '{}'", path.display());
+    }
+
+    Ok(scales)
+}
+
+/// Read the scale table from the file `path`. Every non-empty line that isn't a comment starting
+/// with `#` contains the renormalization and the factorization scale of a single bin, separated by
+/// a comma. The scales are returned squared.
+fn read_scale_table(path: &Path) -> Result<Vec<Mu2>> {
+    fs::read_to_string(path)
+        .context(format!("unable to read '{}'", path.display()))?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (ren, fac) = line
+                .split_once(',')
+                .ok_or_else(|| anyhow!("unable to parse scales '{line}'"))?;
+            let ren: f64 = ren
+                .trim()
+                .parse()
+                .context(format!("unable to parse scales '{line}'"))?;
+            let fac: f64 = fac
+                .trim()
+                .parse()
+                .context(format!("unable to parse scales '{line}'"))?;
+
+            Ok(Mu2 {
+                ren: ren * ren,
+                fac: fac * fac,
+            })
+        })
+        .collect()
+}
+
+/// Return the names of the scales whose variations by `xir` and `xif` require logarithms that the
+/// orders of `grid` selected by `orders` don't have. The factorization-scale logarithms are needed
+/// as soon as there are corrections to the lowest order, and the renormalization-scale
+/// logarithms are needed if the corrected orders already depend on the strong coupling.
+fn missing_scale_logs(grid: &Grid, orders: &[(u32, u32)], xir: f64, xif: f64) -> Vec<&'static str> {
+    let selected: Vec<_> = grid
+        .orders()
+        .iter()
+        .filter(|order| orders.is_empty() || orders.contains(&(order.alphas, order.alpha)))
+        .collect();
+    let has_order = |alphas, alpha| {
+        selected.iter().any(|order| {
+            (order.alphas == alphas)
+                && (order.alpha == alpha)
+                && (order.logxir == 0)
+                && (order.logxif == 0)
+        })
+    };
+    // the orders with QCD corrections to the next-lower order in the strong coupling
+    let corrections: Vec<_> = selected
+        .iter()
+        .filter(|order| {
+            (order.logxir == 0)
+                && (order.logxif == 0)
+                && (order.alphas > 0)
+                && has_order(order.alphas - 1, order.alpha)
+        })
+        .collect();
+    let mut result = Vec::new();
+
+    if !approx_eq!(f64, xir, 1.0, ulps = 4)
+        && corrections.iter().any(|order| order.alphas > 1)
+        && !selected.iter().any(|order| order.logxir > 0)
+    {
+        result.push("renormalization");
+    }
+
+    if !approx_eq!(f64, xif, 1.0, ulps = 4)
+        && !corrections.is_empty()
+        && !selected.iter().any(|order| order.logxif > 0)
+    {
+        result.push("factorization");
+    }
+
+    result
+}
@@ -21,6 +21,9 @@ pub struct Opts {
     /// Ignore differences in the orders and sum them.
     #[arg(long)]
     ignore_orders: bool,
+    /// Compare the orders present in both grids one by one and report the others.
+    #[arg(conflicts_with = "ignore_orders", long)]
+    orders: bool,
     /// Ignore bin limits (but not number of bins).
     #[arg(long)]
     ignore_bin_limits: bool,
@@ -109,7 +112,18 @@ impl Subcommand for Opts {
         orders2.sort_unstable();
         let orders2 = orders2;
 
-        if !self.ignore_orders && (!diff1.is_empty() || !diff2.is_empty()) {
+        if self.orders {
+            for (order, grid) in diff1
+                .iter()
+                .map(|order| (order, "first"))
+                .chain(diff2.iter().map(|order| (order, "second")))
+            {
+                eprintln!(
+                    "warning: order O(as^{} a^{}) is only present in the {grid} grid",
+                    order.alphas, order.alpha
+                );
+            }
+        } else if !self.ignore_orders && (!diff1.is_empty() || !diff2.is_empty()) {
             bail!("selected orders differ");
         }
 
@@ -189,7 +203,15 @@ impl Subcommand for Opts {
                 if result1 == result2 { 0.0 } else { result2 / result1 - 1.0 })));
             }
         } else {
-            let orders = orders1;
+            // without `--orders` both grids have the same orders
+            let orders: Vec<_> = orders1
+                .into_iter()
+                .filter(|order| orders2.contains(order))
+                .collect();
+
+            if orders.is_empty() {
+                bail!("grids have no orders in common");
+            }
 
             for (alphas, alpha) in &orders {
                 let mut cell = cell!(c->format!("O(as^{alphas} a^{alpha})"));
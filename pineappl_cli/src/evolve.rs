@@ -25,11 +25,12 @@ mod eko {
     use serde::Deserialize;
     use std::collections::HashMap;
     use std::ffi::OsString;
-    use std::fs::File;
+    use std::fs::{self, File};
     use std::io::{BufReader, Cursor, Read};
     use std::iter::Zip;
-    use std::path::Path;
+    use std::path::{Path, PathBuf};
     use std::slice::Iter;
+    use std::vec::IntoIter;
     use tar::{Archive, Entries};
 
     #[derive(Deserialize)]
@@ -97,6 +98,56 @@ mod eko {
         bases: BasesV1,
     }
 
+    /// Call `f` with the path and the contents of each file of the EKO at `eko_path`, which is
+    /// either a tarball or a directory with the unpacked contents of one. Paths of files in a
+    /// directory are prefixed with `./`, as they are in the tarballs.
+    fn for_each_entry(
+        eko_path: &Path,
+        f: &mut dyn FnMut(&Path, &mut dyn Read) -> Result<()>,
+    ) -> Result<()> {
+        if eko_path.is_dir() {
+            for_each_file(eko_path, Path::new("."), f)
+        } else {
+            for entry in Archive::new(File::open(eko_path)?).entries_with_seek()? {
+                let mut entry = entry?;
+                let path = entry.path()?.into_owned();
+
+                f(&path, &mut entry)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn for_each_file(
+        dir: &Path,
+        prefix: &Path,
+        f: &mut dyn FnMut(&Path, &mut dyn Read) -> Result<()>,
+    ) -> Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = prefix.join(entry.file_name());
+
+            if entry.file_type()?.is_dir() {
+                for_each_file(&entry.path(), &path, f)?;
+            } else {
+                f(&path, &mut File::open(entry.path())?)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read an operator slice from an LZ4-compressed NPZ file.
+    fn read_operator(reader: impl Read) -> Result<Array4<f64>> {
+        let mut reader = FrameDecoder::new(BufReader::new(reader));
+        let mut buffer = Vec::new();
+        let _ = reader.read_to_end(&mut buffer)?;
+        let mut npz = NpzReader::new(Cursor::new(buffer))?;
+
+        Ok(npz.by_name("operator.npy")?)
+    }
+
     pub enum EkoSlices {
         V0 {
             fac1: Vec<f64>,
@@ -109,22 +160,29 @@ mod eko {
             info: OperatorSliceInfo,
             archive: Archive<File>,
         },
+        // V1 or V2 unpacked into a directory, with one file per operator slice
+        Dir {
+            fac1: HashMap<OsString, f64>,
+            info: OperatorSliceInfo,
+            path: PathBuf,
+        },
     }
 
     impl EkoSlices {
         /// Read the EKO at `eko_path` and return the contents of the `metadata.yaml` file
         /// deserialized into a [`Metadata`] object.
         fn read_metadata(eko_path: &Path) -> Result<Metadata> {
-            for entry in Archive::new(File::open(eko_path)?).entries_with_seek()? {
-                let entry = entry?;
-                let path = entry.path()?;
+            let mut metadata = None;
 
-                if path.ends_with("metadata.yaml") {
-                    return Ok(serde_yaml::from_reader(entry)?);
+            for_each_entry(eko_path, &mut |path, reader| {
+                if metadata.is_none() && path.ends_with("metadata.yaml") {
+                    metadata = Some(serde_yaml::from_reader(reader)?);
                 }
-            }
 
-            Err(anyhow!("no file 'metadata.yaml' in EKO archive found"))
+                Ok(())
+            })?;
+
+            metadata.ok_or_else(|| anyhow!("no file 'metadata.yaml' in EKO archive found"))
         }
 
         pub fn new(eko_path: &Path) -> Result<Self> {
@@ -140,14 +198,13 @@ mod eko {
         fn with_v0(metadata: MetadataV0, eko_path: &Path) -> Result<Self> {
             let mut operator = None;
 
-            for entry in Archive::new(File::open(eko_path)?).entries_with_seek()? {
-                let entry = entry?;
-                let path = entry.path()?;
-
+            for_each_entry(eko_path, &mut |path, reader| {
                 if path.ends_with("operators.npy.lz4") {
-                    operator = Some(Array5::read_npy(FrameDecoder::new(BufReader::new(entry)))?);
+                    operator = Some(Array5::read_npy(FrameDecoder::new(BufReader::new(reader)))?);
                 }
-            }
+
+                Ok(())
+            })?;
 
             let operator =
                 operator.ok_or_else(|| anyhow!("no file 'operator.yaml' in EKO archive found"))?;
@@ -171,10 +228,7 @@ mod eko {
             let mut fac1 = HashMap::new();
             let base64 = GeneralPurpose::new(&URL_SAFE, PAD);
 
-            for entry in Archive::new(File::open(eko_path)?).entries_with_seek()? {
-                let entry = entry?;
-                let path = entry.path()?;
-
+            for_each_entry(eko_path, &mut |path, _| {
                 if path.starts_with("./operators")
                     && (path.extension().is_some_and(|ext| ext == "lz4"))
                     && (path
@@ -185,7 +239,7 @@ mod eko {
                     let Some(file_stem) =
                         path.with_extension("").file_stem().map(ToOwned::to_owned)
                     else {
-                        continue;
+                        return Ok(());
                     };
 
                     let bytes = base64.decode(file_stem.to_string_lossy().as_bytes())?;
@@ -195,7 +249,9 @@ mod eko {
 
                     fac1.insert(file_stem, scale);
                 }
-            }
+
+                Ok(())
+            })?;
 
             let pids0 = metadata.rotations.inputpids.map_or_else(
                 || metadata.rotations.pids.clone(),
@@ -221,9 +277,9 @@ mod eko {
                 },
             );
 
-            Ok(Self::V2 {
+            Self::with_slices(
                 fac1,
-                info: OperatorSliceInfo {
+                OperatorSliceInfo {
                     pid_basis: PidBasis::guess(&pids0),
                     fac0: metadata.mu20,
                     pids0,
@@ -241,31 +297,30 @@ mod eko {
                         .targetgrid
                         .unwrap_or(metadata.rotations.xgrid),
                 },
-                archive: Archive::new(File::open(eko_path)?),
-            })
+                eko_path,
+            )
         }
 
         fn with_v2(metadata: MetadataV2, eko_path: &Path) -> Result<Self> {
             let mut fac1 = HashMap::new();
             let mut operator: Option<OperatorV1> = None;
 
-            for entry in Archive::new(File::open(eko_path)?).entries_with_seek()? {
-                let entry = entry?;
-                let path = entry.path()?;
-
+            for_each_entry(eko_path, &mut |path, reader| {
                 if path.starts_with("./operators")
                     && (path.extension().is_some_and(|ext| ext == "yaml"))
                 {
                     let Some(file_stem) = path.file_stem().map(ToOwned::to_owned) else {
-                        continue;
+                        return Ok(());
                     };
 
-                    let op_info: OperatorInfoV1 = serde_yaml::from_reader(entry)?;
+                    let op_info: OperatorInfoV1 = serde_yaml::from_reader(reader)?;
                     fac1.insert(file_stem, op_info.scale);
                 } else if path.as_os_str() == "./operator.yaml" {
-                    operator = Some(serde_yaml::from_reader(entry)?);
+                    operator = Some(serde_yaml::from_reader(reader)?);
                 }
-            }
+
+                Ok(())
+            })?;
 
             let operator =
                 operator.ok_or_else(|| anyhow!("no file 'operator.yaml' in EKO archive found"))?;
@@ -287,9 +342,9 @@ mod eko {
                 },
             );
 
-            Ok(Self::V2 {
+            Self::with_slices(
                 fac1,
-                info: OperatorSliceInfo {
+                OperatorSliceInfo {
                     pid_basis: PidBasis::guess(&pids0),
                     fac0: operator.mu0 * operator.mu0,
                     pids0,
@@ -307,8 +362,36 @@ mod eko {
                         .targetgrid
                         .unwrap_or_else(|| metadata.bases.xgrid.clone()),
                 },
-                archive: Archive::new(File::open(eko_path)?),
-            })
+                eko_path,
+            )
+        }
+
+        fn with_slices(
+            fac1: HashMap<OsString, f64>,
+            info: OperatorSliceInfo,
+            eko_path: &Path,
+        ) -> Result<Self> {
+            if eko_path.is_dir() {
+                Ok(Self::Dir {
+                    fac1,
+                    info,
+                    path: eko_path.to_owned(),
+                })
+            } else {
+                Ok(Self::V2 {
+                    fac1,
+                    info,
+                    archive: Archive::new(File::open(eko_path)?),
+                })
+            }
+        }
+
+        /// Return the factorization scales of the operator slices.
+        pub fn fac1(&self) -> Vec<f64> {
+            match self {
+                Self::V0 { fac1, .. } => fac1.clone(),
+                Self::V2 { fac1, .. } | Self::Dir { fac1, .. } => fac1.values().copied().collect(),
+            }
         }
 
         pub fn iter_mut(&mut self) -> EkoSlicesIter {
@@ -334,6 +417,20 @@ mod eko {
                         entries: archive.entries_with_seek().unwrap(),
                     }
                 }
+                Self::Dir { fac1, info, path } => {
+                    let mut slices: Vec<_> = fac1
+                        .iter()
+                        .map(|(file_stem, &fac1)| (file_stem.clone(), fac1))
+                        .collect();
+                    // read the slices in ascending order of their factorization scales
+                    slices.sort_by(|(_, lhs), (_, rhs)| lhs.total_cmp(rhs));
+
+                    EkoSlicesIter::Dir {
+                        info: info.clone(),
+                        path: path.clone(),
+                        slices: slices.into_iter(),
+                    }
+                }
             }
         }
     }
@@ -357,6 +454,11 @@ mod eko {
             info: OperatorSliceInfo,
             entries: Entries<'a, File>,
         },
+        Dir {
+            info: OperatorSliceInfo,
+            path: PathBuf,
+            slices: IntoIter<(OsString, f64)>,
+        },
     }
 
     impl<'a> Iterator for EkoSlicesIter<'a> {
@@ -398,11 +500,7 @@ mod eko {
                                     continue;
                                 };
 
-                                let mut reader = FrameDecoder::new(BufReader::new(entry));
-                                let mut buffer = Vec::new();
-                                let _ = reader.read_to_end(&mut buffer)?;
-                                let mut npz = NpzReader::new(Cursor::new(buffer))?;
-                                let operator: Array4<f64> = npz.by_name("operator.npy")?;
+                                let operator = read_operator(entry)?;
 
                                 let mut info = info.clone();
                                 info.fac1 = fac1.get(&file_stem).copied().ok_or_else(|| anyhow!("file '{}.yaml' not found, could not determine the operator's factorization scale", file_stem.to_string_lossy()))?;
@@ -416,6 +514,17 @@ mod eko {
 
                     fun().transpose()
                 }
+                Self::Dir { info, path, slices } => slices.next().map(|(file_stem, fac1)| {
+                    let mut file_name = file_stem;
+                    file_name.push(".npz.lz4");
+                    let operator =
+                        read_operator(File::open(path.join("operators").join(file_name))?)?;
+
+                    let mut info = info.clone();
+                    info.fac1 = fac1;
+
+                    Ok((info, CowArray::from(operator)))
+                }),
             }
         }
     }
@@ -433,7 +542,9 @@ fn evolve_grid(
 ) -> Result<FkTable> {
     use anyhow::bail;
     use eko::EkoSlices;
+    use float_cmp::approx_eq;
     use pineappl::evolution::{AlphasTable, OperatorInfo};
+    use std::io::{self, IsTerminal};
 
     let order_mask: Vec<_> = grid
         .orders()
@@ -452,6 +563,35 @@ fn evolve_grid(
         .collect::<Result<_, _>>()?;
     let alphas_table = AlphasTable::from_grid(grid, xir, &|q2| use_alphas_from.alphas_q2(q2));
 
+    // operators stored as one file per slice may be incomplete, and since we know their scales
+    // without reading the slices themselves, check this before the expensive evolution
+    let grid_fac1 = grid.evolve_info(&order_mask).fac1;
+
+    for (eko, slices) in ekos.iter().zip(&eko_slices) {
+        if !eko.is_dir() {
+            continue;
+        }
+
+        let op_fac1 = slices.fac1();
+        let missing: Vec<_> = grid_fac1
+            .iter()
+            .map(|&fac1| xif * xif * fac1)
+            // use the same tolerance as `Grid::evolve_with_slice_iter`
+            .filter(|&fac1| {
+                !op_fac1
+                    .iter()
+                    .any(|&op| approx_eq!(f64, fac1, op, ulps = 256))
+            })
+            .collect();
+
+        if !missing.is_empty() {
+            bail!(
+                "operator directory '{}' has no slices for muf2 = {missing:?}",
+                eko.display()
+            );
+        }
+    }
+
     if use_old_evolve {
         assert_eq!(eko_slices.len(), 1);
 
@@ -481,16 +621,33 @@ fn evolve_grid(
             bail!("`--use-old-evolve` can only be used with the old EKO format (`V0`)")
         }
     } else {
-        match eko_slices.as_mut_slice() {
-            [eko] => {
-                Ok(grid.evolve_with_slice_iter(eko, &order_mask, (xir, xif), &alphas_table)?)
+        // show the progress only to users sitting in front of a terminal
+        let terminal = io::stderr().is_terminal();
+        let progress = |evolved: usize, total: usize| {
+            if terminal {
+                eprint!("\revolved {evolved} of {total} operator slices");
+
+                if evolved == total {
+                    eprintln!();
+                }
             }
-            [eko_a, eko_b] => Ok(grid.evolve_with_slice_iter2(
+        };
+
+        match eko_slices.as_mut_slice() {
+            [eko] => Ok(grid.evolve_with_slice_iter_with_progress(
+                eko,
+                &order_mask,
+                (xir, xif),
+                &alphas_table,
+                progress,
+            )?),
+            [eko_a, eko_b] => Ok(grid.evolve_with_slice_iter2_with_progress(
                 eko_a,
                 eko_b,
                 &order_mask,
                 (xir, xif),
                 &alphas_table,
+                progress,
             )?),
             _ => unimplemented!(
                 "evolution with {} EKOs is not implemented",
@@ -521,8 +678,8 @@ pub struct Opts {
     /// Path to the input grid.
     #[arg(value_hint = ValueHint::FilePath)]
     input: PathBuf,
-    /// Path to the evolution kernel operator.
-    #[arg(value_hint = ValueHint::FilePath)]
+    /// Path to the evolution kernel operator, or a directory with one file per slice.
+    #[arg(value_hint = ValueHint::AnyPath)]
     eko: PathBuf,
     /// Path to the converted grid.
     #[arg(value_hint = ValueHint::FilePath)]
@@ -573,7 +730,10 @@ impl Subcommand for Opts {
             &[],
             &[],
             &[(self.xir, self.xif)],
+            &[],
             ConvoluteMode::Normal,
+            None,
+            false,
             cfg,
         );
 
@@ -597,7 +757,10 @@ impl Subcommand for Opts {
             &[],
             &[],
             &[(1.0, 1.0)],
+            &[],
             ConvoluteMode::Normal,
+            None,
+            false,
             cfg,
         );
 
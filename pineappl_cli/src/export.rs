@@ -12,21 +12,33 @@ use std::process::ExitCode;
 #[cfg(feature = "applgrid")]
 mod applgrid;
 
+/// Name of the grid type, the results of the converted grid, the number of scale variations in
+/// the results, which orders were converted, and the results of the written file if it was
+/// verified.
+type ConversionResults = (&'static str, Vec<f64>, usize, Vec<bool>, Option<Vec<f64>>);
+
 #[cfg(feature = "applgrid")]
 fn convert_into_applgrid(
     output: &Path,
     grid: &Grid,
     conv_funs: &mut [Pdf],
+    orders: &[(u32, u32)],
     _: usize,
     discard_non_matching_scales: bool,
-) -> Result<(&'static str, Vec<f64>, usize, Vec<bool>)> {
+    verify: bool,
+) -> Result<ConversionResults> {
     // TODO: check also scale-varied results
 
     let (mut applgrid, order_mask) =
-        applgrid::convert_into_applgrid(grid, output, discard_non_matching_scales)?;
+        applgrid::convert_into_applgrid(grid, output, orders, discard_non_matching_scales)?;
     let results = applgrid::convolve_applgrid(applgrid.pin_mut(), conv_funs);
+    let written_results = if verify {
+        Some(applgrid::convolve_written_applgrid(output, conv_funs)?)
+    } else {
+        None
+    };
 
-    Ok(("APPLgrid", results, 1, order_mask))
+    Ok(("APPLgrid", results, 1, order_mask, written_results))
 }
 
 #[cfg(not(feature = "applgrid"))]
@@ -34,9 +46,11 @@ fn convert_into_applgrid(
     _: &Path,
     _: &Grid,
     _: &mut [Pdf],
+    _: &[(u32, u32)],
     _: usize,
     _: bool,
-) -> Result<(&'static str, Vec<f64>, usize, Vec<bool>)> {
+    _: bool,
+) -> Result<ConversionResults> {
     Err(anyhow!(
         "you need to install `pineappl` with feature `applgrid`"
     ))
@@ -46,17 +60,21 @@ fn convert_into_grid(
     output: &Path,
     grid: &Grid,
     conv_funs: &mut [Pdf],
+    orders: &[(u32, u32)],
     scales: usize,
     discard_non_matching_scales: bool,
-) -> Result<(&'static str, Vec<f64>, usize, Vec<bool>)> {
+    verify: bool,
+) -> Result<ConversionResults> {
     if let Some(extension) = output.extension() {
         if extension == "appl" || extension == "root" {
             return convert_into_applgrid(
                 output,
                 grid,
                 conv_funs,
+                orders,
                 scales,
                 discard_non_matching_scales,
+                verify,
             );
         }
     }
@@ -64,6 +82,15 @@ fn convert_into_grid(
     Err(anyhow!("could not detect file format"))
 }
 
+fn rel_diff(a: f64, b: f64) -> f64 {
+    // catches the case where both results are zero
+    if a == b {
+        0.0
+    } else {
+        b / a - 1.0
+    }
+}
+
 /// Converts PineAPPL grids to APPLgrid files.
 #[derive(Parser)]
 pub struct Opts {
@@ -75,12 +102,24 @@ pub struct Opts {
     output: PathBuf,
     /// LHAPDF ID(s) or name of the PDF(s)/FF(s) to check the converted grid with.
     conv_funs: ConvFuns,
+    /// Select the orders that should be exported.
+    #[arg(
+        long,
+        num_args = 1,
+        short,
+        value_delimiter = ',',
+        value_parser = helpers::parse_order
+    )]
+    orders: Vec<(u32, u32)>,
     /// Relative threshold between the table and the converted grid when comparison fails.
     #[arg(default_value = "1e-10", long)]
     accuracy: f64,
     /// Discard non-matching scales that would otherwise lead to panics.
     #[arg(long)]
     discard_non_matching_scales: bool,
+    /// Read the written file again and compare its predictions with the ones of the input grid.
+    #[arg(long)]
+    verify: bool,
     /// Set the number of scale variations to compare with if they are available.
     #[arg(
         default_value_t = 7,
@@ -105,13 +144,16 @@ impl Subcommand for Opts {
         let mut conv_funs = helpers::create_conv_funs(&self.conv_funs)?;
 
         // TODO: figure out `member` from `self.pdfset`
-        let (grid_type, results, scale_variations, order_mask) = convert_into_grid(
-            &self.output,
-            &grid,
-            &mut conv_funs,
-            self.scales,
-            self.discard_non_matching_scales,
-        )?;
+        let (grid_type, results, scale_variations, order_mask, written_results) =
+            convert_into_grid(
+                &self.output,
+                &grid,
+                &mut conv_funs,
+                &self.orders,
+                self.scales,
+                self.discard_non_matching_scales,
+                self.verify,
+            )?;
 
         for Order {
             alphas,
@@ -123,6 +165,10 @@ impl Subcommand for Opts {
             .iter()
             .zip(order_mask.iter())
             .filter_map(|(order, keep)| (!keep).then_some(order.clone()))
+            // don't warn about orders that weren't selected
+            .filter(|order| {
+                self.orders.is_empty() || self.orders.contains(&(order.alphas, order.alpha))
+            })
         {
             println!("WARNING: the order O(as^{alphas} a^{alpha} lr^{logxir} lf^{logxif}) isn't supported by {grid_type} and will be skipped.");
         }
@@ -179,11 +225,10 @@ impl Subcommand for Opts {
                 .zip(reference_results.chunks_exact(scale_variations))
                 .enumerate()
             {
-                // catches the case where both results are zero
                 let rel_diffs: Vec<_> = one
                     .iter()
                     .zip(two.iter())
-                    .map(|(a, b)| if a == b { 0.0 } else { b / a - 1.0 })
+                    .map(|(&a, &b)| rel_diff(a, b))
                     .collect();
 
                 let max_rel_diff = rel_diffs
@@ -211,6 +256,26 @@ impl Subcommand for Opts {
             }
 
             table.printstd();
+
+            if let Some(written_results) = written_results {
+                // UNWRAP: there's at least one bin
+                let (bin, max_rel_diff) = written_results
+                    .iter()
+                    .zip(reference_results.iter().step_by(scale_variations))
+                    .map(|(&a, &b)| rel_diff(a, b).abs())
+                    .enumerate()
+                    .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                    .unwrap();
+
+                println!(
+                    "largest relative difference of the written file: {:.*e} in bin {bin}",
+                    self.digits_rel, max_rel_diff
+                );
+
+                if max_rel_diff.is_nan() || (max_rel_diff > self.accuracy) {
+                    return Err(anyhow!("the written file differs from the input grid"));
+                }
+            }
         }
 
         if different {
@@ -53,6 +53,7 @@ fn reconstruct_subgrid_params(grid: &Grid, order: usize, bin: usize) -> Result<S
 pub fn convert_into_applgrid(
     grid: &Grid,
     output: &Path,
+    orders: &[(u32, u32)],
     discard_non_matching_scales: bool,
 ) -> Result<(UniquePtr<grid>, Vec<bool>)> {
     let bin_info = grid.bin_info();
@@ -114,7 +115,50 @@ pub fn convert_into_applgrid(
         .chain(limits.last().map(|vec| vec[0].1))
         .collect();
 
-    let order_mask = Order::create_mask(grid.orders(), 3, 0, false);
+    let mut order_mask = Order::create_mask(grid.orders(), 3, 0, false);
+
+    if !orders.is_empty() {
+        let supported_mask = order_mask.clone();
+
+        for ((order, keep), &supported) in grid
+            .orders()
+            .iter()
+            .zip(order_mask.iter_mut())
+            .zip(&supported_mask)
+        {
+            let selected = orders.contains(&(order.alphas, order.alpha));
+
+            if selected && !supported && (order.logxir == 0) && (order.logxif == 0) {
+                bail!(
+                    "selected order O(as^{} a^{}), which APPLgrid does not support",
+                    order.alphas,
+                    order.alpha
+                );
+            }
+
+            *keep = *keep && selected;
+        }
+
+        let mut selected: Vec<_> = grid
+            .orders()
+            .iter()
+            .zip(&order_mask)
+            .filter_map(|(order, &keep)| keep.then_some(order.alphas))
+            .collect();
+        selected.sort_unstable();
+
+        if selected.is_empty() {
+            bail!("grid does not contain any of the selected orders");
+        }
+
+        // APPLgrid counts the orders with its number of loops, so they must be consecutive
+        if selected.windows(2).any(|alphas| alphas[1] != alphas[0] + 1) {
+            bail!(
+                "selected orders have non-consecutive powers of alphas, which APPLgrid does not support"
+            );
+        }
+    }
+
     let orders_with_mask: Vec<_> = grid
         .orders()
         .iter()
@@ -311,3 +355,11 @@ pub fn convolve_applgrid(grid: Pin<&mut grid>, conv_funs: &mut [Pdf]) -> Vec<f64
 
     pineappl_applgrid::grid_convolve_with_one(grid, &mut conv_funs[0], nloops, 1.0, 1.0, 1.0)
 }
+
+/// Reads the APPLgrid written to `output` and convolves it with `conv_funs`, which checks that
+/// the file contains the same grid that was converted in memory.
+pub fn convolve_written_applgrid(output: &Path, conv_funs: &mut [Pdf]) -> Result<Vec<f64>> {
+    let mut grid = ffi::make_grid(output.to_str().unwrap())?;
+
+    Ok(convolve_applgrid(grid.pin_mut(), conv_funs))
+}
@@ -1,16 +1,20 @@
 use super::GlobalConfiguration;
 use anyhow::{anyhow, ensure, Context, Error, Result};
 use lhapdf::{Pdf, PdfSet};
-use ndarray::Array3;
-use pineappl::convolutions::LumiCache;
-use pineappl::grid::Grid;
+use ndarray::{Array, Array3, Dimension};
+use pineappl::convolutions::{LumiCache, XfxTable};
+use pineappl::evolution::AlphasTable;
+use pineappl::grid::{ConvolveOptions, DimLabel, Grid};
 use prettytable::format::{FormatBuilder, LinePosition, LineSeparator};
 use prettytable::Table;
-use std::fs::{File, OpenOptions};
+use std::cell::Cell;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
 use std::iter;
 use std::ops::RangeInclusive;
 use std::path::Path;
 use std::process::ExitCode;
+use std::slice;
 use std::str::FromStr;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -97,19 +101,64 @@ pub fn create_conv_funs_for_set(
     Ok((set, conv_funs))
 }
 
+/// Return the PDG MC ID of the particle described by `pdf`, which is given by the LHAPDF field
+/// `Particle`.
+pub fn pdf_particle(pdf: &Pdf) -> Result<i32> {
+    // if the field 'Particle' is missing we assume it's a proton PDF
+    pdf.set().entry("Particle").map_or(Ok(2212), |string| {
+        string.parse().context(format!(
+            "unable to parse the particle '{string}' of the PDF set"
+        ))
+    })
+}
+
 pub fn read_grid(input: &Path) -> Result<Grid> {
     Grid::read(File::open(input).context(format!("unable to open '{}'", input.display()))?)
         .context(format!("unable to read '{}'", input.display()))
 }
 
+pub fn read_grid_header(input: &Path) -> Result<Grid> {
+    Ok(Grid::read_header(
+        File::open(input).context(format!("unable to open '{}'", input.display()))?,
+    )
+    .context(format!("unable to read '{}'", input.display()))?
+    .into_grid())
+}
+
 pub fn write_grid(output: &Path, grid: &Grid) -> Result<ExitCode> {
-    let file = OpenOptions::new()
-        .write(true)
-        .create_new(true)
-        .open(output)
-        .context(format!("unable to write '{}'", output.display()))?;
+    write_grid_compressed(output, grid, None)
+}
+
+/// Write `grid` to `output` using the compression method `compress`, which is either `"lz4"` or
+/// `"none"`. If `compress` is `None` the compression is inferred from the extension of `output`.
+/// If `output` is `-` the grid is written to the standard output. Existing files are only written
+/// to if they are not regular files, for instance named pipes; regular files are never
+/// overwritten.
+pub fn write_grid_compressed(
+    output: &Path,
+    grid: &Grid,
+    compress: Option<&str>,
+) -> Result<ExitCode> {
+    let file: Box<dyn Write> = if output == Path::new("-") {
+        Box::new(io::stdout().lock())
+    } else {
+        let special = fs::metadata(output).is_ok_and(|metadata| !metadata.is_file());
+
+        Box::new(
+            OpenOptions::new()
+                .write(true)
+                .create_new(!special)
+                .open(output)
+                .context(format!("unable to write '{}'", output.display()))?,
+        )
+    };
 
-    if output.extension().map_or(false, |ext| ext == "lz4") {
+    let lz4 = compress.map_or_else(
+        || output.extension().map_or(false, |ext| ext == "lz4"),
+        |compress| compress == "lz4",
+    );
+
+    if lz4 {
         grid.write_lz4(file)?;
     } else {
         grid.write(file)?;
@@ -118,6 +167,21 @@ pub fn write_grid(output: &Path, grid: &Grid) -> Result<ExitCode> {
     Ok(ExitCode::SUCCESS)
 }
 
+/// Write `array` in the NPY format into the file `output`.
+#[cfg(feature = "npy")]
+pub fn write_npy<D: Dimension>(output: &Path, array: &Array<f64, D>) -> Result<()> {
+    use ndarray_npy::WriteNpyExt;
+
+    array
+        .write_npy(File::create(output).context(format!("unable to write '{}'", output.display()))?)
+        .context(format!("unable to write '{}'", output.display()))
+}
+
+#[cfg(not(feature = "npy"))]
+pub fn write_npy<D: Dimension>(_: &Path, _: &Array<f64, D>) -> Result<()> {
+    Err(anyhow!("you need to install `pineappl` with feature `npy`"))
+}
+
 pub fn create_table() -> Table {
     let mut table = Table::new();
     table.set_format(
@@ -141,19 +205,21 @@ pub const SCALES_VECTOR: [(f64, f64); 9] = [
     (0.5, 2.0),
 ];
 
-pub fn labels_and_units(grid: &Grid, integrated: bool) -> (Vec<(String, &str)>, &str, &str) {
+pub fn labels_and_units(grid: &Grid, integrated: bool) -> (Vec<(String, String)>, &str, &str) {
     let key_values = grid.key_values();
 
     (
-        (0..grid.bin_info().dimensions())
-            .map(|d| {
+        grid.bin_dimension_labels()
+            .into_iter()
+            .enumerate()
+            .map(|(d, DimLabel { label, unit, .. })| {
                 (
-                    key_values
-                        .and_then(|kv| kv.get(&format!("x{}_label", d + 1)).cloned())
-                        .unwrap_or_else(|| format!("x{}", d + 1)),
-                    key_values
-                        .and_then(|kv| kv.get(&format!("x{}_unit", d + 1)))
-                        .map_or("", String::as_str),
+                    if label.is_empty() {
+                        format!("x{}", d + 1)
+                    } else {
+                        label
+                    },
+                    unit,
                 )
             })
             .collect(),
@@ -181,27 +247,27 @@ pub enum ConvoluteMode {
     Normal,
 }
 
-pub fn convolve_scales(
-    grid: &Grid,
+// returns `xfx`, or `fill_nan_with` if it is given and `xfx` is not finite, in which case `replaced`
+// is incremented
+fn fill_non_finite(xfx: f64, fill_nan_with: Option<f64>, replaced: &Cell<usize>) -> f64 {
+    match fill_nan_with {
+        Some(value) if !xfx.is_finite() => {
+            replaced.set(replaced.get() + 1);
+            value
+        }
+        _ => xfx,
+    }
+}
+
+// creates the PDFs and the strong coupling from `conv_funs` that apply the settings of `cfg` and
+// passes them, together with the hadrons of the PDFs, to `f`; if non-finite PDF values are
+// replaced with `fill_nan_with`, a warning with their number is printed afterwards
+fn with_funs<T>(
     conv_funs: &mut [Pdf],
-    orders: &[(u32, u32)],
-    bins: &[usize],
-    channels: &[bool],
-    scales: &[(f64, f64)],
-    mode: ConvoluteMode,
+    fill_nan_with: Option<f64>,
     cfg: &GlobalConfiguration,
-) -> Vec<f64> {
-    let orders: Vec<_> = grid
-        .orders()
-        .iter()
-        .map(|order| {
-            orders.is_empty()
-                || orders
-                    .iter()
-                    .any(|other| (order.alphas == other.0) && (order.alpha == other.1))
-        })
-        .collect();
-
+    f: impl FnOnce(&[i32], &[&dyn Fn(i32, f64, f64) -> f64], &dyn Fn(f64) -> f64) -> T,
+) -> T {
     if cfg.force_positive {
         for fun in conv_funs.iter_mut() {
             fun.set_force_positive(1);
@@ -219,7 +285,10 @@ pub fn convolve_scales(
         .iter_mut()
         .map(|fun| (fun.x_min(), fun.x_max()))
         .collect();
-    let mut funs: Vec<_> = conv_funs
+    // number of non-finite PDF values replaced with `fill_nan_with`
+    let replaced = Cell::new(0_usize);
+    let replaced_ref = &replaced;
+    let funs: Vec<_> = conv_funs
         .iter()
         .zip(x_min_max)
         .map(|(fun, (x_min, x_max))| {
@@ -227,45 +296,145 @@ pub fn convolve_scales(
                 if !cfg.allow_extrapolation && (x < x_min || x > x_max) {
                     0.0
                 } else {
-                    fun.xfx_q2(id, x, q2)
+                    fill_non_finite(fun.xfx_q2(id, x, q2), fill_nan_with, replaced_ref)
                 }
             }
         })
         .collect();
-    let mut alphas_funs: Vec<_> = conv_funs
+    let alphas_funs: Vec<_> = conv_funs
         .iter()
         .map(|fun| move |q2| fun.alphas_q2(q2))
         .collect();
     let pdg_ids: Vec<_> = conv_funs
         .iter()
-        .map(|fun| {
-            // if the field 'Particle' is missing we assume it's a proton PDF
-            fun.set()
-                .entry("Particle")
-                .map_or(Ok(2212), |string| string.parse::<i32>())
-                // UNWRAP: if this fails, there's a non-integer string in the LHAPDF info file
-                .unwrap()
+        // UNWRAP: if this fails, there's a non-integer string in the LHAPDF info file
+        .map(|fun| pdf_particle(fun).unwrap())
+        .collect();
+
+    let funs: Vec<&dyn Fn(i32, f64, f64) -> f64> = funs.iter().map(|fun| fun as _).collect();
+
+    let result = f(&pdg_ids, &funs, &alphas_funs[cfg.use_alphas_from]);
+
+    if let (Some(value), count @ 1..) = (fill_nan_with, replaced.get()) {
+        eprintln!("warning: replaced {count} non-finite PDF values with {value}");
+    }
+
+    result
+}
+
+// creates a `LumiCache` from `conv_funs` that applies the settings of `cfg` and passes it to `f`,
+// see `with_funs`
+fn with_lumi_cache<T>(
+    conv_funs: &mut [Pdf],
+    fill_nan_with: Option<f64>,
+    cfg: &GlobalConfiguration,
+    f: impl FnOnce(&mut LumiCache) -> T,
+) -> T {
+    with_funs(conv_funs, fill_nan_with, cfg, |pdg_ids, funs, alphas| {
+        let mut funs = funs.to_vec();
+        let mut alphas = alphas;
+
+        // TODO: write a new constructor of `LumiCache` that accepts a vector of all the arguments
+        let mut cache = match funs.as_mut_slice() {
+            [funs0] => LumiCache::with_one(pdg_ids[0], funs0, &mut alphas),
+            [funs0, funs1] => {
+                LumiCache::with_two(pdg_ids[0], funs0, pdg_ids[1], funs1, &mut alphas)
+            }
+            // TODO: convert this into an error
+            _ => panic!(
+                "convolutions with {} convolution functions is not supported",
+                funs.len()
+            ),
+        };
+
+        f(&mut cache)
+    })
+}
+
+// tabulates the PDFs and the strong coupling of `conv_funs` at the nodes of `grid` for the scale
+// factors `scales` and passes the tables to `f`, see `with_funs`
+fn with_tables<T>(
+    grid: &Grid,
+    conv_funs: &mut [Pdf],
+    scales: &[(f64, f64)],
+    fill_nan_with: Option<f64>,
+    cfg: &GlobalConfiguration,
+    f: impl FnOnce(&[XfxTable], &AlphasTable) -> T,
+) -> T {
+    with_funs(conv_funs, fill_nan_with, cfg, |pdg_ids, funs, alphas| {
+        let xfx_tables: Vec<_> = funs
+            .iter()
+            .zip(pdg_ids)
+            .map(|(&fun, &pdg)| XfxTable::from_grid(grid, pdg, scales, fun))
+            .collect();
+
+        // the renormalization scales of the table must be sorted
+        let mut nodes: Vec<_> = scales
+            .iter()
+            .flat_map(|&(xir, _)| {
+                let table = AlphasTable::from_grid(grid, xir, alphas);
+                table.ren1.into_iter().zip(table.alphas)
+            })
+            .collect();
+        nodes.sort_by(|(lhs, _), (rhs, _)| lhs.total_cmp(rhs));
+        nodes.dedup_by(|(lhs, _), (rhs, _)| lhs.total_cmp(rhs).is_eq());
+        let (ren1, alphas) = nodes.into_iter().unzip();
+
+        f(&xfx_tables, &AlphasTable { ren1, alphas })
+    })
+}
+
+pub fn convolve_scales(
+    grid: &Grid,
+    conv_funs: &mut [Pdf],
+    orders: &[(u32, u32)],
+    bins: &[usize],
+    channels: &[bool],
+    scales: &[(f64, f64)],
+    bin_corrections: &[f64],
+    mode: ConvoluteMode,
+    fill_nan_with: Option<f64>,
+    precompute_tables: bool,
+    cfg: &GlobalConfiguration,
+) -> Vec<f64> {
+    let orders: Vec<_> = grid
+        .orders()
+        .iter()
+        .map(|order| {
+            orders.is_empty()
+                || orders
+                    .iter()
+                    .any(|other| (order.alphas == other.0) && (order.alpha == other.1))
         })
         .collect();
 
-    // TODO: write a new constructor of `LumiCache` that accepts a vector of all the arguments
-    let mut cache = match funs.as_mut_slice() {
-        [funs0] => LumiCache::with_one(pdg_ids[0], funs0, &mut alphas_funs[cfg.use_alphas_from]),
-        [funs0, funs1] => LumiCache::with_two(
-            pdg_ids[0],
-            funs0,
-            pdg_ids[1],
-            funs1,
-            &mut alphas_funs[cfg.use_alphas_from],
-        ),
-        // TODO: convert this into an error
-        _ => panic!(
-            "convolutions with {} convolution functions is not supported",
-            conv_funs.len()
-        ),
+    let options = ConvolveOptions {
+        order_mask: &orders,
+        bin_indices: bins,
+        channel_mask: channels,
+        xi: scales,
+        bin_corrections,
+        ..ConvolveOptions::default()
     };
 
-    let mut results = grid.convolve(&mut cache, &orders, bins, channels, scales);
+    let mut results = if precompute_tables {
+        with_tables(
+            grid,
+            conv_funs,
+            scales,
+            fill_nan_with,
+            cfg,
+            |xfx, alphas| {
+                grid.convolve_with_tables(xfx, alphas, &options)
+                    // UNWRAP: the tables contain all nodes of `grid`
+                    .unwrap()
+            },
+        )
+    } else {
+        with_lumi_cache(conv_funs, fill_nan_with, cfg, |cache| {
+            grid.convolve_with_options(slice::from_mut(cache), &options)
+        })
+    };
 
     match mode {
         ConvoluteMode::Asymmetry => {
@@ -324,7 +493,10 @@ pub fn convolve(
         bins,
         lumis,
         &SCALES_VECTOR[0..scales],
+        &[],
         mode,
+        None,
+        false,
         cfg,
     )
 }
@@ -352,70 +524,21 @@ pub fn convolve_subgrid(
     lumi: usize,
     cfg: &GlobalConfiguration,
 ) -> Array3<f64> {
-    if cfg.force_positive {
-        for fun in conv_funs.iter_mut() {
-            fun.set_force_positive(1);
-        }
-    }
-
-    // TODO: promote this to an error
-    assert!(
-        cfg.use_alphas_from < conv_funs.len(),
-        "expected `use_alphas_from` to be `0` or `1`, is `{}`",
-        cfg.use_alphas_from
-    );
-
-    let x_min_max: Vec<_> = conv_funs
-        .iter_mut()
-        .map(|fun| (fun.x_min(), fun.x_max()))
-        .collect();
-    let mut funs: Vec<_> = conv_funs
-        .iter()
-        .zip(x_min_max)
-        .map(|(fun, (x_min, x_max))| {
-            move |id, x, q2| {
-                if !cfg.allow_extrapolation && (x < x_min || x > x_max) {
-                    0.0
-                } else {
-                    fun.xfx_q2(id, x, q2)
-                }
-            }
-        })
-        .collect();
-    let mut alphas_funs: Vec<_> = conv_funs
-        .iter()
-        .map(|fun| move |q2| fun.alphas_q2(q2))
-        .collect();
-    let pdg_ids: Vec<_> = conv_funs
-        .iter()
-        .map(|fun| {
-            // if the field 'Particle' is missing we assume it's a proton PDF
-            fun.set()
-                .entry("Particle")
-                .map_or(Ok(2212), |string| string.parse::<i32>())
-                // UNWRAP: if this fails, there's a non-integer string in the LHAPDF info file
-                .unwrap()
-        })
-        .collect();
-
-    // TODO: write a new constructor of `LumiCache` that accepts a vector of all the arguments
-    let mut cache = match funs.as_mut_slice() {
-        [funs0] => LumiCache::with_one(pdg_ids[0], funs0, &mut alphas_funs[cfg.use_alphas_from]),
-        [funs0, funs1] => LumiCache::with_two(
-            pdg_ids[0],
-            funs0,
-            pdg_ids[1],
-            funs1,
-            &mut alphas_funs[cfg.use_alphas_from],
-        ),
-        // TODO: convert this into an error
-        _ => panic!(
-            "convolutions with {} convolution functions is not supported",
-            conv_funs.len()
-        ),
-    };
+    with_lumi_cache(conv_funs, None, cfg, |cache| {
+        grid.convolve_subgrid(cache, order, bin, lumi, 1.0, 1.0)
+    })
+}
 
-    grid.convolve_subgrid(&mut cache, order, bin, lumi, 1.0, 1.0)
+pub fn convolve_full(
+    grid: &Grid,
+    conv_funs: &mut [Pdf],
+    xi: (f64, f64),
+    fill_nan_with: Option<f64>,
+    cfg: &GlobalConfiguration,
+) -> Array3<f64> {
+    with_lumi_cache(conv_funs, fill_nan_with, cfg, |cache| {
+        grid.convolve_full(cache, xi)
+    })
 }
 
 pub fn parse_integer_range(range: &str) -> Result<RangeInclusive<usize>> {
@@ -437,6 +560,29 @@ pub fn parse_integer_range(range: &str) -> Result<RangeInclusive<usize>> {
     }
 }
 
+/// Return the exponents of the couplings of the orders of `grid` without logarithms that
+/// [`Grid::order_mask_for`] selects for `max_as` and `max_a`.
+pub fn orders_with_max_powers(
+    grid: &Grid,
+    max_as: Option<u32>,
+    max_a: Option<u32>,
+) -> Result<Vec<(u32, u32)>> {
+    let orders: Vec<_> = grid
+        .orders()
+        .iter()
+        .zip(grid.order_mask_for(max_as, max_a, false))
+        .filter(|&(order, selected)| selected && (order.logxir == 0) && (order.logxif == 0))
+        .map(|(order, _)| (order.alphas, order.alpha))
+        .collect();
+
+    ensure!(
+        !orders.is_empty(),
+        "none of the orders of the grid is selected by `--max-as` and `--max-a`"
+    );
+
+    Ok(orders)
+}
+
 pub fn parse_order(order: &str) -> Result<(u32, u32)> {
     let mut alphas = 0;
     let mut alpha = 0;
@@ -472,7 +618,8 @@ pub fn parse_order(order: &str) -> Result<(u32, u32)> {
 
 #[cfg(test)]
 mod test {
-    use super::ConvFuns;
+    use super::{fill_non_finite, ConvFuns};
+    use std::cell::Cell;
 
     #[test]
     fn conv_fun_from_str() {
@@ -490,4 +637,28 @@ mod test {
             }
         );
     }
+
+    #[test]
+    fn fill_non_finite_pdf_values() {
+        let replaced = Cell::new(0);
+
+        // a PDF returning NaN or infinity is replaced, finite values are kept
+        assert_eq!(
+            fill_non_finite(f64::NAN, Some(0.0), &replaced).to_bits(),
+            0.0_f64.to_bits()
+        );
+        assert_eq!(
+            fill_non_finite(f64::INFINITY, Some(1.0), &replaced).to_bits(),
+            1.0_f64.to_bits()
+        );
+        assert_eq!(
+            fill_non_finite(0.5, Some(0.0), &replaced).to_bits(),
+            0.5_f64.to_bits()
+        );
+        assert_eq!(replaced.get(), 2);
+
+        // without `--fill-nan-with` non-finite values are passed through
+        assert!(fill_non_finite(f64::NAN, None, &replaced).is_nan());
+        assert_eq!(replaced.get(), 2);
+    }
 }
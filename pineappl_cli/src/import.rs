@@ -4,7 +4,7 @@ use anyhow::{anyhow, Result};
 use clap::builder::{PossibleValuesParser, TypedValueParser};
 use clap::{Parser, ValueHint};
 use lhapdf::Pdf;
-use pineappl::grid::Grid;
+use pineappl::grid::{Grid, Reference};
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
@@ -57,6 +57,7 @@ fn convert_fastnlo(
     scales: usize,
     fnlo_mur: Option<&str>,
     fnlo_muf: Option<&str>,
+    silence_fastnlo: bool,
 ) -> Result<(&'static str, Grid, Vec<f64>, usize)> {
     use pineappl_fastnlo::ffi;
     use std::ptr;
@@ -64,6 +65,9 @@ fn convert_fastnlo(
     // TODO: convert this into an error?
     assert_eq!(conv_funs.lhapdf_names.len(), 1);
 
+    // fastNLO's output is restored when `_silenced` goes out of scope
+    let _silenced = silence_fastnlo.then(fastnlo::SilencedStdout::new);
+
     let mut file = ffi::make_fastnlo_lhapdf_with_name_file_set(
         input.to_str().unwrap(),
         &conv_funs.lhapdf_names[0],
@@ -126,6 +130,7 @@ fn convert_fastnlo(
     _: usize,
     _: Option<&str>,
     _: Option<&str>,
+    _: bool,
 ) -> Result<(&'static str, Grid, Vec<f64>, usize)> {
     Err(anyhow!(
         "you need to install `pineappl` with feature `fastnlo`"
@@ -156,6 +161,7 @@ fn convert_grid(
     scales: usize,
     fnlo_mur: Option<&str>,
     fnlo_muf: Option<&str>,
+    silence_fastnlo: bool,
 ) -> Result<(&'static str, Grid, Vec<f64>, usize)> {
     if let Some(extension) = input.extension() {
         if extension == "tab"
@@ -166,7 +172,15 @@ fn convert_grid(
                     .map_or(false, |ext| ext == "tab"))
         {
             return convert_fastnlo(
-                input, alpha, fun_names, member, dis_pid, scales, fnlo_mur, fnlo_muf,
+                input,
+                alpha,
+                fun_names,
+                member,
+                dis_pid,
+                scales,
+                fnlo_mur,
+                fnlo_muf,
+                silence_fastnlo,
             );
         } else if extension == "dat" {
             return convert_fktable(input, dis_pid);
@@ -237,6 +251,9 @@ pub struct Opts {
     /// factorization scale.
     #[arg(long, value_parser = PossibleValuesParser::new(fnlo_mu_possible_values()))]
     fnlo_muf: Option<String>,
+    /// If importing a fastNLO table, silence the output fastNLO writes to the standard output.
+    #[arg(long)]
+    silence_fastnlo: bool,
     /// Set the number of fractional digits shown for absolute numbers.
     #[arg(default_value_t = 7, long, value_name = "ABS")]
     digits_abs: usize,
@@ -268,6 +285,7 @@ impl Subcommand for Opts {
             self.scales,
             self.fnlo_mur.as_deref(),
             self.fnlo_muf.as_deref(),
+            self.silence_fastnlo,
         )?;
 
         if !self.no_optimize {
@@ -293,6 +311,16 @@ impl Subcommand for Opts {
             // if both grids don't have the same number of bins there's bug in the program
             assert_eq!(results.len(), reference_results.len());
 
+            // store the prediction of the imported grid to make it available after the import
+            grid.set_reference(&Reference {
+                pdf: self.conv_funs.label.clone(),
+                results: reference_results
+                    .iter()
+                    .step_by(scale_variations)
+                    .copied()
+                    .collect(),
+            })?;
+
             let mut table = helpers::create_table();
             let mut titles = row![c => "b", "PineAPPL", grid_type, "rel. diff"];
 
@@ -15,6 +15,22 @@ use pineappl_fastnlo::ffi::{
 use std::f64::consts::TAU;
 use std::mem;
 
+/// Discards everything fastNLO writes to `std::cout` as long as this object is alive.
+pub struct SilencedStdout;
+
+impl SilencedStdout {
+    pub fn new() -> Self {
+        ffi::SilenceStdout(true);
+        Self
+    }
+}
+
+impl Drop for SilencedStdout {
+    fn drop(&mut self) {
+        ffi::SilenceStdout(false);
+    }
+}
+
 fn pid_to_pdg_id(pid: i32) -> i32 {
     match pid {
         -6..=-1 | 1..=6 => pid,
@@ -101,12 +117,10 @@ fn convert_coeff_add_fix(
 
     let mut grid = Grid::new(
         create_lumi(table_as_add_base, comb, dis_pid),
-        vec![Order {
-            alphas: table_as_add_base.GetNpow().try_into().unwrap(),
+        vec![Order::with_couplings(
+            table_as_add_base.GetNpow().try_into().unwrap(),
             alpha,
-            logxir: 0,
-            logxif: 0,
-        }],
+        )],
         (0..=bins)
             .map(|limit| u16::try_from(limit).unwrap().into())
             .collect(),
@@ -401,6 +415,7 @@ pub fn convert_fastnlo_table(file: &fastNLOLHAPDF, alpha: u32, dis_pid: i32) ->
 
     let bins: usize = file_as_table.GetNObsBin().try_into().unwrap();
     let mut grids = Vec::new();
+    let mut functional_forms = None;
 
     for id in 0.. {
         // TODO: there doesn't seem to be a better way than trying an index and stopping whenever a
@@ -427,6 +442,7 @@ pub fn convert_fastnlo_table(file: &fastNLOLHAPDF, alpha: u32, dis_pid: i32) ->
             } else {
                 let mur_ff = file_as_reader.GetMuRFunctionalForm();
                 let muf_ff = file_as_reader.GetMuFFunctionalForm();
+                functional_forms = Some((mur_ff, muf_ff));
 
                 grids.push(convert_coeff_add_flex(
                     unsafe { &*converted },
@@ -490,5 +506,12 @@ pub fn convert_fastnlo_table(file: &fastNLOLHAPDF, alpha: u32, dis_pid: i32) ->
         &ffi::GetScDescr(file_as_table).join("\n"),
     );
 
+    // the subgrids of flexible-scale tables only store the renormalization and factorization
+    // scales computed from both scales of the table, so remember how they were computed
+    if let Some((mur_ff, muf_ff)) = functional_forms {
+        result.set_key_value("fastnlo_mur_functional_form", &mur_ff.to_string());
+        result.set_key_value("fastnlo_muf_functional_form", &muf_ff.to_string());
+    }
+
     Ok(result)
 }
@@ -5,6 +5,7 @@
 
 mod analyze;
 mod channels;
+mod complete;
 mod convolve;
 mod diff;
 mod evolve;
@@ -53,6 +54,8 @@ pub trait Subcommand {
 pub enum SubcommandEnum {
     Analyze(analyze::Opts),
     Channels(channels::Opts),
+    #[command(hide = true)]
+    Complete(complete::Opts),
     Convolve(convolve::Opts),
     Diff(diff::Opts),
     Evolve(evolve::Opts),
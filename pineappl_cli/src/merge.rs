@@ -1,6 +1,7 @@
 use super::helpers;
 use super::{GlobalConfiguration, Subcommand};
 use anyhow::Result;
+use clap::builder::PossibleValuesParser;
 use clap::{Parser, ValueHint};
 use std::path::PathBuf;
 use std::process::ExitCode;
@@ -8,16 +9,44 @@ use std::process::ExitCode;
 /// Merges one or more PineAPPL grids together.
 #[derive(Parser)]
 pub struct Opts {
-    /// Path of the merged PineAPPL file.
-    #[arg(value_hint = ValueHint::FilePath)]
-    output: PathBuf,
+    /// Path of the merged PineAPPL file; `-` writes to the standard output.
+    #[arg(required_unless_present = "check", value_hint = ValueHint::FilePath)]
+    output: Option<PathBuf>,
     /// Path(s) of the files that should be merged.
-    #[arg(required = true, value_hint = ValueHint::FilePath)]
+    #[arg(required_unless_present = "check", value_hint = ValueHint::FilePath)]
     input: Vec<PathBuf>,
+    /// Compression of the output; by default inferred from its extension.
+    #[arg(long, value_parser = PossibleValuesParser::new(["lz4", "none"]))]
+    compress: Option<String>,
+    /// Only check whether the grids can be merged with the first one, without writing anything.
+    #[arg(
+        conflicts_with_all = ["output", "input", "compress"],
+        long,
+        num_args = 2..,
+        value_hint = ValueHint::FilePath,
+        value_name = "GRID"
+    )]
+    check: Vec<PathBuf>,
 }
 
 impl Subcommand for Opts {
     fn run(&self, _: &GlobalConfiguration) -> Result<ExitCode> {
+        if let Some((grid0, grids)) = self.check.split_first() {
+            let grid0 = helpers::read_grid(grid0)?;
+            let mut exit_code = ExitCode::SUCCESS;
+
+            for i in grids {
+                if let Err(err) = grid0.check_mergeable(&helpers::read_grid(i)?) {
+                    println!("{}: {err}", i.display());
+                    exit_code = ExitCode::FAILURE;
+                } else {
+                    println!("{}: compatible", i.display());
+                }
+            }
+
+            return Ok(exit_code);
+        }
+
         let (input0, input_rest) = self.input.split_first().unwrap();
         let mut grid0 = helpers::read_grid(input0)?;
 
@@ -25,6 +54,11 @@ impl Subcommand for Opts {
             grid0.merge(helpers::read_grid(i)?)?;
         }
 
-        helpers::write_grid(&self.output, &grid0)
+        // UNWRAP: `output` is required without `--check`
+        helpers::write_grid_compressed(
+            self.output.as_ref().unwrap(),
+            &grid0,
+            self.compress.as_deref(),
+        )
     }
 }
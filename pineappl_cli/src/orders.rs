@@ -1,6 +1,6 @@
 use super::helpers::{self, ConvFuns, ConvoluteMode};
 use super::{GlobalConfiguration, Subcommand};
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::{Parser, ValueHint};
 use prettytable::{cell, Row};
 use std::path::PathBuf;
@@ -30,6 +30,12 @@ pub struct Opts {
         value_parser = helpers::parse_order
     )]
     normalize: Vec<(u32, u32)>,
+    /// Show only the orders whose power of the strong coupling is at most MAX.
+    #[arg(long, value_name = "MAX")]
+    max_as: Option<u32>,
+    /// Show only the orders whose power of the electromagnetic coupling is at most MAX.
+    #[arg(long, value_name = "MAX")]
+    max_a: Option<u32>,
     /// Set the number of fractional digits shown for absolute numbers.
     #[arg(default_value_t = 7, long, value_name = "ABS")]
     digits_abs: usize,
@@ -43,14 +49,24 @@ impl Subcommand for Opts {
         let grid = helpers::read_grid(&self.input)?;
         let mut conv_funs = helpers::create_conv_funs(&self.conv_funs)?;
 
+        let selected = helpers::orders_with_max_powers(&grid, self.max_as, self.max_a)?;
         let mut orders: Vec<_> = grid
             .orders()
             .iter()
             .filter(|order| (order.logxir == 0) && (order.logxif == 0))
+            .filter(|order| selected.contains(&(order.alphas, order.alpha)))
             .collect();
         orders.sort();
         let orders = orders;
 
+        if let Some((alphas, alpha)) = self.normalize.iter().find(|&&(alphas, alpha)| {
+            !orders
+                .iter()
+                .any(|order| (order.alphas == alphas) && (order.alpha == alpha))
+        }) {
+            bail!("the order O(as^{alphas} a^{alpha}) given to `--normalize` is not shown");
+        }
+
         let limits = helpers::convolve_limits(
             &grid,
             &[],
@@ -14,6 +14,8 @@ ylabel = ""
 xlog = False
 ylog = False
 scales = 1
+ratio_to = 0
+channel_breakdown_linestyles = []
 plot_panels = {
     "int": False,
     "abs": False,
@@ -68,7 +70,7 @@ stylesheet = {
 
 # panel plot labels
 ylabel_ratio_pdf = r"Ratio to {{central_pdf}}"
-ylabel_double_ratio_pdf = r"Ratio to NLO"
+ylabel_double_ratio_pdf = r"Ratio to {first} / {second}"
 ylabel_rel_ewonoff = r"NLO EW on/off [\si{\percent}]"
 ylabel_rel_pdfunc = r"PDF uncertainty [\si{\percent}]"
 ylabel_rel_pdfpull = r"Pull [$\sigma$]"
@@ -79,13 +81,6 @@ label_rel_ewonoff_scale_unc = f"{scales}-p. scale var."
 label_rel_ewonoff_pdf_unc = r"PDF uncertainty"
 
 
-# linestyle for the channel breakdown shown in the panel `plot_abs_pdfs`. If the array
-# is empty, no channel breakdown will be shown, otherwise the most important channels,
-# as many as linestyles are given. See also
-# https://matplotlib.org/stable/gallery/lines_bars_and_markers/linestyles.html
-channel_breakdown_linestyles = []
-
-
 def main(active_panels):
     """Build a plot figure with various panels."""
     # Find the active panels
@@ -231,13 +226,13 @@ def plot_abs(axis, /, x, y, ymin, ymax, slice_label="", **_kwargs):
 
 
 def plot_ratio_pdf(axis, /, x, pdf_results, slice_label="", **_kwargs):
-    axis.set_ylabel(ylabel_ratio_pdf.format(central_pdf=pdf_results[0][0]))
+    axis.set_ylabel(ylabel_ratio_pdf.format(central_pdf=pdf_results[ratio_to][0]))
 
     for index, i in enumerate(pdf_results):
         label, y, ymin, ymax = i
-        y = y / pdf_results[0][1]
-        ymin = ymin / pdf_results[0][1]
-        ymax = ymax / pdf_results[0][1]
+        y = y / pdf_results[ratio_to][1]
+        ymin = ymin / pdf_results[ratio_to][1]
+        ymax = ymax / pdf_results[ratio_to][1]
 
         axis.step(x, y, color=colors[index], linewidth=1.0, where="post")
         axis.fill_between(
@@ -280,18 +275,22 @@ def plot_ratio_pdf(axis, /, x, pdf_results, slice_label="", **_kwargs):
 
 
 def plot_double_ratio_pdf(axis, /, x, pdf_results, slice_label="", **_kwargs):
-    axis.set_ylabel(ylabel_double_ratio_pdf)
+    # the results are compared in pairs: results with an even index are divided by the first and
+    # results with an odd index by the second result of the pair that contains `ratio_to`
+    first = ratio_to - ratio_to % 2
+    second = min(first + 1, len(pdf_results) - 1)
+    axis.set_ylabel(
+        ylabel_double_ratio_pdf.format(
+            first=pdf_results[first][0], second=pdf_results[second][0]
+        )
+    )
 
     for index, i in enumerate(pdf_results):
         label, y, ymin, ymax = i
-        if index == 0 or index == 2:
-            y = y / pdf_results[0][1]
-            ymin = ymin / pdf_results[0][1]
-            ymax = ymax / pdf_results[0][1]
-        else:
-            y = y / pdf_results[1][1]
-            ymin = ymin / pdf_results[1][1]
-            ymax = ymax / pdf_results[1][1]
+        denominator = pdf_results[second if index % 2 == 1 else first][1]
+        y = y / denominator
+        ymin = ymin / denominator
+        ymax = ymax / denominator
         axis.step(x, y, color=colors[index], linewidth=1.0, where="post")
         axis.fill_between(
             x,
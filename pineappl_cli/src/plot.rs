@@ -1,17 +1,17 @@
 use super::helpers::{self, ConvFuns, ConvoluteMode};
 use super::{GlobalConfiguration, Subcommand};
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::builder::{PossibleValuesParser, TypedValueParser};
 use clap::{Parser, ValueHint};
 use itertools::Itertools;
 use ndarray::Axis;
-use pineappl::boc::Channel;
 use pineappl::convolutions::Convolution;
-use pineappl::pids::PidBasis;
+use pineappl::grid::DimLabel;
 use pineappl::subgrid::Subgrid;
 use rayon::{prelude::*, ThreadPoolBuilder};
 use std::fmt::Write;
 use std::num::NonZeroUsize;
+use std::num::ParseIntError;
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::thread;
@@ -29,9 +29,15 @@ pub struct Opts {
     /// LHAPDF id(s) or name of the PDF set(s).
     #[arg(required = true)]
     conv_funs: Vec<ConvFuns>,
-    /// Choose for which convolution function the uncertainty should be calculated.
-    #[arg(default_value = "0", long, value_name = "IDX")]
-    conv_fun_uncert_from: usize,
+    /// Choose for which convolution function(s) the uncertainty should be calculated. Multiple
+    /// indices or `all` add the uncertainties in quadrature, assuming they are uncorrelated.
+    #[arg(
+        default_value = "0",
+        long,
+        value_name = "IDX",
+        value_parser = parse_conv_fun_indices
+    )]
+    conv_fun_uncert_from: ConvFunIndices,
     /// Set the number of scale variations.
     #[arg(
         default_value_t = 7,
@@ -58,8 +64,60 @@ pub struct Opts {
     /// Disable the (time-consuming) calculation of PDF uncertainties.
     #[arg(long)]
     no_conv_fun_unc: bool,
+    /// Set the path of the plots without the file extension, instead of using the grid's name.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "NAME")]
+    output: Option<PathBuf>,
+    /// Index of the PDF set that the results in the PDF ratio panels are divided by.
+    #[arg(
+        conflicts_with = "subgrid_pull",
+        default_value_t = 0,
+        long,
+        value_name = "IDX"
+    )]
+    ratio_to: usize,
+    /// Show the N channels with the largest contributions in the panel with the absolute
+    /// predictions of all PDF sets.
+    #[arg(conflicts_with_all = ["asymmetry", "subgrid_pull"], long, value_name = "N")]
+    channel_breakdown: Option<NonZeroUsize>,
 }
 
+/// Indices of the convolution functions whose uncertainties are calculated.
+#[derive(Clone)]
+enum ConvFunIndices {
+    All,
+    Indices(Vec<usize>),
+}
+
+impl ConvFunIndices {
+    fn indices(&self, conv_funs: &ConvFuns) -> Vec<usize> {
+        match self {
+            Self::All => (0..conv_funs.lhapdf_names.len()).collect(),
+            Self::Indices(indices) => indices.clone(),
+        }
+    }
+}
+
+fn parse_conv_fun_indices(arg: &str) -> Result<ConvFunIndices, ParseIntError> {
+    if arg == "all" {
+        Ok(ConvFunIndices::All)
+    } else {
+        arg.split(',')
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map(ConvFunIndices::Indices)
+    }
+}
+
+/// Matplotlib linestyles for the channel breakdown, which are repeated if more channels are shown.
+const CHANNEL_BREAKDOWN_LINESTYLES: [&str; 6] = [
+    "\"dashed\"",
+    "\"dotted\"",
+    "\"dashdot\"",
+    "(0, (5, 1))",
+    "(0, (3, 1, 1, 1))",
+    "(0, (1, 3))",
+];
+
 /// Convert `slice` to (unformatted) Python list.
 fn map_format_join(slice: &[f64]) -> String {
     slice.iter().map(|x| format!("{x}")).join(", ")
@@ -78,34 +136,6 @@ fn map_format_e_join_repeat_last(slice: &[f64]) -> String {
         .join(", ")
 }
 
-/// Convert a channel to a good Python string representation.
-fn map_format_channel(
-    channel: &Channel,
-    has_pdf1: bool,
-    has_pdf2: bool,
-    pid_basis: PidBasis,
-) -> String {
-    channel
-        .entry()
-        .iter()
-        .map(|&(a, b, _)| {
-            format!(
-                "{}{}",
-                if has_pdf1 {
-                    pid_basis.to_latex_str(a)
-                } else {
-                    ""
-                },
-                if has_pdf2 {
-                    pid_basis.to_latex_str(b)
-                } else {
-                    ""
-                }
-            )
-        })
-        .join(" + ")
-}
-
 /// Convert channel contributions to Python tuples.
 fn map_format_channels(channels: &[(String, Vec<f64>)]) -> String {
     channels
@@ -193,9 +223,18 @@ impl Subcommand for Opts {
                 ConvoluteMode::Normal
             };
 
+            if self.ratio_to >= self.conv_funs.len() {
+                bail!(
+                    "`--ratio-to` is {}, but only {} PDF sets were given",
+                    self.ratio_to,
+                    self.conv_funs.len()
+                );
+            }
+
             let grid = helpers::read_grid(&self.input)?;
             let mut conv_funs = helpers::create_conv_funs(&self.conv_funs[0])?;
             let slices = grid.bin_info().slices();
+            let dim_labels = grid.bin_dimension_labels();
             let mut data_string = String::new();
 
             data_string.push_str("[\n");
@@ -203,19 +242,19 @@ impl Subcommand for Opts {
             for (slice, label) in slices.iter().zip(slices.iter().map(|&(begin, end)| {
                 (0..grid.bin_info().dimensions() - 1)
                     .map(|d| {
+                        let DimLabel {
+                            label_tex, unit, ..
+                        } = &dim_labels[d];
+
                         format!(
                             "$\\SI{{{left}}}{{{unit}}} < {obs} < \\SI{{{right}}}{{{unit}}}$",
                             left = grid.bin_info().left(d)[begin],
-                            obs = grid
-                                .key_values()
-                                .and_then(|map| map.get(&format!("x{}_label_tex", d + 1)).cloned())
-                                .unwrap_or_else(|| format!("x{}", d + 1))
-                                .replace('$', ""),
+                            obs = if label_tex.is_empty() {
+                                format!("x{}", d + 1)
+                            } else {
+                                label_tex.replace('$', "")
+                            },
                             right = grid.bin_info().right(d)[end - 1],
-                            unit = grid
-                                .key_values()
-                                .and_then(|map| map.get(&format!("x{}_unit", d + 1)).cloned())
-                                .unwrap_or_default()
                         )
                     })
                     .join(r"\\")
@@ -295,51 +334,80 @@ impl Subcommand for Opts {
 
                             Ok(vec![results; 3])
                         } else {
-                            let (set, funs) = helpers::create_conv_funs_for_set(
-                                conv_funs,
-                                self.conv_fun_uncert_from,
-                            )?;
-
-                            let pdf_results: Vec<_> = funs
-                                .into_par_iter()
-                                .flat_map(|mut funs| {
-                                    helpers::convolve(
-                                        &grid,
-                                        &mut funs,
-                                        &[],
-                                        &bins,
-                                        &[],
-                                        1,
-                                        mode,
-                                        cfg,
-                                    )
-                                })
-                                .collect();
-
-                            let bins = mid.len();
-
-                            let mut central = Vec::with_capacity(bins);
-                            let mut min = Vec::with_capacity(bins);
-                            let mut max = Vec::with_capacity(bins);
-
-                            for bin in 0..bins {
-                                let values: Vec<_> = pdf_results
-                                    .iter()
-                                    .skip(bin)
-                                    .step_by(bins)
-                                    .copied()
+                            let bin_count = mid.len();
+
+                            let mut central = Vec::with_capacity(bin_count);
+                            let mut unc_central = Vec::with_capacity(bin_count);
+                            let mut errminus = vec![0.0_f64; bin_count];
+                            let mut errplus = vec![0.0_f64; bin_count];
+
+                            for (i, index) in self
+                                .conv_fun_uncert_from
+                                .indices(conv_funs)
+                                .into_iter()
+                                .enumerate()
+                            {
+                                let (set, funs) =
+                                    helpers::create_conv_funs_for_set(conv_funs, index)?;
+
+                                let pdf_results: Vec<_> = funs
+                                    .into_par_iter()
+                                    .flat_map(|mut funs| {
+                                        helpers::convolve(
+                                            &grid,
+                                            &mut funs,
+                                            &[],
+                                            &bins,
+                                            &[],
+                                            1,
+                                            mode,
+                                            cfg,
+                                        )
+                                    })
                                     .collect();
 
-                                let uncertainty =
-                                    set.uncertainty(&values, lhapdf::CL_1_SIGMA, false).unwrap();
-                                central.push(
-                                    conv_funs.members[self.conv_fun_uncert_from]
-                                        .map_or(uncertainty.central, |member| values[member]),
-                                );
-                                min.push(uncertainty.central - uncertainty.errminus);
-                                max.push(uncertainty.central + uncertainty.errplus);
+                                for bin in 0..bin_count {
+                                    let values: Vec<_> = pdf_results
+                                        .iter()
+                                        .skip(bin)
+                                        .step_by(bin_count)
+                                        .copied()
+                                        .collect();
+
+                                    let uncertainty = set
+                                        .uncertainty(&values, lhapdf::CL_1_SIGMA, false)
+                                        .unwrap();
+
+                                    // the central values are taken from the first convolution
+                                    // function
+                                    if i == 0 {
+                                        central.push(
+                                            conv_funs.members[index]
+                                                .map_or(uncertainty.central, |member| {
+                                                    values[member]
+                                                }),
+                                        );
+                                        unc_central.push(uncertainty.central);
+                                    }
+
+                                    // the uncertainties of different convolution functions are
+                                    // assumed to be uncorrelated
+                                    errminus[bin] = errminus[bin].hypot(uncertainty.errminus);
+                                    errplus[bin] = errplus[bin].hypot(uncertainty.errplus);
+                                }
                             }
 
+                            let min = unc_central
+                                .iter()
+                                .zip(&errminus)
+                                .map(|(central, err)| central - err)
+                                .collect();
+                            let max = unc_central
+                                .iter()
+                                .zip(&errplus)
+                                .map(|(central, err)| central + err)
+                                .collect();
+
                             Ok(vec![central, min, max])
                         }
                     })
@@ -398,11 +466,12 @@ impl Subcommand for Opts {
                             let mut channel_mask = vec![false; grid.channels().len()];
                             channel_mask[channel] = true;
                             (
-                                map_format_channel(
-                                    &grid.channels()[channel],
-                                    grid.convolutions()[0] != Convolution::None,
-                                    grid.convolutions()[1] != Convolution::None,
+                                grid.channels()[channel].to_tex(
                                     grid.pid_basis(),
+                                    [
+                                        grid.convolutions()[0] != Convolution::None,
+                                        grid.convolutions()[1] != Convolution::None,
+                                    ],
                                 ),
                                 helpers::convolve(
                                     &grid,
@@ -469,29 +538,32 @@ impl Subcommand for Opts {
             vector.sort();
             let vector = vector;
 
-            let mut output = self.input.clone();
+            let output = self.output.clone().unwrap_or_else(|| {
+                let mut output = self.input.clone();
 
-            // remove ".lz4" and ".pineappl" extension
-            if let Some(x) = output.extension() {
-                if x == "lz4" {
-                    output = Path::new(output.file_stem().unwrap()).to_path_buf();
+                // remove ".lz4" and ".pineappl" extension
+                if let Some(x) = output.extension() {
+                    if x == "lz4" {
+                        output = Path::new(output.file_stem().unwrap()).to_path_buf();
+                    }
                 }
-            }
-            if let Some(x) = output.extension() {
-                if x == "pineappl" {
-                    output = Path::new(output.file_stem().unwrap()).to_path_buf();
+                if let Some(x) = output.extension() {
+                    if x == "pineappl" {
+                        output = Path::new(output.file_stem().unwrap()).to_path_buf();
+                    }
                 }
-            }
 
-            let xaxis = format!("x{}", grid.bin_info().dimensions());
-            let xunit = key_values
-                .get(&format!("{xaxis}_unit"))
-                .map_or("", String::as_str);
+                output
+            });
+
+            // UNWRAP: every grid has at least one bin dimension
+            let DimLabel {
+                label_tex: xlabel_tex,
+                unit: xunit,
+                ..
+            } = grid.bin_dimension_labels().pop().unwrap();
             let xlabel = format!(
-                "{}{}",
-                key_values
-                    .get(&format!("{xaxis}_label_tex"))
-                    .map_or("", String::as_str),
+                "{xlabel_tex}{}",
                 if xunit.is_empty() {
                     String::new()
                 } else {
@@ -514,13 +586,20 @@ impl Subcommand for Opts {
             let bins = grid.bin_info().bins();
             let nconvs = self.conv_funs.len();
 
+            if self.channel_breakdown.is_some() && (bins == 1) {
+                eprintln!(
+                    "warning: `--channel-breakdown` is ignored, because the grid has only a single bin"
+                );
+            }
+
             let enable_int = bins == 1;
             let enable_abs = !enable_int;
             // TODO: only enable if there are EW corrections
             let enable_rel_ewonoff = enable_abs;
-            let enable_abs_pdfs = !(nconvs == 1 || bins == 1);
-            let enable_ratio_pdf = enable_abs_pdfs;
-            let enable_double_ratio_pdf = enable_abs_pdfs;
+            // with a single PDF set, this panel is only needed to show the channel breakdown
+            let enable_abs_pdfs = !(bins == 1 || (nconvs == 1 && self.channel_breakdown.is_none()));
+            let enable_ratio_pdf = !(nconvs == 1 || bins == 1);
+            let enable_double_ratio_pdf = enable_ratio_pdf;
             let enable_rel_pdfunc = !(nconvs == 1 || bins == 1 || self.no_conv_fun_unc);
             let enable_rel_pdfpull = enable_rel_pdfunc;
 
@@ -531,6 +610,12 @@ ylabel = r\"{ylabel}\"
 xlog = {xlog}
 ylog = {ylog}
 scales = {scales}
+ratio_to = {ratio_to}
+# linestyle for the channel breakdown shown in the panel `plot_abs_pdfs`. If the array
+# is empty, no channel breakdown will be shown, otherwise the most important channels,
+# as many as linestyles are given. See also
+# https://matplotlib.org/stable/gallery/lines_bars_and_markers/linestyles.html
+channel_breakdown_linestyles = [{channel_breakdown_linestyles}]
 plot_panels = {{
     \"int\": {enable_int},
     \"abs\": {enable_abs},
@@ -556,6 +641,12 @@ output = r\"{output}\"",
                 ylog = map_bool(ylog),
                 title = title,
                 scales = self.scales,
+                ratio_to = self.ratio_to,
+                channel_breakdown_linestyles = CHANNEL_BREAKDOWN_LINESTYLES
+                    .iter()
+                    .cycle()
+                    .take(self.channel_breakdown.map_or(0, NonZeroUsize::get))
+                    .join(", "),
                 output = output.to_str().unwrap(),
             );
 
@@ -592,10 +683,14 @@ metadata = {{
             // TODO: enforce two arguments with clap
             assert_eq!(self.conv_funs.len(), 2);
 
+            let indices = self.conv_fun_uncert_from.indices(&self.conv_funs[0]);
+            let &[conv_fun_uncert_from] = indices.as_slice() else {
+                bail!("`--subgrid-pull` requires a single index for `--conv-fun-uncert-from`");
+            };
             let (set1, mut conv_funs1) =
-                helpers::create_conv_funs_for_set(&self.conv_funs[0], self.conv_fun_uncert_from)?;
+                helpers::create_conv_funs_for_set(&self.conv_funs[0], conv_fun_uncert_from)?;
             let (set2, mut conv_funs2) =
-                helpers::create_conv_funs_for_set(&self.conv_funs[1], self.conv_fun_uncert_from)?;
+                helpers::create_conv_funs_for_set(&self.conv_funs[1], conv_fun_uncert_from)?;
             let (order, bin, channel) = self
                 .subgrid_pull
                 .iter()
@@ -606,8 +701,8 @@ metadata = {{
             let cl = lhapdf::CL_1_SIGMA;
             let grid = helpers::read_grid(&self.input)?;
 
-            let member1 = self.conv_funs[0].members[self.conv_fun_uncert_from];
-            let member2 = self.conv_funs[1].members[self.conv_fun_uncert_from];
+            let member1 = self.conv_funs[0].members[conv_fun_uncert_from];
+            let member2 = self.conv_funs[1].members[conv_fun_uncert_from];
 
             let values1: Vec<_> = conv_funs1
                 .par_iter_mut()
@@ -710,7 +805,11 @@ metadata = {{
                 include_str!("subgrid-pull-plot.py"),
                 x1 = map_format_e_join(&x1_vals),
                 x2 = map_format_e_join(&x2_vals),
-                z = map_format_e_join(&vals)
+                z = map_format_e_join(&vals),
+                output = self
+                    .output
+                    .as_deref()
+                    .map_or("plot", |output| output.to_str().unwrap())
             );
         }
 
@@ -1,11 +1,14 @@
-use super::helpers;
+use super::helpers::{self, ConvoluteMode};
 use super::{GlobalConfiguration, Subcommand};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::{Args, Parser, ValueHint};
 use itertools::Itertools;
 use pineappl::boc::Order;
+use pineappl::convolutions::Convolution;
 use pineappl::fk_table::FkTable;
+use pineappl::grid::{DimLabel, Reference};
 use prettytable::{cell, row, Row};
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 use std::process::ExitCode;
 
@@ -24,9 +27,21 @@ struct Group {
     /// Show the bins of a grid.
     #[arg(long, short)]
     bins: bool,
+    /// Show the bins of a grid as a LaTeX table.
+    #[arg(long)]
+    bins_tex: bool,
     /// Show the channel definition of a grid.
     #[arg(alias = "lumis", long)]
     channels: bool,
+    /// Show the channel definition of a grid as a LaTeX table.
+    #[arg(long)]
+    channels_tex: bool,
+    /// Show the convolutions of a grid and the particle IDs they must provide.
+    #[arg(long)]
+    convolutions: bool,
+    /// Compare the key-value pairs with the ones of another grid.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "OTHER")]
+    compare_metadata: Option<PathBuf>,
     /// Check if input is an FK table.
     #[arg(long)]
     fktable: bool,
@@ -46,6 +61,12 @@ struct Group {
     /// Shows all key-value pairs stored in the grid.
     #[arg(long)]
     show: bool,
+    /// Shows all key-value pairs stored in the grid as a JSON object.
+    #[arg(long)]
+    metadata_json: bool,
+    /// Show the reference results stored in the grid.
+    #[arg(long)]
+    reference: bool,
 }
 
 /// Read out information of a grid.
@@ -56,11 +77,21 @@ pub struct Opts {
     input: PathBuf,
     #[command(flatten)]
     group: Group,
+    /// Only read orders, bins, channels and metadata. This is the default for every option except
+    /// `--fktable`, and the switch is kept for backwards compatibility.
+    #[arg(conflicts_with = "fktable", hide = true, long)]
+    header_only: bool,
 }
 
 impl Subcommand for Opts {
     fn run(&self, _: &GlobalConfiguration) -> Result<ExitCode> {
-        let mut grid = helpers::read_grid(&self.input)?;
+        // only the FK-table check needs the subgrids, everything else is stored in the header,
+        // which is much faster to read for large grids
+        let mut grid = if self.group.fktable {
+            helpers::read_grid(&self.input)?
+        } else {
+            helpers::read_grid_header(&self.input)?
+        };
 
         let mut table = helpers::create_table();
 
@@ -96,6 +127,114 @@ impl Subcommand for Opts {
 
                 row.add_cell(cell!(r->format!("{}", normalizations[bin])));
             }
+        } else if self.group.reference {
+            let Reference { pdf, results } = grid
+                .reference()?
+                .ok_or_else(|| anyhow!("the grid does not store a reference"))?;
+            let (x, _, y_unit) = helpers::labels_and_units(&grid, false);
+            let mut titles = Row::empty();
+            titles.add_cell(cell!(c->"b"));
+
+            for (x_label, x_unit) in x {
+                let mut cell = cell!(c->format!("{x_label}\n[{x_unit}]"));
+                cell.set_hspan(2);
+                titles.add_cell(cell);
+            }
+            titles.add_cell(cell!(c->format!("{pdf}\n[{y_unit}]")));
+
+            table.set_titles(titles);
+
+            for (bin, (limits, result)) in
+                helpers::convolve_limits(&grid, &[], ConvoluteMode::Normal)
+                    .into_iter()
+                    .zip(results)
+                    .enumerate()
+            {
+                let row = table.add_empty_row();
+                row.add_cell(cell!(r->format!("{bin}")));
+
+                for (left, right) in limits {
+                    row.add_cell(cell!(r->format!("{left}")));
+                    row.add_cell(cell!(r->format!("{right}")));
+                }
+
+                row.add_cell(cell!(r->format!("{result:.7e}")));
+            }
+        } else if self.group.bins_tex {
+            let dimensions = grid.bin_info().dimensions();
+
+            let labels: Vec<_> = grid
+                .bin_dimension_labels()
+                .into_iter()
+                .enumerate()
+                .map(
+                    |(
+                        d,
+                        DimLabel {
+                            label_tex, unit, ..
+                        },
+                    )| {
+                        let label = if label_tex.is_empty() {
+                            format!("$x{}$", d + 1)
+                        } else {
+                            label_tex
+                        };
+
+                        if unit.is_empty() {
+                            label
+                        } else {
+                            format!("{label} [\\si{{{unit}}}]")
+                        }
+                    },
+                )
+                .collect();
+
+            let left_limits: Vec<_> = (0..dimensions).map(|i| grid.bin_info().left(i)).collect();
+            let right_limits: Vec<_> = (0..dimensions).map(|i| grid.bin_info().right(i)).collect();
+            let normalizations = grid.bin_info().normalizations();
+
+            println!("\\begin{{tabular}}{{r{}r}}", "c".repeat(dimensions));
+            println!("\\hline");
+            println!("b & {} & norm \\\\", labels.join(" & "));
+            println!("\\hline");
+
+            for (bin, norm) in normalizations.iter().enumerate() {
+                let limits = left_limits
+                    .iter()
+                    .zip(&right_limits)
+                    .map(|(left, right)| format!("$[{}, {}]$", left[bin], right[bin]))
+                    .join(" & ");
+
+                println!("{bin} & {limits} & ${norm}$ \\\\");
+            }
+
+            println!("\\hline");
+            println!("\\end{{tabular}}");
+
+            return Ok(ExitCode::SUCCESS);
+        } else if self.group.channels_tex {
+            let convolutions = grid.convolutions();
+            let convolutions = [
+                convolutions[0] != Convolution::None,
+                convolutions[1] != Convolution::None,
+            ];
+
+            println!("\\begin{{tabular}}{{rl}}");
+            println!("\\hline");
+            println!("c & channel \\\\");
+            println!("\\hline");
+
+            for (index, channel) in grid.channels().iter().enumerate() {
+                println!(
+                    "{index} & ${}$ \\\\",
+                    channel.to_tex(grid.pid_basis(), convolutions)
+                );
+            }
+
+            println!("\\hline");
+            println!("\\end{{tabular}}");
+
+            return Ok(ExitCode::SUCCESS);
         } else if self.group.fktable {
             if let Err(err) = FkTable::try_from(grid) {
                 println!("no\n{err}");
@@ -128,6 +267,36 @@ impl Subcommand for Opts {
                     row.add_cell(cell!(format!("{factor} \u{d7} ({id1:2}, {id2:2})")));
                 }
             }
+        } else if self.group.convolutions {
+            table.set_titles(row![c => "i", "convolution", "pids"]);
+
+            for (index, convolution) in grid.convolutions().iter().enumerate() {
+                let pids = grid.distinct_pids(index).iter().join(", ");
+
+                table.add_row(row![index.to_string(), convolution.to_string(), pids]);
+            }
+        } else if let Some(other) = &self.group.compare_metadata {
+            let mut other = helpers::read_grid_header(other)?;
+
+            grid.upgrade();
+            other.upgrade();
+
+            // UNWRAP: after upgrading there's always a key-value storage
+            let lhs = grid.key_values().unwrap_or_else(|| unreachable!());
+            let rhs = other.key_values().unwrap_or_else(|| unreachable!());
+
+            let keys: BTreeSet<_> = lhs.keys().chain(rhs.keys()).collect();
+
+            for key in keys {
+                match (lhs.get(key), rhs.get(key)) {
+                    (Some(a), Some(b)) if a != b => println!("{key}: differs"),
+                    (Some(_), None) => println!("{key}: only in first grid"),
+                    (None, Some(_)) => println!("{key}: only in second grid"),
+                    _ => {}
+                }
+            }
+
+            return Ok(ExitCode::SUCCESS);
         } else if self.group.ew || self.group.qcd {
             let mut sorted_grid_orders: Vec<_> = grid
                 .orders()
@@ -199,6 +368,16 @@ impl Subcommand for Opts {
                     }
                 },
             );
+        } else if self.group.metadata_json {
+            grid.upgrade();
+
+            let key_values: BTreeMap<_, _> = grid
+                .key_values()
+                .unwrap_or_else(|| unreachable!())
+                .iter()
+                .collect();
+
+            println!("{}", serde_json::to_string(&key_values)?);
         } else {
             table.set_titles(row![c => "o", "order"]);
 
@@ -61,4 +61,4 @@ axes[1].set_xlabel(r"$x_1$")
 axes[1].set_ylabel(r"$x_2$")
 
 figure.colorbar(mesh, ax=axes, extend="min")
-figure.savefig("plot.pdf")
+figure.savefig(r"{output}.pdf")
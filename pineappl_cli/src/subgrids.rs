@@ -1,10 +1,17 @@
 use super::helpers;
 use super::{GlobalConfiguration, Subcommand};
-use anyhow::Result;
+use anyhow::{bail, Result};
+use clap::builder::PossibleValuesParser;
 use clap::{Args, Parser, ValueHint};
+use ndarray::s;
+use pineappl::grid::Grid;
 use pineappl::subgrid::Mu2;
-use pineappl::subgrid::{Subgrid, SubgridEnum};
+use pineappl::subgrid::Subgrid;
 use prettytable::{cell, row};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::mem;
 use std::path::PathBuf;
 use std::process::ExitCode;
 
@@ -35,6 +42,36 @@ struct Group {
     /// Show grid statistics (figures are the number of entries).
     #[arg(long)]
     stats: bool,
+    /// Show a summary of all subgrids instead of one row per subgrid.
+    #[arg(
+        conflicts_with_all = ["type_", "mur", "mur2", "muf", "muf2", "x1", "x2", "stats"],
+        long
+    )]
+    total: bool,
+    /// Show which orders and channels `pineappl write --optimize` would remove or merge, without
+    /// modifying the grid.
+    #[arg(
+        conflicts_with_all = ["type_", "mur", "mur2", "muf", "muf2", "x1", "x2", "stats", "total"],
+        long
+    )]
+    optimize_preview: bool,
+    /// Show the number of non-zero entries, nodes and estimated bytes of each subgrid, sorted by
+    /// decreasing memory usage.
+    #[arg(
+        conflicts_with_all = ["type_", "mur", "mur2", "muf", "muf2", "x1", "x2", "stats", "total", "optimize_preview"],
+        long
+    )]
+    memory: bool,
+}
+
+/// Summary of all subgrids printed by `--total`.
+#[derive(Default, Serialize)]
+struct Total {
+    subgrids: usize,
+    empty: usize,
+    filled: usize,
+    bytes: usize,
+    kinds: BTreeMap<String, usize>,
 }
 
 /// Print information about the internal subgrid types.
@@ -51,11 +88,155 @@ pub struct Opts {
     /// Set the number of digits shown for numerical values.
     #[arg(default_value_t = 3, long)]
     digits: usize,
+    /// Set the output format of the summary, which is a table by default.
+    #[arg(long, value_parser = PossibleValuesParser::new(["table", "json"]))]
+    format: Option<String>,
+}
+
+impl Opts {
+    fn print_optimize_preview(grid: &Grid) {
+        let subgrids = grid.subgrids();
+        let format_indices = |indices: Vec<String>| {
+            if indices.is_empty() {
+                "none".to_owned()
+            } else {
+                indices.join(", ")
+            }
+        };
+
+        let empty_orders: Vec<_> = (0..grid.orders().len())
+            .filter(|&order| {
+                subgrids
+                    .slice(s![order, .., ..])
+                    .iter()
+                    .all(Subgrid::is_empty)
+            })
+            .map(|order| order.to_string())
+            .collect();
+        // whether a channel has non-empty subgrids after the channels were symmetrized and merged
+        let mut non_empty: Vec<_> = (0..grid.channels().len())
+            .map(|channel| {
+                !subgrids
+                    .slice(s![.., .., channel])
+                    .iter()
+                    .all(Subgrid::is_empty)
+            })
+            .collect();
+
+        for (index, other_index) in grid.symmetrizable_channels() {
+            non_empty[index] |= mem::take(&mut non_empty[other_index]);
+        }
+
+        let mergeable = grid.mergeable_channels();
+
+        for &(index, other_index, _) in &mergeable {
+            non_empty[other_index] |= mem::take(&mut non_empty[index]);
+        }
+
+        let empty_channels: Vec<_> = non_empty
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, non_empty)| !non_empty)
+            .map(|(channel, _)| channel.to_string())
+            .collect();
+        let mergeable_channels: Vec<_> = mergeable
+            .into_iter()
+            .map(|(index, other_index, factor)| {
+                format!("{index} into {other_index} (factor {factor})")
+            })
+            .collect();
+
+        println!("empty orders: {}", format_indices(empty_orders));
+        println!("empty channels: {}", format_indices(empty_channels));
+        println!("mergeable channels: {}", format_indices(mergeable_channels));
+    }
+
+    fn print_memory(&self, grid: &Grid) {
+        let subgrids = grid.subgrids();
+        let mut stats: Vec<_> = grid
+            .subgrid_stats()
+            .into_iter()
+            .filter(|stats| {
+                self.show_empty || !subgrids[[stats.order, stats.bin, stats.channel]].is_empty()
+            })
+            .collect();
+        stats.sort_by_key(|stats| Reverse(stats.bytes));
+
+        let mut table = helpers::create_table();
+        table.set_titles(row![c => "o", "b", "c", "type", "non-zeros", "nodes", "bytes"]);
+
+        for stats in stats {
+            table.add_row(row![
+                l->stats.order,
+                l->stats.bin,
+                l->stats.channel,
+                l->stats.kind,
+                r->stats.non_zeros,
+                r->stats.nodes,
+                r->stats.bytes
+            ]);
+        }
+
+        table.printstd();
+    }
+
+    fn print_total(&self, grid: &Grid) -> Result<()> {
+        let mut total = Total::default();
+
+        for stats in grid.subgrid_stats() {
+            total.subgrids += 1;
+            if grid.subgrids()[[stats.order, stats.bin, stats.channel]].is_empty() {
+                total.empty += 1;
+            }
+            total.filled += stats.non_zeros;
+            total.bytes += stats.bytes;
+            *total.kinds.entry(stats.kind.to_string()).or_default() += 1;
+        }
+
+        if self.format.as_deref() == Some("json") {
+            println!("{}", serde_json::to_string(&total)?);
+        } else {
+            let mut table = helpers::create_table();
+            let mut titles = row![c => "subgrids", "empty", "filled", "bytes"];
+            let mut row = row![r => total.subgrids, total.empty, total.filled, total.bytes];
+
+            for (kind, count) in total.kinds {
+                titles.add_cell(cell!(c->kind));
+                row.add_cell(cell!(r->count.to_string()));
+            }
+
+            table.set_titles(titles);
+            table.add_row(row);
+            table.printstd();
+        }
+
+        Ok(())
+    }
 }
 
 impl Subcommand for Opts {
     fn run(&self, _: &GlobalConfiguration) -> Result<ExitCode> {
+        if self.format.is_some() && !self.group.total {
+            bail!("`--format` requires `--total`");
+        }
+
         let grid = helpers::read_grid(&self.input)?;
+
+        if self.group.total {
+            self.print_total(&grid)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+
+        if self.group.optimize_preview {
+            Self::print_optimize_preview(&grid);
+            return Ok(ExitCode::SUCCESS);
+        }
+
+        if self.group.memory {
+            self.print_memory(&grid);
+            return Ok(ExitCode::SUCCESS);
+        }
+
         let mut table = helpers::create_table();
         let mut titles = row![c => "o", "b", "c"];
 
@@ -100,17 +281,7 @@ impl Subcommand for Opts {
             row.add_cell(cell!(l->format!("{channel}")));
 
             if self.group.type_ {
-                row.add_cell(cell!(l->
-                    match subgrid {
-                        SubgridEnum::LagrangeSubgridV1(_) => "LagrangeSubgridV1",
-                        SubgridEnum::NtupleSubgridV1(_) => "NtupleSubgridV1",
-                        SubgridEnum::LagrangeSparseSubgridV1(_) => "LagrangeSparseSubgridV1",
-                        SubgridEnum::LagrangeSubgridV2(_) => "LagrangeSubgridV2",
-                        SubgridEnum::ImportOnlySubgridV1(_) => "ImportOnlySubgridV1",
-                        SubgridEnum::ImportOnlySubgridV2(_) => "ImportOnlySubgridV2",
-                        SubgridEnum::EmptySubgridV1(_) => "EmptySubgridV1",
-                    }
-                ));
+                row.add_cell(cell!(l->subgrid.kind().to_string()));
             }
             if self.group.mur {
                 let values: Vec<_> = subgrid
@@ -1,8 +1,10 @@
 use super::helpers::{self, ConvFuns, ConvoluteMode};
 use super::{GlobalConfiguration, Subcommand};
-use anyhow::{Error, Result};
+use anyhow::{bail, Error, Result};
 use clap::builder::{PossibleValuesParser, TypedValueParser};
 use clap::{Args, Parser, ValueHint};
+use lhapdf::PdfSet;
+use ndarray::Array2;
 use prettytable::{cell, Row};
 use rayon::{prelude::*, ThreadPoolBuilder};
 use std::num::NonZeroUsize;
@@ -71,6 +73,20 @@ pub struct Opts {
     /// Show integrated numbers (without bin widths) instead of differential ones.
     #[arg(long, short)]
     integrated: bool,
+    /// Show the total uncertainties, adding the relative convolution function and scale-envelope
+    /// uncertainties in quadrature.
+    #[arg(long, requires_all = ["conv_fun", "scale_env"])]
+    decompose: bool,
+    /// Write the covariance matrix of the bins, adding the covariances of the first convolution
+    /// function set at the confidence level `--cl` and of the scale variations, in the NPY format
+    /// into FILE.
+    #[arg(
+        long,
+        requires_all = ["conv_fun", "scale_cov"],
+        value_hint = ValueHint::FilePath,
+        value_name = "FILE"
+    )]
+    covariance: Option<PathBuf>,
     /// Select orders manually.
     #[arg(
         long,
@@ -111,13 +127,13 @@ impl Subcommand for Opts {
             .build_global()
             .unwrap();
 
-        let conv_fun_results: Vec<Vec<_>> = self
+        let conv_fun_members: Vec<(PdfSet, Vec<Vec<_>>)> = self
             .group
             .conv_fun
             .iter()
             .map(|&index| {
                 let (set, funs) = helpers::create_conv_funs_for_set(&self.conv_funs, index)?;
-                let results: Vec<_> = funs
+                let results = funs
                     .into_par_iter()
                     .map(|mut funs| {
                         Ok::<_, Error>(helpers::convolve(
@@ -137,6 +153,12 @@ impl Subcommand for Opts {
                     })
                     .collect::<Result<_, _>>()?;
 
+                Ok((set, results))
+            })
+            .collect::<Result<_, Error>>()?;
+        let conv_fun_results: Vec<Vec<_>> = conv_fun_members
+            .iter()
+            .map(|(set, results)| {
                 (0..results[0].len())
                     // transpose results
                     .map(|bin| {
@@ -145,9 +167,9 @@ impl Subcommand for Opts {
                             .collect::<Vec<_>>()
                     })
                     .map(|values| Ok(set.uncertainty(&values, self.cl, false)?))
-                    .collect::<Result<_, _>>()
+                    .collect::<Result<_, Error>>()
             })
-            .collect::<Result<_, Error>>()?;
+            .collect::<Result<_, _>>()?;
         let scales_max = self
             .group
             .scale_env
@@ -172,6 +194,15 @@ impl Subcommand for Opts {
             cfg,
         );
 
+        if let Some(output) = &self.covariance {
+            // UNWRAPs: `--covariance` requires `--conv-fun` and `--scale-cov`
+            let (set, results) = &conv_fun_members[0];
+            let covariance = conv_fun_covariance(set, results, self.cl)?
+                + scale_covariance(&scale_results, scales_max, self.group.scale_cov.unwrap());
+
+            helpers::write_npy(output, &covariance)?;
+        }
+
         let (x, y_label, y_unit) = helpers::labels_and_units(&grid, self.integrated);
         let mut title = Row::empty();
         title.add_cell(cell!(c->"b"));
@@ -204,6 +235,15 @@ impl Subcommand for Opts {
             title.add_cell(cell!(c->format!("{}pt-svar (env)\n[%]", scales)).with_hspan(2));
         }
 
+        if self.decompose {
+            for &index in &self.group.conv_fun {
+                title.add_cell(
+                    cell!(c->format!("total {}\n[%]", self.conv_funs.lhapdf_names[index]))
+                        .with_hspan(2),
+                );
+            }
+        }
+
         let mut table = helpers::create_table();
         table.set_titles(title);
 
@@ -234,7 +274,7 @@ impl Subcommand for Opts {
             }
 
             if let Some(scales) = self.group.scale_cov {
-                let ns = if scales == 3 { 1.0 } else { 2.0 } / f64::from(scales - 1);
+                let ns = scale_normalization(scales);
                 let unc = (ns
                     * scale_res
                         .iter()
@@ -249,7 +289,7 @@ impl Subcommand for Opts {
                 row.add_cell(cell!(r->format!("{:.*}", self.digits_rel, rel_unc)));
             }
 
-            if let Some(scales) = self.group.scale_env {
+            let scale_env = self.group.scale_env.map(|scales| {
                 let min_value = scale_res
                     .iter()
                     .take(usize::from(scales))
@@ -263,9 +303,29 @@ impl Subcommand for Opts {
                 let scale_neg = 100.0 * (min_value / scale_res[0] - 1.0);
                 let scale_pos = 100.0 * (max_value / scale_res[0] - 1.0);
 
+                (scale_neg, scale_pos)
+            });
+
+            if let Some((scale_neg, scale_pos)) = scale_env {
                 row.add_cell(cell!(r->format!("{:.*}", self.digits_rel, scale_neg)));
                 row.add_cell(cell!(r->format!("{:.*}", self.digits_rel, scale_pos)));
             }
+
+            if self.decompose {
+                // UNWRAP: `--decompose` requires `--scale-env`
+                let (scale_neg, scale_pos) = scale_env.unwrap();
+
+                for uncertainty in conv_fun_results.iter().map(|results| &results[bin]) {
+                    // add the lower and upper relative uncertainties separately in quadrature
+                    let total_neg =
+                        -(100.0 * uncertainty.errminus / uncertainty.central).hypot(scale_neg);
+                    let total_pos =
+                        (100.0 * uncertainty.errplus / uncertainty.central).hypot(scale_pos);
+
+                    row.add_cell(cell!(r->format!("{:.*}", self.digits_rel, total_neg)));
+                    row.add_cell(cell!(r->format!("{:.*}", self.digits_rel, total_pos)));
+                }
+            }
         }
 
         table.printstd();
@@ -273,3 +333,73 @@ impl Subcommand for Opts {
         Ok(ExitCode::SUCCESS)
     }
 }
+
+/// Normalization of the sum of squared differences used by the covariance method for `scales`
+/// scale variations.
+fn scale_normalization(scales: u16) -> f64 {
+    (if scales == 3 { 1.0 } else { 2.0 }) / f64::from(scales - 1)
+}
+
+/// Returns the covariance matrix of the bins obtained with the covariance method from the first
+/// `scales` of the `scales_max` scale-varied results of each bin in `scale_results`. The scale
+/// variations are assumed to be fully correlated across the bins.
+fn scale_covariance(scale_results: &[f64], scales_max: usize, scales: u16) -> Array2<f64> {
+    let ns = scale_normalization(scales);
+    let bins: Vec<_> = scale_results.chunks_exact(scales_max).collect();
+
+    Array2::from_shape_fn((bins.len(), bins.len()), |(i, j)| {
+        ns * (1..usize::from(scales))
+            .map(|k| (bins[i][k] - bins[i][0]) * (bins[j][k] - bins[j][0]))
+            .sum::<f64>()
+    })
+}
+
+/// Returns the covariance matrix of the bins for the members of `set`, whose `results` are
+/// indexed by member and bin. The covariance is calculated following the error type of the set
+/// and rescaled from the confidence level of the set to `cl`, given in per cent.
+fn conv_fun_covariance(set: &PdfSet, results: &[Vec<f64>], cl: f64) -> Result<Array2<f64>> {
+    let bins = results[0].len();
+    // the first member is the central one
+    let members = &results[1..];
+    let error_type = set.error_type();
+
+    let covariance = match error_type.as_str() {
+        "replicas" => {
+            let n = f64::from(u32::try_from(members.len())?);
+            let mean: Vec<_> = (0..bins)
+                .map(|bin| members.iter().map(|member| member[bin]).sum::<f64>() / n)
+                .collect();
+
+            Array2::from_shape_fn((bins, bins), |(i, j)| {
+                members
+                    .iter()
+                    .map(|member| (member[i] - mean[i]) * (member[j] - mean[j]))
+                    .sum::<f64>()
+                    / (n - 1.0)
+            })
+        }
+        "hessian" => Array2::from_shape_fn((bins, bins), |(i, j)| {
+            members
+                .chunks_exact(2)
+                .map(|pair| 0.25 * (pair[0][i] - pair[1][i]) * (pair[0][j] - pair[1][j]))
+                .sum()
+        }),
+        "symmhessian" => Array2::from_shape_fn((bins, bins), |(i, j)| {
+            members
+                .iter()
+                .map(|member| (member[i] - results[0][i]) * (member[j] - results[0][j]))
+                .sum()
+        }),
+        _ => bail!("the covariance for sets with error type '{error_type}' is not supported"),
+    };
+
+    // the ratio of the uncertainties at `cl` and at the confidence level of the set (negative
+    // values select the latter) doesn't depend on the values, as long as they're not all equal
+    let values = (0..u32::try_from(results.len())?)
+        .map(f64::from)
+        .collect::<Vec<_>>();
+    let scale = set.uncertainty(&values, cl, false)?.errsymm
+        / set.uncertainty(&values, -1.0, false)?.errsymm;
+
+    Ok(covariance * scale * scale)
+}
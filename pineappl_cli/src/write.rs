@@ -1,14 +1,17 @@
 use super::helpers;
 use super::{GlobalConfiguration, Subcommand};
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, ensure, Context, Result};
 use clap::builder::{PossibleValuesParser, TypedValueParser};
 use clap::{
     value_parser, Arg, ArgAction, ArgMatches, Args, Command, Error, FromArgMatches, Parser,
     ValueHint,
 };
+use ndarray::Array2;
 use pineappl::bin::BinRemapper;
 use pineappl::boc::{Channel, Order};
+use pineappl::convolutions::Convolution;
 use pineappl::fk_table::{FkAssumptions, FkTable};
+use pineappl::grid::GridOptFlags;
 use pineappl::pids::PidBasis;
 use std::fs;
 use std::ops::RangeInclusive;
@@ -21,9 +24,18 @@ pub struct Opts {
     /// Path to the input grid.
     #[arg(value_hint = ValueHint::FilePath)]
     input: PathBuf,
-    /// Path of the modified PineAPPL file.
+    /// Path of the modified PineAPPL file; `-` writes to the standard output.
     #[arg(value_hint = ValueHint::FilePath)]
     output: PathBuf,
+    /// Compression of the output; by default inferred from its extension.
+    #[arg(long, value_parser = PossibleValuesParser::new(["lz4", "none"]))]
+    compress: Option<String>,
+    /// With `--split-channels`, only split channels with more than N combinations.
+    #[arg(long, requires = "split_channels", value_name = "N")]
+    max_combinations: Option<usize>,
+    /// Print what `--optimize` changed.
+    #[arg(long)]
+    verbose: bool,
     #[command(flatten)]
     more_args: MoreArgs,
 }
@@ -32,12 +44,17 @@ pub struct Opts {
 enum OpsArg {
     Cc1(bool),
     Cc2(bool),
+    ChannelBinKfactors(String),
     DedupChannels(i64),
     DeleteBins(Vec<RangeInclusive<usize>>),
     DeleteChannels(Vec<RangeInclusive<usize>>),
     DeleteOrders(Vec<RangeInclusive<usize>>),
     DeleteKey(String),
+    Kfactor((usize, String)),
+    MaxPower(u32),
     MergeBins(Vec<RangeInclusive<usize>>),
+    MinPower(u32),
+    NoPhoton(bool),
     Optimize(bool),
     OptimizeFkTable(FkAssumptions),
     Remap(String),
@@ -49,6 +66,7 @@ enum OpsArg {
     Scale(f64),
     ScaleByBin(Vec<f64>),
     ScaleByOrder(Vec<f64>),
+    SetConvType((usize, String)),
     SetKeyFile(Vec<String>),
     SetKeyValue(Vec<String>),
     SplitChannels(bool),
@@ -70,7 +88,7 @@ impl FromArgMatches for MoreArgs {
             args.resize(indices.iter().max().unwrap() + 1, None);
 
             match id.as_str() {
-                "cc1" | "cc2" | "optimize" | "split_channels" | "upgrade" => {
+                "cc1" | "cc2" | "no_photon" | "optimize" | "split_channels" | "upgrade" => {
                     let arguments: Vec<Vec<_>> = matches
                         .remove_occurrences(&id)
                         .unwrap()
@@ -83,6 +101,7 @@ impl FromArgMatches for MoreArgs {
                         args[index] = Some(match id.as_str() {
                             "cc1" => OpsArg::Cc1(arg[0]),
                             "cc2" => OpsArg::Cc2(arg[0]),
+                            "no_photon" => OpsArg::NoPhoton(arg[0]),
                             "optimize" => OpsArg::Optimize(arg[0]),
                             "split_channels" => OpsArg::SplitChannels(arg[0]),
                             "upgrade" => OpsArg::Upgrade(arg[0]),
@@ -103,6 +122,21 @@ impl FromArgMatches for MoreArgs {
                         });
                     }
                 }
+                "max_power" | "min_power" => {
+                    for (index, arg) in indices.into_iter().zip(
+                        matches
+                            .remove_occurrences(&id)
+                            .unwrap()
+                            .map(Iterator::collect::<Vec<_>>),
+                    ) {
+                        assert_eq!(arg.len(), 1);
+                        args[index] = Some(match id.as_str() {
+                            "max_power" => OpsArg::MaxPower(arg[0]),
+                            "min_power" => OpsArg::MinPower(arg[0]),
+                            _ => unreachable!(),
+                        });
+                    }
+                }
                 "dedup_channels" => {
                     for (index, arg) in indices.into_iter().zip(
                         matches
@@ -117,7 +151,7 @@ impl FromArgMatches for MoreArgs {
                         });
                     }
                 }
-                "delete_key" | "remap" => {
+                "channel_bin_kfactors" | "delete_key" | "remap" => {
                     for (index, mut arg) in indices.into_iter().zip(
                         matches
                             .remove_occurrences(&id)
@@ -126,6 +160,9 @@ impl FromArgMatches for MoreArgs {
                     ) {
                         assert_eq!(arg.len(), 1);
                         args[index] = Some(match id.as_str() {
+                            "channel_bin_kfactors" => {
+                                OpsArg::ChannelBinKfactors(arg.pop().unwrap())
+                            }
                             "delete_key" => OpsArg::DeleteKey(arg.pop().unwrap()),
                             "remap" => OpsArg::Remap(arg.pop().unwrap()),
                             _ => unreachable!(),
@@ -177,7 +214,7 @@ impl FromArgMatches for MoreArgs {
                         });
                     }
                 }
-                "rewrite_channel" | "rewrite_order" => {
+                "kfactor" | "rewrite_channel" | "rewrite_order" | "set_conv_type" => {
                     for (index, arg) in indices.into_iter().zip(
                         matches
                             .remove_occurrences(&id)
@@ -187,6 +224,9 @@ impl FromArgMatches for MoreArgs {
                         assert_eq!(arg.len(), 2);
 
                         args[index] = Some(match id.as_str() {
+                            "kfactor" => {
+                                OpsArg::Kfactor((str::parse(&arg[0]).unwrap(), arg[1].clone()))
+                            }
                             "rewrite_channel" => OpsArg::RewriteChannel((
                                 str::parse(&arg[0]).unwrap(),
                                 str::parse(&arg[1]).unwrap(),
@@ -195,6 +235,9 @@ impl FromArgMatches for MoreArgs {
                                 str::parse(&arg[0]).unwrap(),
                                 str::parse(&arg[1]).unwrap(),
                             )),
+                            "set_conv_type" => {
+                                OpsArg::SetConvType((str::parse(&arg[0]).unwrap(), arg[1].clone()))
+                            }
                             _ => unreachable!(),
                         });
                     }
@@ -279,6 +322,14 @@ impl Args for MoreArgs {
                 .value_name("ENABLE")
                 .value_parser(clap::value_parser!(bool)),
         )
+        .arg(
+            Arg::new("channel_bin_kfactors")
+                .action(ArgAction::Append)
+                .help("Scale each channel and bin with a factor read from a file")
+                .long("channel-bin-kfactors")
+                .value_hint(ValueHint::FilePath)
+                .value_name("FILE"),
+        )
         .arg(
             Arg::new("dedup_channels")
                 .action(ArgAction::Append)
@@ -327,6 +378,22 @@ impl Args for MoreArgs {
                 .long("delete-key")
                 .value_name("KEY"),
         )
+        .arg(
+            Arg::new("kfactor")
+                .action(ArgAction::Append)
+                .help("Scale the order with index IDX and its scale logarithms with bin-dependent factors read from FILE")
+                .long("kfactor")
+                .num_args(2)
+                .value_names(["IDX", "FILE"]),
+        )
+        .arg(
+            Arg::new("max_power")
+                .action(ArgAction::Append)
+                .help("Delete orders whose sum of as and a powers is larger than POWER")
+                .long("max-power")
+                .value_name("POWER")
+                .value_parser(value_parser!(u32)),
+        )
         .arg(
             Arg::new("merge_bins")
                 .action(ArgAction::Append)
@@ -337,6 +404,25 @@ impl Args for MoreArgs {
                 .value_name("BIN1-BIN2,...")
                 .value_parser(helpers::parse_integer_range),
         )
+        .arg(
+            Arg::new("min_power")
+                .action(ArgAction::Append)
+                .help("Delete orders whose sum of as and a powers is smaller than POWER")
+                .long("min-power")
+                .value_name("POWER")
+                .value_parser(value_parser!(u32)),
+        )
+        .arg(
+            Arg::new("no_photon")
+                .action(ArgAction::Append)
+                .default_missing_value("true")
+                .help("Remove the contributions of photon-initiated channels")
+                .long("no-photon")
+                .num_args(0..=1)
+                .require_equals(true)
+                .value_name("ENABLE")
+                .value_parser(clap::value_parser!(bool)),
+        )
         .arg(
             Arg::new("optimize")
                 .action(ArgAction::Append)
@@ -439,13 +525,21 @@ impl Args for MoreArgs {
         .arg(
             Arg::new("scale_by_order")
                 .action(ArgAction::Append)
-                .help("Scales all grids with order-dependent factors")
+                .help("Scales all grids with order-dependent factors and an optional global factor")
                 .long("scale-by-order")
                 .num_args(1)
                 .value_delimiter(',')
                 .value_name("AS,AL,LR,LF")
                 .value_parser(value_parser!(f64)),
         )
+        .arg(
+            Arg::new("set_conv_type")
+                .action(ArgAction::Append)
+                .help("Set the type of the convolution with index IDX, keeping its hadron unless TYPE is followed by ':<PID>'")
+                .long("set-conv-type")
+                .num_args(2)
+                .value_names(["IDX", "TYPE"]),
+        )
         .arg(
             Arg::new("set_key_value")
                 .action(ArgAction::Append)
@@ -494,6 +588,37 @@ impl Args for MoreArgs {
     }
 }
 
+/// Reads a matrix of factors from `file`, with each line corresponding to a channel and each
+/// whitespace-separated column corresponding to a bin. Empty lines and lines starting with `#` are
+/// ignored.
+fn read_kfactors(file: &str) -> Result<Array2<f64>> {
+    let rows = fs::read_to_string(file)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            line.split_whitespace()
+                .map(|factor| {
+                    factor
+                        .parse::<f64>()
+                        .with_context(|| format!("unable to parse factor '{factor}' in '{file}'"))
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let columns = rows.first().map_or(0, Vec::len);
+    ensure!(
+        rows.iter().all(|row| row.len() == columns),
+        "the rows in '{file}' have different numbers of factors"
+    );
+
+    Ok(Array2::from_shape_vec(
+        (rows.len(), columns),
+        rows.into_iter().flatten().collect(),
+    )?)
+}
+
 impl Subcommand for Opts {
     fn run(&self, _: &GlobalConfiguration) -> Result<ExitCode> {
         let mut grid = helpers::read_grid(&self.input)?;
@@ -535,6 +660,9 @@ impl Subcommand for Opts {
                         grid.set_convolution(1, grid.convolutions()[1].charge_conjugate());
                     }
                 }
+                OpsArg::ChannelBinKfactors(file) => {
+                    grid.scale_by_channel_and_bin(&read_kfactors(file)?)?;
+                }
                 OpsArg::DedupChannels(ulps) => {
                     grid.dedup_channels(*ulps);
                 }
@@ -550,12 +678,43 @@ impl Subcommand for Opts {
                 OpsArg::DeleteKey(key) => {
                     grid.key_values_mut().remove(key);
                 }
+                OpsArg::Kfactor((order, file)) => {
+                    let factors = read_kfactors(file)?;
+
+                    ensure!(
+                        factors.nrows() <= 1 || factors.ncols() <= 1,
+                        "the factors in '{file}' must be given in a single row or column"
+                    );
+
+                    grid.scale_order_by_bin(*order, &factors.into_iter().collect::<Vec<_>>())?;
+                }
+                OpsArg::MaxPower(_) | OpsArg::MinPower(_) => {
+                    // the logarithmic orders share the coupling powers of their physical order and
+                    // are therefore kept or deleted together with it; powers of additional
+                    // couplings, see `Grid::extra_couplings`, are not counted
+                    let keep = |power| match *arg {
+                        OpsArg::MaxPower(max) => power <= max,
+                        OpsArg::MinPower(min) => power >= min,
+                        _ => unreachable!(),
+                    };
+                    let order_indices: Vec<_> = grid
+                        .orders()
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(index, order)| {
+                            (!keep(order.alphas + order.alpha)).then_some(index)
+                        })
+                        .collect();
+                    grid.delete_orders(&order_indices);
+                }
                 OpsArg::MergeBins(ranges) => {
-                    // TODO: sort after increasing start indices
-                    for range in ranges.iter().rev() {
-                        grid.merge_bins(*range.start()..(range.end() + 1))?;
-                    }
+                    let groups: Vec<_> = ranges
+                        .iter()
+                        .map(|range| *range.start()..(range.end() + 1))
+                        .collect();
+                    grid.merge_bins_grouped(&groups)?;
                 }
+                OpsArg::NoPhoton(true) => grid.remove_photon_channels(),
                 OpsArg::Remap(remapping) => grid.set_remapper(str::parse(remapping)?)?,
                 OpsArg::RemapNorm(factor) => {
                     let remapper = grid
@@ -600,11 +759,43 @@ impl Subcommand for Opts {
                 OpsArg::RewriteOrder((index, order)) => {
                     grid.orders_mut()[*index] = order.clone();
                 }
+                OpsArg::SetConvType((index, type_)) => {
+                    let mut convolution: Convolution = type_.parse()?;
+
+                    // keep the hadron of the convolution, unless `type_` specifies one
+                    if !type_.contains(':') && convolution != Convolution::None {
+                        if let Some(pid) =
+                            grid.convolutions().get(*index).and_then(Convolution::pid)
+                        {
+                            convolution = format!("{type_}:{pid}").parse()?;
+                        }
+                    }
+
+                    grid.try_set_convolution(*index, convolution)?;
+                }
                 OpsArg::RotatePidBasis(pid_basis) => {
                     grid.rotate_pid_basis(*pid_basis);
                 }
                 OpsArg::Scale(factor) => grid.scale(*factor),
-                OpsArg::Optimize(true) => grid.optimize(),
+                OpsArg::Optimize(true) => {
+                    if self.verbose {
+                        let report = grid.optimize_using_report(GridOptFlags::all());
+
+                        println!(
+                            "optimize: {} subgrids converted, {} channels symmetrized, {} orders \
+                             stripped, {} channels merged, {} channels stripped, {} -> {} bytes",
+                            report.subgrids_converted,
+                            report.channels_symmetrized,
+                            report.orders_stripped,
+                            report.channels_merged,
+                            report.channels_stripped,
+                            report.bytes_before,
+                            report.bytes_after
+                        );
+                    } else {
+                        grid.optimize();
+                    }
+                }
                 OpsArg::OptimizeFkTable(assumptions) => {
                     let mut fk_table = FkTable::try_from(grid)?;
                     fk_table.optimize(*assumptions);
@@ -612,7 +803,21 @@ impl Subcommand for Opts {
                 }
                 OpsArg::ScaleByBin(factors) => grid.scale_by_bin(factors),
                 OpsArg::ScaleByOrder(factors) => {
-                    grid.scale_by_order(factors[0], factors[1], factors[2], factors[3], 1.0);
+                    // the factors are the ones for alphas, alpha, logxir, logxif and an optional
+                    // global factor, which applies to all orders
+                    ensure!(
+                        matches!(factors.len(), 4 | 5),
+                        "`--scale-by-order` expects four or five factors, but {} were given",
+                        factors.len()
+                    );
+
+                    grid.scale_by_order(
+                        factors[0],
+                        factors[1],
+                        factors[2],
+                        factors[3],
+                        factors.get(4).copied().unwrap_or(1.0),
+                    );
                 }
                 OpsArg::SetKeyValue(key_value) => {
                     grid.set_key_value(&key_value[0], &key_value[1]);
@@ -620,16 +825,23 @@ impl Subcommand for Opts {
                 OpsArg::SetKeyFile(key_file) => {
                     grid.set_key_value(&key_file[0], &fs::read_to_string(&key_file[1])?);
                 }
-                OpsArg::SplitChannels(true) => grid.split_channels(),
+                OpsArg::SplitChannels(true) => {
+                    if let Some(max_combinations) = self.max_combinations {
+                        grid.split_channels_limited(max_combinations);
+                    } else {
+                        grid.split_channels();
+                    }
+                }
                 OpsArg::Upgrade(true) => grid.upgrade(),
                 OpsArg::Cc1(false)
                 | OpsArg::Cc2(false)
+                | OpsArg::NoPhoton(false)
                 | OpsArg::Optimize(false)
                 | OpsArg::SplitChannels(false)
                 | OpsArg::Upgrade(false) => {}
             }
         }
 
-        helpers::write_grid(&self.output, &grid)
+        helpers::write_grid_compressed(&self.output, &grid, self.compress.as_deref())
     }
 }
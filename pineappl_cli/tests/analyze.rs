@@ -1,11 +1,14 @@
 use assert_cmd::Command;
+use predicates::prelude::*;
+use predicates::str;
 
 const HELP_STR: &str = "Perform various analyses with grids
 
 Usage: pineappl analyze <COMMAND>
 
 Commands:
-  ckf  Compare K-factors with channel K factors (ckf)
+  ckf            Compare K-factors with channel K factors (ckf)
+  optimize-gain  Estimate the reduction of the file size achieved by optimizing a grid, without writing it
 
 Options:
   -h, --help  Print help
@@ -27,6 +30,18 @@ Options:
   -h, --help              Print help
 ";
 
+const OPTIMIZE_GAIN_HELP_STR: &str = "Estimate the reduction of the file size achieved by optimizing a grid, without writing it
+
+Usage: pineappl analyze optimize-gain [OPTIONS] <INPUT>
+
+Arguments:
+  <INPUT>  Path to the input grid
+
+Options:
+      --digits-rel <REL>  Set the number of fractional digits shown for relative numbers [default: 2]
+  -h, --help              Print help
+";
+
 const CKF_STR: &str = "b   etal    bin-K c  K   c  K   c  K   c  K   c  K  
      []                                             
 -+----+----+-----+-+----+-+----+-+----+-+----+-+----
@@ -130,3 +145,29 @@ fn ckf_with_bad_limit() {
         .failure()
         .stderr(CKF_WITH_BAD_LIMIT_STR);
 }
+
+#[test]
+fn optimize_gain_help() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(["analyze", "optimize-gain", "--help"])
+        .assert()
+        .success()
+        .stdout(OPTIMIZE_GAIN_HELP_STR);
+}
+
+#[test]
+fn optimize_gain() {
+    // the compressed sizes depend on the version of the compression library, so only check that
+    // both rows are printed
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "analyze",
+            "optimize-gain",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+        ])
+        .assert()
+        .success()
+        .stdout(str::contains("file").and(str::contains("uncompressed")));
+}
@@ -0,0 +1,34 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use predicates::str;
+
+const HELP_STR: &str = "Generate shell completions for all subcommands
+
+Usage: pineappl complete <SHELL>
+
+Arguments:
+  <SHELL>  Shell for which the completions are generated [possible values: bash, elvish, fish, powershell, zsh]
+
+Options:
+  -h, --help  Print help
+";
+
+#[test]
+fn help() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(["complete", "--help"])
+        .assert()
+        .success()
+        .stdout(HELP_STR);
+}
+
+#[test]
+fn bash() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(["complete", "bash"])
+        .assert()
+        .success()
+        .stdout(str::starts_with("_pineappl() {").and(str::contains("--scale-by-order")));
+}
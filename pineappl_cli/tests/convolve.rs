@@ -1,23 +1,41 @@
 use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::NamedTempFile;
+use float_cmp::assert_approx_eq;
 use predicates::str;
 
 const HELP_STR: &str = "Convolutes a PineAPPL grid with a PDF set
 
-Usage: pineappl convolve [OPTIONS] <INPUT> <CONV_FUNS>...
+Usage: pineappl convolve [OPTIONS] <INPUT> [CONV_FUNS]...
 
 Arguments:
   <INPUT>         Path of the input grid
-  <CONV_FUNS>...  LHAPDF id(s) or name of the PDF set(s)
+  [CONV_FUNS]...  LHAPDF id(s) or name of the PDF set(s). If none is given, the PDF set stored in the metadata key `results_pdf` is used
 
 Options:
-  -b, --bins <BINS>       Selects a subset of bins
-  -i, --integrated        Show integrated numbers (without bin widths) instead of differential ones
-  -o, --orders <ORDERS>   Select orders manually
-      --xir <XIR>         Set the variation of the renormalization scale [default: 1.0]
-      --xif <XIF>         Set the variation of the factorization scale [default: 1.0]
-      --digits-abs <ABS>  Set the number of fractional digits shown for absolute numbers [default: 7]
-      --digits-rel <REL>  Set the number of fractional digits shown for relative numbers [default: 2]
-  -h, --help              Print help
+  -b, --bins <BINS>                Selects a subset of bins
+  -i, --integrated                 Show integrated numbers (without bin widths) instead of differential ones
+  -o, --orders <ORDERS>            Select orders manually
+      --max-as <MAX>               Select the orders whose power of the strong coupling is at most MAX
+      --max-a <MAX>                Select the orders whose power of the electromagnetic coupling is at most MAX
+      --xir <XIR>                  Set the variation of the renormalization scale [default: 1.0]
+      --xif <XIF>                  Set the variation of the factorization scale [default: 1.0]
+      --scale-scan <FACTORS>       Convolve with all combinations of FACTORS for the renormalization and factorization scale
+      --scales-from-file <FILE>    Convolve with the factors for the renormalization and factorization scale given in FILE
+      --scale-table <FILE>         Replace the renormalization and factorization scale of each bin with the values in FILE
+      --bin-corrections <FILE>     Multiply the normalized result of each bin with the corresponding factor in FILE
+      --digits-abs <ABS>           Set the number of fractional digits shown for absolute numbers [default: 7]
+      --digits-rel <REL>           Set the number of fractional digits shown for relative numbers [default: 2]
+      --write-predictions <FILE>   Write the predictions of the first PDF set as a grid into FILE
+      --embed-reference <FILE>     Store the results of the first PDF set as reference in the metadata of the grid and write it into FILE
+      --full-decomposition <FILE>  Write the contributions of each order, channel and bin of the first PDF set as an array with the axes in this order into the `.npy` FILE
+      --fill-nan-with <VALUE>      Replace non-finite PDF values, for instance outside the PDF's region of validity, with VALUE
+      --threads <THREADS>          Number of threads to utilize [default: 1]
+      --chunk-bins <BINS>          Number of bins convolved by a single thread at a time. If not given, the bins are distributed evenly over the threads
+      --strict                     Abort instead of warning when the particle of a PDF set does not match the hadron of the corresponding convolution of the grid
+      --convergence                Show the cumulative results of the leading and each higher order, and their ratios
+      --precompute-tables          Evaluate the PDFs and the strong coupling at all nodes of the grid before convolving it
+  -h, --help                       Print help
 ";
 
 const DEFAULT_STR: &str = "b   etal    dsig/detal 
@@ -33,12 +51,67 @@ const DEFAULT_STR: &str = "b   etal    dsig/detal
 7    4  4.5 2.7517266e1
 ";
 
+const BIN_CORRECTIONS_STR: &str = "b   etal    dsig/detal 
+     []        [pb]    
+-+----+----+-----------
+0    2 2.25 7.5459110e2
+1 2.25  2.5 6.9028342e3
+2  2.5 2.75 6.0025198e2
+3 2.75    3 4.8552235e3
+4    3 3.25 3.6195456e2
+5 3.25  3.5 2.4586691e3
+6  3.5    4 1.1586851e2
+7    4  4.5 2.7517266e2
+";
+
+const WRONG_BIN_CORRECTIONS_STR: &str =
+    "Error: the grid has 8 bins, but 2 bin corrections were given
+";
+
+const SCALE_SCAN_STR: &str = "b   etal     (r=1,f=1) 
+     []        [pb]    
+-+----+----+-----------
+0    2 2.25 7.5459110e2
+1 2.25  2.5 6.9028342e2
+2  2.5 2.75 6.0025198e2
+3 2.75    3 4.8552235e2
+4    3 3.25 3.6195456e2
+5 3.25  3.5 2.4586691e2
+6  3.5    4 1.1586851e2
+7    4  4.5 2.7517266e1
+";
+
+const SCALES_FROM_FILE_STR: &str = "b   etal     (r=1,f=1)   (r=1,f=1) 
+     []        [pb]        [pb]    
+-+----+----+-----------+-----------
+0    2 2.25 7.5459110e2 7.5459110e2
+1 2.25  2.5 6.9028342e2 6.9028342e2
+2  2.5 2.75 6.0025198e2 6.0025198e2
+3 2.75    3 4.8552235e2 4.8552235e2
+4    3 3.25 3.6195456e2 3.6195456e2
+5 3.25  3.5 2.4586691e2 2.4586691e2
+6  3.5    4 1.1586851e2 1.1586851e2
+7    4  4.5 2.7517266e1 2.7517266e1
+";
+
+const SCALES_FROM_FILE_FRAGMENTATION_STR: &str =
+    "Error: unable to vary the fragmentation scale in '1,1,2', because grids don't support it
+";
+
+const SCALE_SCAN_MULTIPLE_PDFS_STR: &str =
+    "Error: `--scale-scan` can only be used with a single PDF set
+";
+
 const USE_ALPHAS_FROM_ERROR_STR: &str = "expected `use_alphas_from` to be `0` or `1`, is `2`
 ";
 
 const THREE_PDF_ERROR_STR: &str = "convolutions with 3 convolution functions is not supported
 ";
 
+const MISSING_RESULTS_PDF_STR: &str =
+    "Error: no PDF set given and the grid does not have the metadata key `results_pdf`
+";
+
 const FORCE_POSITIVE_STR: &str = "b   etal    dsig/detal 
      []        [pb]    
 -+----+----+-----------
@@ -168,6 +241,14 @@ const ORDERS_A2_A3_STR: &str = "b   etal    dsig/detal
 7    4  4.5 2.2163265e1
 ";
 
+const MAX_AS_WITH_ORDERS_STR: &str =
+    "error: the argument '--max-as <MAX>' cannot be used with '--orders <ORDERS>'
+
+Usage: pineappl convolve --max-as <MAX> <INPUT> <CONV_FUNS>...
+
+For more information, try '--help'.
+";
+
 const WRONG_ORDERS_STR: &str = "error: invalid value 'a2a2as2' for '--orders <ORDERS>': unable to parse order; too many couplings in 'a2a2as2'
 
 For more information, try '--help'.
@@ -211,6 +292,287 @@ fn default() {
         .stdout(DEFAULT_STR);
 }
 
+#[test]
+fn strict() {
+    // the PDF set is a proton PDF, as expected by the grid, so no warning or error is emitted
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "--use-alphas-from=1",
+            "convolve",
+            "--strict",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed,NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stderr("")
+        .stdout(DEFAULT_STR);
+}
+
+#[test]
+fn fill_nan_with() {
+    // the PDF values are all finite, so nothing gets replaced and no warning is printed
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "--use-alphas-from=1",
+            "convolve",
+            "--fill-nan-with=0",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed,NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stderr("")
+        .stdout(DEFAULT_STR);
+}
+
+#[test]
+fn threads_chunk_bins() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "--use-alphas-from=1",
+            "convolve",
+            "--threads=2",
+            "--chunk-bins=3",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed,NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(DEFAULT_STR);
+}
+
+#[test]
+fn write_predictions() {
+    let output = NamedTempFile::new("predictions.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "--use-alphas-from=1",
+            "convolve",
+            &format!("--write-predictions={}", output.path().to_str().unwrap()),
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed,NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(DEFAULT_STR);
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "--use-alphas-from=1",
+            "convolve",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed,NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(DEFAULT_STR);
+}
+
+#[test]
+fn embed_reference() {
+    let output = NamedTempFile::new("reference.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "--use-alphas-from=1",
+            "convolve",
+            &format!("--embed-reference={}", output.path().to_str().unwrap()),
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed,NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(DEFAULT_STR);
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(["read", "--get=reference", output.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(str::starts_with("7.545911"));
+
+    // without a PDF set the one stored with the reference is used
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "--use-alphas-from=1",
+            "convolve",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(DEFAULT_STR);
+}
+
+#[test]
+fn embed_reference_with_bins() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            "--embed-reference=out.pineappl.lz4",
+            "--bins=0",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(str::contains(
+            "the argument '--embed-reference <FILE>' cannot be used with '--bins <BINS>'",
+        ));
+}
+
+#[test]
+fn embed_reference_with_orders() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            "--embed-reference=out.pineappl.lz4",
+            "--orders=a2",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(str::contains(
+            "the argument '--embed-reference <FILE>' cannot be used with '--orders <ORDERS>'",
+        ));
+}
+
+#[test]
+fn embed_reference_with_xir() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            "--embed-reference=out.pineappl.lz4",
+            "--xir=2",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(str::contains(
+            "the argument '--embed-reference <FILE>' cannot be used with '--xir <XIR>'",
+        ));
+}
+
+#[test]
+fn bin_corrections() {
+    let corrections = NamedTempFile::new("corrections.txt").unwrap();
+    corrections.write_str("1 10\n1 10\n1 10\n1 10\n").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            &format!("--bin-corrections={}", corrections.path().to_str().unwrap()),
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(BIN_CORRECTIONS_STR);
+}
+
+#[test]
+fn scale_table() {
+    // the LHCb grid uses the W-boson mass as scale, so this is the same as not changing anything
+    let scales = NamedTempFile::new("scales.csv").unwrap();
+    scales
+        .write_str(
+            "# mur,muf\n80.352,80.352\n80.352,80.352\n80.352,80.352\n80.352,80.352\n\
+             80.352,80.352\n80.352,80.352\n80.352,80.352\n80.352,80.352\n",
+        )
+        .unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            &format!("--scale-table={}", scales.path().to_str().unwrap()),
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(DEFAULT_STR);
+}
+
+#[test]
+fn scale_table_with_xir() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            "--scale-table=scales.csv",
+            "--xir=2",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(str::contains(
+            "the argument '--scale-table <FILE>' cannot be used with '--xir <XIR>'",
+        ));
+}
+
+#[cfg(feature = "npy")]
+#[test]
+fn full_decomposition() {
+    use ndarray::{Array3, Axis};
+    use ndarray_npy::ReadNpyExt;
+    use std::fs::File;
+
+    let output = NamedTempFile::new("full.npy").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            &format!("--full-decomposition={}", output.path().to_str().unwrap()),
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(DEFAULT_STR);
+
+    let full = Array3::<f64>::read_npy(File::open(output.path()).unwrap()).unwrap();
+    let results = full.sum_axis(Axis(0)).sum_axis(Axis(0));
+
+    assert_eq!(full.dim().2, 8);
+    assert_approx_eq!(f64, results[0], 7.5459110e2, epsilon = 1e-4);
+    assert_approx_eq!(f64, results[7], 2.7517266e1, epsilon = 1e-5);
+}
+
+#[test]
+fn wrong_bin_corrections() {
+    let corrections = NamedTempFile::new("corrections.txt").unwrap();
+    corrections.write_str("1 2\n").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            &format!("--bin-corrections={}", corrections.path().to_str().unwrap()),
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(WRONG_BIN_CORRECTIONS_STR);
+}
+
 #[test]
 fn use_alphas_from_error() {
     Command::cargo_bin("pineappl")
@@ -240,6 +602,16 @@ fn three_pdf_error() {
         .stderr(str::contains(THREE_PDF_ERROR_STR));
 }
 
+#[test]
+fn missing_results_pdf() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(["convolve", "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4"])
+        .assert()
+        .failure()
+        .stderr(MISSING_RESULTS_PDF_STR);
+}
+
 #[test]
 fn force_positive() {
     Command::cargo_bin("pineappl")
@@ -408,6 +780,155 @@ fn wrong_orders() {
         .stderr(WRONG_ORDERS_STR);
 }
 
+#[test]
+fn scale_scan() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            "--scale-scan=1",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(SCALE_SCAN_STR);
+}
+
+#[test]
+fn scales_from_file() {
+    let scales = NamedTempFile::new("scales.csv").unwrap();
+    scales.write_str("# xir,xif,xia\n1,1\n1,1,1\n").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            &format!("--scales-from-file={}", scales.path().to_str().unwrap()),
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(SCALES_FROM_FILE_STR);
+}
+
+#[test]
+fn scales_from_file_fragmentation() {
+    let scales = NamedTempFile::new("scales.csv").unwrap();
+    scales.write_str("1,1,2\n").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            &format!("--scales-from-file={}", scales.path().to_str().unwrap()),
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(SCALES_FROM_FILE_FRAGMENTATION_STR);
+}
+
+#[test]
+fn scales_from_file_with_scale_scan() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            "--scales-from-file=scales.csv",
+            "--scale-scan=1,2",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "the argument '--scales-from-file <FILE>' cannot be used with '--scale-scan <FACTORS>'",
+        ));
+}
+
+#[test]
+fn convergence() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            "--convergence",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("NLO/LO"));
+}
+
+#[test]
+fn convergence_with_orders() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            "--convergence",
+            "--orders=a2",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "the argument '--convergence' cannot be used with '--orders <ORDERS>'",
+        ));
+}
+
+#[test]
+fn max_as_with_orders() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            "--max-as=1",
+            "--orders=a2",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(MAX_AS_WITH_ORDERS_STR);
+}
+
+#[test]
+fn precompute_tables() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            "--precompute-tables",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(DEFAULT_STR);
+}
+
+#[test]
+fn scale_scan_multiple_pdfs() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            "--scale-scan=0.5,1,2",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(SCALE_SCAN_MULTIPLE_PDFS_STR);
+}
+
 #[test]
 fn xir_xif() {
     Command::cargo_bin("pineappl")
@@ -421,5 +942,7 @@ fn xir_xif() {
         ])
         .assert()
         .success()
-        .stdout(XIR_XIF_STR);
+        .stdout(XIR_XIF_STR)
+        // the grid has all logarithms needed for the scale variation
+        .stderr("");
 }
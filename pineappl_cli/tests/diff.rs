@@ -12,6 +12,7 @@ Arguments:
 
 Options:
       --ignore-orders      Ignore differences in the orders and sum them
+      --orders             Compare the orders present in both grids one by one and report the others
       --ignore-bin-limits  Ignore bin limits (but not number of bins)
       --ignore-channels    Ignore differences in the channel definition
       --orders1 <ORDERS1>  Select orders of the first grid
@@ -178,6 +179,25 @@ fn orders_differ() {
         .stdout("");
 }
 
+#[test]
+fn orders_differ_orders() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "diff",
+            "--orders",
+            "--orders1=a2",
+            "--orders2=a2,a2as1",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stderr("warning: order O(as^1 a^2) is only present in the second grid\n")
+        .stdout(ORDERS1_A2_ORDERS2_A2_STR);
+}
+
 #[test]
 fn bin_limits_differ() {
     let output = NamedTempFile::new("remapped.pineappl.lz4").unwrap();
@@ -1,7 +1,9 @@
 #![cfg(feature = "evolve")]
 
 use assert_cmd::Command;
-use assert_fs::NamedTempFile;
+use assert_fs::{NamedTempFile, TempDir};
+use std::fs::File;
+use tar::Archive;
 
 const HELP_STR: &str = "Evolve a grid with an evolution kernel operator to an FK table
 
@@ -9,7 +11,7 @@ Usage: pineappl evolve [OPTIONS] <INPUT> <EKO> <OUTPUT> <CONV_FUNS>
 
 Arguments:
   <INPUT>      Path to the input grid
-  <EKO>        Path to the evolution kernel operator
+  <EKO>        Path to the evolution kernel operator, or a directory with one file per slice
   <OUTPUT>     Path to the converted grid
   <CONV_FUNS>  LHAPDF ID(s) or name of the PDF(s)/FF(s)
 
@@ -345,6 +347,61 @@ fn lhcb_wp_7tev_v2_xif_2_error() {
         .stdout("");
 }
 
+#[test]
+fn lhcb_wp_7tev_v2_dir() {
+    let eko = TempDir::new().unwrap();
+    let output = NamedTempFile::new("fktable2d.lz4").unwrap();
+
+    Archive::new(File::open("../test-data/LHCB_WP_7TEV_v2.tar").unwrap())
+        .unpack(eko.path())
+        .unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "evolve",
+            "--digits-abs=16",
+            "--digits-rel=16",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            eko.path().to_str().unwrap(),
+            output.path().to_str().unwrap(),
+            "NNPDF40_nlo_as_01180",
+            "--orders=a2,as1a2",
+        ])
+        .assert()
+        .success()
+        .stdout(LHCB_WP_7TEV_V2_STR);
+}
+
+#[test]
+fn lhcb_wp_7tev_v2_dir_xif_2_error() {
+    let eko = TempDir::new().unwrap();
+    let output = NamedTempFile::new("fktable2e.lz4").unwrap();
+
+    Archive::new(File::open("../test-data/LHCB_WP_7TEV_v2.tar").unwrap())
+        .unpack(eko.path())
+        .unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "evolve",
+            "--digits-abs=16",
+            "--digits-rel=16",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            eko.path().to_str().unwrap(),
+            output.path().to_str().unwrap(),
+            "NNPDF40_nlo_as_01180",
+            "--orders=a2,as1a2",
+            "--xif=2",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "has no slices for muf2 = [25825.775616000003]",
+        ));
+}
+
 #[test]
 fn e906nlo_bin_00() {
     let input = NamedTempFile::new("E906nlo_bin_00_unique_bin_limits.pineappl.lz4").unwrap();
@@ -3,6 +3,9 @@ use assert_cmd::Command;
 #[cfg(feature = "applgrid")]
 use assert_fs::NamedTempFile;
 
+#[cfg(feature = "applgrid")]
+use predicates::prelude::*;
+
 const HELP_STR: &str = "Converts PineAPPL grids to APPLgrid files
 
 Usage: pineappl export [OPTIONS] <INPUT> <OUTPUT> <CONV_FUNS>
@@ -13,8 +16,10 @@ Arguments:
   <CONV_FUNS>  LHAPDF ID(s) or name of the PDF(s)/FF(s) to check the converted grid with
 
 Options:
+  -o, --orders <ORDERS>              Select the orders that should be exported
       --accuracy <ACCURACY>          Relative threshold between the table and the converted grid when comparison fails [default: 1e-10]
       --discard-non-matching-scales  Discard non-matching scales that would otherwise lead to panics
+      --verify                       Read the written file again and compare its predictions with the ones of the input grid
   -s, --scales <SCALES>              Set the number of scale variations to compare with if they are available [default: 7] [possible values: 1, 3, 7, 9]
       --digits-abs <ABS>             Set the number of fractional digits shown for absolute numbers [default: 7]
       --digits-rel <REL>             Set the number of fractional digits shown for relative numbers [default: 7]
@@ -130,6 +135,29 @@ fn export_applgrid() {
         .stdout(predicates::str::ends_with(EXPORT_APPLGRID_STR));
 }
 
+#[test]
+#[cfg(feature = "applgrid")]
+fn export_applgrid_verify() {
+    let output = NamedTempFile::new("converted.appl").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "export",
+            "--verify",
+            "../test-data/LHCB_DY_8TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains(EXPORT_APPLGRID_STR).and(predicates::str::contains(
+                "largest relative difference of the written file: ",
+            )),
+        );
+}
+
 #[test]
 #[cfg(feature = "applgrid")]
 fn export_dis_applgrid() {
@@ -161,3 +189,42 @@ fn export_dis_applgrid() {
         .success()
         .stdout(predicates::str::ends_with(EXPORT_DIS_APPLGRID_STR));
 }
+
+#[test]
+#[cfg(feature = "applgrid")]
+fn export_applgrid_orders() {
+    let output = NamedTempFile::new("converted-lo.appl").unwrap();
+
+    // orders that weren't selected must not trigger any warnings
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "export",
+            "--orders=a2",
+            "../test-data/LHCB_DY_8TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("WARNING").not());
+}
+
+#[test]
+#[cfg(feature = "applgrid")]
+fn export_applgrid_unsupported_orders() {
+    let output = NamedTempFile::new("converted-ew.appl").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "export",
+            "--orders=a2,a3",
+            "../test-data/LHCB_DY_8TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr("Error: selected order O(as^0 a^3), which APPLgrid does not support\n");
+}
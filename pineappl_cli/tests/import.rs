@@ -19,6 +19,7 @@ Options:
   -s, --scales <SCALES>      Set the number of scale variations to compare with if they are available [default: 7] [possible values: 1, 3, 7, 9]
       --fnlo-mur <FNLO_MUR>  If importing a fastNLO flexible-scale grid, use the specified functional form for the renormalization scale [possible values: kScale1, kScale2, kQuadraticSum, kQuadraticMean, kQuadraticSumOver4, kLinearMean, kLinearSum, kScaleMax, kScaleMin, kProd, kS2plusS1half, kPow4Sum, kWgtAvg, kS2plusS1fourth, kExpProd2]
       --fnlo-muf <FNLO_MUF>  If importing a fastNLO flexible-scale grid, use the specified functional form for the factorization scale [possible values: kScale1, kScale2, kQuadraticSum, kQuadraticMean, kQuadraticSumOver4, kLinearMean, kLinearSum, kScaleMax, kScaleMin, kProd, kS2plusS1half, kPow4Sum, kWgtAvg, kS2plusS1fourth, kExpProd2]
+      --silence-fastnlo      If importing a fastNLO table, silence the output fastNLO writes to the standard output
       --digits-abs <ABS>     Set the number of fractional digits shown for absolute numbers [default: 7]
       --digits-rel <REL>     Set the number of fractional digits shown for relative numbers [default: 7]
       --no-optimize          Do not optimize converted grid
@@ -42,6 +43,7 @@ Options:
   -s, --scales <SCALES>      Set the number of scale variations to compare with if they are available [default: 7] [possible values: 1, 3, 7, 9]
       --fnlo-mur <FNLO_MUR>  If importing a fastNLO flexible-scale grid, use the specified functional form for the renormalization scale
       --fnlo-muf <FNLO_MUF>  If importing a fastNLO flexible-scale grid, use the specified functional form for the factorization scale
+      --silence-fastnlo      If importing a fastNLO table, silence the output fastNLO writes to the standard output
       --digits-abs <ABS>     Set the number of fractional digits shown for absolute numbers [default: 7]
       --digits-rel <REL>     Set the number of fractional digits shown for relative numbers [default: 7]
       --no-optimize          Do not optimize converted grid
@@ -382,6 +384,47 @@ fn import_flex_grid_scale_1() {
         .stdout(predicates::str::ends_with(IMPORT_FLEX_GRID_SCALE_1_STR));
 }
 
+#[test]
+#[cfg(feature = "fastnlo")]
+fn import_flex_grid_functional_forms() {
+    let output = NamedTempFile::new("converted2.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "import",
+            "../test-data/applfast-h1-incjets-fnlo-arxiv-0706.3722-xsec000.tab.gz",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+            "--fnlo-mur=kScale1",
+            "--fnlo-muf=kScale2",
+        ])
+        .assert()
+        .success();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "read",
+            "--get=fastnlo_mur_functional_form",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("kScale1\n");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "read",
+            "--get=fastnlo_muf_functional_form",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("kScale2\n");
+}
+
 #[test]
 #[cfg(feature = "fastnlo")]
 fn import_flex_grid_scale_2() {
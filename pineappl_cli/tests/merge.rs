@@ -3,14 +3,16 @@ use assert_fs::NamedTempFile;
 
 const HELP_STR: &str = "Merges one or more PineAPPL grids together
 
-Usage: pineappl merge <OUTPUT> <INPUT>...
+Usage: pineappl merge [OPTIONS] [OUTPUT] [INPUT]...
 
 Arguments:
-  <OUTPUT>    Path of the merged PineAPPL file
-  <INPUT>...  Path(s) of the files that should be merged
+  [OUTPUT]    Path of the merged PineAPPL file; `-` writes to the standard output
+  [INPUT]...  Path(s) of the files that should be merged
 
 Options:
-  -h, --help  Print help
+      --compress <COMPRESS>     Compression of the output; by default inferred from its extension [possible values: lz4, none]
+      --check <GRID> <GRID>...  Only check whether the grids can be merged with the first one, without writing anything
+  -h, --help                    Print help
 ";
 
 const DEFAULT_STR: &str = "b   etal    dsig/detal 
@@ -63,3 +65,81 @@ fn default() {
         .success()
         .stdout(DEFAULT_STR);
 }
+
+#[test]
+fn compress() {
+    let output = NamedTempFile::new("merged.pineappl").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "merge",
+            "--compress=lz4",
+            output.path().to_str().unwrap(),
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    // the output must be LZ4-compressed even though the file extension doesn't say so
+    assert!(std::fs::read(output.path())
+        .unwrap()
+        .starts_with(&[0x04, 0x22, 0x4d, 0x18]));
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            output.path().to_str().unwrap(),
+            "NNPDF40_nnlo_as_01180",
+        ])
+        .assert()
+        .success()
+        .stdout(DEFAULT_STR);
+}
+
+#[test]
+fn check() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "merge",
+            "--check",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+        ])
+        .assert()
+        .success()
+        .stdout("../test-data/LHCB_WP_7TEV_opt.pineappl.lz4: compatible\n");
+}
+
+#[test]
+fn check_incompatible() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "merge",
+            "--check",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "../test-data/LHCB_DY_8TEV.pineappl.lz4",
+        ])
+        .assert()
+        .failure()
+        .stdout(predicates::str::starts_with(
+            "../test-data/LHCB_DY_8TEV.pineappl.lz4: can not merge bins",
+        ));
+}
+
+#[test]
+fn missing_input() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(["merge", "merged.pineappl.lz4"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "the following required arguments were not provided:\n  <INPUT>...",
+        ));
+}
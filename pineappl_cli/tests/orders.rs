@@ -12,6 +12,8 @@ Options:
   -a, --absolute               Show absolute numbers of each perturbative order
   -i, --integrated             Show integrated numbers (without bin widths) instead of differential ones
   -n, --normalize <NORMALIZE>  Normalize contributions to the specified orders
+      --max-as <MAX>           Show only the orders whose power of the strong coupling is at most MAX
+      --max-a <MAX>            Show only the orders whose power of the electromagnetic coupling is at most MAX
       --digits-abs <ABS>       Set the number of fractional digits shown for absolute numbers [default: 7]
       --digits-rel <REL>       Set the number of fractional digits shown for relative numbers [default: 2]
   -h, --help                   Print help
@@ -83,6 +85,27 @@ const NORMALIZE_A2_AS1A2_STR: &str = "b   etal    dsig/detal  O(as^0 a^2) O(as^1
 7    4  4.5 2.7517266e1       80.70       19.30       -0.79
 ";
 
+const MAX_AS_STR: &str = "b   etal    dsig/detal  O(as^0 a^2) O(as^0 a^3)
+     []        [pb]         [%]         [%]    
+-+----+----+-----------+-----------+-----------
+0    2 2.25 6.4283381e2      100.00       -1.21
+1 2.25  2.5 5.8945001e2      100.00       -1.10
+2  2.5 2.75 5.1037764e2      100.00       -1.02
+3 2.75    3 4.1158725e2      100.00       -0.91
+4    3 3.25 3.0554001e2      100.00       -0.84
+5 3.25  3.5 2.0639857e2      100.00       -0.81
+6  3.5    4 9.6046494e1      100.00       -0.84
+7    4  4.5 2.2163265e1      100.00       -0.98
+";
+
+const MAX_A_WITHOUT_ORDERS_STR: &str =
+    "Error: none of the orders of the grid is selected by `--max-as` and `--max-a`
+";
+
+const MAX_AS_NORMALIZE_STR: &str =
+    "Error: the order O(as^1 a^2) given to `--normalize` is not shown
+";
+
 #[test]
 fn help() {
     Command::cargo_bin("pineappl")
@@ -167,3 +190,49 @@ fn normalize_a2_as1a2() {
         .success()
         .stdout(NORMALIZE_A2_AS1A2_STR);
 }
+
+#[test]
+fn max_as() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "orders",
+            "--max-as=0",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(MAX_AS_STR);
+}
+
+#[test]
+fn max_a_without_orders() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "orders",
+            "--max-a=1",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(MAX_A_WITHOUT_ORDERS_STR);
+}
+
+#[test]
+fn max_as_normalize() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "orders",
+            "--max-as=0",
+            "--normalize=as1a2",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(MAX_AS_NORMALIZE_STR);
+}
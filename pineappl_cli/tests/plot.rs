@@ -1,4 +1,5 @@
 use assert_cmd::Command;
+use predicates::prelude::*;
 use predicates::str;
 use std::num::NonZeroUsize;
 use std::thread;
@@ -12,12 +13,15 @@ Arguments:
   <CONV_FUNS>...  LHAPDF id(s) or name of the PDF set(s)
 
 Options:
-      --conv-fun-uncert-from <IDX>     Choose for which convolution function the uncertainty should be calculated [default: 0]
+      --conv-fun-uncert-from <IDX>     Choose for which convolution function(s) the uncertainty should be calculated. Multiple indices or `all` add the uncertainties in quadrature, assuming they are uncorrelated [default: 0]
   -s, --scales <SCALES>                Set the number of scale variations [default: 7] [possible values: 1, 3, 7, 9]
       --subgrid-pull <ORDER,BIN,CHAN>  Show the pull for a specific grid three-dimensionally
       --asymmetry                      Plot the asymmetry
       --threads <THREADS>              Number of threads to utilize [default: {}]
       --no-conv-fun-unc                Disable the (time-consuming) calculation of PDF uncertainties
+      --output <NAME>                  Set the path of the plots without the file extension, instead of using the grid's name
+      --ratio-to <IDX>                 Index of the PDF set that the results in the PDF ratio panels are divided by [default: 0]
+      --channel-breakdown <N>          Show the N channels with the largest contributions in the panel with the absolute predictions of all PDF sets
   -h, --help                           Print help
 ";
 
@@ -35,6 +39,12 @@ ylabel = r"$\frac{\mathrm{d}\sigma}{\mathrm{d}\eta_{\bar{\ell}}}$ [\si{pb}]"
 xlog = False
 ylog = False
 scales = 7
+ratio_to = 0
+# linestyle for the channel breakdown shown in the panel `plot_abs_pdfs`. If the array
+# is empty, no channel breakdown will be shown, otherwise the most important channels,
+# as many as linestyles are given. See also
+# https://matplotlib.org/stable/gallery/lines_bars_and_markers/linestyles.html
+channel_breakdown_linestyles = []
 plot_panels = {
     "int": False,
     "abs": True,
@@ -86,7 +96,7 @@ stylesheet = {
 
 # panel plot labels
 ylabel_ratio_pdf = r"Ratio to {{central_pdf}}"
-ylabel_double_ratio_pdf = r"Ratio to NLO"
+ylabel_double_ratio_pdf = r"Ratio to {first} / {second}"
 ylabel_rel_ewonoff = r"NLO EW on/off [\si{\percent}]"
 ylabel_rel_pdfunc = r"PDF uncertainty [\si{\percent}]"
 ylabel_rel_pdfpull = r"Pull [$\sigma$]"
@@ -97,13 +107,6 @@ label_rel_ewonoff_scale_unc = f"{scales}-p. scale var."
 label_rel_ewonoff_pdf_unc = r"PDF uncertainty"
 
 
-# linestyle for the channel breakdown shown in the panel `plot_abs_pdfs`. If the array
-# is empty, no channel breakdown will be shown, otherwise the most important channels,
-# as many as linestyles are given. See also
-# https://matplotlib.org/stable/gallery/lines_bars_and_markers/linestyles.html
-channel_breakdown_linestyles = []
-
-
 def main(active_panels):
     """Build a plot figure with various panels."""
     # Find the active panels
@@ -249,13 +252,13 @@ def plot_abs(axis, /, x, y, ymin, ymax, slice_label="", **_kwargs):
 
 
 def plot_ratio_pdf(axis, /, x, pdf_results, slice_label="", **_kwargs):
-    axis.set_ylabel(ylabel_ratio_pdf.format(central_pdf=pdf_results[0][0]))
+    axis.set_ylabel(ylabel_ratio_pdf.format(central_pdf=pdf_results[ratio_to][0]))
 
     for index, i in enumerate(pdf_results):
         label, y, ymin, ymax = i
-        y = y / pdf_results[0][1]
-        ymin = ymin / pdf_results[0][1]
-        ymax = ymax / pdf_results[0][1]
+        y = y / pdf_results[ratio_to][1]
+        ymin = ymin / pdf_results[ratio_to][1]
+        ymax = ymax / pdf_results[ratio_to][1]
 
         axis.step(x, y, color=colors[index], linewidth=1.0, where="post")
         axis.fill_between(
@@ -298,18 +301,22 @@ def plot_ratio_pdf(axis, /, x, pdf_results, slice_label="", **_kwargs):
 
 
 def plot_double_ratio_pdf(axis, /, x, pdf_results, slice_label="", **_kwargs):
-    axis.set_ylabel(ylabel_double_ratio_pdf)
+    # the results are compared in pairs: results with an even index are divided by the first and
+    # results with an odd index by the second result of the pair that contains `ratio_to`
+    first = ratio_to - ratio_to % 2
+    second = min(first + 1, len(pdf_results) - 1)
+    axis.set_ylabel(
+        ylabel_double_ratio_pdf.format(
+            first=pdf_results[first][0], second=pdf_results[second][0]
+        )
+    )
 
     for index, i in enumerate(pdf_results):
         label, y, ymin, ymax = i
-        if index == 0 or index == 2:
-            y = y / pdf_results[0][1]
-            ymin = ymin / pdf_results[0][1]
-            ymax = ymax / pdf_results[0][1]
-        else:
-            y = y / pdf_results[1][1]
-            ymin = ymin / pdf_results[1][1]
-            ymax = ymax / pdf_results[1][1]
+        denominator = pdf_results[second if index % 2 == 1 else first][1]
+        y = y / denominator
+        ymin = ymin / denominator
+        ymax = ymax / denominator
         axis.step(x, y, color=colors[index], linewidth=1.0, where="post")
         axis.fill_between(
             x,
@@ -638,7 +645,7 @@ axes[1].set_xlabel(r"$x_1$")
 axes[1].set_ylabel(r"$x_2$")
 
 figure.colorbar(mesh, ax=axes, extend="min")
-figure.savefig("plot.pdf")
+figure.savefig(r"plot.pdf")
 "#;
 
 const DRELL_YAN_AFB_STR: &str = r#"#!/usr/bin/env python3
@@ -655,6 +662,12 @@ ylabel = r"$\frac{\mathrm{d}\sigma}{\mathrm{d}\cos \theta^*}$ [\si{pb}]"
 xlog = False
 ylog = False
 scales = 7
+ratio_to = 0
+# linestyle for the channel breakdown shown in the panel `plot_abs_pdfs`. If the array
+# is empty, no channel breakdown will be shown, otherwise the most important channels,
+# as many as linestyles are given. See also
+# https://matplotlib.org/stable/gallery/lines_bars_and_markers/linestyles.html
+channel_breakdown_linestyles = []
 plot_panels = {
     "int": False,
     "abs": True,
@@ -706,7 +719,7 @@ stylesheet = {
 
 # panel plot labels
 ylabel_ratio_pdf = r"Ratio to {{central_pdf}}"
-ylabel_double_ratio_pdf = r"Ratio to NLO"
+ylabel_double_ratio_pdf = r"Ratio to {first} / {second}"
 ylabel_rel_ewonoff = r"NLO EW on/off [\si{\percent}]"
 ylabel_rel_pdfunc = r"PDF uncertainty [\si{\percent}]"
 ylabel_rel_pdfpull = r"Pull [$\sigma$]"
@@ -717,13 +730,6 @@ label_rel_ewonoff_scale_unc = f"{scales}-p. scale var."
 label_rel_ewonoff_pdf_unc = r"PDF uncertainty"
 
 
-# linestyle for the channel breakdown shown in the panel `plot_abs_pdfs`. If the array
-# is empty, no channel breakdown will be shown, otherwise the most important channels,
-# as many as linestyles are given. See also
-# https://matplotlib.org/stable/gallery/lines_bars_and_markers/linestyles.html
-channel_breakdown_linestyles = []
-
-
 def main(active_panels):
     """Build a plot figure with various panels."""
     # Find the active panels
@@ -869,13 +875,13 @@ def plot_abs(axis, /, x, y, ymin, ymax, slice_label="", **_kwargs):
 
 
 def plot_ratio_pdf(axis, /, x, pdf_results, slice_label="", **_kwargs):
-    axis.set_ylabel(ylabel_ratio_pdf.format(central_pdf=pdf_results[0][0]))
+    axis.set_ylabel(ylabel_ratio_pdf.format(central_pdf=pdf_results[ratio_to][0]))
 
     for index, i in enumerate(pdf_results):
         label, y, ymin, ymax = i
-        y = y / pdf_results[0][1]
-        ymin = ymin / pdf_results[0][1]
-        ymax = ymax / pdf_results[0][1]
+        y = y / pdf_results[ratio_to][1]
+        ymin = ymin / pdf_results[ratio_to][1]
+        ymax = ymax / pdf_results[ratio_to][1]
 
         axis.step(x, y, color=colors[index], linewidth=1.0, where="post")
         axis.fill_between(
@@ -918,18 +924,22 @@ def plot_ratio_pdf(axis, /, x, pdf_results, slice_label="", **_kwargs):
 
 
 def plot_double_ratio_pdf(axis, /, x, pdf_results, slice_label="", **_kwargs):
-    axis.set_ylabel(ylabel_double_ratio_pdf)
+    # the results are compared in pairs: results with an even index are divided by the first and
+    # results with an odd index by the second result of the pair that contains `ratio_to`
+    first = ratio_to - ratio_to % 2
+    second = min(first + 1, len(pdf_results) - 1)
+    axis.set_ylabel(
+        ylabel_double_ratio_pdf.format(
+            first=pdf_results[first][0], second=pdf_results[second][0]
+        )
+    )
 
     for index, i in enumerate(pdf_results):
         label, y, ymin, ymax = i
-        if index == 0 or index == 2:
-            y = y / pdf_results[0][1]
-            ymin = ymin / pdf_results[0][1]
-            ymax = ymax / pdf_results[0][1]
-        else:
-            y = y / pdf_results[1][1]
-            ymin = ymin / pdf_results[1][1]
-            ymax = ymax / pdf_results[1][1]
+        denominator = pdf_results[second if index % 2 == 1 else first][1]
+        y = y / denominator
+        ymin = ymin / denominator
+        ymax = ymax / denominator
         axis.step(x, y, color=colors[index], linewidth=1.0, where="post")
         axis.fill_between(
             x,
@@ -1202,6 +1212,12 @@ ylabel = r"$\frac{\mathrm{d}\sigma}{\mathrm{d}\cos \theta^*}$ [\si{pb}]"
 xlog = False
 ylog = False
 scales = 7
+ratio_to = 0
+# linestyle for the channel breakdown shown in the panel `plot_abs_pdfs`. If the array
+# is empty, no channel breakdown will be shown, otherwise the most important channels,
+# as many as linestyles are given. See also
+# https://matplotlib.org/stable/gallery/lines_bars_and_markers/linestyles.html
+channel_breakdown_linestyles = []
 plot_panels = {
     "int": False,
     "abs": True,
@@ -1253,7 +1269,7 @@ stylesheet = {
 
 # panel plot labels
 ylabel_ratio_pdf = r"Ratio to {{central_pdf}}"
-ylabel_double_ratio_pdf = r"Ratio to NLO"
+ylabel_double_ratio_pdf = r"Ratio to {first} / {second}"
 ylabel_rel_ewonoff = r"NLO EW on/off [\si{\percent}]"
 ylabel_rel_pdfunc = r"PDF uncertainty [\si{\percent}]"
 ylabel_rel_pdfpull = r"Pull [$\sigma$]"
@@ -1264,13 +1280,6 @@ label_rel_ewonoff_scale_unc = f"{scales}-p. scale var."
 label_rel_ewonoff_pdf_unc = r"PDF uncertainty"
 
 
-# linestyle for the channel breakdown shown in the panel `plot_abs_pdfs`. If the array
-# is empty, no channel breakdown will be shown, otherwise the most important channels,
-# as many as linestyles are given. See also
-# https://matplotlib.org/stable/gallery/lines_bars_and_markers/linestyles.html
-channel_breakdown_linestyles = []
-
-
 def main(active_panels):
     """Build a plot figure with various panels."""
     # Find the active panels
@@ -1416,13 +1425,13 @@ def plot_abs(axis, /, x, y, ymin, ymax, slice_label="", **_kwargs):
 
 
 def plot_ratio_pdf(axis, /, x, pdf_results, slice_label="", **_kwargs):
-    axis.set_ylabel(ylabel_ratio_pdf.format(central_pdf=pdf_results[0][0]))
+    axis.set_ylabel(ylabel_ratio_pdf.format(central_pdf=pdf_results[ratio_to][0]))
 
     for index, i in enumerate(pdf_results):
         label, y, ymin, ymax = i
-        y = y / pdf_results[0][1]
-        ymin = ymin / pdf_results[0][1]
-        ymax = ymax / pdf_results[0][1]
+        y = y / pdf_results[ratio_to][1]
+        ymin = ymin / pdf_results[ratio_to][1]
+        ymax = ymax / pdf_results[ratio_to][1]
 
         axis.step(x, y, color=colors[index], linewidth=1.0, where="post")
         axis.fill_between(
@@ -1465,18 +1474,22 @@ def plot_ratio_pdf(axis, /, x, pdf_results, slice_label="", **_kwargs):
 
 
 def plot_double_ratio_pdf(axis, /, x, pdf_results, slice_label="", **_kwargs):
-    axis.set_ylabel(ylabel_double_ratio_pdf)
+    # the results are compared in pairs: results with an even index are divided by the first and
+    # results with an odd index by the second result of the pair that contains `ratio_to`
+    first = ratio_to - ratio_to % 2
+    second = min(first + 1, len(pdf_results) - 1)
+    axis.set_ylabel(
+        ylabel_double_ratio_pdf.format(
+            first=pdf_results[first][0], second=pdf_results[second][0]
+        )
+    )
 
     for index, i in enumerate(pdf_results):
         label, y, ymin, ymax = i
-        if index == 0 or index == 2:
-            y = y / pdf_results[0][1]
-            ymin = ymin / pdf_results[0][1]
-            ymax = ymax / pdf_results[0][1]
-        else:
-            y = y / pdf_results[1][1]
-            ymin = ymin / pdf_results[1][1]
-            ymax = ymax / pdf_results[1][1]
+        denominator = pdf_results[second if index % 2 == 1 else first][1]
+        y = y / denominator
+        ymin = ymin / denominator
+        ymax = ymax / denominator
         axis.step(x, y, color=colors[index], linewidth=1.0, where="post")
         axis.fill_between(
             x,
@@ -1880,6 +1893,12 @@ if __name__ == "__main__":
 const THREE_PDF_ERROR_STR: &str = "convolutions with 3 convolution functions is not supported
 ";
 
+const WRONG_CONV_FUN_UNCERT_FROM_STR: &str =
+    "error: invalid value '0,x' for '--conv-fun-uncert-from <IDX>': invalid digit found in string
+
+For more information, try '--help'.
+";
+
 #[test]
 fn help() {
     Command::cargo_bin("pineappl")
@@ -1913,6 +1932,23 @@ fn default() {
         .stdout(DEFAULT_STR);
 }
 
+#[test]
+fn output() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "plot",
+            "--output=plots/wp",
+            "--threads=1",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+            "NNPDF40_nnlo_as_01180=NNPDF4.0",
+        ])
+        .assert()
+        .success()
+        .stdout(DEFAULT_STR.replace("output = r\"LHCB_WP_7TEV_opt\"", "output = r\"plots/wp\""));
+}
+
 #[test]
 fn subgrid_pull() {
     Command::cargo_bin("pineappl")
@@ -1978,3 +2014,95 @@ fn drell_yan_mass_slices() {
         .success()
         .stdout(DRELL_YAN_MASS_SLICES_STR);
 }
+
+#[test]
+fn wrong_conv_fun_uncert_from() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "plot",
+            "--conv-fun-uncert-from=0,x",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(WRONG_CONV_FUN_UNCERT_FROM_STR);
+}
+
+#[test]
+fn ratio_to_out_of_range() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "plot",
+            "--ratio-to=2",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+            "NNPDF40_nnlo_as_01180",
+        ])
+        .assert()
+        .failure()
+        .stderr(str::contains(
+            "Error: `--ratio-to` is 2, but only 2 PDF sets were given",
+        ));
+}
+
+#[test]
+fn channel_breakdown() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "plot",
+            "--channel-breakdown=3",
+            "--threads=1",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(
+            str::contains(r#"channel_breakdown_linestyles = ["dashed", "dotted", "dashdot"]"#)
+                .and(str::contains(r#""abs_pdfs": True"#))
+                // the PDF ratio panels need more than one PDF set
+                .and(str::contains(r#""ratio_pdf": False"#))
+                .and(str::contains(r#""double_ratio_pdf": False"#)),
+        );
+}
+
+#[test]
+fn channel_breakdown_single_bin() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "plot",
+            "--channel-breakdown=3",
+            "--threads=1",
+            "../test-data/CMS_TTB_8TEV_2D_TTM_TRAP_TOT-opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stderr(str::contains(
+            "warning: `--channel-breakdown` is ignored, because the grid has only a single bin",
+        ))
+        .stdout(str::contains(r#""abs_pdfs": False"#));
+}
+
+#[test]
+fn channel_breakdown_with_asymmetry() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "plot",
+            "--asymmetry",
+            "--channel-breakdown=3",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(str::contains(
+            "the argument '--asymmetry' cannot be used with '--channel-breakdown <N>'",
+        ));
+}
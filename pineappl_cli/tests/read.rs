@@ -1,25 +1,33 @@
 use assert_cmd::Command;
+use assert_fs::NamedTempFile;
+use predicates::str;
 
 const HELP_STR: &str = "Read out information of a grid
 
-Usage: pineappl read <--orders|--orders-spaces|--orders-long|--bins|--channels|--fktable|--ew|--get <KEY>|--keys|--qcd|--show> <INPUT>
+Usage: pineappl read <--orders|--orders-spaces|--orders-long|--bins|--bins-tex|--channels|--channels-tex|--convolutions|--compare-metadata <OTHER>|--fktable|--ew|--get <KEY>|--keys|--qcd|--show|--metadata-json|--reference> <INPUT>
 
 Arguments:
   <INPUT>  Path to the input grid
 
 Options:
-  -o, --orders         Show the orders of a grid, stripping zero powers
-      --orders-spaces  Show the orders of a grid, replacing zero powers with spaces
-      --orders-long    Show the orders of a grid, including zero powers
-  -b, --bins           Show the bins of a grid
-      --channels       Show the channel definition of a grid
-      --fktable        Check if input is an FK table
-      --ew             For each order print a list of the largest EW order
-      --get <KEY>      Gets an internal key-value pair
-      --keys           Show all keys stored in the grid
-      --qcd            For each order print a list of the largest QCD order
-      --show           Shows all key-value pairs stored in the grid
-  -h, --help           Print help
+  -o, --orders                    Show the orders of a grid, stripping zero powers
+      --orders-spaces             Show the orders of a grid, replacing zero powers with spaces
+      --orders-long               Show the orders of a grid, including zero powers
+  -b, --bins                      Show the bins of a grid
+      --bins-tex                  Show the bins of a grid as a LaTeX table
+      --channels                  Show the channel definition of a grid
+      --channels-tex              Show the channel definition of a grid as a LaTeX table
+      --convolutions              Show the convolutions of a grid and the particle IDs they must provide
+      --compare-metadata <OTHER>  Compare the key-value pairs with the ones of another grid
+      --fktable                   Check if input is an FK table
+      --ew                        For each order print a list of the largest EW order
+      --get <KEY>                 Gets an internal key-value pair
+      --keys                      Show all keys stored in the grid
+      --qcd                       For each order print a list of the largest QCD order
+      --show                      Shows all key-value pairs stored in the grid
+      --metadata-json             Shows all key-value pairs stored in the grid as a JSON object
+      --reference                 Show the reference results stored in the grid
+  -h, --help                      Print help
 ";
 
 const BINS_STR: &str = "b   etal    norm
@@ -34,6 +42,22 @@ const BINS_STR: &str = "b   etal    norm
 7    4  4.5  0.5
 ";
 
+const BINS_TEX_STR: &str = r"\begin{tabular}{rcr}
+\hline
+b & $\eta_{\bar{\ell}}$ & norm \\
+\hline
+0 & $[2, 2.25]$ & $0.25$ \\
+1 & $[2.25, 2.5]$ & $0.25$ \\
+2 & $[2.5, 2.75]$ & $0.25$ \\
+3 & $[2.75, 3]$ & $0.25$ \\
+4 & $[3, 3.25]$ & $0.25$ \\
+5 & $[3.25, 3.5]$ & $0.25$ \\
+6 & $[3.5, 4]$ & $0.5$ \\
+7 & $[4, 4.5]$ & $0.5$ \\
+\hline
+\end{tabular}
+";
+
 const CHANNELS_STR: &str = "c    entry        entry
 -+------------+------------
 0 1 × ( 2, -1) 1 × ( 4, -3)
@@ -43,6 +67,25 @@ const CHANNELS_STR: &str = "c    entry        entry
 4 1 × ( 2, 22) 1 × ( 4, 22)
 ";
 
+const CHANNELS_TEX_STR: &str = r"\begin{tabular}{rl}
+\hline
+c & channel \\
+\hline
+0 & $\mathrm{u}\bar{\mathrm{d}} + \mathrm{c}\bar{\mathrm{s}}$ \\
+1 & $\mathrm{g}\bar{\mathrm{s}} + \mathrm{g}\bar{\mathrm{d}}$ \\
+2 & $\gamma\bar{\mathrm{s}} + \gamma\bar{\mathrm{d}}$ \\
+3 & $\mathrm{u}\mathrm{g} + \mathrm{c}\mathrm{g}$ \\
+4 & $\mathrm{u}\gamma + \mathrm{c}\gamma$ \\
+\hline
+\end{tabular}
+";
+
+const CONVOLUTIONS_STR: &str = "i  convolution       pids
+-+-------------+--------------
+0 unpolpdf:2212 2, 4, 21, 22
+1 unpolpdf:2212 -3, -1, 21, 22
+";
+
 const ORDERS_STR: &str = "o      order
 -+----------------
 0 O(a^2)
@@ -76,7 +119,7 @@ multiple orders detected
 
 const WRONG_ORDERS_STR: &str = "error: the argument '--orders' cannot be used with '--orders-long'
 
-Usage: pineappl read <--orders|--orders-spaces|--orders-long|--bins|--channels|--fktable|--ew|--get <KEY>|--keys|--qcd|--show> <INPUT>
+Usage: pineappl read <--orders|--orders-spaces|--orders-long|--bins|--bins-tex|--channels|--channels-tex|--convolutions|--compare-metadata <OTHER>|--fktable|--ew|--get <KEY>|--keys|--qcd|--show|--metadata-json|--reference> <INPUT>
 
 For more information, try '--help'.
 ";
@@ -543,10 +586,22 @@ y_unit: pb
 
 const WRONG_ARGUMENTS_STR: &str = "error: the argument '--ew' cannot be used with '--qcd'
 
-Usage: pineappl read <--orders|--orders-spaces|--orders-long|--bins|--channels|--fktable|--ew|--get <KEY>|--keys|--qcd|--show> <INPUT>
+Usage: pineappl read <--orders|--orders-spaces|--orders-long|--bins|--bins-tex|--channels|--channels-tex|--convolutions|--compare-metadata <OTHER>|--fktable|--ew|--get <KEY>|--keys|--qcd|--show|--metadata-json|--reference> <INPUT>
 
 For more information, try '--help'.
 ";
+const REFERENCE_STR: &str = "b   etal    NNPDF31_nlo_as_0118_luxqed
+     []                [pb]           
+-+----+----+--------------------------
+0    2 2.25                7.5459110e2
+1 2.25  2.5                6.9028342e2
+2  2.5 2.75                6.0025198e2
+3 2.75    3                4.8552235e2
+4    3 3.25                3.6195456e2
+5 3.25  3.5                2.4586691e2
+6  3.5    4                1.1586851e2
+7    4  4.5                2.7517266e1
+";
 
 #[test]
 fn help() {
@@ -572,6 +627,20 @@ fn bins() {
         .stdout(BINS_STR);
 }
 
+#[test]
+fn bins_tex() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "read",
+            "--bins-tex",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+        ])
+        .assert()
+        .success()
+        .stdout(BINS_TEX_STR);
+}
+
 #[test]
 fn channels() {
     Command::cargo_bin("pineappl")
@@ -586,6 +655,48 @@ fn channels() {
         .stdout(CHANNELS_STR);
 }
 
+#[test]
+fn channels_tex() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "read",
+            "--channels-tex",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+        ])
+        .assert()
+        .success()
+        .stdout(CHANNELS_TEX_STR);
+}
+
+#[test]
+fn convolutions() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "read",
+            "--convolutions",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+        ])
+        .assert()
+        .success()
+        .stdout(CONVOLUTIONS_STR);
+}
+
+#[test]
+fn compare_metadata() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "read",
+            "--compare-metadata=../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+        ])
+        .assert()
+        .success()
+        .stdout("");
+}
+
 #[test]
 fn orders() {
     Command::cargo_bin("pineappl")
@@ -600,6 +711,21 @@ fn orders() {
         .stdout(ORDERS_STR);
 }
 
+#[test]
+fn orders_header_only() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "read",
+            "--orders",
+            "--header-only",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+        ])
+        .assert()
+        .success()
+        .stdout(ORDERS_STR);
+}
+
 #[test]
 fn orders_long() {
     Command::cargo_bin("pineappl")
@@ -723,6 +849,63 @@ fn show() {
         .stdout(SHOW_STR);
 }
 
+#[test]
+fn metadata_json() {
+    let output = NamedTempFile::new("json.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "write",
+            "--set-key-value",
+            "quoted",
+            "a \"b\"\tc\\d\ne",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(["read", "--metadata-json", output.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(str::starts_with("{\""))
+        .stdout(str::contains(r#""quoted":"a \"b\"\tc\\d\ne""#));
+}
+
+#[test]
+fn reference() {
+    let output = NamedTempFile::new("reference.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "write",
+            "--set-key-value",
+            "results_pdf",
+            "NNPDF31_nlo_as_0118_luxqed",
+            "--set-key-value",
+            "reference",
+            "7.5459110e2 6.9028342e2 6.0025198e2 4.8552235e2 3.6195456e2 2.4586691e2 1.1586851e2 \
+             2.7517266e1",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(["read", "--reference", output.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(REFERENCE_STR);
+}
+
 #[test]
 fn wrong_arguments() {
     Command::cargo_bin("pineappl")
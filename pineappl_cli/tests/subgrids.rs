@@ -1,24 +1,30 @@
 use assert_cmd::Command;
+use predicates::prelude::*;
+use predicates::str;
 
 const HELP_STR: &str = "Print information about the internal subgrid types
 
-Usage: pineappl subgrids [OPTIONS] <--type|--mur|--mur2|--muf|--muf2|--x1|--x2|--stats> <INPUT>
+Usage: pineappl subgrids [OPTIONS] <--type|--mur|--mur2|--muf|--muf2|--x1|--x2|--stats|--total|--optimize-preview|--memory> <INPUT>
 
 Arguments:
   <INPUT>  Path to the input grid
 
 Options:
-      --show-empty       Show empty subgrids
-      --type             Show the subgrid type
-      --mur              Show the renormalization grid values
-      --mur2             Show the squared renormalization grid values
-      --muf              Show the factorization grid values
-      --muf2             Show the squared factorization grid values
-      --x1               Show the x1 grid values
-      --x2               Show the x2 grid values
-      --stats            Show grid statistics (figures are the number of entries)
-      --digits <DIGITS>  Set the number of digits shown for numerical values [default: 3]
-  -h, --help             Print help
+      --show-empty        Show empty subgrids
+      --type              Show the subgrid type
+      --mur               Show the renormalization grid values
+      --mur2              Show the squared renormalization grid values
+      --muf               Show the factorization grid values
+      --muf2              Show the squared factorization grid values
+      --x1                Show the x1 grid values
+      --x2                Show the x2 grid values
+      --stats             Show grid statistics (figures are the number of entries)
+      --total             Show a summary of all subgrids instead of one row per subgrid
+      --optimize-preview  Show which orders and channels `pineappl write --optimize` would remove or merge, without modifying the grid
+      --memory            Show the number of non-zero entries, nodes and estimated bytes of each subgrid, sorted by decreasing memory usage
+      --digits <DIGITS>   Set the number of digits shown for numerical values [default: 3]
+      --format <FORMAT>   Set the output format of the summary, which is a table by default [possible values: table, json]
+  -h, --help              Print help
 ";
 
 const MUF_STR: &str = "o b c  muf
@@ -669,6 +675,22 @@ const TYPE_STR: &str = "o b c        type
 4 7 4 ImportOnlySubgridV2
 ";
 
+const TOTAL_STR: &str = "subgrids empty filled bytes  EmptySubgridV1 ImportOnlySubgridV2
+--------+-----+------+------+--------------+-------------------
+     200    96  99563 931264             96                 104
+";
+
+const TOTAL_JSON_STR: &str = r#"{"subgrids":200,"empty":96,"filled":99563,"bytes":931264,"kinds":{"EmptySubgridV1":96,"ImportOnlySubgridV2":104}}
+"#;
+
+const FORMAT_WITHOUT_TOTAL_STR: &str = "Error: `--format` requires `--total`
+";
+
+const OPTIMIZE_PREVIEW_STR: &str = "empty orders: none
+empty channels: none
+mergeable channels: none
+";
+
 const TYPE_SHOW_EMPTY_STR: &str = "o b c        type
 -+-+-+-------------------
 0 0 0 ImportOnlySubgridV2
@@ -1169,6 +1191,101 @@ fn stats() {
         .stdout(STATS_STR);
 }
 
+#[test]
+fn total() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "subgrids",
+            "--total",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+        ])
+        .assert()
+        .success()
+        .stdout(TOTAL_STR);
+}
+
+#[test]
+fn total_json() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "subgrids",
+            "--total",
+            "--format=json",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+        ])
+        .assert()
+        .success()
+        .stdout(TOTAL_JSON_STR);
+}
+
+#[test]
+fn format_without_total() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "subgrids",
+            "--type",
+            "--format=json",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+        ])
+        .assert()
+        .failure()
+        .stderr(FORMAT_WITHOUT_TOTAL_STR);
+}
+
+#[test]
+fn optimize_preview() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "subgrids",
+            "--optimize-preview",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+        ])
+        .assert()
+        .success()
+        .stdout(OPTIMIZE_PREVIEW_STR);
+}
+
+#[test]
+fn memory() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "subgrids",
+            "--memory",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+        ])
+        .assert()
+        .success()
+        .stdout(
+            str::contains("type")
+                .and(str::contains("non-zeros"))
+                .and(str::contains("bytes"))
+                .and(str::contains("ImportOnlySubgridV2"))
+                .and(str::contains("EmptySubgridV1").not()),
+        );
+}
+
+#[test]
+fn memory_with_type() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "subgrids",
+            "--memory",
+            "--type",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+        ])
+        .assert()
+        .failure()
+        .stderr(str::contains(
+            "the argument '--memory' cannot be used with '--type'",
+        ));
+}
+
 #[test]
 fn type_() {
     Command::cargo_bin("pineappl")
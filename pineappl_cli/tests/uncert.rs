@@ -17,6 +17,8 @@ Options:
       --scale-env[=<SCALES>]  Calculate the envelope of results where renormalization and factorization scales varied [possible values: 3, 7, 9]
       --cl <CL>               Confidence level in per cent, for convolution function uncertainties [default: 68.26894921370858]
   -i, --integrated            Show integrated numbers (without bin widths) instead of differential ones
+      --decompose             Show the total uncertainties, adding the relative convolution function and scale-envelope uncertainties in quadrature
+      --covariance <FILE>     Write the covariance matrix of the bins, adding the covariances of the first convolution function set at the confidence level `--cl` and of the scale variations, in the NPY format into FILE
   -o, --orders <ORDERS>       Select orders manually
       --threads <THREADS>     Number of threads to utilize [default: {}]
       --digits-abs <ABS>      Set the number of fractional digits shown for absolute numbers [default: 7]
@@ -143,6 +145,23 @@ const SCALE_ENV_9_STR: &str = "b   etal    dsig/detal  9pt-svar (env)
 7    4  4.5 2.7517266e1   -5.36    5.22
 ";
 
+const DECOMPOSE_WITHOUT_SCALE_ENV_STR: &str =
+    "error: the following required arguments were not provided:
+  --scale-env[=<SCALES>]
+
+Usage: pineappl uncert --decompose <--conv-fun[=<IDX>]|--scale-abs[=<SCALES>]|--scale-cov[=<SCALES>]|--scale-env[=<SCALES>]> <INPUT> <CONV_FUNS>
+
+For more information, try '--help'.
+";
+const COVARIANCE_WITHOUT_SCALE_COV_STR: &str =
+    "error: the following required arguments were not provided:
+  --scale-cov[=<SCALES>]
+
+Usage: pineappl uncert --covariance <FILE> <--conv-fun[=<IDX>]|--scale-abs[=<SCALES>]|--scale-cov[=<SCALES>]|--scale-env[=<SCALES>]> <INPUT> <CONV_FUNS>
+
+For more information, try '--help'.
+";
+
 #[test]
 fn help() {
     Command::cargo_bin("pineappl")
@@ -301,3 +320,93 @@ fn scale_env_9() {
         .success()
         .stdout(SCALE_ENV_9_STR);
 }
+
+#[test]
+fn decompose_without_scale_env() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "uncert",
+            "--decompose",
+            "--conv-fun",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(DECOMPOSE_WITHOUT_SCALE_ENV_STR);
+}
+
+#[cfg(feature = "npy")]
+fn covariance_with_cl(cl: &str) -> ndarray::Array2<f64> {
+    use assert_fs::NamedTempFile;
+    use ndarray_npy::ReadNpyExt;
+    use std::fs::File;
+
+    let output = NamedTempFile::new("covariance.npy").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "uncert",
+            "--conv-fun",
+            "--scale-cov",
+            &format!("--cl={cl}"),
+            &format!("--covariance={}", output.path().to_str().unwrap()),
+            "--threads=1",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success();
+
+    ndarray::Array2::read_npy(File::open(output.path()).unwrap()).unwrap()
+}
+
+#[cfg(feature = "npy")]
+#[test]
+fn covariance() {
+    let covariance = covariance_with_cl("68.26894921370858");
+
+    assert_eq!(covariance.dim(), (8, 8));
+    assert_eq!(covariance, covariance.t());
+    assert!(covariance.diag().iter().all(|&variance| variance > 0.0));
+}
+
+#[cfg(feature = "npy")]
+#[test]
+fn covariance_cl() {
+    // the scale covariance doesn't depend on `--cl`, the PDF covariance grows with the square of
+    // the number of standard deviations
+    let one_sigma = covariance_with_cl("68.26894921370858");
+    let two_sigma = covariance_with_cl("95.44997361036415");
+    let three_sigma = covariance_with_cl("99.73002039367398");
+
+    for (delta2, delta3) in (&two_sigma - &one_sigma)
+        .iter()
+        .zip((&three_sigma - &one_sigma).iter())
+    {
+        float_cmp::assert_approx_eq!(
+            f64,
+            8.0 * delta2,
+            3.0 * delta3,
+            epsilon = 1e-6 * delta3.abs()
+        );
+    }
+}
+
+#[test]
+fn covariance_without_scale_cov() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "uncert",
+            "--covariance=covariance.npy",
+            "--conv-fun",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .failure()
+        .stderr(COVARIANCE_WITHOUT_SCALE_COV_STR);
+}
@@ -1,5 +1,6 @@
 use assert_cmd::Command;
 use assert_fs::{fixture::FileWriteStr, NamedTempFile};
+use predicates::prelude::*;
 
 const HELP_STR: &str = "Write a grid modified by various operations
 
@@ -7,17 +8,25 @@ Usage: pineappl write [OPTIONS] <INPUT> <OUTPUT>
 
 Arguments:
   <INPUT>   Path to the input grid
-  <OUTPUT>  Path of the modified PineAPPL file
+  <OUTPUT>  Path of the modified PineAPPL file; `-` writes to the standard output
 
 Options:
+      --compress <COMPRESS>            Compression of the output; by default inferred from its extension [possible values: lz4, none]
+      --max-combinations <N>           With `--split-channels`, only split channels with more than N combinations
+      --verbose                        Print what `--optimize` changed
       --cc1[=<ENABLE>]                 Charge conjugate the first initial state [possible values: true, false]
       --cc2[=<ENABLE>]                 Charge conjugate the second initial state [possible values: true, false]
+      --channel-bin-kfactors <FILE>    Scale each channel and bin with a factor read from a file
       --dedup-channels[=<ULPS>]        Deduplicate channels assuming numbers differing by ULPS are the same
       --delete-bins <BIN1-BIN2,...>    Delete bins with the specified indices
       --delete-channels <CH1-CH2,...>  Delete channels with the specified indices
       --delete-orders <O1-O2,...>      Delete orders with the specified indices
       --delete-key <KEY>               Delete an internal key-value pair
+      --kfactor <IDX> <FILE>           Scale the order with index IDX and its scale logarithms with bin-dependent factors read from FILE
+      --max-power <POWER>              Delete orders whose sum of as and a powers is larger than POWER
       --merge-bins <BIN1-BIN2,...>     Merge specific bins together
+      --min-power <POWER>              Delete orders whose sum of as and a powers is smaller than POWER
+      --no-photon[=<ENABLE>]           Remove the contributions of photon-initiated channels [possible values: true, false]
       --optimize[=<ENABLE>]            Optimize internal data structure to minimize memory and disk usage [possible values: true, false]
       --optimize-fk-table <OPTIMI>     Optimize internal data structure of an FkTable to minimize memory and disk usage [possible values: Nf6Ind, Nf6Sym, Nf5Ind, Nf5Sym, Nf4Ind, Nf4Sym, Nf3Ind, Nf3Sym]
       --remap <REMAPPING>              Modify the bin dimensions and widths
@@ -28,7 +37,8 @@ Options:
       --rotate-pid-basis <BASIS>       Rotate the PID basis for this grid [possible values: PDG, EVOL]
   -s, --scale <SCALE>                  Scales all grids with the given factor
       --scale-by-bin <BIN1,BIN2,...>   Scale each bin with a different factor
-      --scale-by-order <AS,AL,LR,LF>   Scales all grids with order-dependent factors
+      --scale-by-order <AS,AL,LR,LF>   Scales all grids with order-dependent factors and an optional global factor
+      --set-conv-type <IDX> <TYPE>     Set the type of the convolution with index IDX, keeping its hadron unless TYPE is followed by ':<PID>'
       --set-key-value <KEY> <VALUE>    Set an internal key-value pair
       --set-key-file <KEY> <FILE>      Set an internal key-value pair, with value being read from a file
       --split-channels[=<ENABLE>]      Split the grid such that each channel contains only a single PID combination [possible values: true, false]
@@ -154,6 +164,10 @@ const REMAP_STR: &str = "b etal  x2  x3  dsig/detal
 7  1  2 2 4 4 5 2.7517266e0
 ";
 
+const MERGE_BINS_UNORDERED_STR: &str =
+    "Error: the ranges of bins 2..4 and 0..2 overlap or are not in increasing order
+";
+
 const REMAP_NO_REMAPPER_STR: &str = "Error: grid does not have a remapper
 ";
 
@@ -444,6 +458,14 @@ const DELETE_ORDERS_STR: &str = "o      order
 1 O(as^1 a^2 lf^1)
 ";
 
+const MIN_MAX_POWER_STR: &str = "o      order
+-+----------------
+0 O(as^1 a^2)
+1 O(as^1 a^2 lf^1)
+2 O(a^3)
+3 O(a^3 lf^1)
+";
+
 #[test]
 fn help() {
     Command::cargo_bin("pineappl")
@@ -590,6 +612,46 @@ fn delete_channels() {
         .stdout(DELETE_CHANNELS_STR);
 }
 
+#[test]
+fn kfactor_without_order() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "write",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "output.pineappl.lz4",
+            "--kfactor",
+            "kfactors.txt",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "error: 2 values required for '--kfactor <IDX> <FILE>' but 1 was provided",
+        ));
+}
+
+#[test]
+fn kfactor_wrong_number_of_factors() {
+    let output = NamedTempFile::new("kfactor.pineappl.lz4").unwrap();
+    let file = NamedTempFile::new("kfactors.txt").unwrap();
+
+    file.write_str("# NNLO/NLO\n1.1\n1.2\n").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "write",
+            "--kfactor",
+            "1",
+            file.path().to_str().unwrap(),
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr("Error: the grid has 8 bins, but 2 factors were given\n");
+}
+
 #[test]
 fn key_value() {
     let output = NamedTempFile::new("set.pineappl.lz4").unwrap();
@@ -651,6 +713,113 @@ fn merge_bins() {
         .stdout(MERGE_BINS_STR);
 }
 
+#[test]
+fn merge_bins_unordered() {
+    let output = NamedTempFile::new("bins.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "write",
+            "--merge-bins=2-3,0-1",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(MERGE_BINS_UNORDERED_STR);
+}
+
+#[test]
+fn set_conv_type() {
+    let output = NamedTempFile::new("conv-type.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "write",
+            "--set-conv-type",
+            "1",
+            "UnpolFF",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "read",
+            "--get=convolution_type_2",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("UnpolFF\n");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "read",
+            "--get=convolution_particle_2",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("2212\n");
+}
+
+#[test]
+fn set_conv_type_none() {
+    let output = NamedTempFile::new("conv-type.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "write",
+            "--set-conv-type",
+            "0",
+            "none",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "Error: the convolution with index 0 can not be set: 'none' requires a single particle",
+        ));
+}
+
+#[test]
+fn no_photon() {
+    let output = NamedTempFile::new("no-photon.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "write",
+            "--no-photon",
+            "../test-data/LHCB_DY_8TEV.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(["read", "--channels", output.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("(22,")
+                .not()
+                .and(predicates::str::contains(", 22)").not()),
+        );
+}
+
 #[test]
 fn optimize() {
     // use `.pineappl` extension without `.lz4` to test `Grid::write` without compresssion
@@ -790,6 +959,51 @@ fn scale_by_order() {
         .stdout(SCALE_BY_ORDER_STR);
 }
 
+#[test]
+fn scale_by_order_with_global() {
+    let output = NamedTempFile::new("merged.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "write",
+            "--scale-by-order=2,1,0.5,0.5,0.5",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "convolve",
+            output.path().to_str().unwrap(),
+            "NNPDF31_nlo_as_0118_luxqed",
+        ])
+        .assert()
+        .success()
+        .stdout(SCALE_BY_ORDER_STR);
+}
+
+#[test]
+fn scale_by_order_wrong_number_of_factors() {
+    let output = NamedTempFile::new("merged.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "write",
+            "--scale-by-order=2,1,0.5",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr("Error: `--scale-by-order` expects four or five factors, but 3 were given\n");
+}
+
 #[test]
 fn split_channels() {
     let output = NamedTempFile::new("split-channels.pineappl.lz4").unwrap();
@@ -825,6 +1039,43 @@ fn split_channels() {
         .stdout(SPLIT_CHANNELS_STR);
 }
 
+#[test]
+fn optimize_verbose() {
+    let output = NamedTempFile::new("optimize-verbose.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "write",
+            "--optimize",
+            "--verbose",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("optimize: "));
+}
+
+#[test]
+fn max_combinations_without_split_channels() {
+    let output = NamedTempFile::new("max-combinations.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "write",
+            "--max-combinations=2",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "the following required arguments were not provided:\n  --split-channels[=<ENABLE>]",
+        ));
+}
+
 #[test]
 fn dedup_channels() {
     let output = NamedTempFile::new("dedup-channels.pineappl.lz4").unwrap();
@@ -1154,3 +1405,62 @@ fn delete_orders() {
         .success()
         .stdout(DELETE_ORDERS_STR);
 }
+
+#[test]
+fn min_max_power() {
+    let output = NamedTempFile::new("min-max-power.pineappl.lz4").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "write",
+            "--min-power=3",
+            "--max-power=3",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args(["read", "--orders", output.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(MIN_MAX_POWER_STR);
+}
+
+#[test]
+fn stdout() {
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "write",
+            "--compress=none",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            "-",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::function(|out: &[u8]| {
+            out.starts_with(b"PineAPPL")
+        }));
+}
+
+#[test]
+fn existing_output() {
+    let output = NamedTempFile::new("existing.pineappl").unwrap();
+    output.write_str("").unwrap();
+
+    Command::cargo_bin("pineappl")
+        .unwrap()
+        .args([
+            "write",
+            "../test-data/LHCB_WP_7TEV_opt.pineappl.lz4",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unable to write"));
+}
@@ -1,6 +1,7 @@
 #include "pineappl_fastnlo/src/fastnlo.hpp"
 
 #include <algorithm>
+#include <iostream>
 #include <iterator>
 #include <string>
 
@@ -65,6 +66,21 @@ std::unique_ptr<fastNLOLHAPDF> make_fastnlo_lhapdf_with_name_file_set(
     return std::unique_ptr<fastNLOLHAPDF>(new fastNLOLHAPDF(arg0, arg1, PDFSet));
 }
 
+void SilenceStdout(bool silence)
+{
+    // the original buffer of `std::cout`, if it is currently silenced
+    static std::streambuf* buffer = nullptr;
+
+    if (silence && (buffer == nullptr)) {
+        // without a buffer `std::cout` sets its `badbit` and discards all output
+        buffer = std::cout.rdbuf(nullptr);
+    } else if (!silence && (buffer != nullptr)) {
+        // restoring the buffer also clears the `badbit`
+        std::cout.rdbuf(buffer);
+        buffer = nullptr;
+    }
+}
+
 rust::Vec<double> GetCrossSection(fastNLOReader& reader, bool lNorm)
 {
     return std_vector_to_rust_vec(reader.GetCrossSection(lNorm));
@@ -35,6 +35,8 @@ rust::Vec<double> GetXNodes1(fastNLOCoeffAddBase const& coeffs, int iObsBin);
 
 rust::Vec<double> GetXNodes2(fastNLOCoeffAddBase const& coeffs, int iObsBin);
 
+void SilenceStdout(bool silence);
+
 rust::Vec<double> GetCrossSection(fastNLOReader& reader, bool lNorm);
 
 rust::Vec<rust::String> GetDimLabels(fastNLOTable const& table);
@@ -8,6 +8,7 @@
 #![allow(clippy::must_use_candidate)]
 #![allow(missing_docs)]
 
+use std::fmt;
 use std::str::FromStr;
 use thiserror::Error;
 
@@ -145,6 +146,8 @@ pub mod ffi {
             _: bool,
         ) -> Vec<f64>;
 
+        fn SilenceStdout(_: bool);
+
         fn GetCrossSection(_: Pin<&mut fastNLOReader>, _: bool) -> Vec<f64>;
         fn GetNx(_: &fastNLOCoeffAddFlex, _: usize) -> usize;
         fn GetDimLabels(_: &fastNLOTable) -> Vec<String>;
@@ -223,6 +226,31 @@ impl FromStr for ffi::EScaleFunctionalForm {
     }
 }
 
+impl fmt::Display for ffi::EScaleFunctionalForm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Self::kScale1 => "kScale1",
+            Self::kScale2 => "kScale2",
+            Self::kQuadraticSum => "kQuadraticSum",
+            Self::kQuadraticMean => "kQuadraticMean",
+            Self::kQuadraticSumOver4 => "kQuadraticSumOver4",
+            Self::kLinearMean => "kLinearMean",
+            Self::kLinearSum => "kLinearSum",
+            Self::kScaleMax => "kScaleMax",
+            Self::kScaleMin => "kScaleMin",
+            Self::kProd => "kProd",
+            Self::kS2plusS1half => "kS2plusS1half",
+            Self::kPow4Sum => "kPow4Sum",
+            Self::kWgtAvg => "kWgtAvg",
+            Self::kS2plusS1fourth => "kS2plusS1fourth",
+            Self::kExpProd2 => "kExpProd2",
+            Self::kExtern => "kExtern",
+            Self::kConst => "kConst",
+            _ => unreachable!(),
+        })
+    }
+}
+
 impl ffi::EScaleFunctionalForm {
     pub fn compute_scale(self, s1: f64, s2: f64) -> f64 {
         match self {
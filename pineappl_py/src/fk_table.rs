@@ -5,6 +5,8 @@ use numpy::{IntoPyArray, PyArray1, PyArray4};
 use pineappl::convolutions::LumiCache;
 use pineappl::fk_table::{FkAssumptions, FkTable};
 use pineappl::grid::Grid;
+use pineappl::pids::PidBasis;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use std::collections::HashMap;
 use std::fs::File;
@@ -29,12 +31,106 @@ pub struct PyFkAssumptions {
 #[pymethods]
 impl PyFkAssumptions {
     /// Constructor.
+    ///
+    /// Parameters
+    /// ----------
+    /// assumption : str
+    ///     name of the assumption, one of `Nf6Ind`, `Nf6Sym`, `Nf5Ind`, `Nf5Sym`, `Nf4Ind`,
+    ///     `Nf4Sym`, `Nf3Ind` or `Nf3Sym`
     #[new]
-    pub fn new(assumption: &str) -> Self {
-        PyFkAssumptions {
-            fk_assumptions: FkAssumptions::from_str(assumption).unwrap(),
+    pub fn new(assumption: &str) -> PyResult<Self> {
+        Ok(Self {
+            fk_assumptions: FkAssumptions::from_str(assumption)
+                .map_err(|err| PyValueError::new_err(err.to_string()))?,
+        })
+    }
+
+    /// All quark PDFs are non-zero at the FK table scale and completely independent.
+    #[classattr]
+    #[allow(non_snake_case)]
+    fn Nf6Ind() -> Self {
+        Self {
+            fk_assumptions: FkAssumptions::Nf6Ind,
         }
     }
+
+    /// Like `Nf6Ind`, but the PDFs of top and anti-top quarks are the same.
+    #[classattr]
+    #[allow(non_snake_case)]
+    fn Nf6Sym() -> Self {
+        Self {
+            fk_assumptions: FkAssumptions::Nf6Sym,
+        }
+    }
+
+    /// Like `Nf6Ind`, but the PDFs of top and anti-top quarks are zero.
+    #[classattr]
+    #[allow(non_snake_case)]
+    fn Nf5Ind() -> Self {
+        Self {
+            fk_assumptions: FkAssumptions::Nf5Ind,
+        }
+    }
+
+    /// Like `Nf5Ind`, but the PDFs of bottom and anti-bottom quarks are the same.
+    #[classattr]
+    #[allow(non_snake_case)]
+    fn Nf5Sym() -> Self {
+        Self {
+            fk_assumptions: FkAssumptions::Nf5Sym,
+        }
+    }
+
+    /// Like `Nf5Ind`, but the PDFs of bottom and anti-bottom quarks are zero.
+    #[classattr]
+    #[allow(non_snake_case)]
+    fn Nf4Ind() -> Self {
+        Self {
+            fk_assumptions: FkAssumptions::Nf4Ind,
+        }
+    }
+
+    /// Like `Nf4Ind`, but the PDFs of charm and anti-charm quarks are the same.
+    #[classattr]
+    #[allow(non_snake_case)]
+    fn Nf4Sym() -> Self {
+        Self {
+            fk_assumptions: FkAssumptions::Nf4Sym,
+        }
+    }
+
+    /// Like `Nf4Ind`, but the PDFs of charm and anti-charm quarks are zero.
+    #[classattr]
+    #[allow(non_snake_case)]
+    fn Nf3Ind() -> Self {
+        Self {
+            fk_assumptions: FkAssumptions::Nf3Ind,
+        }
+    }
+
+    /// Like `Nf3Ind`, but the PDFs of strange and anti-strange quarks are the same.
+    #[classattr]
+    #[allow(non_snake_case)]
+    fn Nf3Sym() -> Self {
+        Self {
+            fk_assumptions: FkAssumptions::Nf3Sym,
+        }
+    }
+
+    /// Return the name of the assumption.
+    pub fn __str__(&self) -> String {
+        self.fk_assumptions.to_string()
+    }
+
+    /// Return a representation that can be evaluated to reconstruct the object.
+    pub fn __repr__(&self) -> String {
+        format!("FkAssumptions('{}')", self.fk_assumptions)
+    }
+
+    /// Compare two assumptions.
+    pub fn __eq__(&self, other: PyRef<Self>) -> bool {
+        self.fk_assumptions == other.fk_assumptions
+    }
 }
 
 #[pymethods]
@@ -306,8 +402,24 @@ impl PyFkTable {
     /// assumptions : PyFkAssumptions
     ///     assumptions about the FkTable properties, declared by the user, deciding which
     ///     optimizations are possible
-    pub fn optimize(&mut self, assumptions: PyRef<PyFkAssumptions>) {
-        self.fk_table.optimize(assumptions.fk_assumptions)
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///     if the channels of the FkTable are not given in the evolution basis, which the
+    ///     assumptions refer to
+    pub fn optimize(&mut self, assumptions: PyRef<PyFkAssumptions>) -> PyResult<()> {
+        let pid_basis = self.fk_table.grid().pid_basis();
+
+        if pid_basis != PidBasis::Evol {
+            return Err(PyValueError::new_err(format!(
+                "FkAssumptions require channels in the evolution basis, but the FkTable uses {pid_basis:?}"
+            )));
+        }
+
+        self.fk_table.optimize(assumptions.fk_assumptions);
+
+        Ok(())
     }
 }
 
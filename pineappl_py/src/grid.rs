@@ -41,21 +41,28 @@ impl PyGrid {
     ///     bin configurations
     /// subgrid_params : PySubgridParams
     ///     subgrid parameters
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///     if a factor of the channels is not finite, if the bin limits are not finite, unique and
+    ///     sorted in increasing order, or if the interpolation ranges are invalid
     #[new]
     pub fn new_grid(
         channels: Vec<PyRef<PyChannel>>,
         orders: Vec<PyRef<PyOrder>>,
         bin_limits: Vec<f64>,
         subgrid_params: PySubgridParams,
-    ) -> Self {
-        Self {
-            grid: Grid::new(
+    ) -> PyResult<Self> {
+        Ok(Self {
+            grid: Grid::try_new(
                 channels.iter().map(|pyc| pyc.entry.clone()).collect(),
                 orders.iter().map(|pyo| pyo.order.clone()).collect(),
                 bin_limits,
                 subgrid_params.subgrid_params,
-            ),
-        }
+            )
+            .map_err(|err| PyValueError::new_err(err.to_string()))?,
+        })
     }
 
     /// Add a point to the grid.
@@ -216,7 +223,8 @@ impl PyGrid {
         channel: usize,
         subgrid: PySubgridEnum,
     ) {
-        self.grid.subgrids_mut()[[order, bin, channel]] = subgrid.subgrid_enum;
+        self.grid
+            .set_subgrid(order, bin, channel, subgrid.subgrid_enum);
     }
 
     /// Set the bin normalizations.
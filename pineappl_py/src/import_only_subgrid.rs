@@ -37,18 +37,9 @@ impl PyImportOnlySubgridV2 {
         x1_grid: Vec<f64>,
         x2_grid: Vec<f64>,
     ) -> Self {
-        let mut sparse_array = SparseArray3::new(mu2_grid.len(), x1_grid.len(), x2_grid.len());
-
-        for ((imu2, ix1, ix2), value) in array
-            .as_array()
-            .indexed_iter()
-            .filter(|((_, _, _), value)| **value != 0.0)
-        {
-            sparse_array[[imu2, ix1, ix2]] = *value;
-        }
         Self {
-            import_only_subgrid: ImportOnlySubgridV2::new(
-                sparse_array,
+            import_only_subgrid: ImportOnlySubgridV2::from_array(
+                array.as_array(),
                 mu2_grid
                     .iter()
                     .map(|(ren, fac)| Mu2 {
@@ -1,4 +1,5 @@
 import numpy as np
+import pytest
 
 import pineappl
 
@@ -82,3 +83,42 @@ class TestFkTable:
             assert False
         except:  # noqa: E722
             assert True
+
+    def test_fk_assumptions(self):
+        for name in [
+            "Nf6Ind",
+            "Nf6Sym",
+            "Nf5Ind",
+            "Nf5Sym",
+            "Nf4Ind",
+            "Nf4Sym",
+            "Nf3Ind",
+            "Nf3Sym",
+        ]:
+            assumption = getattr(pineappl.fk_table.FkAssumptions, name)
+            assert str(assumption) == name
+            assert assumption == pineappl.fk_table.FkAssumptions(name)
+
+        with pytest.raises(ValueError, match="unknown variant for FkAssumptions"):
+            pineappl.fk_table.FkAssumptions("Nf7Ind")
+
+    def test_optimize(self):
+        g = self.fake_grid()
+        xs = np.linspace(0.5, 1.0, 5)
+        subgrid = pineappl.import_only_subgrid.ImportOnlySubgridV1(
+            xs[np.newaxis, :, np.newaxis],
+            np.array([90.0]),
+            xs,
+            np.array([1.0]),
+        )
+        g.set_subgrid(0, 0, 0, subgrid.into())
+
+        # the channels are given in the PDG basis, for which the assumptions are meaningless
+        fk = pineappl.fk_table.FkTable(g)
+        with pytest.raises(ValueError, match="evolution basis"):
+            fk.optimize(pineappl.fk_table.FkAssumptions.Nf3Sym)
+
+        g.set_key_value("lumi_id_types", "evol")
+        fk = pineappl.fk_table.FkTable(g)
+        fk.optimize(pineappl.fk_table.FkAssumptions.Nf3Sym)
+        assert fk.key_values()["fk_assumptions"] == "Nf3Sym"
@@ -29,6 +29,10 @@ class TestGrid:
         assert len(g.orders()) == 1
         assert g.orders()[0].as_tuple() == (3, 0, 0, 0)
 
+    def test_init_invalid(self):
+        with pytest.raises(ValueError, match="index 2 is smaller than its predecessor"):
+            self.fake_grid([0.0, 2.0, 1.0])
+
     def test_set_subgrid(self):
         g = self.fake_grid()
 
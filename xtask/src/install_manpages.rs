@@ -31,7 +31,7 @@ fn render_manpages(path: &Path, cmd: &clap::Command, version: &str) -> Result<()
             path.join(format!("{name}.1")),
         )?))?;
 
-    for subcmd in cmd.get_subcommands() {
+    for subcmd in cmd.get_subcommands().filter(|subcmd| !subcmd.is_hide_set()) {
         render_manpages(path, subcmd, version)?;
     }
 